@@ -0,0 +1,58 @@
+## Beschriftungen vor dem, was ein Eintrag aufführt, mit Doppelpunkt.
+forms = Formen:
+example = Beispiel:
+synonyms = Synonyme:
+antonyms = Antonyme:
+origin = Herkunft:
+see-also = Siehe auch:
+pronunciations = Aussprachen:
+source = Quelle:
+say-it = Gesprochen:
+confused-with = Oft verwechselt mit
+
+## Aktionen zum Weiterklicken, angezeigt als „Beschriftung: Wort“.
+action-see-also = Siehe auch
+action-synonym = Synonym
+action-antonym = Antonym
+action-phrase = Auch in der Wendung
+action-did-you-mean = Meintest du
+action-retry = Erneut versuchen
+action-cached = Zwischengespeicherte Fassung öffnen
+search-web = Im Web nach { $word } suchen
+
+## Titel und Meldungen.
+definition-of = Definition von „{ $word }“
+from-query = (aus „{ $query }“)
+not-found = Keine Definition gefunden
+not-found-for = Keine Definition für „{ $word }“ gefunden
+also-tried = auch versucht: { $words }
+did-you-mean = Meintest du: { $words }?
+could-not-look-up = „{ $word }“ konnte nicht nachgeschlagen werden
+api-error = API-Fehler: { $title }
+api-error-status = API-Fehler (Status { $status }): { $title }
+message = Meldung:
+resolution = Lösung:
+parse-error = Unlesbare Antwort für „{ $word }“
+parse-error-body = Die Antwort der API ließ sich nicht lesen. Rohdaten: { $body }
+http-error = HTTP-Fehler (Status { $status }) für „{ $word }“
+http-error-body = Die Fehlerantwort ließ sich nicht lesen. Rohdaten: { $body }
+
+## Der tui-Browser: Eingabeaufforderung, Tasten und Statuszeile.
+tui-prompt = Wort eingeben und Eingabetaste drücken.
+tui-help = Eingabe nachschlagen · Tab Bereich wechseln · Alt-←/→ Verlauf · c kopieren · p abspielen · b Lesezeichen · q beenden
+tui-looking-up = „{ $word }“ wird nachgeschlagen…
+tui-fetch-failed = Fehler beim Abrufen der Definition von „{ $word }“.
+tui-parse-failed = Die Antwort der API ließ sich nicht lesen (Status { $status }).
+tui-parse-error = Lesefehler: { $error }
+tui-oldest = Schon beim ältesten Eintrag des Verlaufs.
+tui-newest = Schon beim neuesten Eintrag des Verlaufs.
+tui-history-not-saved = Verlauf konnte nicht gespeichert werden: { $error }
+tui-copied = Definition in die Zwischenablage kopiert.
+tui-copy-failed = Kopieren fehlgeschlagen: { $error }
+tui-playing = Aussprache wird abgespielt…
+tui-speaking = Wird vorgelesen (Sprachsynthese)…
+tui-no-audio = Keine Aufnahme der Aussprache: { $error }
+tui-playback-failed = Wiedergabe fehlgeschlagen: { $error }
+tui-bookmarked = „{ $word }“ als Lesezeichen gespeichert.
+tui-unbookmarked = Lesezeichen für „{ $word }“ entfernt.
+tui-bookmarks-not-saved = Lesezeichen konnten nicht gespeichert werden: { $error }
@@ -0,0 +1,63 @@
+# The strings sherlock-dictionary shows around definitions, in the subset of
+# Fluent it reads: one `id = text` per line, with `{ $name }` where a value
+# goes. A translation leaves out what it hasn't translated, and English is
+# shown instead.
+
+## Labels in front of what an entry lists, with their colon.
+forms = Forms:
+example = Example:
+synonyms = Synonyms:
+antonyms = Antonyms:
+origin = Origin:
+see-also = See also:
+pronunciations = Pronunciations:
+source = Source:
+say-it = Say it:
+confused-with = Often confused with
+
+## Click-through actions, shown as "label: word".
+action-see-also = See also
+action-synonym = Synonym
+action-antonym = Antonym
+action-phrase = Also in the phrase
+action-did-you-mean = Did you mean
+action-retry = Retry
+action-cached = Open cached version
+search-web = Search the web for { $word }
+
+## Titles and messages.
+definition-of = Definition of "{ $word }"
+from-query = (from "{ $query }")
+not-found = No definition found
+not-found-for = No definition found for '{ $word }'
+also-tried = also tried { $words }
+did-you-mean = Did you mean: { $words }?
+could-not-look-up = Couldn't look up '{ $word }'
+api-error = API Error: { $title }
+api-error-status = API Error (Status { $status }): { $title }
+message = Message:
+resolution = Resolution:
+parse-error = Parsing Error for '{ $word }'
+parse-error-body = Failed to parse API response. Raw body: { $body }
+http-error = HTTP Error (Status { $status }) for '{ $word }'
+http-error-body = Failed to parse error response. Raw body: { $body }
+
+## The tui browser: its prompt, its keys and what it says in the status line.
+tui-prompt = Type a word and press Enter.
+tui-help = Enter define · Tab switch pane · Alt-←/→ history · c copy · p play · b bookmark · q quit
+tui-looking-up = Looking up "{ $word }"…
+tui-fetch-failed = Error fetching definition for '{ $word }'.
+tui-parse-failed = Failed to parse API response (Status { $status }).
+tui-parse-error = Parsing error: { $error }
+tui-oldest = Already at the oldest history entry.
+tui-newest = Already at the newest history entry.
+tui-history-not-saved = Could not save history: { $error }
+tui-copied = Copied definition to the clipboard.
+tui-copy-failed = Copy failed: { $error }
+tui-playing = Playing pronunciation…
+tui-speaking = Speaking (synthesized)…
+tui-no-audio = No pronunciation audio: { $error }
+tui-playback-failed = Playback failed: { $error }
+tui-bookmarked = Bookmarked "{ $word }".
+tui-unbookmarked = Removed bookmark for "{ $word }".
+tui-bookmarks-not-saved = Could not save bookmarks: { $error }
@@ -0,0 +1,58 @@
+## Etiquetas delante de lo que enumera una entrada, con sus dos puntos.
+forms = Formas:
+example = Ejemplo:
+synonyms = Sinónimos:
+antonyms = Antónimos:
+origin = Origen:
+see-also = Véase también:
+pronunciations = Pronunciaciones:
+source = Fuente:
+say-it = Se pronuncia:
+confused-with = A menudo confundido con
+
+## Acciones para seguir buscando, mostradas como «etiqueta: palabra».
+action-see-also = Véase también
+action-synonym = Sinónimo
+action-antonym = Antónimo
+action-phrase = También en la expresión
+action-did-you-mean = Quizás quisiste decir
+action-retry = Reintentar
+action-cached = Abrir la versión en caché
+search-web = Buscar { $word } en la web
+
+## Títulos y mensajes.
+definition-of = Definición de «{ $word }»
+from-query = (de «{ $query }»)
+not-found = No se encontró ninguna definición
+not-found-for = No se encontró ninguna definición de «{ $word }»
+also-tried = también se probó { $words }
+did-you-mean = ¿Quizás quisiste decir: { $words }?
+could-not-look-up = No se pudo buscar «{ $word }»
+api-error = Error de la API: { $title }
+api-error-status = Error de la API (estado { $status }): { $title }
+message = Mensaje:
+resolution = Solución:
+parse-error = Respuesta ilegible para «{ $word }»
+parse-error-body = No se pudo leer la respuesta de la API. Cuerpo: { $body }
+http-error = Error HTTP (estado { $status }) para «{ $word }»
+http-error-body = No se pudo leer la respuesta de error. Cuerpo: { $body }
+
+## El navegador tui: su invitación, sus teclas y su línea de estado.
+tui-prompt = Escribe una palabra y pulsa Intro.
+tui-help = Intro definir · Tab cambiar de panel · Alt-←/→ historial · c copiar · p escuchar · b marcador · q salir
+tui-looking-up = Buscando «{ $word }»…
+tui-fetch-failed = Error al obtener la definición de «{ $word }».
+tui-parse-failed = No se pudo leer la respuesta de la API (estado { $status }).
+tui-parse-error = Error de lectura: { $error }
+tui-oldest = Ya estás en la entrada más antigua del historial.
+tui-newest = Ya estás en la entrada más reciente del historial.
+tui-history-not-saved = No se pudo guardar el historial: { $error }
+tui-copied = Definición copiada al portapapeles.
+tui-copy-failed = No se pudo copiar: { $error }
+tui-playing = Reproduciendo la pronunciación…
+tui-speaking = Leyendo en voz alta (síntesis)…
+tui-no-audio = No hay grabación de la pronunciación: { $error }
+tui-playback-failed = No se pudo reproducir: { $error }
+tui-bookmarked = «{ $word }» añadida a los marcadores.
+tui-unbookmarked = «{ $word }» quitada de los marcadores.
+tui-bookmarks-not-saved = No se pudieron guardar los marcadores: { $error }
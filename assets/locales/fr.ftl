@@ -0,0 +1,58 @@
+## Libellés devant ce qu’une entrée énumère, avec leurs deux-points.
+forms = Formes :
+example = Exemple :
+synonyms = Synonymes :
+antonyms = Antonymes :
+origin = Origine :
+see-also = Voir aussi :
+pronunciations = Prononciations :
+source = Source :
+say-it = Se prononce :
+confused-with = Souvent confondu avec
+
+## Actions pour poursuivre, affichées comme « libellé : mot ».
+action-see-also = Voir aussi
+action-synonym = Synonyme
+action-antonym = Antonyme
+action-phrase = Aussi dans l’expression
+action-did-you-mean = Vouliez-vous dire
+action-retry = Réessayer
+action-cached = Ouvrir la version en cache
+search-web = Chercher { $word } sur le Web
+
+## Titres et messages.
+definition-of = Définition de « { $word } »
+from-query = (depuis « { $query } »)
+not-found = Aucune définition trouvée
+not-found-for = Aucune définition trouvée pour « { $word } »
+also-tried = essayé aussi : { $words }
+did-you-mean = Vouliez-vous dire : { $words } ?
+could-not-look-up = Impossible de chercher « { $word } »
+api-error = Erreur de l’API : { $title }
+api-error-status = Erreur de l’API (statut { $status }) : { $title }
+message = Message :
+resolution = Solution :
+parse-error = Réponse illisible pour « { $word } »
+parse-error-body = Impossible de lire la réponse de l’API. Corps brut : { $body }
+http-error = Erreur HTTP (statut { $status }) pour « { $word } »
+http-error-body = Impossible de lire la réponse d’erreur. Corps brut : { $body }
+
+## Le navigateur tui : son invite, ses touches et sa ligne d’état.
+tui-prompt = Tapez un mot et appuyez sur Entrée.
+tui-help = Entrée définir · Tab changer de panneau · Alt-←/→ historique · c copier · p écouter · b favori · q quitter
+tui-looking-up = Recherche de « { $word } »…
+tui-fetch-failed = Erreur en cherchant la définition de « { $word } ».
+tui-parse-failed = Réponse de l’API illisible (statut { $status }).
+tui-parse-error = Erreur de lecture : { $error }
+tui-oldest = Déjà à la plus ancienne entrée de l’historique.
+tui-newest = Déjà à la plus récente entrée de l’historique.
+tui-history-not-saved = Impossible d’enregistrer l’historique : { $error }
+tui-copied = Définition copiée dans le presse-papiers.
+tui-copy-failed = Échec de la copie : { $error }
+tui-playing = Lecture de la prononciation…
+tui-speaking = Lecture (synthèse vocale)…
+tui-no-audio = Pas d’enregistrement de la prononciation : { $error }
+tui-playback-failed = Échec de la lecture : { $error }
+tui-bookmarked = « { $word } » ajouté aux favoris.
+tui-unbookmarked = « { $word } » retiré des favoris.
+tui-bookmarks-not-saved = Impossible d’enregistrer les favoris : { $error }
@@ -0,0 +1,222 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    cli::Format,
+    cmudict, gloss, locale,
+    providers::Settings,
+    segment,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+    wordlist::WordList,
+};
+
+/// Words of this many syllables or more count as hard for the fog index.
+const COMPLEX_SYLLABLES: usize = 3;
+
+/// Counts from a text that the readability formulas are built on.
+struct Counts {
+    sentences: usize,
+    words: usize,
+    syllables: usize,
+    /// Words with [`COMPLEX_SYLLABLES`] or more.
+    complex: usize,
+}
+
+impl Counts {
+    fn of(text: &str) -> Self {
+        let sentences = text
+            .split(['.', '!', '?'])
+            .filter(|sentence| sentence.chars().any(char::is_alphabetic))
+            .count()
+            .max(1);
+        let mut counts = Counts {
+            sentences,
+            words: 0,
+            syllables: 0,
+            complex: 0,
+        };
+        for word in text
+            .split(|c: char| !(c.is_alphabetic() || c == '\''))
+            .filter(|word| !word.is_empty())
+        {
+            let syllables = syllables(word);
+            counts.words += 1;
+            counts.syllables += syllables;
+            if syllables >= COMPLEX_SYLLABLES {
+                counts.complex += 1;
+            }
+        }
+        counts
+    }
+
+    fn words_per_sentence(&self) -> f64 {
+        self.words as f64 / self.sentences as f64
+    }
+
+    fn syllables_per_word(&self) -> f64 {
+        self.syllables as f64 / self.words.max(1) as f64
+    }
+
+    /// Flesch reading ease: 100 is very easy, below 30 is academic.
+    fn reading_ease(&self) -> f64 {
+        206.835 - 1.015 * self.words_per_sentence() - 84.6 * self.syllables_per_word()
+    }
+
+    /// Flesch–Kincaid: the US school grade the text suits.
+    fn grade(&self) -> f64 {
+        0.39 * self.words_per_sentence() + 11.8 * self.syllables_per_word() - 15.59
+    }
+
+    /// Gunning fog: years of education needed to follow the text on first read.
+    fn fog(&self) -> f64 {
+        0.4 * (self.words_per_sentence() + 100.0 * self.complex as f64 / self.words.max(1) as f64)
+    }
+}
+
+/// The syllables in an English word, from CMUdict when it has the word and
+/// otherwise estimated.
+pub fn syllables(word: &str) -> usize {
+    cmudict::syllables(word).unwrap_or_else(|| estimate_syllables(word))
+}
+
+/// An estimate of the syllables in an English word: groups of vowels, less
+/// a silent final "e".
+pub fn estimate_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let vowel = |c: char| "aeiouy".contains(c);
+    let mut count = 0;
+    let mut previous = false;
+    for c in word.chars() {
+        let is_vowel = vowel(c);
+        if is_vowel && !previous {
+            count += 1;
+        }
+        previous = is_vowel;
+    }
+    // "make", but not "table" or "agree".
+    if word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// `analyze <file>`: readability scores for the text in `path` and the
+/// `top` rarest words in it, each with a one-line definition.
+pub async fn run(
+    path: &Path,
+    top: usize,
+    settings: &Settings,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            eprintln!("Error: {} doesn't exist", path.display());
+            std::process::exit(1);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let counts = Counts::of(&content);
+
+    let list = WordList::shared();
+    let mut candidates: Vec<String> = segment::words(&content)
+        .into_iter()
+        .filter(|word| word.chars().all(char::is_alphabetic))
+        .collect();
+    // Unranked words are the rarest; among them, longer ones first.
+    candidates.sort_by_key(|word| {
+        (
+            std::cmp::Reverse(list.rank(word).unwrap_or(usize::MAX)),
+            std::cmp::Reverse(word.chars().count()),
+        )
+    });
+    // Some of the rarest are names and typos with no entry, so ask for more.
+    candidates.truncate(top * 2);
+    let found = gloss::glosses(&candidates, settings).await;
+    let rarest: Vec<(&String, &gloss::Gloss)> = candidates
+        .iter()
+        .filter_map(|word| Some((word, found.get(word)?)))
+        .take(top)
+        .collect();
+
+    let stats = vec![
+        vec![String::from("Words"), locale::number(counts.words as u64)],
+        vec![
+            String::from("Sentences"),
+            locale::number(counts.sentences as u64),
+        ],
+        vec![
+            String::from("Words per sentence"),
+            locale::decimal(counts.words_per_sentence(), 1),
+        ],
+        vec![
+            String::from("Syllables per word"),
+            locale::decimal(counts.syllables_per_word(), 2),
+        ],
+        vec![
+            String::from("Flesch reading ease"),
+            locale::decimal(counts.reading_ease(), 1),
+        ],
+        vec![
+            String::from("Flesch–Kincaid grade"),
+            locale::decimal(counts.grade(), 1),
+        ],
+        vec![
+            String::from("Gunning fog"),
+            locale::decimal(counts.fog(), 1),
+        ],
+    ];
+    let words: Vec<Vec<String>> = rarest
+        .iter()
+        .map(|(word, gloss)| {
+            vec![
+                word.to_string(),
+                gloss.part_of_speech.to_string(),
+                gloss.definition.clone(),
+            ]
+        })
+        .collect();
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            content.push_str(&text::table(&stats));
+            if !words.is_empty() {
+                content.push_str("\n─── <b><i>Rarest words</i></b> ───\n\n");
+                content.push_str(&text::table(&words));
+            }
+            content.push_str("</span>");
+            let actions = rarest
+                .iter()
+                .map(|(_, gloss)| {
+                    ApplicationAction::lookup_word("Define", &gloss.headword, settings)
+                })
+                .collect();
+            let name = path.file_name().unwrap_or(path.as_os_str());
+            let response = SherlockPipeResponse::new(
+                format!("Readability of {}", name.to_string_lossy()),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            print!("{}", text::table(&stats));
+            if !words.is_empty() {
+                println!("\n─── Rarest words ───\n");
+                print!("{}", text::table(&words));
+            }
+        }
+        Format::Oneline => {
+            let rarest: Vec<&str> = rarest.iter().map(|(word, _)| word.as_str()).collect();
+            println!(
+                "reading ease {:.1}, grade {:.1}, {} words; rarest: {}",
+                counts.reading_ease(),
+                counts.grade(),
+                counts.words,
+                rarest.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
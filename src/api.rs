@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt, ops::ControlFlow, str::FromStr, sync::Mutex};
+use surf::StatusCode;
+
+use crate::{cefr::Level, gender::Gender, http, labels::Label, providers::Provider};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionResponse {
+    pub word: String,
+    pub phonetic: Option<String>,
+    pub phonetics: Vec<Phonetic>,
+    pub meanings: Vec<Meaning>,
+    pub source_urls: Option<Vec<String>>,
+    pub origin: Option<String>,
+    /// Which provider in the chain this entry came from.
+    #[serde(skip)]
+    pub provider: Option<Provider>,
+}
+impl DefinitionResponse {
+    /// First non-empty pronunciation audio URL, if the API supplied one.
+    pub fn audio_url(&self) -> Option<&str> {
+        self.phonetics
+            .iter()
+            .filter_map(|p| p.audio.as_deref())
+            .find(|url| !url.is_empty())
+    }
+
+    /// Puts the pronunciations recorded in `accent` first and makes their
+    /// IPA the entry's, so every frontend surfaces them.
+    pub fn prefer_accent(&mut self, accent: Accent) {
+        self.phonetics
+            .sort_by_key(|phonetic| phonetic.accent() != Some(accent));
+        if let Some(text) = self
+            .phonetics
+            .iter()
+            .filter(|p| p.accent() == Some(accent))
+            .find_map(|p| p.text.clone())
+            .filter(|text| !text.is_empty())
+        {
+            self.phonetic = Some(text);
+        }
+    }
+
+    /// Every distinct IPA transcription, with its accent when known:
+    /// `/həˈləʊ/ (UK)`.
+    pub fn pronunciations(&self) -> Vec<String> {
+        let mut seen: Vec<&str> = Vec::new();
+        let mut found = Vec::new();
+        for phonetic in &self.phonetics {
+            let Some(text) = phonetic.text.as_deref().filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            if seen.contains(&text) {
+                continue;
+            }
+            seen.push(text);
+            found.push(match phonetic.accent() {
+                Some(accent) => format!("{} ({})", text, accent),
+                None => text.to_string(),
+            });
+        }
+        found
+    }
+
+    /// The entry's IPA, preferring the top-level `phonetic` field.
+    pub fn ipa(&self) -> Option<&str> {
+        self.phonetic
+            .as_deref()
+            .or_else(|| self.phonetics.iter().find_map(|p| p.text.as_deref()))
+            .filter(|text| !text.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Phonetic {
+    pub text: Option<String>,
+    pub audio: Option<String>,
+    pub source_url: Option<String>,
+    pub license: Option<License>,
+}
+
+impl Phonetic {
+    /// The accent dictionaryapi.dev recorded this in, going by the audio file
+    /// name: `hello-uk.mp3`.
+    pub fn accent(&self) -> Option<Accent> {
+        let audio = self.audio.as_deref()?;
+        let stem = audio.rsplit('/').next()?.split('.').next()?;
+        let (_, suffix) = stem.rsplit_once('-')?;
+        suffix.parse().ok()
+    }
+}
+
+/// Where a pronunciation is from. Picked with `--accent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Accent {
+    Uk,
+    Us,
+    Au,
+}
+impl fmt::Display for Accent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Accent::Uk => "UK",
+            Accent::Us => "US",
+            Accent::Au => "AU",
+        })
+    }
+}
+impl FromStr for Accent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "uk" => Ok(Accent::Uk),
+            "us" => Ok(Accent::Us),
+            "au" => Ok(Accent::Au),
+            _ => Err(format!("Unknown accent '{}' (expected uk, us or au)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct License {
+    pub name: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meaning {
+    #[serde(rename = "partOfSpeech")]
+    pub part_of_speech: String,
+    pub definitions: Vec<Definition>,
+    pub synonyms: Option<Vec<String>>,
+    pub antonyms: Option<Vec<String>>,
+    /// Inflected forms with what they are, "ran (simple past)", from
+    /// providers that list them.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub forms: Option<Vec<String>>,
+    /// A noun's grammatical gender, from providers that record it.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub gender: Option<Gender>,
+}
+impl Meaning {
+    /// The part of speech as a meaning's heading shows it, with a noun's
+    /// gender: "noun (f)".
+    pub fn heading(&self) -> Cow<'_, str> {
+        match self.gender {
+            Some(gender) => Cow::Owned(format!(
+                "{} ({})",
+                self.part_of_speech,
+                gender.abbreviation()
+            )),
+            None => Cow::Borrowed(&self.part_of_speech),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Definition {
+    pub definition: String,
+    pub example: Option<String>,
+    pub synonyms: Option<Vec<String>>,
+    pub antonyms: Option<Vec<String>>,
+    /// Estimated reading level, filled in when CEFR grading is enabled.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub level: Option<Level>,
+    /// Where the sense is used ("UK", "US"…), taken from its usage labels.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Usage labels ("informal", "archaic"…) taken from the front of the
+    /// definition.
+    #[serde(skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<Label>,
+}
+impl Definition {
+    /// The definition, the example and the synonyms and antonyms, one per
+    /// line of a copied sense. Only the lists are joined into new strings.
+    pub fn to_vec(&self) -> Vec<Cow<'_, str>> {
+        let mut collect: Vec<Cow<str>> = Vec::with_capacity(4);
+        collect.push(Cow::Borrowed(&self.definition));
+        if let Some(example) = &self.example {
+            collect.push(Cow::Borrowed(example));
+        }
+        if let Some(synonyms) = &self.synonyms {
+            collect.push(Cow::Owned(synonyms.join(", ")));
+        }
+        if let Some(antonyms) = &self.antonyms {
+            collect.push(Cow::Owned(antonyms.join(", ")));
+        }
+        collect
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiErrorResponse {
+    pub title: String,
+    pub message: String,
+    pub resolution: String,
+}
+
+/// Everything a lookup against dictionaryapi.dev can come back with, so each
+/// frontend (Sherlock pipe, TUI, ...) can present it in its own way.
+#[derive(Debug)]
+pub enum Lookup {
+    Found(Vec<DefinitionResponse>),
+    NotFound,
+    ApiError {
+        status: StatusCode,
+        error: ApiErrorResponse,
+    },
+    ParseError {
+        status: StatusCode,
+        body: String,
+        error: serde_json::Error,
+    },
+}
+
+/// What's done with each entry of the answers that follow, set by
+/// [`stream_to`].
+type Sink = Box<dyn FnMut(&DefinitionResponse) -> ControlFlow<()> + Send>;
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Hands each entry of dictionaryapi.dev's answers to `sink` as soon as it's
+/// parsed, rather than once the whole body is, so output can start while a
+/// word with hundreds of senses is still being read. Reading stops when
+/// `sink` breaks, and the answer only has the entries read until then.
+pub fn stream_to(sink: impl FnMut(&DefinitionResponse) -> ControlFlow<()> + Send + 'static) {
+    *SINK.lock().unwrap() = Some(Box::new(sink));
+}
+
+pub async fn lookup(word: &str, lang: &str) -> Result<Lookup, surf::Error> {
+    let definition_url = format!(
+        "https://api.dictionaryapi.dev/api/v2/entries/{}/{}",
+        lang, word
+    );
+
+    let mut response = http::provider_get(Provider::DictionaryApi, &definition_url)?.await?;
+    let status = response.status();
+    let body_text = response.body_string().await?;
+
+    if status.is_success() {
+        // Attempt to parse the response as a vector of DefinitionResponse (successful case).
+        let parsed = match SINK.lock().unwrap().as_mut() {
+            Some(sink) => read_entries(&body_text, sink),
+            None => serde_json::from_str::<Vec<DefinitionResponse>>(&body_text),
+        };
+        match parsed {
+            Ok(definitions) if definitions.is_empty() => Ok(Lookup::NotFound),
+            Ok(definitions) => Ok(Lookup::Found(definitions)),
+            // If parsing as Vec<DefinitionResponse> failed, it might be an error object
+            // even if the status was 200 OK (less common, but possible for "not found"
+            // if the API returns a 200 with an error payload).
+            Err(e) => Ok(classify_error(status, body_text, Some(e))),
+        }
+    } else {
+        // Handle non-success HTTP status codes (e.g., 404 Not Found, 500 Internal Server Error).
+        // In these cases, the body is often an error object.
+        Ok(classify_error(status, body_text, None))
+    }
+}
+
+/// The definition list in `body`, read an entry at a time with a
+/// [`serde_json::StreamDeserializer`] and each handed to `each` as soon as
+/// it's parsed, until `each` breaks.
+fn read_entries(
+    body: &str,
+    mut each: impl FnMut(&DefinitionResponse) -> ControlFlow<()>,
+) -> Result<Vec<DefinitionResponse>, serde_json::Error> {
+    // Whatever isn't a list of entries is left to the whole-body parse,
+    // which says where it went wrong.
+    let whole = || serde_json::from_str(body);
+    let Some(mut rest) = body.trim_start().strip_prefix('[') else {
+        return whole();
+    };
+    let mut entries = Vec::new();
+    if let Some(after) = rest.trim_start().strip_prefix(']') {
+        return match after.trim().is_empty() {
+            true => Ok(entries),
+            false => whole(),
+        };
+    }
+    loop {
+        let mut stream = serde_json::Deserializer::from_str(rest).into_iter();
+        let entry = match stream.next() {
+            Some(entry) => entry?,
+            None => return whole(),
+        };
+        rest = rest[stream.byte_offset()..].trim_start();
+        let flow = each(&entry);
+        entries.push(entry);
+        if flow.is_break() {
+            return Ok(entries);
+        }
+        match rest.split_at_checked(1) {
+            Some((",", after)) => rest = after,
+            Some(("]", after)) if after.trim().is_empty() => return Ok(entries),
+            _ => return whole(),
+        }
+    }
+}
+
+/// Interprets a body that isn't a definition list as one of the API's error objects.
+/// `original` is the error from the first parse attempt, which is more useful for
+/// debugging than the error-object parse failure when both fail.
+fn classify_error(status: StatusCode, body: String, original: Option<serde_json::Error>) -> Lookup {
+    match serde_json::from_str::<ApiErrorResponse>(&body) {
+        // Check if the API error indicates "No Definitions Found"
+        Ok(api_error) if api_error.title == "No Definitions Found" => Lookup::NotFound,
+        Ok(api_error) => Lookup::ApiError {
+            status,
+            error: api_error,
+        },
+        Err(e) => Lookup::ParseError {
+            status,
+            body,
+            error: original.unwrap_or(e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str) -> String {
+        format!(
+            r#"{{"word": "{}", "phonetic": null, "phonetics": [], "meanings": [], "source_urls": null, "origin": null}}"#,
+            word
+        )
+    }
+
+    fn words(entries: &[DefinitionResponse]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.word.as_str()).collect()
+    }
+
+    #[test]
+    fn reads_each_entry_in_turn() {
+        let body = format!(
+            "[{}, {}\n,{} ]\n",
+            entry("set"),
+            entry("sets"),
+            entry("setting")
+        );
+        let mut seen = Vec::new();
+        let entries = read_entries(&body, |entry| {
+            seen.push(entry.word.clone());
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(words(&entries), ["set", "sets", "setting"]);
+        assert_eq!(seen, ["set", "sets", "setting"]);
+        assert!(read_entries(" [ ] ", |_| ControlFlow::Continue(()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn stops_when_told() {
+        let body = format!("[{}, {}, not even json", entry("set"), entry("sets"));
+        let entries = read_entries(&body, |_| ControlFlow::Break(())).unwrap();
+        assert_eq!(words(&entries), ["set"]);
+    }
+
+    #[test]
+    fn malformed_lists_fail_like_whole_bodies() {
+        let each = |_: &DefinitionResponse| ControlFlow::Continue(());
+        for body in [
+            format!("[{} {}]", entry("set"), entry("sets")),
+            format!("[{},]", entry("set")),
+            format!("[{}] trailing", entry("set")),
+            format!("[{}", entry("set")),
+            String::from(r#"{"title": "No Definitions Found"}"#),
+            String::from("[] []"),
+        ] {
+            assert!(read_entries(&body, each).is_err(), "{}", body);
+        }
+    }
+}
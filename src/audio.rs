@@ -0,0 +1,176 @@
+#[cfg(feature = "tui")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "tui")]
+use std::error::Error;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[cfg(feature = "tui")]
+use crate::http;
+use crate::{config::Config, storage, text};
+
+/// Size the audio cache is kept under unless the config says otherwise.
+const DEFAULT_MAX_MB: u64 = 50;
+
+/// The most the audio cache may take up, from `audio_cache_mb`.
+pub fn max_bytes() -> u64 {
+    Config::load().audio_cache_mb.unwrap_or(DEFAULT_MAX_MB) * 1_000_000
+}
+
+/// A local copy of the recording at `url`, downloaded on first use. Files are
+/// named after a hash of the URL, so the same recording is kept once however
+/// many words link to it; each use marks it recently used for [`prune`].
+#[cfg(feature = "tui")]
+pub async fn fetch(url: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let path = path(url);
+    if path.is_file() {
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(SystemTime::now())?;
+        return Ok(path);
+    }
+    let mut response = http::get(url).await?;
+    if !response.status().is_success() {
+        return Err(format!("{} for {}", response.status(), url).into());
+    }
+    storage::replace_file(&path, &response.body_bytes().await?)?;
+    prune(max_bytes())?;
+    match path.is_file() {
+        true => Ok(path),
+        false => Err(format!("{} is bigger than the audio cache", url).into()),
+    }
+}
+
+/// Deletes the least recently used recordings until the cache takes up at
+/// most `max_bytes`. Returns how many were deleted and the bytes freed.
+pub fn prune(max_bytes: u64) -> io::Result<(usize, u64)> {
+    let dir = dir();
+    // Lookups that finish together would otherwise both count, and try to
+    // delete, the same recordings.
+    storage::with_lock(&dir, || prune_in(&dir, max_bytes))
+}
+
+fn prune_in(dir: &Path, max_bytes: u64) -> io::Result<(usize, u64)> {
+    let mut files = Vec::new();
+    recordings(dir, &mut files)?;
+    files.sort();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    let (mut removed, mut freed) = (0, 0);
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                removed += 1;
+                freed += len;
+            }
+            // Already gone, which is all deleting it was for.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        total -= len;
+    }
+    Ok((removed, freed))
+}
+
+/// Adds the recordings in `dir` to `files`, with when each was last used and
+/// its size, including the ones copied out of each MDict's `.mdd`, which are
+/// kept in a folder per dictionary. Files still being written are left out.
+fn recordings(dir: &Path, files: &mut Vec<(SystemTime, u64, PathBuf)>) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Another process may have pruned it since the folder was read.
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if metadata.is_dir() {
+            recordings(&path, files)?;
+        } else if metadata.is_file() && path.extension().is_none_or(|e| e != "tmp") {
+            files.push((metadata.modified()?, metadata.len(), path));
+        }
+    }
+    Ok(())
+}
+
+/// `cache prune-audio`: shrinks the audio cache to `max_mb`, or to the
+/// configured size.
+pub fn run_prune(max_mb: Option<u64>) -> io::Result<()> {
+    let max_bytes = max_mb.map_or_else(max_bytes, |mb| mb * 1_000_000);
+    let (removed, freed) = prune(max_bytes)?;
+    match removed {
+        0 => println!("The audio cache is under {}.", text::size(max_bytes)),
+        1 => println!("Removed 1 recording, {}.", text::size(freed)),
+        n => println!("Removed {} recordings, {}.", n, text::size(freed)),
+    }
+    Ok(())
+}
+
+fn dir() -> PathBuf {
+    storage::cache_dir().join("audio")
+}
+
+/// Where the recording at `url` is kept, keeping its extension so players
+/// that go by it still can.
+#[cfg(feature = "tui")]
+fn path(url: &str) -> PathBuf {
+    let digest = Sha256::digest(url.as_bytes());
+    let name: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    let extension = Path::new(url.split(['?', '#']).next().unwrap_or(url))
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| {
+            extension.len() <= 4 && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    match extension {
+        Some(extension) => dir().join(format!("{}.{}", name, extension)),
+        None => dir().join(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn prunes_oldest_first_leaving_downloads_under_way() {
+        let dir = std::env::temp_dir().join(format!("sherlock-audio-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let old = SystemTime::now() - Duration::from_secs(60);
+        for (name, modified) in [
+            ("old.mp3", old),
+            ("new.mp3", SystemTime::now()),
+            ("dict/sound.wav", old),
+            ("new.mp3.1.0.tmp", old),
+        ] {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, [0; 10]).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        assert_eq!(prune_in(&dir, 10).unwrap(), (2, 20));
+        assert!(dir.join("new.mp3").is_file());
+        assert!(dir.join("new.mp3.1.0.tmp").is_file());
+        assert!(!dir.join("old.mp3").exists());
+        assert!(!dir.join("dict/sound.wav").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
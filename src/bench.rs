@@ -0,0 +1,142 @@
+// Timings of the paths every lookup goes through, to catch slowdowns before
+// they're released. They're ignored by `cargo test`; run them with
+// `cargo test --release bench -- --ignored --nocapture --test-threads 1`.
+
+use serde_json::json;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    api::{DefinitionResponse, Lookup},
+    index::WordIndex,
+    providers::{Resolved, Settings},
+    sherlock, text,
+};
+
+/// Runs `f` for about a second, after a few runs to warm up, and prints how
+/// long each run took on average.
+fn measure<T>(name: &str, mut f: impl FnMut() -> T) {
+    for _ in 0..3 {
+        black_box(f());
+    }
+    let started = Instant::now();
+    let mut runs = 0;
+    while started.elapsed() < Duration::from_secs(1) {
+        black_box(f());
+        runs += 1;
+    }
+    let per_run = started.elapsed() / runs;
+    eprintln!("{:<28} {:>12.2?} per run, {} runs", name, per_run, runs);
+}
+
+/// An answer the size of dictionaryapi.dev's for "set": a few entries with
+/// hundreds of senses between them, most with an example and synonyms.
+fn set_sized() -> String {
+    let meanings: Vec<_> = ["noun", "verb", "adjective"]
+        .iter()
+        .map(|pos| {
+            let definitions: Vec<_> = (0..50)
+                .map(|i| {
+                    json!({
+                        "definition": format!("(transitive, {}) To put (something) down in sense {}, resting.", pos, i),
+                        "example": format!("Set the {} down over there.", i),
+                        "synonyms": ["place", "put", "lay"],
+                        "antonyms": [],
+                    })
+                })
+                .collect();
+            json!({
+                "partOfSpeech": pos,
+                "definitions": definitions,
+                "synonyms": ["arrange", "fix"],
+                "antonyms": [],
+            })
+        })
+        .collect();
+    let entry = json!({
+        "word": "set",
+        "phonetic": "/sɛt/",
+        "phonetics": [{ "text": "/sɛt/", "audio": "" }],
+        "meanings": meanings,
+        "source_urls": ["https://en.wiktionary.org/wiki/set"],
+        "origin": null,
+    });
+    serde_json::to_string(&vec![entry; 3]).unwrap()
+}
+
+fn entries() -> Vec<DefinitionResponse> {
+    serde_json::from_str(&set_sized()).unwrap()
+}
+
+#[test]
+#[ignore = "a benchmark: cargo test --release bench -- --ignored --nocapture --test-threads 1"]
+fn deserializing() {
+    let body = set_sized();
+    measure("deserializing", || {
+        serde_json::from_str::<Vec<DefinitionResponse>>(&body).unwrap()
+    });
+}
+
+#[test]
+#[ignore = "a benchmark: cargo test --release bench -- --ignored --nocapture --test-threads 1"]
+fn formatting() {
+    let entries = entries();
+    let settings = Settings::default();
+    measure("preparing", || {
+        settings.prepare(entries.clone(), settings.content_filter)
+    });
+    let prepared = settings.prepare(entries.clone(), settings.content_filter);
+    measure("formatting plain", || {
+        text::plain(&prepared, &settings.lang)
+    });
+    measure("formatting oneline", || {
+        text::oneline(&prepared, &settings.lang)
+    });
+    measure("formatting a sherlock tile", || {
+        let resolved = Resolved {
+            query: String::from("set"),
+            word: String::from("set"),
+            sources: Vec::new(),
+            lookup: Lookup::Found(entries.clone()),
+            summary: None,
+            others: Vec::new(),
+            tried: Vec::new(),
+        };
+        sherlock::respond(resolved, &settings)
+    });
+}
+
+#[test]
+#[ignore = "a benchmark: cargo test --release bench -- --ignored --nocapture --test-threads 1"]
+fn removing_parens() {
+    let definitions: Vec<String> = entries()
+        .iter()
+        .flat_map(|entry| &entry.meanings)
+        .flat_map(|meaning| &meaning.definitions)
+        .map(|definition| definition.definition.clone())
+        .collect();
+    measure("remove_parens, 450 senses", || {
+        definitions
+            .iter()
+            .map(|definition| sherlock::remove_parens(definition))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[test]
+#[ignore = "a benchmark: cargo test --release bench -- --ignored --nocapture --test-threads 1"]
+fn index_lookups() {
+    let started = Instant::now();
+    let index = WordIndex::shared();
+    eprintln!(
+        "{:<28} {:>12.2?}",
+        "opening the word index",
+        started.elapsed()
+    );
+    measure("index rank", || index.rank("set"));
+    measure("index suggestions", || index.suggest("se", 5));
+    measure("index fuzzy, 1 edit", || index.fuzzy("recieve", 1));
+    measure("index fuzzy, 2 edits", || index.fuzzy("recieve", 2));
+}
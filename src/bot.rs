@@ -0,0 +1,394 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, BufRead, BufReader, Write},
+    time::{Duration, Instant},
+};
+use surf::{RequestBuilder, StatusCode};
+use tokio::sync::mpsc;
+
+use crate::{api::Lookup, history, http, providers::Settings, spelling, text};
+
+/// How long a Matrix sync waits for new messages. Below the 60 seconds a
+/// request may take by default, so it ends with an answer rather than a
+/// timeout.
+const POLL: Duration = Duration::from_secs(25);
+/// How long the bot waits after a failed sync before trying again.
+const RETRY: Duration = Duration::from_secs(10);
+/// The longest message IRC servers pass on whole is 512 bytes, with the
+/// command, the target and the sender's prefix; the answer gets the rest.
+const IRC_TEXT: usize = 400;
+
+/// Where `bot` answers and how much, from the `bot` section of
+/// `config.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Bot {
+    pub matrix: Option<Matrix>,
+    pub irc: Option<Irc>,
+    /// What a message starts with to ask for a definition: "!define word".
+    pub command: String,
+    /// Lookups each user may ask for in a minute; the bot ignores the rest.
+    pub per_minute: usize,
+}
+impl Default for Bot {
+    fn default() -> Self {
+        Self {
+            matrix: None,
+            irc: None,
+            command: String::from("!define"),
+            per_minute: 5,
+        }
+    }
+}
+
+/// A Matrix account and the room it answers in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Matrix {
+    /// The homeserver's address, e.g. `https://matrix.org`.
+    pub homeserver: String,
+    /// The bot account's access token.
+    pub access_token: String,
+    /// The room's ID or alias, `!abc:matrix.org` or `#words:matrix.org`.
+    pub room: String,
+}
+
+/// An IRC server and the channel the bot joins. The connection is plain
+/// text; TLS needs a bouncer or a tunnel like stunnel in between.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Irc {
+    pub server: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub nick: String,
+    pub channel: String,
+    /// The server's password, e.g. a bouncer's.
+    pub password: Option<String>,
+}
+
+fn default_port() -> u16 {
+    6667
+}
+
+/// The lookups each user asked for in the last minute.
+struct Limiter {
+    per_minute: usize,
+    asked: HashMap<String, VecDeque<Instant>>,
+}
+impl Limiter {
+    fn new(per_minute: usize) -> Self {
+        Self {
+            per_minute,
+            asked: HashMap::new(),
+        }
+    }
+
+    /// Whether `user` may have another lookup now, counting it if so.
+    fn allow(&mut self, user: &str) -> bool {
+        let now = Instant::now();
+        let asked = self.asked.entry(user.to_string()).or_default();
+        while asked
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= Duration::from_secs(60))
+        {
+            asked.pop_front();
+        }
+        if asked.len() >= self.per_minute {
+            return false;
+        }
+        asked.push_back(now);
+        true
+    }
+}
+
+/// `bot`: answers `!define <word>` in the Matrix room and the IRC channel the
+/// config names, with `--oneline`'s line, until it's stopped. Each user may
+/// ask `per_minute` times a minute. The lookups aren't kept in the history.
+pub async fn run(settings: &Settings, bot: &Bot) -> Result<(), Box<dyn std::error::Error>> {
+    if bot.matrix.is_none() && bot.irc.is_none() {
+        eprintln!(
+            "Error: 'bot' needs a Matrix room or an IRC channel in the config's \"bot\" section"
+        );
+        std::process::exit(1);
+    }
+    let matrix = async {
+        if let Some(matrix) = &bot.matrix {
+            self::matrix(settings, bot, matrix).await?;
+        }
+        Ok::<(), Box<dyn std::error::Error>>(())
+    };
+    let irc = async {
+        if let Some(irc) = &bot.irc {
+            self::irc(settings, bot, irc).await?;
+        }
+        Ok(())
+    };
+    tokio::try_join!(matrix, irc)?;
+    Ok(())
+}
+
+/// The word `message` asks about, when it's a command: "" for the command
+/// alone.
+fn asked<'a>(bot: &Bot, message: &'a str) -> Option<&'a str> {
+    let rest = message.strip_prefix(&bot.command)?;
+    match rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        true => Some(rest.trim()),
+        false => None,
+    }
+}
+
+/// The word's line, or why there isn't one.
+async fn answer(settings: &Settings, bot: &Bot, word: &str) -> String {
+    if word.is_empty() {
+        return format!("Usage: {} <word>", bot.command);
+    }
+    let resolved = match settings.resolve(word).await {
+        Ok(resolved) => resolved,
+        Err(e) => return format!("Couldn't look up '{}': {}", word, e),
+    };
+    let line = match (resolved.lookup, &resolved.summary) {
+        (Lookup::Found(entries), _) => text::oneline(
+            &settings.prepare(entries, settings.content_filter),
+            &settings.lang,
+        ),
+        (_, Some(summary)) => Some(summary.oneline()),
+        _ => None,
+    };
+    line.unwrap_or_else(|| {
+        let suggestions = spelling::suggest(word, settings, 3);
+        match suggestions.is_empty() {
+            true => format!("No definition found for '{}'.", word),
+            false => format!(
+                "No definition found for '{}'. Did you mean {}?",
+                word,
+                suggestions.join(", ")
+            ),
+        }
+    })
+}
+
+/// Answers in a Matrix room, over the client-server API: joins it, then
+/// long-polls `/sync` for messages sent after the bot started and replies to
+/// commands with notices, the message type meant for bots.
+async fn matrix(settings: &Settings, bot: &Bot, matrix: &Matrix) -> Result<(), surf::Error> {
+    let api = format!(
+        "{}/_matrix/client/v3",
+        matrix.homeserver.trim_end_matches('/')
+    );
+    let authorized = |request: RequestBuilder| {
+        request.header("Authorization", format!("Bearer {}", matrix.access_token))
+    };
+    let client = http::uncached();
+
+    let whoami: Value = send(authorized(client.get(format!("{}/account/whoami", api)))).await?;
+    let me = whoami["user_id"].as_str().unwrap_or_default().to_string();
+    let joined: Value = send(
+        authorized(client.post(format!("{}/join/{}", api, encode(&matrix.room))))
+            .body_json(&json!({}))?,
+    )
+    .await?;
+    let room = joined["room_id"]
+        .as_str()
+        .unwrap_or(&matrix.room)
+        .to_string();
+    eprintln!("Answering {} in {} as {}", bot.command, matrix.room, me);
+
+    let filter = json!({
+        "room": { "rooms": [room], "timeline": { "types": ["m.room.message"] } },
+        "presence": { "not_types": ["*"] },
+        "account_data": { "not_types": ["*"] },
+    })
+    .to_string();
+    let mut since: Option<String> = None;
+    let mut limiter = Limiter::new(bot.per_minute);
+    let mut sent = 0;
+    loop {
+        let mut url = format!("{}/sync?filter={}", api, encode(&filter));
+        match &since {
+            Some(since) => url.push_str(&format!(
+                "&since={}&timeout={}",
+                encode(since),
+                POLL.as_millis()
+            )),
+            // The first sync only says where "now" is.
+            None => url.push_str("&timeout=0"),
+        }
+        let sync: Value = match send(authorized(client.get(url))).await {
+            Ok(sync) => sync,
+            // A token that's been logged out won't work again.
+            Err(e) if since.is_some() && e.status() != StatusCode::Unauthorized => {
+                eprintln!("Warning: the Matrix sync failed, trying again: {}", e);
+                tokio::time::sleep(RETRY).await;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let first = since.is_none();
+        since = sync["next_batch"].as_str().map(String::from);
+        if first {
+            continue;
+        }
+        let events = sync["rooms"]["join"][&room]["timeline"]["events"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for event in events {
+            let sender = event["sender"].as_str().unwrap_or_default();
+            let body = event["content"]["body"].as_str().unwrap_or_default();
+            if sender == me || event["content"]["msgtype"] != "m.text" {
+                continue;
+            }
+            let Some(word) = asked(bot, body) else {
+                continue;
+            };
+            if !limiter.allow(sender) {
+                eprintln!("Ignored {}, who asked too often", sender);
+                continue;
+            }
+            let answer = answer(settings, bot, word).await;
+            sent += 1;
+            let url = format!(
+                "{}/rooms/{}/send/m.room.message/{}-{}",
+                api,
+                encode(&room),
+                history::now(),
+                sent
+            );
+            let reply = json!({
+                "msgtype": "m.notice",
+                "body": answer,
+                "m.relates_to": { "m.in_reply_to": { "event_id": event["event_id"] } },
+            });
+            if let Err(e) = send(authorized(client.put(url)).body_json(&reply)?).await {
+                eprintln!("Warning: couldn't answer {}: {}", sender, e);
+            }
+        }
+    }
+}
+
+/// `request`'s JSON answer, or the homeserver's error as one.
+async fn send(request: RequestBuilder) -> Result<Value, surf::Error> {
+    let mut response = request.await?;
+    let body: Value = response.body_json().await.unwrap_or_default();
+    match response.status().is_success() {
+        true => Ok(body),
+        false => Err(surf::Error::from_str(
+            response.status(),
+            match body["error"].as_str() {
+                Some(error) => format!("{} ({})", error, body["errcode"].as_str().unwrap_or("?")),
+                None => format!("the homeserver answered {}", response.status()),
+            },
+        )),
+    }
+}
+
+/// Room IDs, aliases and sync tokens go in the path or the query whole.
+fn encode(part: &str) -> String {
+    utf8_percent_encode(part, NON_ALPHANUMERIC).to_string()
+}
+
+/// Answers in an IRC channel and in private messages, until the server
+/// closes the connection.
+async fn irc(settings: &Settings, bot: &Bot, irc: &Irc) -> Result<(), io::Error> {
+    let stream = tokio::net::TcpStream::connect((irc.server.as_str(), irc.port))
+        .await?
+        .into_std()?;
+    stream.set_nonblocking(false)?;
+    let mut writer = stream.try_clone()?;
+    let (sender, mut lines) = mpsc::unbounded_channel();
+    // Reading the socket blocks, so it's kept off the runtime's only thread.
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                // Servers pass on whatever encoding clients send.
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&line).trim_end().to_string();
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut line_out = |line: &str| writer.write_all(format!("{}\r\n", line).as_bytes());
+    if let Some(password) = &irc.password {
+        line_out(&format!("PASS {}", password))?;
+    }
+    let mut nick = irc.nick.clone();
+    line_out(&format!("NICK {}", nick))?;
+    line_out(&format!("USER {} 0 * :{}", nick, env!("CARGO_PKG_NAME")))?;
+
+    let mut limiter = Limiter::new(bot.per_minute);
+    while let Some(line) = lines.recv().await {
+        let (prefix, rest) = match line.strip_prefix(':') {
+            Some(line) => line.split_once(' ').unwrap_or((line, "")),
+            None => ("", line.as_str()),
+        };
+        let (command, params) = rest.split_once(' ').unwrap_or((rest, ""));
+        match command {
+            "PING" => line_out(&format!("PONG {}", params))?,
+            // Registered: the channel can be joined now.
+            "001" => {
+                line_out(&format!("JOIN {}", irc.channel))?;
+                eprintln!("Answering {} in {} as {}", bot.command, irc.channel, nick);
+            }
+            // The nick is taken.
+            "433" => {
+                nick.push('_');
+                line_out(&format!("NICK {}", nick))?;
+            }
+            "PRIVMSG" => {
+                let Some((target, text)) = params.split_once(" :") else {
+                    continue;
+                };
+                let from = prefix.split('!').next().unwrap_or_default();
+                let Some(word) = asked(bot, text) else {
+                    continue;
+                };
+                if !limiter.allow(from) {
+                    eprintln!("Ignored {}, who asked too often", from);
+                    continue;
+                }
+                let answer: String = answer(settings, bot, word)
+                    .await
+                    .chars()
+                    .map(|c| match c {
+                        '\r' | '\n' => ' ',
+                        c => c,
+                    })
+                    .collect();
+                // In the channel the answer is addressed to whoever asked;
+                // a private message is answered privately.
+                let (to, answer) = match target.starts_with(['#', '&']) {
+                    true => (target, format!("{}: {}", from, answer)),
+                    false => (from, answer),
+                };
+                line_out(&format!("PRIVMSG {} :{}", to, truncate(&answer, IRC_TEXT)))?;
+            }
+            _ => {}
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        format!("{} closed the connection", irc.server),
+    ))
+}
+
+/// `text` cut to at most `max` bytes, at a character boundary, with an
+/// ellipsis when anything was cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut end = max - '…'.len_utf8();
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
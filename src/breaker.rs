@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+use surf::{
+    middleware::{Middleware, Next},
+    Client, Request, Response, StatusCode,
+};
+
+use crate::{history, http, providers::Provider, storage};
+
+/// Failures in a row that trip a provider's breaker.
+const THRESHOLD: u32 = 3;
+/// How long a tripped provider is skipped before it's tried again.
+const COOLDOWN_SECS: u64 = 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct State {
+    failures: u32,
+    /// Unix time until which the provider is skipped.
+    open_until: u64,
+}
+
+/// Every provider's breaker, kept in the cache dir since each keystroke in
+/// Sherlock is a new process.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Breakers(HashMap<String, State>);
+impl Breakers {
+    fn path() -> PathBuf {
+        storage::cache_dir().join("breakers.json")
+    }
+
+    fn load() -> Self {
+        storage::load_json(&Self::path())
+    }
+
+    fn save(&self) {
+        // Losing the state only means a dead provider gets tried again.
+        let _ = storage::save_json(&Self::path(), self);
+    }
+}
+
+/// Seconds left before `provider` is tried again, if its breaker is open.
+pub fn open_for(provider: Provider) -> Option<u64> {
+    let now = history::now();
+    let breakers = Breakers::load();
+    let until = breakers.0.get(provider.name())?.open_until;
+    (until > now).then(|| until - now)
+}
+
+/// Counts `provider`'s failures and skips it for a while once it has failed
+/// [`THRESHOLD`] times in a row, so a dead site doesn't hold up every
+/// keystroke until it times out. While it's skipped, its requests are
+/// answered from the response cache or fail at once, and the chain moves on
+/// to the next provider. The first success closes the breaker again.
+pub struct Breaker {
+    pub provider: Provider,
+}
+
+#[surf::utils::async_trait]
+impl Middleware for Breaker {
+    async fn handle(&self, req: Request, client: Client, next: Next<'_>) -> surf::Result<Response> {
+        if let Some(left) = open_for(self.provider) {
+            if let Some(cached) = http::cached(req.url().as_str()) {
+                return Ok(cached);
+            }
+            return Err(surf::Error::from_str(
+                StatusCode::ServiceUnavailable,
+                format!(
+                    "{} failed {} times in a row; trying it again in {}s",
+                    self.provider, THRESHOLD, left
+                ),
+            ));
+        }
+        let result = next.run(req, client).await;
+        let failed = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(_) => true,
+        };
+        // Lookups of other keystrokes count this provider's failures too.
+        storage::with_lock(&Breakers::path(), || {
+            let mut breakers = Breakers::load();
+            let state = breakers
+                .0
+                .entry(self.provider.name().to_string())
+                .or_default();
+            match failed {
+                true => {
+                    state.failures += 1;
+                    if state.failures >= THRESHOLD {
+                        state.open_until = history::now() + COOLDOWN_SECS;
+                    }
+                    breakers.save();
+                }
+                // Saved only when it changes, not on every lookup.
+                false if state.failures > 0 => {
+                    *state = State::default();
+                    breakers.save();
+                }
+                false => {}
+            }
+        });
+        result
+    }
+}
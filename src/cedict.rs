@@ -0,0 +1,602 @@
+use regex::{Captures, Regex};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    config::Config,
+    index::OffsetIndex,
+    offline,
+    providers::Provider,
+    storage::{self, Bytes},
+    text,
+};
+
+/// The names CC-CEDICT goes by in the dictionaries dir: as it's unzipped,
+/// and as `dict install cedict` downloads it.
+const FILES: &[&str] = &["cedict_ts.u8", "cedict_ts.u8.gz"];
+
+/// Entries shown for a pinyin query, which many words share.
+const LIMIT: usize = 5;
+
+/// The colors tones are shown in, first to fifth (neutral), as Pleco has them.
+pub const TONE_COLORS: [&str; 5] = ["#e30000", "#02b31c", "#1510f0", "#8900bf", "#777777"];
+/// The same as ANSI colors: red, green, blue, magenta and grey.
+const ANSI_COLORS: [&str; 5] = ["31", "32", "34", "35", "90"];
+
+/// A word glosses point at, `學|学[xue2]` or `学[xue2]`.
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:[^\s|\[\],]+\|)?([^\s|\[\],]+)\[([^\]]+)\]").unwrap());
+
+/// One line of CC-CEDICT: `學習 学习 [xue2 xi2] /to learn/to study/`.
+struct Line<'a> {
+    traditional: &'a str,
+    simplified: &'a str,
+    /// With tone numbers, `u:` for ü.
+    pinyin: &'a str,
+    glosses: Vec<&'a str>,
+}
+impl<'a> Line<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        if line.starts_with('#') {
+            return None;
+        }
+        let (traditional, rest) = line.split_once(' ')?;
+        let (simplified, rest) = rest.split_once(" [")?;
+        let (pinyin, rest) = rest.split_once("] /")?;
+        let glosses = rest
+            .trim_end()
+            .trim_end_matches('/')
+            .split('/')
+            .filter(|gloss| !gloss.is_empty())
+            .collect();
+        Some(Self {
+            traditional,
+            simplified,
+            pinyin,
+            glosses,
+        })
+    }
+
+    /// The pinyin without spaces, lower-cased, with `v` for ü and tone
+    /// numbers only when `tones`, to compare typed pinyin with.
+    fn key(&self, tones: bool) -> String {
+        pinyin_key(self.pinyin, tones)
+    }
+}
+
+/// Where CC-CEDICT is installed, if it is.
+fn path() -> Option<PathBuf> {
+    let dir = offline::dictionaries_dir();
+    FILES
+        .iter()
+        .map(|file| dir.join(file))
+        .find(|path| path.exists())
+}
+
+/// CC-CEDICT's text and an index of where each word's lines are in it, by
+/// characters and by pinyin with and without tones.
+struct Cedict {
+    text: Bytes,
+    index: OffsetIndex,
+}
+
+/// The dictionary mapped, and unzipped and indexed into the cache the first
+/// time, once per run.
+fn dictionary() -> io::Result<&'static Cedict> {
+    static CEDICT: OnceLock<Result<Cedict, String>> = OnceLock::new();
+    let cedict = CEDICT.get_or_init(|| {
+        let path = path().ok_or_else(|| {
+            format!(
+                "CC-CEDICT isn't installed; get it with 'dict install cedict' or put {} in {}",
+                FILES[0],
+                offline::dictionaries_dir().display()
+            )
+        })?;
+        open(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))
+    });
+    cedict.as_ref().map_err(|e| io::Error::other(e.clone()))
+}
+
+fn open(path: &Path) -> io::Result<Cedict> {
+    let sources = [path.to_path_buf()];
+    let dir = storage::cache_dir().join("dictionaries");
+    let text = match path.extension().is_some_and(|e| e == "gz") {
+        true => storage::cached(&dir.join(FILES[0]), &sources, || gunzip(&fs::read(path)?))?,
+        false => Bytes::Mapped(storage::map_file(path)?),
+    };
+    // Named after the version, like the word index.
+    let index = format!("{}.{}.fst", FILES[0], env!("CARGO_PKG_VERSION"));
+    let index = storage::cached(&dir.join(index), &sources, || {
+        Ok(OffsetIndex::build(keys(text.as_ref())))
+    })?;
+    Ok(Cedict {
+        text,
+        index: OffsetIndex::new(index)?,
+    })
+}
+
+/// The keys each line of `text` is found by, and where the line starts.
+fn keys(text: &[u8]) -> Vec<(String, u64)> {
+    let mut keys = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive(|&b| b == b'\n') {
+        let start = offset as u64;
+        offset += line.len();
+        let Some(line) = std::str::from_utf8(line).ok().and_then(Line::parse) else {
+            continue;
+        };
+        keys.push((characters_key(line.simplified), start));
+        keys.push((characters_key(line.traditional), start));
+        keys.push((pinyin_index_key(&line.key(false), false), start));
+        keys.push((pinyin_index_key(&line.key(true), true), start));
+    }
+    keys
+}
+
+fn characters_key(characters: &str) -> String {
+    format!("c{}", characters)
+}
+
+/// Pinyin keys are kept apart from each other and from characters, so `xue`
+/// typed without tones doesn't match `xue2`'s key.
+fn pinyin_index_key(key: &str, tones: bool) -> String {
+    match tones {
+        true => format!("t{}", key),
+        false => format!("p{}", key),
+    }
+}
+
+impl Cedict {
+    /// The lines with `key`, in the order the dictionary has them.
+    fn lines(&self, key: &str) -> impl Iterator<Item = Line<'_>> {
+        let text = self.text.as_ref();
+        self.index.get(key).into_iter().filter_map(move |start| {
+            let line = text.get(start as usize..)?;
+            let end = line.iter().position(|&b| b == b'\n').unwrap_or(line.len());
+            Line::parse(std::str::from_utf8(&line[..end]).ok()?)
+        })
+    }
+}
+
+#[cfg(feature = "compression")]
+fn gunzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "compression"))]
+fn gunzip(_: &[u8]) -> io::Result<Vec<u8>> {
+    Err(offline::without_compression("cedict_ts.u8.gz files"))
+}
+
+/// Chinese words from CC-CEDICT, looked up in simplified or traditional
+/// characters, or in pinyin with or without tone numbers: `学习`, `xue2xi2`
+/// or `xuexi`.
+pub fn lookup(word: &str) -> io::Result<Lookup> {
+    let dictionary = dictionary()?;
+    let word = word.trim();
+    let entries: Vec<DefinitionResponse> = match word.chars().any(text::is_ideograph) {
+        true => dictionary.lines(&characters_key(word)).map(entry).collect(),
+        false => {
+            let tones = word.chars().any(|c| c.is_ascii_digit());
+            let query = pinyin_key(&fold_tone_marks(word), tones);
+            dictionary
+                .lines(&pinyin_index_key(&query, tones))
+                .take(LIMIT)
+                .map(entry)
+                .collect()
+        }
+    };
+    Ok(match entries.is_empty() {
+        true => Lookup::NotFound,
+        false => Lookup::Found(entries),
+    })
+}
+
+fn entry(line: Line) -> DefinitionResponse {
+    let pinyin = marked(line.pinyin);
+    let zhuyin = zhuyin(line.pinyin);
+    let definitions = line
+        .glosses
+        .iter()
+        .map(|gloss| Definition {
+            definition: gloss_text(gloss),
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        })
+        .collect();
+    let forms = (line.traditional != line.simplified)
+        .then(|| vec![format!("{} (traditional)", line.traditional)]);
+    // The reading the config prefers is the entry's; both are listed.
+    let reading = match (Config::load().zhuyin, &zhuyin) {
+        (true, Some(zhuyin)) => zhuyin.clone(),
+        _ => pinyin.clone(),
+    };
+    DefinitionResponse {
+        word: line.simplified.to_string(),
+        phonetic: Some(reading),
+        phonetics: [Some(pinyin), zhuyin]
+            .into_iter()
+            .flatten()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings: vec![Meaning {
+            part_of_speech: String::from("word"),
+            definitions,
+            synonyms: None,
+            antonyms: None,
+            forms,
+            gender: None,
+        }],
+        source_urls: Some(vec![format!(
+            "https://www.mdbg.net/chinese/dictionary?wdqb={}",
+            line.simplified
+        )]),
+        origin: None,
+        provider: None,
+    }
+}
+
+/// A gloss as it reads: `CL:個|个[ge4]` is `measure word: 个 (gè)`, and
+/// words it points at are written in simplified characters and marked pinyin.
+fn gloss_text(gloss: &str) -> String {
+    let gloss = match gloss.strip_prefix("CL:") {
+        Some(classifiers) => format!("measure word: {}", classifiers.replace(',', ", ")),
+        None => gloss.to_string(),
+    };
+    REFERENCE
+        .replace_all(&gloss, |caps: &Captures| {
+            format!("{} ({})", &caps[1], marked(&caps[2]))
+        })
+        .into_owned()
+}
+
+fn pinyin_key(pinyin: &str, tones: bool) -> String {
+    pinyin
+        .to_lowercase()
+        .replace("u:", "v")
+        .replace('ü', "v")
+        .chars()
+        .filter(|c| c.is_alphanumeric() && (tones || !c.is_ascii_digit()))
+        .collect()
+}
+
+/// What each tone mark goes on, and the vowel it makes for tones one to four.
+const MARKS: &[(char, [char; 4])] = &[
+    ('a', ['ā', 'á', 'ǎ', 'à']),
+    ('e', ['ē', 'é', 'ě', 'è']),
+    ('i', ['ī', 'í', 'ǐ', 'ì']),
+    ('o', ['ō', 'ó', 'ǒ', 'ò']),
+    ('u', ['ū', 'ú', 'ǔ', 'ù']),
+    ('ü', ['ǖ', 'ǘ', 'ǚ', 'ǜ']),
+    ('A', ['Ā', 'Á', 'Ǎ', 'À']),
+    ('E', ['Ē', 'É', 'Ě', 'È']),
+    ('I', ['Ī', 'Í', 'Ǐ', 'Ì']),
+    ('O', ['Ō', 'Ó', 'Ǒ', 'Ò']),
+    ('U', ['Ū', 'Ú', 'Ǔ', 'Ù']),
+    ('Ü', ['Ǖ', 'Ǘ', 'Ǚ', 'Ǜ']),
+];
+
+/// Typed pinyin with its tone marks taken off: `xuéxí` is `xuexi`.
+fn fold_tone_marks(pinyin: &str) -> String {
+    pinyin
+        .chars()
+        .map(|c| {
+            MARKS
+                .iter()
+                .find(|(_, marked)| marked.contains(&c))
+                .map_or(c, |(vowel, _)| *vowel)
+        })
+        .collect()
+}
+
+/// A syllable without its tone number, and the tone: 5 for the neutral one,
+/// and for syllables written without a number.
+fn split_tone(syllable: &str) -> (&str, usize) {
+    match syllable.chars().last().and_then(|c| c.to_digit(10)) {
+        Some(tone @ 1..=5) => (&syllable[..syllable.len() - 1], tone as usize),
+        _ => (syllable, 5),
+    }
+}
+
+/// CC-CEDICT's numbered pinyin with tone marks: `xue2 xi2` is `xué xí`.
+fn marked(pinyin: &str) -> String {
+    let syllables: Vec<String> = pinyin
+        .split(' ')
+        .map(|syllable| {
+            let (body, tone) = split_tone(syllable);
+            let body = body.replace("u:", "ü").replace("U:", "Ü");
+            if tone == 5 {
+                return body;
+            }
+            // On a or e, on the o of ou, and else on the last vowel.
+            let vowels: Vec<(usize, char)> = body
+                .char_indices()
+                .filter(|(_, c)| MARKS.iter().any(|(vowel, _)| vowel == c))
+                .collect();
+            let target = vowels
+                .iter()
+                .find(|(_, c)| matches!(c, 'a' | 'e' | 'A' | 'E'))
+                .or_else(|| {
+                    vowels.iter().find(|(i, c)| {
+                        matches!(c, 'o' | 'O') && body[i + 1..].starts_with(['u', 'U'])
+                    })
+                })
+                .or(vowels.last());
+            match target {
+                Some(&(i, vowel)) => {
+                    let (_, marks) = MARKS.iter().find(|(v, _)| *v == vowel).unwrap();
+                    let mut marked = body.clone();
+                    marked.replace_range(i..i + vowel.len_utf8(), &marks[tone - 1].to_string());
+                    marked
+                }
+                None => body,
+            }
+        })
+        .collect();
+    syllables.join(" ")
+}
+
+const INITIALS: &[(&str, &str)] = &[
+    ("zh", "ㄓ"),
+    ("ch", "ㄔ"),
+    ("sh", "ㄕ"),
+    ("b", "ㄅ"),
+    ("p", "ㄆ"),
+    ("m", "ㄇ"),
+    ("f", "ㄈ"),
+    ("d", "ㄉ"),
+    ("t", "ㄊ"),
+    ("n", "ㄋ"),
+    ("l", "ㄌ"),
+    ("g", "ㄍ"),
+    ("k", "ㄎ"),
+    ("h", "ㄏ"),
+    ("j", "ㄐ"),
+    ("q", "ㄑ"),
+    ("x", "ㄒ"),
+    ("r", "ㄖ"),
+    ("z", "ㄗ"),
+    ("c", "ㄘ"),
+    ("s", "ㄙ"),
+];
+
+/// Finals as they're spelled after an initial, with `y` and `w` spellings
+/// already turned into `i`, `u` and `ü`.
+const FINALS: &[(&str, &str)] = &[
+    ("", ""),
+    ("a", "ㄚ"),
+    ("o", "ㄛ"),
+    ("e", "ㄜ"),
+    ("ê", "ㄝ"),
+    ("ai", "ㄞ"),
+    ("ei", "ㄟ"),
+    ("ao", "ㄠ"),
+    ("ou", "ㄡ"),
+    ("an", "ㄢ"),
+    ("en", "ㄣ"),
+    ("ang", "ㄤ"),
+    ("eng", "ㄥ"),
+    ("ong", "ㄨㄥ"),
+    ("er", "ㄦ"),
+    ("i", "ㄧ"),
+    ("ia", "ㄧㄚ"),
+    ("io", "ㄧㄛ"),
+    ("ie", "ㄧㄝ"),
+    ("iai", "ㄧㄞ"),
+    ("iao", "ㄧㄠ"),
+    ("iou", "ㄧㄡ"),
+    ("ian", "ㄧㄢ"),
+    ("in", "ㄧㄣ"),
+    ("iang", "ㄧㄤ"),
+    ("ing", "ㄧㄥ"),
+    ("iong", "ㄩㄥ"),
+    ("u", "ㄨ"),
+    ("ua", "ㄨㄚ"),
+    ("uo", "ㄨㄛ"),
+    ("uai", "ㄨㄞ"),
+    ("uei", "ㄨㄟ"),
+    ("uan", "ㄨㄢ"),
+    ("uen", "ㄨㄣ"),
+    ("uang", "ㄨㄤ"),
+    ("ueng", "ㄨㄥ"),
+    ("ü", "ㄩ"),
+    ("üe", "ㄩㄝ"),
+    ("üan", "ㄩㄢ"),
+    ("ün", "ㄩㄣ"),
+];
+
+/// CC-CEDICT's numbered pinyin in zhuyin (bopomofo): `xue2 xi2` is `ㄒㄩㄝˊ
+/// ㄒㄧˊ`. `None` for syllables zhuyin has no spelling for, such as the
+/// Latin letters in `AA制`.
+fn zhuyin(pinyin: &str) -> Option<String> {
+    let mut syllables = Vec::new();
+    for syllable in pinyin.split(' ') {
+        let (body, tone) = split_tone(syllable);
+        let body = body.to_lowercase().replace("u:", "ü");
+        if !body.chars().any(char::is_alphabetic) {
+            syllables.push(body);
+            continue;
+        }
+        // The 儿 of erhua, which has no tone of its own.
+        if body == "r" {
+            syllables.push(String::from("ㄦ"));
+            continue;
+        }
+        let (initial, rest) = INITIALS
+            .iter()
+            .find(|(spelling, _)| body.starts_with(spelling))
+            .map_or(("", body.as_str()), |(spelling, zhuyin)| {
+                (*zhuyin, &body[spelling.len()..])
+            });
+        let rest = match initial {
+            "" => zero_initial(rest),
+            // After j, q and x, u is ü.
+            "ㄐ" | "ㄑ" | "ㄒ" if rest.starts_with('u') => format!("ü{}", &rest[1..]),
+            // zhi, chi, shi, ri, zi, ci and si have no vowel of their own.
+            "ㄓ" | "ㄔ" | "ㄕ" | "ㄖ" | "ㄗ" | "ㄘ" | "ㄙ" if rest == "i" => String::new(),
+            _ => rest.to_string(),
+        };
+        // Spelled short in pinyin: liu is liou, gui guei and lun luen.
+        let rest = match rest.as_str() {
+            "iu" => "iou",
+            "ui" => "uei",
+            "un" => "uen",
+            rest => rest,
+        };
+        let (_, final_) = FINALS.iter().find(|(spelling, _)| *spelling == rest)?;
+        let spelled = format!("{}{}", initial, final_);
+        syllables.push(match tone {
+            2 => format!("{}ˊ", spelled),
+            3 => format!("{}ˇ", spelled),
+            4 => format!("{}ˋ", spelled),
+            5 => format!("˙{}", spelled),
+            _ => spelled,
+        });
+    }
+    Some(syllables.join(" "))
+}
+
+/// A final spelled without an initial, as it would be after one: `yue` is
+/// `üe`, `wei` is `uei` and `you` is `iou`.
+fn zero_initial(rest: &str) -> String {
+    if let Some(after) = rest.strip_prefix("yu") {
+        format!("ü{}", after)
+    } else if let Some(after) = rest.strip_prefix("yi") {
+        format!("i{}", after)
+    } else if let Some(after) = rest.strip_prefix('y') {
+        format!("i{}", after)
+    } else if let Some(after) = rest.strip_prefix("wu") {
+        format!("u{}", after)
+    } else if let Some(after) = rest.strip_prefix('w') {
+        format!("u{}", after)
+    } else {
+        rest.to_string()
+    }
+}
+
+/// The tone of a syllable in marked pinyin or zhuyin, 5 for the neutral one.
+fn tone(syllable: &str) -> usize {
+    for c in syllable.chars() {
+        match c {
+            'ˊ' => return 2,
+            'ˇ' => return 3,
+            'ˋ' => return 4,
+            '˙' => return 5,
+            _ => {}
+        }
+        if let Some((_, marks)) = MARKS.iter().find(|(_, marks)| marks.contains(&c)) {
+            return marks.iter().position(|m| *m == c).unwrap() + 1;
+        }
+    }
+    // Unmarked zhuyin is the first tone; unmarked pinyin the neutral one.
+    match syllable
+        .chars()
+        .any(|c| ('\u{3105}'..='\u{312F}').contains(&c))
+    {
+        true => 1,
+        false => 5,
+    }
+}
+
+/// Whether `entry` came from CC-CEDICT, whose readings get tone colors.
+pub fn is_from(entry: &DefinitionResponse) -> bool {
+    entry.provider == Some(Provider::Cedict)
+}
+
+/// The syllables of a reading and their tones, when the config's
+/// `tone_colors` asks for them.
+pub fn tones(reading: &str) -> Option<Vec<(&str, usize)>> {
+    Config::load().tone_colors.then(|| {
+        reading
+            .split(' ')
+            .map(|syllable| (syllable, tone(syllable)))
+            .collect()
+    })
+}
+
+/// `reading` with each syllable in its tone's color, as Pango markup.
+pub fn pango(reading: &str) -> String {
+    match tones(reading) {
+        Some(syllables) => syllables
+            .iter()
+            .map(|(syllable, tone)| {
+                format!(
+                    "<span foreground=\"{}\">{}</span>",
+                    TONE_COLORS[tone - 1],
+                    syllable
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => reading.to_string(),
+    }
+}
+
+/// `reading` with each syllable in its tone's color, when it's printed to a
+/// terminal.
+pub fn ansi(reading: &str) -> String {
+    if !io::stdout().is_terminal() {
+        return reading.to_string();
+    }
+    match tones(reading) {
+        Some(syllables) => syllables
+            .iter()
+            .map(|(syllable, tone)| format!("\x1b[{}m{}\x1b[0m", ANSI_COLORS[tone - 1], syllable))
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => reading.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_lines_by_characters_and_pinyin() {
+        let text = "\
+# CC-CEDICT
+學習 学习 [xue2 xi2] /to learn/to study/
+學 学 [xue2] /to learn/
+雪 雪 [xue3] /snow/
+"
+        .as_bytes()
+        .to_vec();
+        let index = OffsetIndex::build(keys(&text));
+        let cedict = Cedict {
+            text: Bytes::Built(text),
+            index: OffsetIndex::new(Bytes::Built(index)).unwrap(),
+        };
+        let glosses = |key: &str| -> Vec<String> {
+            cedict
+                .lines(key)
+                .map(|line| line.glosses.join("/"))
+                .collect()
+        };
+        assert_eq!(glosses(&characters_key("學習")), ["to learn/to study"]);
+        assert_eq!(glosses(&characters_key("学")), ["to learn"]);
+        assert_eq!(
+            glosses(&pinyin_index_key("xue", false)),
+            ["to learn", "snow"]
+        );
+        assert_eq!(glosses(&pinyin_index_key("xue3", true)), ["snow"]);
+        assert!(glosses(&pinyin_index_key("xue", true)).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::{api::DefinitionResponse, wordlist::WordList};
+
+/// Common European Framework of Reference levels, easiest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Level {
+    A1,
+    A2,
+    B1,
+    B2,
+    C1,
+    C2,
+}
+impl Level {
+    pub const ALL: [Level; 6] = [
+        Level::A1,
+        Level::A2,
+        Level::B1,
+        Level::B2,
+        Level::C1,
+        Level::C2,
+    ];
+
+    /// The level a learner typically knows a word of frequency rank `rank` at.
+    /// Unranked words count as the hardest.
+    fn for_rank(rank: Option<usize>) -> Self {
+        match rank {
+            Some(0..500) => Level::A1,
+            Some(500..1000) => Level::A2,
+            Some(1000..2000) => Level::B1,
+            Some(2000..4000) => Level::B2,
+            Some(4000..8000) => Level::C1,
+            _ => Level::C2,
+        }
+    }
+}
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown CEFR level '{}' (expected A1 to C2)", s))
+    }
+}
+
+/// Rank of `word` or of its stem for regular inflections ("running" → "run").
+fn rank(words: &WordList, word: &str) -> Option<usize> {
+    if let Some(rank) = words.rank(word) {
+        return Some(rank);
+    }
+    ["s", "es", "ed", "d", "ing", "ly", "er", "est"]
+        .iter()
+        .filter_map(|suffix| word.strip_suffix(suffix))
+        .flat_map(|stem| {
+            let mut candidates = vec![stem.to_string(), format!("{}e", stem)];
+            // Doubled consonants: "running" → "runn" → "run".
+            let mut last = stem.chars().rev();
+            if let (Some(a), Some(b)) = (last.next(), last.next()) {
+                if a == b && stem.len() > 2 {
+                    candidates.push(stem[..stem.len() - a.len_utf8()].to_string());
+                }
+            }
+            candidates
+        })
+        .filter_map(|stem| words.rank(&stem))
+        .min()
+}
+
+/// Estimates how hard a definition is to read from how rare its words are: the
+/// level of the word at the 80th percentile of rarity, so one unusual word
+/// doesn't sink an otherwise plain sentence.
+pub fn grade(text: &str, words: &WordList) -> Level {
+    let mut levels: Vec<Level> = text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|w| w.len() > 2)
+        .map(|w| Level::for_rank(rank(words, &w.to_lowercase())))
+        .collect();
+    if levels.is_empty() {
+        return Level::A1;
+    }
+    levels.sort_unstable();
+    levels[(levels.len() - 1) * 4 / 5]
+}
+
+/// Tags every sense with its level, dropping senses above `max`.
+pub fn annotate(entries: &mut Vec<DefinitionResponse>, words: &WordList, max: Option<Level>) {
+    for entry in entries.iter_mut() {
+        for meaning in &mut entry.meanings {
+            for def in &mut meaning.definitions {
+                def.level = Some(grade(&def.definition, words));
+            }
+            if let Some(max) = max {
+                meaning.definitions.retain(|def| def.level <= Some(max));
+            }
+        }
+        entry.meanings.retain(|m| !m.definitions.is_empty());
+    }
+    entries.retain(|e| !e.meanings.is_empty());
+}
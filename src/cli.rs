@@ -0,0 +1,618 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::{
+    export, i18n, known, labels, packs,
+    providers::{Provider, Settings},
+    schema,
+};
+
+pub const USAGE: &str = "\
+Usage: sherlock-dictionary [options] <word_to_define>
+       sherlock-dictionary [options] forms <word>
+       sherlock-dictionary [options] compare <word> <word>
+       sherlock-dictionary [options] family <word>
+       sherlock-dictionary [options] etymology <word>
+       sherlock-dictionary [options] homophones <word>
+       sherlock-dictionary [options] rhymes <word>
+       sherlock-dictionary [options] meter \"<line>\"
+       sherlock-dictionary [options] syllables \"<text>\" [json]
+       sherlock-dictionary [options] cognates <word> [--lang de,fr,es]
+       sherlock-dictionary [options] translations <word> [--to fr,de]
+       sherlock-dictionary [options] examples <word> [--to fr]
+       sherlock-dictionary [options] concordance <word> [--corpus <dir>]
+       sherlock-dictionary [options] gloss \"<sentence>\"
+       sherlock-dictionary [options] analyze <file> [--top <n>]
+       sherlock-dictionary -- [options] hardwords [--rarer-than <n>] < <file>
+       sherlock-dictionary -- [options] quiz
+       sherlock-dictionary -- [options] stats [waybar]
+       sherlock-dictionary export ics > reviews.ics
+       sherlock-dictionary schema sherlock|entries
+       sherlock-dictionary -- sync
+       sherlock-dictionary -- [options] daemon [--idle-timeout <minutes>]
+       sherlock-dictionary -- [options] install-service
+       sherlock-dictionary -- [options] mcp
+       sherlock-dictionary -- [options] bot
+       sherlock-dictionary -- [options] wotd [publish]
+       sherlock-dictionary -- encrypt
+       sherlock-dictionary history redact <word>
+       sherlock-dictionary cache prune-audio [<MB>]
+       sherlock-dictionary -- [options] doctor [json]
+       sherlock-dictionary config check
+       sherlock-dictionary -- [options] tui
+       sherlock-dictionary dict list|install <pack>|remove <pack>
+       sherlock-dictionary known list|add <word>...|remove <word>...|import <file>
+       sherlock-dictionary [options] --interactive
+       sherlock-dictionary [options] --lsp
+       sherlock-dictionary [options] --msgpack-rpc
+
+In the sherlock format a lone word is always looked up, even one that names a
+subcommand; a leading '--' runs the subcommand instead.
+
+Options:
+  -i, --interactive      Define words typed at a prompt
+      --lsp              Serve definitions of the word under the cursor to editors,
+                         as a language server on stdin and stdout
+      --msgpack-rpc      Answer Neovim's MessagePack-RPC requests on stdin and stdout
+  -f, --format <fmt>     Output format: sherlock (default), plain, oneline or sexp
+      --oneline          Print \"word /IPA/ (pos): definition\" instead of a Sherlock tile
+      --protocol <ver>   Sherlock's pipe format: sherlock-v1 (default), one tile, or
+                         sherlock-v2, an array of tiles
+  -l, --lang <code>      Dictionary language (default: en)
+      --ui-lang <code>   Language of labels and messages: en, de, es or fr
+                         (default: the locale's)
+  -p, --provider <list>  Where to look words up, comma-separated to fall back
+                         from one to the next (default: dictionaryapi)
+      --simple           Simple English: 3 common senses, no jargon, easy pronunciation
+      --cefr             Tag each sense with an estimated CEFR level
+      --max-level <lvl>  Hide senses harder than a CEFR level (A1 to C2)
+      --exclude-labels <list>
+                         Hide senses with these usage labels, e.g. archaic,vulgar
+      --accent <accent>  Pronunciation to show first: uk, us or au
+      --corpus <dir>     Text files for 'concordance' to search
+      --private          Don't keep this lookup in the history or caches
+      --ipv4-only        Connect over IPv4 only, where IPv6 is broken
+      --exact            Look the query up as typed: no modifiers, dictionary forms,
+                         other spellings, suggestions or Wikipedia
+      --cached           Answer from the response cache, however old, without
+                         going online
+      --profile <name>   Use one of the config's profiles
+      --domain <name>    Look in a specialty glossary first: medical, legal or technical
+      --to <list>        Languages for 'translations' and 'examples', comma-separated
+      --top <n>          How many rare words 'analyze' lists (default: 10)
+      --rarer-than <n>   Words 'hardwords' lists are rarer than the n most common
+                         (default: 'vocabulary' from the config, or 3000)
+      --idle-timeout <minutes>
+                         How long a 'daemon' systemd started waits for a lookup
+                         before it exits (default: 'idle_minutes', or 30)";
+
+#[derive(Debug)]
+pub enum Command {
+    /// Print a single word's definition in the chosen [`Format`].
+    Define(String),
+    /// Inflection and conjugation tables.
+    Forms(String),
+    /// Words derived from the same base.
+    Family(String),
+    /// Where a word came from, as a tree of ancestor forms.
+    Etymology(String),
+    /// Words pronounced the same, and those one sound away.
+    Homophones(String),
+    /// Perfect and slant rhymes, scored.
+    Rhymes(String),
+    /// A line's stress pattern and the meter it fits.
+    Meter(String),
+    /// Syllables per word, per line and in all, as JSON when `true`.
+    Syllables(String, bool),
+    /// Related words in other languages, limited to the given language codes
+    /// unless there are none.
+    Cognates(String, Vec<String>),
+    /// Wiktionary's translation tables, limited to the given language codes
+    /// unless there are none.
+    Translations(String, Vec<String>),
+    /// Real sentences that use the word, with translations into the given
+    /// language codes.
+    Examples(String, Vec<String>),
+    /// Uses of the word in the user's own text files.
+    Concordance(String),
+    /// A short definition of every uncommon word in a sentence.
+    Gloss(String),
+    /// Readability scores for a text file and its rarest words.
+    Analyze(PathBuf, usize),
+    /// The rare words in the text on stdin, those rarer than the given
+    /// frequency rank.
+    HardWords(usize),
+    /// Two words' senses side by side.
+    Compare(String, String),
+    /// Review the bookmarked words that are due, or score the answer to a
+    /// question: the word asked about and the guess.
+    Quiz(Option<(String, String)>),
+    /// Lookup, quiz and cache statistics; as waybar's JSON when true.
+    Stats(bool),
+    /// The review schedule in a format other programs read.
+    Export(export::Kind),
+    /// A JSON Schema of one of the JSON outputs.
+    Schema(schema::Kind),
+    /// Merge the user's words with other machines through `sync_dir`.
+    Sync,
+    /// Keep the most looked-up words fresh in the cache, exiting after the
+    /// given minutes without a lookup when systemd started it.
+    Daemon(Option<u64>),
+    /// Write a systemd user service for `daemon`, started on demand.
+    InstallService,
+    /// Dictionary tools for local assistants, over the Model Context Protocol.
+    Mcp,
+    /// Answer `!define` in the configured Matrix room and IRC channel.
+    Bot,
+    /// The word of the day, looked up; with `true`, added to the configured
+    /// feed and mailed instead.
+    Wotd(bool),
+    /// Remove every trace of a word from the history and caches.
+    Redact(String),
+    /// Re-save the private files with the current encryption settings.
+    Encrypt,
+    /// Shrink the audio cache to the given size in MB, or the configured one.
+    PruneAudio(Option<u64>),
+    /// Check the config, providers, dictionaries and caches; as JSON when true.
+    Doctor(bool),
+    /// List the mistakes in the config file.
+    ConfigCheck,
+    /// No query in Sherlock: a reminder of due reviews.
+    Idle,
+    /// Manage downloadable offline dictionaries.
+    Dict(packs::Action),
+    /// Manage the list of words the user already knows.
+    Known(known::Action),
+    Tui,
+    Interactive,
+    /// Hover definitions for editors, over the Language Server Protocol.
+    Lsp,
+    /// Structured definitions for Neovim plugins, over MessagePack-RPC.
+    MsgpackRpc,
+}
+
+/// How `Define` prints its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// JSON for Sherlock's `bulk_text` launcher.
+    Sherlock,
+    /// Human-readable text for terminals.
+    Plain,
+    /// A single plain line for prompts, status bars and bots.
+    Oneline,
+    /// Lisp data Emacs can `read`. Only definitions and their synonyms and
+    /// antonyms have it; other commands print plain text.
+    Sexp,
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub format: Format,
+    pub settings: Settings,
+    /// Time the parts of a lookup and print them to stderr.
+    pub bench: bool,
+}
+
+/// Parses the arguments after the program name on top of `settings` and the
+/// `format` from the config file. Errors are meant to be shown to the user
+/// alongside [`USAGE`].
+pub fn parse(
+    args: impl IntoIterator<Item = String>,
+    mut settings: Settings,
+    mut format: Format,
+) -> Result<Cli, String> {
+    let mut interactive = false;
+    let mut lsp = false;
+    let mut msgpack_rpc = false;
+    let mut bench = false;
+    let mut to = Vec::new();
+    let mut top = 10;
+    let mut rarer_than = None;
+    let mut idle_timeout = None;
+    let mut positional: Vec<String> = Vec::new();
+
+    let mut args = args.into_iter().peekable();
+    // What Sherlock passes is a word to look up, even "daemon" or "quiz", so
+    // there a lone name only runs its subcommand after a leading `--`.
+    let marked = args.next_if(|arg| arg == "--").is_some();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--interactive" => interactive = true,
+            "--lsp" => lsp = true,
+            "--msgpack-rpc" => msgpack_rpc = true,
+            "-f" | "--format" => {
+                format = match value(&mut args, &arg)?.as_str() {
+                    "sherlock" => Format::Sherlock,
+                    "plain" => Format::Plain,
+                    "oneline" => Format::Oneline,
+                    "sexp" => Format::Sexp,
+                    other => return Err(format!("Unknown format '{}'", other)),
+                }
+            }
+            "--oneline" => format = Format::Oneline,
+            "--simple" => settings.simple = true,
+            "--cefr" => settings.cefr = true,
+            "--private" => settings.private = true,
+            "--ipv4-only" => settings.ipv4_only = true,
+            "--cached" => settings.cached = true,
+            "--exact" => settings.exact = true,
+            // Left out of the usage: it's for measuring, not for lookups.
+            "--bench-lookup" => bench = true,
+            // Read by [`profile`] before the config was loaded.
+            "--profile" => {
+                value(&mut args, &arg)?;
+            }
+            "--max-level" => settings.max_level = Some(value(&mut args, &arg)?.parse()?),
+            "--exclude-labels" => {
+                settings.exclude_labels = labels::parse_list(&value(&mut args, &arg)?)?;
+            }
+            "--accent" => settings.accent = Some(value(&mut args, &arg)?.parse()?),
+            "--protocol" => settings.protocol = value(&mut args, &arg)?.parse()?,
+            "-l" | "--lang" => settings.lang = value(&mut args, &arg)?,
+            "--ui-lang" => {
+                let ui_lang = value(&mut args, &arg)?;
+                let code = i18n::language(&ui_lang).ok_or_else(|| {
+                    format!(
+                        "There's no translation for '{}'; there are {}",
+                        ui_lang,
+                        i18n::languages().join(", ")
+                    )
+                })?;
+                settings.ui_lang = Some(code.to_string());
+            }
+            "--corpus" => settings.corpus = Some(value(&mut args, &arg)?.into()),
+            "--domain" => settings.domain = Some(value(&mut args, &arg)?.parse()?),
+            "--to" => to = languages(&value(&mut args, &arg)?),
+            "--top" => {
+                top = value(&mut args, &arg)?
+                    .parse()
+                    .map_err(|_| String::from("'--top' needs a number"))?;
+            }
+            "--rarer-than" => {
+                rarer_than = Some(
+                    value(&mut args, &arg)?
+                        .parse()
+                        .map_err(|_| String::from("'--rarer-than' needs a number"))?,
+                );
+            }
+            "--idle-timeout" => {
+                idle_timeout = Some(
+                    value(&mut args, &arg)?
+                        .parse()
+                        .map_err(|_| String::from("'--idle-timeout' needs a number of minutes"))?,
+                );
+            }
+            "-p" | "--provider" => {
+                settings.providers = Provider::parse_chain(&value(&mut args, &arg)?)?;
+            }
+            "--" => positional.extend(args.by_ref()),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Unknown option '{}'", flag));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = if lsp {
+        Command::Lsp
+    } else if msgpack_rpc {
+        Command::MsgpackRpc
+    } else if interactive {
+        Command::Interactive
+    } else {
+        match positional.first().map(String::as_str) {
+            // Sherlock runs with an empty query before anything is typed.
+            None if format == Format::Sherlock => Command::Idle,
+            Some(word) if format == Format::Sherlock && word.trim().is_empty() => Command::Idle,
+            None => return Err(String::from("No word provided.")),
+            Some(word) if format == Format::Sherlock && !marked && positional.len() == 1 => {
+                Command::Define(word.to_string())
+            }
+            Some("tui") => Command::Tui,
+            Some("forms") => Command::Forms(argument(&positional, "forms")?),
+            Some("family") => Command::Family(argument(&positional, "family")?),
+            Some("etymology") => Command::Etymology(argument(&positional, "etymology")?),
+            Some("homophones") => Command::Homophones(argument(&positional, "homophones")?),
+            Some("rhymes") => Command::Rhymes(argument(&positional, "rhymes")?),
+            Some("meter") => match &positional[1..] {
+                [] => return Err(String::from("'meter' needs a line")),
+                words => Command::Meter(words.join(" ")),
+            },
+            Some("syllables") => match &positional[1..] {
+                [] => return Err(String::from("'syllables' needs some text")),
+                [words @ .., output] if output == "json" && !words.is_empty() => {
+                    Command::Syllables(words.join(" "), true)
+                }
+                words => Command::Syllables(words.join(" "), false),
+            },
+            // Cognates are in other languages, so `--lang` picks which ones.
+            Some("cognates") => Command::Cognates(
+                argument(&positional, "cognates")?,
+                languages(&settings.lang),
+            ),
+            Some("concordance") => Command::Concordance(argument(&positional, "concordance")?),
+            Some("gloss") => match &positional[1..] {
+                [] => return Err(String::from("'gloss' needs a sentence")),
+                words => Command::Gloss(words.join(" ")),
+            },
+            Some("analyze") => Command::Analyze(argument(&positional, "analyze")?.into(), top),
+            Some("hardwords") => match &positional[1..] {
+                [] => Command::HardWords(rarer_than.or(settings.vocabulary).unwrap_or(3000)),
+                _ => return Err(String::from("'hardwords' reads its text from stdin")),
+            },
+            Some("examples") => Command::Examples(argument(&positional, "examples")?, to),
+            Some("translations") => {
+                Command::Translations(argument(&positional, "translations")?, to)
+            }
+            Some("compare") => match &positional[1..] {
+                [first, second] => Command::Compare(first.clone(), second.clone()),
+                _ => return Err(String::from("'compare' needs two words")),
+            },
+            Some("quiz") => match &positional[1..] {
+                [] => Command::Quiz(None),
+                [word, guess] => Command::Quiz(Some((word.clone(), guess.clone()))),
+                _ => return Err(String::from("'quiz' takes no words")),
+            },
+            Some("stats") => match &positional[1..] {
+                [] => Command::Stats(false),
+                [output] if output == "waybar" => Command::Stats(true),
+                _ => return Err(String::from("'stats' only takes 'waybar'")),
+            },
+            Some("export") => match &positional[1..] {
+                [kind] if kind == "ics" => Command::Export(export::Kind::Ics),
+                _ => return Err(String::from("'export' needs ics")),
+            },
+            Some("schema") => match &positional[1..] {
+                [kind] if kind == "sherlock" => Command::Schema(schema::Kind::Sherlock),
+                [kind] if kind == "entries" => Command::Schema(schema::Kind::Entries),
+                _ => return Err(String::from("'schema' needs sherlock or entries")),
+            },
+            Some("sync") => match &positional[1..] {
+                [] => Command::Sync,
+                _ => return Err(String::from("'sync' takes no arguments")),
+            },
+            Some("daemon") => match &positional[1..] {
+                [] => Command::Daemon(idle_timeout),
+                _ => return Err(String::from("'daemon' takes no arguments")),
+            },
+            Some("install-service") => match &positional[1..] {
+                [] => Command::InstallService,
+                _ => return Err(String::from("'install-service' takes no arguments")),
+            },
+            Some("mcp") => match &positional[1..] {
+                [] => Command::Mcp,
+                _ => return Err(String::from("'mcp' takes no arguments")),
+            },
+            Some("bot") => match &positional[1..] {
+                [] => Command::Bot,
+                _ => return Err(String::from("'bot' takes no arguments")),
+            },
+            Some("wotd") => match &positional[1..] {
+                [] => Command::Wotd(false),
+                [action] if action == "publish" => Command::Wotd(true),
+                _ => return Err(String::from("'wotd' only takes 'publish'")),
+            },
+            Some("history") => match &positional[1..] {
+                [action, word] if action == "redact" => Command::Redact(word.clone()),
+                [action] if action == "redact" => {
+                    return Err(String::from("'history redact' needs a word"))
+                }
+                _ => return Err(String::from("'history' needs redact")),
+            },
+            Some("encrypt") => match &positional[1..] {
+                [] => Command::Encrypt,
+                _ => return Err(String::from("'encrypt' takes no arguments")),
+            },
+            Some("cache") => match &positional[1..] {
+                [action] if action == "prune-audio" => Command::PruneAudio(None),
+                [action, mb] if action == "prune-audio" => Command::PruneAudio(Some(
+                    mb.parse()
+                        .map_err(|_| String::from("'cache prune-audio' takes a size in MB"))?,
+                )),
+                _ => return Err(String::from("'cache' needs prune-audio")),
+            },
+            Some("doctor") => match &positional[1..] {
+                [] => Command::Doctor(false),
+                [output] if output == "json" => Command::Doctor(true),
+                _ => return Err(String::from("'doctor' only takes 'json'")),
+            },
+            Some("config") => match &positional[1..] {
+                [action] if action == "check" => Command::ConfigCheck,
+                _ => return Err(String::from("'config' needs check")),
+            },
+            Some("dict") => Command::Dict(dict_action(&positional)?),
+            Some("known") => Command::Known(known_action(&positional)?),
+            Some(word) => Command::Define(word.to_string()),
+        }
+    };
+    Ok(Cli {
+        command,
+        format,
+        settings,
+        bench,
+    })
+}
+
+/// The `--profile` in `args`, which has to be known before the config is
+/// loaded and so before [`parse`].
+pub fn profile(args: &[String]) -> Option<String> {
+    let marked = args.first().is_some_and(|arg| arg == "--");
+    let mut args = args
+        .iter()
+        .skip(usize::from(marked))
+        .take_while(|arg| *arg != "--");
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next().cloned();
+        }
+    }
+    None
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("Option '{}' needs a value", flag))
+}
+
+/// The single argument a subcommand takes, e.g. the word in `forms <word>`.
+fn argument(positional: &[String], command: &str) -> Result<String, String> {
+    match positional {
+        [_, word] => Ok(word.clone()),
+        [_] => Err(format!("'{}' needs a word", command)),
+        _ => Err(format!("'{}' takes a single word", command)),
+    }
+}
+
+/// A comma-separated list of language codes, English left out: the commands
+/// that take one look at other languages.
+fn languages(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|code| code.trim().to_string())
+        .filter(|code| !code.is_empty() && code != "en")
+        .collect()
+}
+
+fn dict_action(positional: &[String]) -> Result<packs::Action, String> {
+    let args: Vec<&str> = positional.iter().map(String::as_str).collect();
+    match args[1..] {
+        ["list"] => Ok(packs::Action::List),
+        ["install", pack] => Ok(packs::Action::Install(pack.to_string())),
+        ["remove", pack] => Ok(packs::Action::Remove(pack.to_string())),
+        ["install" | "remove", ..] => Err(format!("'dict {}' needs a single pack", args[1])),
+        _ => Err(String::from("'dict' needs list, install or remove")),
+    }
+}
+
+fn known_action(positional: &[String]) -> Result<known::Action, String> {
+    let args: Vec<&str> = positional.iter().map(String::as_str).collect();
+    let words = || positional[2..].to_vec();
+    match args[1..] {
+        ["list"] => Ok(known::Action::List),
+        ["add", _, ..] => Ok(known::Action::Add(words())),
+        ["remove", _, ..] => Ok(known::Action::Remove(words())),
+        ["import", file] => Ok(known::Action::Import(file.into())),
+        ["add" | "remove"] => Err(format!("'known {}' needs at least one word", args[1])),
+        ["import", ..] => Err(String::from("'known import' needs a single file")),
+        _ => Err(String::from("'known' needs list, add, remove or import")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every name `parse` takes for a subcommand.
+    const SUBCOMMANDS: &[&str] = &[
+        "tui",
+        "forms",
+        "family",
+        "etymology",
+        "homophones",
+        "rhymes",
+        "meter",
+        "syllables",
+        "cognates",
+        "concordance",
+        "gloss",
+        "analyze",
+        "hardwords",
+        "examples",
+        "translations",
+        "compare",
+        "quiz",
+        "stats",
+        "export",
+        "schema",
+        "sync",
+        "daemon",
+        "install-service",
+        "mcp",
+        "bot",
+        "wotd",
+        "history",
+        "encrypt",
+        "cache",
+        "doctor",
+        "config",
+        "dict",
+        "known",
+    ];
+
+    fn run(args: &[&str], format: Format) -> Result<Cli, String> {
+        let args = args.iter().map(|arg| arg.to_string());
+        parse(args, Settings::default(), format)
+    }
+
+    #[test]
+    fn sherlock_looks_subcommand_names_up() {
+        for name in SUBCOMMANDS {
+            let cli = run(&[name], Format::Sherlock).unwrap();
+            assert!(
+                matches!(&cli.command, Command::Define(word) if word == name),
+                "'{}' gave {:?}",
+                name,
+                cli.command
+            );
+        }
+    }
+
+    #[test]
+    fn sherlock_looks_names_up_after_options() {
+        let cli = run(&["--lang", "de", "--", "daemon"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Define(word) if word == "daemon"));
+    }
+
+    #[test]
+    fn leading_marker_runs_subcommands() {
+        let cli = run(&["--", "daemon"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Daemon(None)));
+        let cli = run(&["--", "--idle-timeout", "5", "daemon"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Daemon(Some(5))));
+        let cli = run(&["--", "quiz", "--", "-ism", "-ist"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Quiz(Some(_))));
+    }
+
+    #[test]
+    fn bot_only_after_marker() {
+        let cli = run(&["bot"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Define(word) if word == "bot"));
+        let cli = run(&["--", "bot"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Bot));
+        assert!(run(&["--", "bot", "now"], Format::Sherlock).is_err());
+    }
+
+    #[test]
+    fn other_formats_run_subcommands() {
+        let cli = run(&["quiz"], Format::Plain).unwrap();
+        assert!(matches!(cli.command, Command::Quiz(None)));
+        let cli = run(&["-f", "plain", "daemon"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Daemon(None)));
+        assert!(run(&["forms"], Format::Oneline).is_err());
+    }
+
+    #[test]
+    fn subcommands_with_arguments_run() {
+        let cli = run(&["stats", "waybar"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Stats(true)));
+        let cli = run(&["history", "redact", "cat"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Redact(word) if word == "cat"));
+        let cli = run(&["forms", "run"], Format::Sherlock).unwrap();
+        assert!(matches!(cli.command, Command::Forms(word) if word == "run"));
+    }
+
+    #[test]
+    fn profile_after_marker() {
+        let args: Vec<String> = ["--", "--profile", "work", "sync"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(profile(&args).as_deref(), Some("work"));
+        let args: Vec<String> = ["--", "--profile"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(profile(&args), None);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use crate::{api::DefinitionResponse, offline};
+
+/// Names the CMU Pronouncing Dictionary goes by, in
+/// [`offline::dictionaries_dir`].
+const FILES: &[&str] = &["cmudict.dict", "cmudict-0.7b", "cmudict.txt"];
+
+/// ARPABET phonemes, as CMUdict writes them, in IPA. Unstressed AH and ER are
+/// the schwas.
+const IPA: &[(&str, &str)] = &[
+    ("AA", "ɑ"),
+    ("AE", "æ"),
+    ("AH", "ʌ"),
+    ("AH0", "ə"),
+    ("AO", "ɔ"),
+    ("AW", "aʊ"),
+    ("AY", "aɪ"),
+    ("B", "b"),
+    ("CH", "tʃ"),
+    ("D", "d"),
+    ("DH", "ð"),
+    ("EH", "ɛ"),
+    ("ER", "ɝ"),
+    ("ER0", "ɚ"),
+    ("EY", "eɪ"),
+    ("F", "f"),
+    ("G", "ɡ"),
+    ("HH", "h"),
+    ("IH", "ɪ"),
+    ("IY", "i"),
+    ("JH", "dʒ"),
+    ("K", "k"),
+    ("L", "l"),
+    ("M", "m"),
+    ("N", "n"),
+    ("NG", "ŋ"),
+    ("OW", "oʊ"),
+    ("OY", "ɔɪ"),
+    ("P", "p"),
+    ("R", "ɹ"),
+    ("S", "s"),
+    ("SH", "ʃ"),
+    ("T", "t"),
+    ("TH", "θ"),
+    ("UH", "ʊ"),
+    ("UW", "u"),
+    ("V", "v"),
+    ("W", "w"),
+    ("Y", "j"),
+    ("Z", "z"),
+    ("ZH", "ʒ"),
+];
+
+/// One way a word is said: its ARPABET phonemes, the vowels ending in their
+/// stress, 1 for primary, 2 for secondary and 0 for none.
+pub struct Pronunciation {
+    pub word: String,
+    pub phonemes: Vec<String>,
+}
+impl Pronunciation {
+    /// As CMUdict writes it: "F L AW1 ER0".
+    pub fn arpabet(&self) -> String {
+        self.phonemes.join(" ")
+    }
+
+    /// In IPA, with the stress marks before the stressed vowels as Datamuse
+    /// writes them: "flˈaʊɚ".
+    pub fn ipa(&self) -> String {
+        self.phonemes
+            .iter()
+            .map(|p| {
+                let mark = match p.chars().last() {
+                    Some('1') => "ˈ",
+                    Some('2') => "ˌ",
+                    _ => "",
+                };
+                format!("{}{}", mark, phoneme(p))
+            })
+            .collect()
+    }
+
+    /// One per vowel.
+    pub fn syllables(&self) -> usize {
+        self.phonemes.iter().filter(|p| is_vowel(p)).count()
+    }
+
+    /// Each syllable's stress, as its vowel's digit: "10" for flower, "010"
+    /// for banana.
+    pub fn stress(&self) -> String {
+        self.phonemes
+            .iter()
+            .filter_map(|p| p.chars().last().filter(char::is_ascii_digit))
+            .collect()
+    }
+
+    /// What a perfect rhyme has to match: the last stressed vowel and all
+    /// that follows it, "AW1 ER0" for flower. Words without a stressed vowel
+    /// rhyme from their last vowel.
+    pub fn rhyme(&self) -> &[String] {
+        let start = self
+            .phonemes
+            .iter()
+            .rposition(|p| p.ends_with(['1', '2']))
+            .or_else(|| self.phonemes.iter().rposition(|p| is_vowel(p)))
+            .unwrap_or(0);
+        &self.phonemes[start..]
+    }
+}
+
+/// CMUdict, read once per run.
+pub struct Dictionary {
+    entries: Vec<Pronunciation>,
+    /// Each word's pronunciations, as indexes into `entries`.
+    words: HashMap<String, Vec<usize>>,
+}
+impl Dictionary {
+    /// The installed CMUdict, or why there isn't one.
+    pub fn shared() -> io::Result<&'static Dictionary> {
+        static DICTIONARY: OnceLock<Result<Dictionary, String>> = OnceLock::new();
+        DICTIONARY
+            .get_or_init(|| {
+                let path = path().ok_or_else(|| {
+                    format!(
+                        "CMUdict isn't installed; put {} in {}",
+                        FILES[0],
+                        offline::dictionaries_dir().display()
+                    )
+                })?;
+                // 0.7b is Latin-1, with the odd accented letter in a comment.
+                let bytes = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                Ok(Self::parse(&String::from_utf8_lossy(&bytes)))
+            })
+            .as_ref()
+            .map_err(|e| io::Error::new(ErrorKind::NotFound, e.clone()))
+    }
+
+    /// Reads either layout: 0.7b's "WORD  W ER1 D" with ";;;" comments, or
+    /// the current "word W ER1 D # comment". Variants are "word(2)".
+    fn parse(text: &str) -> Self {
+        let entries: Vec<Pronunciation> = text
+            .lines()
+            .filter(|line| !line.starts_with(";;;"))
+            .filter_map(|line| {
+                let line = line.split_once('#').map_or(line, |(entry, _)| entry);
+                let (word, phonemes) = line.trim().split_once(char::is_whitespace)?;
+                let word = word.split_once('(').map_or(word, |(w, _)| w).to_lowercase();
+                Some(Pronunciation {
+                    word,
+                    phonemes: phonemes.split_whitespace().map(String::from).collect(),
+                })
+            })
+            .collect();
+        let mut words: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            words.entry(entry.word.clone()).or_default().push(i);
+        }
+        Self { entries, words }
+    }
+
+    /// Every pronunciation of every word.
+    pub fn entries(&self) -> &[Pronunciation] {
+        &self.entries
+    }
+
+    /// `word`'s pronunciations, the most common first.
+    pub fn lookup(&self, word: &str) -> Vec<&Pronunciation> {
+        self.words
+            .get(&word.to_lowercase())
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.entries[i])
+            .collect()
+    }
+}
+
+/// Where CMUdict is installed, if it is.
+fn path() -> Option<PathBuf> {
+    let dir = offline::dictionaries_dir();
+    FILES
+        .iter()
+        .map(|file| dir.join(file))
+        .find(|path| path.exists())
+}
+
+pub fn is_installed() -> bool {
+    path().is_some()
+}
+
+fn is_vowel(phoneme: &str) -> bool {
+    phoneme.ends_with(|c: char| c.is_ascii_digit())
+}
+
+/// `phoneme` without its stress digit.
+pub fn unstressed(phoneme: &str) -> &str {
+    phoneme.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+/// One ARPABET phoneme in IPA, without its stress.
+pub fn phoneme(arpabet: &str) -> &'static str {
+    let bare = unstressed(arpabet);
+    let key = match arpabet.ends_with('0') && (bare == "AH" || bare == "ER") {
+        true => arpabet,
+        false => bare,
+    };
+    IPA.iter()
+        .find(|(code, _)| *code == key)
+        .map_or("?", |(_, ipa)| ipa)
+}
+
+/// The syllables in `word` as CMUdict counts them, when it's installed and
+/// has the word.
+pub fn syllables(word: &str) -> Option<usize> {
+    let dictionary = Dictionary::shared().ok()?;
+    dictionary.lookup(word).first().map(|p| p.syllables())
+}
+
+/// Gives English entries that came without a pronunciation CMUdict's, when
+/// it's installed, so offline dictionaries have one too.
+pub fn annotate(entries: &mut [DefinitionResponse]) {
+    if entries.iter().all(|entry| entry.ipa().is_some()) {
+        return;
+    }
+    let Ok(dictionary) = Dictionary::shared() else {
+        return;
+    };
+    for entry in entries.iter_mut().filter(|entry| entry.ipa().is_none()) {
+        if let Some(pronunciation) = dictionary.lookup(&entry.word).first() {
+            entry.phonetic = Some(format!("/{}/", pronunciation.ipa()));
+        }
+    }
+}
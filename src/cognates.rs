@@ -0,0 +1,179 @@
+use crate::{
+    cli::Format,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    spelling, text, wiktionary,
+};
+
+/// How a word in another language is related to the English one, in the
+/// order they're preferred when the same word turns up more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Relation {
+    /// Shares an ancestor, per the etymology's "cognate with".
+    Cognate,
+    /// Borrowed from English, per the Descendants section.
+    Borrowing,
+    /// A translation spelled so much like the word that it's almost surely
+    /// related.
+    Translation,
+}
+impl Relation {
+    fn label(self) -> &'static str {
+        match self {
+            Relation::Cognate => "cognate",
+            Relation::Borrowing => "borrowed from English",
+            Relation::Translation => "translation",
+        }
+    }
+}
+
+struct Relative {
+    language: String,
+    word: String,
+    relation: Relation,
+}
+
+/// `cognates <word>`: related words in other languages, limited to
+/// `languages` when it isn't empty.
+pub async fn run(word: &str, languages: &[String], format: Format) -> Result<(), surf::Error> {
+    let word = word.trim();
+    let page = wiktionary::fetch_page(word).await?;
+    let mut relatives = page
+        .as_deref()
+        .and_then(|page| wiktionary::language_section(page, "English"))
+        .map(|english| relatives(word, english))
+        .unwrap_or_default();
+    relatives.retain(|r| languages.is_empty() || languages.contains(&r.language));
+    relatives.sort_by_key(|r| {
+        (
+            languages.iter().position(|l| *l == r.language),
+            language_name(&r.language),
+            r.relation,
+        )
+    });
+
+    if relatives.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No cognates found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No cognates found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = relatives
+        .iter()
+        .map(|r| {
+            vec![
+                language_name(&r.language),
+                r.word.clone(),
+                r.relation.label().to_string(),
+            ]
+        })
+        .collect();
+    match format {
+        Format::Sherlock => {
+            let content = format!(
+                "<span font_desc=\"monospace\">\n{}</span>",
+                text::table(&rows)
+            );
+            let actions = relatives
+                .iter()
+                .map(|r| {
+                    ApplicationAction::copy(
+                        format!("{} {}", language_name(&r.language), r.word),
+                        r.word.clone(),
+                    )
+                })
+                .collect();
+            let response =
+                SherlockPipeResponse::new(format!(r#"Cognates of "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => print!("{}", text::table(&rows)),
+        Format::Oneline => {
+            let words: Vec<String> = relatives
+                .iter()
+                .map(|r| format!("{} {}", r.language, r.word))
+                .collect();
+            println!("{}: {}", word, words.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn language_name(code: &str) -> String {
+    wiktionary::language_name(code).unwrap_or(code).to_string()
+}
+
+/// Every relative the English section of a page names, once per language and
+/// spelling.
+fn relatives(word: &str, english: &str) -> Vec<Relative> {
+    let mut found: Vec<Relative> = Vec::new();
+    let mut add = |language: &str, term: &str, relation: Relation| {
+        let term = wiktionary::plain(term);
+        // Reconstructed forms (*ǵenh₁-) are ancestors, not words anyone uses.
+        if term.is_empty() || term.starts_with('*') || language == "en" {
+            return;
+        }
+        let key = folded(&term);
+        match found
+            .iter_mut()
+            .find(|r| r.language == language && folded(&r.word) == key)
+        {
+            Some(existing) => existing.relation = existing.relation.min(relation),
+            None => found.push(Relative {
+                language: language.to_string(),
+                word: term,
+                relation,
+            }),
+        }
+    };
+
+    for section in wiktionary::sections(english, "Etymology") {
+        for template in wiktionary::templates(section, "cog") {
+            if let [language, term, ..] = template.positional[..] {
+                add(language, term, Relation::Cognate);
+            }
+        }
+    }
+    for section in wiktionary::sections(english, "Descendants") {
+        for (_, template) in wiktionary::templates_named(section, &["desc", "desctree"]) {
+            if let [language, term, ..] = template.positional[..] {
+                add(language, term, Relation::Borrowing);
+            }
+        }
+    }
+    let english_word = folded(word);
+    for section in wiktionary::sections(english, "Translations") {
+        for (_, template) in wiktionary::templates_named(section, &["t", "t+", "tt", "tt+"]) {
+            if let [language, term, ..] = template.positional[..] {
+                if looks_related(&english_word, &folded(term)) {
+                    add(language, term, Relation::Translation);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Lower-cased without accents, so "Nation" and "nación" compare as near
+/// spellings of "nation".
+fn folded(word: &str) -> String {
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .map(text::fold_accent)
+        .collect()
+}
+
+/// Whether a translation is close enough in spelling to count as related: at
+/// most one edit for every two letters. Translations in other scripts never are.
+fn looks_related(word: &str, translation: &str) -> bool {
+    let longest = word.chars().count().max(translation.chars().count());
+    longest >= 3 && spelling::distance(word, translation) * 2 <= longest
+}
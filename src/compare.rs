@@ -0,0 +1,268 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::{
+    api::{DefinitionResponse, Lookup},
+    cli::Format,
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text::{self, Numbering},
+};
+
+/// Width of each column in the side-by-side view.
+const COLUMN: usize = 36;
+/// Senses shown per part of speech; near-synonyms differ in the first few.
+const SENSES: usize = 3;
+
+/// A leading "(informal, chiefly US)" label list.
+static LABELS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\(([^)]*)\)").unwrap());
+
+/// Labels that say how a word is used rather than what it means.
+const REGISTERS: &[&str] = &[
+    "formal",
+    "informal",
+    "colloquial",
+    "slang",
+    "literary",
+    "poetic",
+    "archaic",
+    "dated",
+    "obsolete",
+    "rare",
+    "humorous",
+    "derogatory",
+    "offensive",
+    "vulgar",
+    "euphemistic",
+    "technical",
+    "dialectal",
+    "british",
+    "uk",
+    "us",
+    "american",
+    "australian",
+];
+
+/// What one side of the comparison knows about its word.
+struct Side {
+    word: String,
+    entries: Vec<DefinitionResponse>,
+}
+impl Side {
+    fn related(&self, antonyms: bool) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        for meaning in self.entries.iter().flat_map(|e| &e.meanings) {
+            let lists = std::iter::once(match antonyms {
+                true => &meaning.antonyms,
+                false => &meaning.synonyms,
+            })
+            .chain(meaning.definitions.iter().map(|d| match antonyms {
+                true => &d.antonyms,
+                false => &d.synonyms,
+            }));
+            for word in lists.flatten().flatten() {
+                let word = word.to_lowercase();
+                if !words.contains(&word) {
+                    words.push(word);
+                }
+            }
+        }
+        words
+    }
+
+    /// Register labels on any sense, lower-cased.
+    fn registers(&self) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        let definitions = self
+            .entries
+            .iter()
+            .flat_map(|e| &e.meanings)
+            .flat_map(|m| &m.definitions);
+        for definition in definitions {
+            for label in &definition.labels {
+                let label = label.to_string();
+                if !found.contains(&label) {
+                    found.push(label);
+                }
+            }
+            let Some(labels) = LABELS.captures(&definition.definition) else {
+                continue;
+            };
+            for label in labels[1].split([',', ';']) {
+                let label = label.trim().trim_start_matches("chiefly ").to_lowercase();
+                if REGISTERS.contains(&label.as_str()) && !found.contains(&label) {
+                    found.push(label);
+                }
+            }
+        }
+        found
+    }
+
+    /// The first few senses under each part of speech, wrapped to a column.
+    fn column(&self) -> Vec<String> {
+        let mut lines = vec![self.word.clone(), String::new()];
+        let numbering = Numbering::of(&self.entries);
+        for meaning in self.entries.iter().flat_map(|e| &e.meanings) {
+            lines.push(format!("─── {} ───", meaning.heading()));
+            for (i, def) in meaning.definitions.iter().take(SENSES).enumerate() {
+                for (j, line) in text::wrap(&def.definition, COLUMN - 4)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let label = numbering.label(i + 1);
+                    lines.push(match j {
+                        0 => format!("{}{}", label, line),
+                        _ => format!("{:width$}{}", "", line, width = label.len()),
+                    });
+                }
+            }
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+/// What the two words have in common and where they part ways.
+struct Differences {
+    shared_synonyms: Vec<String>,
+    shared_antonyms: Vec<String>,
+    /// Registers only the first, then only the second word is labelled with.
+    registers: (Vec<String>, Vec<String>),
+    /// Whether each word lists the other as a synonym.
+    listed_as_synonym: (bool, bool),
+}
+impl Differences {
+    fn new(first: &Side, second: &Side) -> Self {
+        let both = |a: Vec<String>, b: Vec<String>| -> Vec<String> {
+            a.into_iter()
+                .filter(|w| b.contains(w) && *w != first.word && *w != second.word)
+                .collect()
+        };
+        let synonyms = (first.related(false), second.related(false));
+        let (first_registers, second_registers) = (first.registers(), second.registers());
+        let only = |a: &[String], b: &[String]| -> Vec<String> {
+            a.iter().filter(|r| !b.contains(r)).cloned().collect()
+        };
+        Self {
+            listed_as_synonym: (
+                synonyms.0.contains(&second.word),
+                synonyms.1.contains(&first.word),
+            ),
+            shared_synonyms: both(synonyms.0, synonyms.1),
+            shared_antonyms: both(first.related(true), second.related(true)),
+            registers: (
+                only(&first_registers, &second_registers),
+                only(&second_registers, &first_registers),
+            ),
+        }
+    }
+
+    /// One line per finding.
+    fn lines(&self, first: &str, second: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.listed_as_synonym {
+            (true, true) => lines.push(format!(
+                "{} and {} list each other as synonyms",
+                first, second
+            )),
+            (true, false) => lines.push(format!("{} lists {} as a synonym", first, second)),
+            (false, true) => lines.push(format!("{} lists {} as a synonym", second, first)),
+            (false, false) => {}
+        }
+        if !self.shared_synonyms.is_empty() {
+            lines.push(format!(
+                "Shared synonyms: {}",
+                self.shared_synonyms.join(", ")
+            ));
+        }
+        if !self.shared_antonyms.is_empty() {
+            lines.push(format!(
+                "Shared antonyms: {}",
+                self.shared_antonyms.join(", ")
+            ));
+        }
+        for (word, registers) in [(first, &self.registers.0), (second, &self.registers.1)] {
+            if !registers.is_empty() {
+                lines.push(format!("Only {} is {}", word, registers.join(", ")));
+            }
+        }
+        lines
+    }
+}
+
+/// `compare <word> <word>`: the senses of two words side by side, with what
+/// they share and how their registers differ.
+pub async fn run(
+    first: &str,
+    second: &str,
+    settings: &Settings,
+    format: Format,
+) -> Result<(), surf::Error> {
+    let (a, b) = tokio::join!(settings.resolve(first), settings.resolve(second));
+    let mut sides = Vec::new();
+    for resolved in [a?, b?] {
+        let entries = match resolved.lookup {
+            Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+            _ => Vec::new(),
+        };
+        if entries.is_empty() {
+            not_found(&resolved.query, format);
+            return Ok(());
+        }
+        sides.push(Side {
+            word: resolved.word.to_lowercase(),
+            entries,
+        });
+    }
+    let (first, second) = (&sides[0], &sides[1]);
+    let findings = Differences::new(first, second).lines(&first.word, &second.word);
+    let columns = text::side_by_side(&first.column(), &second.column(), COLUMN);
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            content.push_str(&columns);
+            for line in &findings {
+                content.push_str(&format!("<i>{}</i>\n", line));
+            }
+            content.push_str("</span>");
+            let actions = [&first.word, &second.word]
+                .into_iter()
+                .map(|word| ApplicationAction::lookup_word("Define", word, settings))
+                .collect();
+            let response = SherlockPipeResponse::new(
+                format!(r#""{}" vs "{}""#, first.word, second.word),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            print!("{}", columns);
+            for line in &findings {
+                println!("{}", line);
+            }
+        }
+        Format::Oneline => match findings.is_empty() {
+            true => println!("{} vs {}: nothing in common", first.word, second.word),
+            false => println!("{} vs {}: {}", first.word, second.word, findings.join("; ")),
+        },
+    }
+    Ok(())
+}
+
+fn not_found(word: &str, format: Format) {
+    match format {
+        Format::Sherlock => {
+            let response = SherlockPipeResponse::message(
+                format!(r#"No definition found for "{}""#, word),
+                String::new(),
+            );
+            response.print();
+        }
+        _ => {
+            eprintln!("No definition found for '{}'.", word);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli::Format,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    storage,
+};
+
+/// Characters of context on each side of the word.
+const CONTEXT: usize = 40;
+/// Lines shown; the total is still reported.
+const LIMIT: usize = 25;
+/// Files that are read as plain text.
+const EXTENSIONS: &[&str] = &["txt", "md", "text", "rst", "org", "tex"];
+
+/// Which files in the corpus contain each word, so a lookup only reads the
+/// files that can match. Rebuilt whenever a file is added, removed or changed.
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    files: Vec<PathBuf>,
+    /// Lower-cased words and the positions in `files` of the files using them.
+    postings: BTreeMap<String, Vec<usize>>,
+}
+
+/// One keyword-in-context line.
+struct Line {
+    file: PathBuf,
+    left: String,
+    word: String,
+    right: String,
+}
+
+/// `concordance <word>`: every use of the word in the text files under
+/// `corpus`, centred on the word.
+pub fn run(word: &str, corpus: Option<&Path>, format: Format) -> Result<(), io::Error> {
+    let Some(corpus) = corpus else {
+        eprintln!("Error: 'concordance' needs --corpus <dir> or \"corpus\" in config.json");
+        std::process::exit(1);
+    };
+    let corpus = storage::expand_home(corpus);
+    let index = index(&corpus)?;
+    let word = word.trim();
+    let lines = lines(&index, word);
+
+    if lines.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response = SherlockPipeResponse::message(
+                    format!(r#"No uses of "{}" in {}"#, word, corpus.display()),
+                    String::new(),
+                );
+                response.print();
+            }
+            _ => {
+                eprintln!("'{}' doesn't occur in {}.", word, corpus.display());
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let total = lines.len();
+    let shown = &lines[..total.min(LIMIT)];
+    let name = |line: &Line| {
+        line.file
+            .strip_prefix(&corpus)
+            .unwrap_or(&line.file)
+            .display()
+            .to_string()
+    };
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            for line in shown {
+                content.push_str(&format!(
+                    "{:>width$}<b>{}</b>{:<width$}  <i>{}</i>\n",
+                    line.left,
+                    line.word,
+                    line.right,
+                    name(line),
+                    width = CONTEXT
+                ));
+            }
+            if total > shown.len() {
+                content.push_str(&format!("\n<i>{} more</i>\n", total - shown.len()));
+            }
+            content.push_str("</span>");
+            let actions = shown
+                .iter()
+                .map(|line| {
+                    let text = format!("{}{}{}", line.left, line.word, line.right);
+                    ApplicationAction::copy(text.trim().to_string(), text.trim().to_string())
+                })
+                .collect();
+            let response = SherlockPipeResponse::new(
+                format!(r#""{}" in {} ({} uses)"#, word, corpus.display(), total),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            for line in shown {
+                println!(
+                    "{:>width$}[{}]{:<width$}  ({})",
+                    line.left,
+                    line.word,
+                    line.right,
+                    name(line),
+                    width = CONTEXT
+                );
+            }
+            if total > shown.len() {
+                println!("… {} more", total - shown.len());
+            }
+        }
+        Format::Oneline => {
+            let line = &lines[0];
+            println!(
+                "{}[{}]{} ({} uses)",
+                line.left.trim_start(),
+                line.word,
+                line.right.trim_end(),
+                total
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Where the index for `corpus` is cached, one file per corpus directory.
+fn index_path(corpus: &Path) -> PathBuf {
+    let digest = Sha256::digest(corpus.to_string_lossy().as_bytes());
+    let name: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    storage::cache_dir()
+        .join("concordance")
+        .join(format!("{}.json", name))
+}
+
+/// Text files under `dir`, following subdirectories.
+fn text_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            text_files(&path, files)?;
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The cached index for `corpus` if it's still current, a freshly built and
+/// saved one otherwise.
+fn index(corpus: &Path) -> io::Result<Index> {
+    let mut files = Vec::new();
+    text_files(corpus, &mut files)?;
+    files.sort();
+    let path = index_path(corpus);
+    let cached: Index = storage::load_json(&path);
+    if cached.files == files && storage::is_fresh(&path, &files) {
+        return Ok(cached);
+    }
+
+    let mut postings: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, file) in files.iter().enumerate() {
+        let Ok(text) = fs::read_to_string(file) else {
+            continue;
+        };
+        for token in text.split(|c: char| !c.is_alphanumeric()) {
+            if token.is_empty() {
+                continue;
+            }
+            let ids = postings.entry(token.to_lowercase()).or_default();
+            if ids.last() != Some(&i) {
+                ids.push(i);
+            }
+        }
+    }
+    let index = Index { files, postings };
+    if let Err(e) = storage::save_json(&path, &index) {
+        eprintln!("Warning: couldn't save the concordance index: {}", e);
+    }
+    Ok(index)
+}
+
+/// Every use of `word` in the files the index lists for it, in file order.
+fn lines(index: &Index, word: &str) -> Vec<Line> {
+    let Some(ids) = index.postings.get(&word.to_lowercase()) else {
+        return Vec::new();
+    };
+    let pattern = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word))).unwrap();
+    let mut lines = Vec::new();
+    for &id in ids {
+        let file = &index.files[id];
+        let Ok(text) = fs::read_to_string(file) else {
+            continue;
+        };
+        for found in pattern.find_iter(&text) {
+            let before: Vec<char> = text[..found.start()].chars().rev().take(CONTEXT).collect();
+            let after: String = text[found.end()..].chars().take(CONTEXT).collect();
+            lines.push(Line {
+                file: file.clone(),
+                left: flatten(&before.into_iter().rev().collect::<String>()),
+                word: found.as_str().to_string(),
+                right: flatten(&after),
+            });
+        }
+    }
+    lines
+}
+
+/// Context on one line: newlines and runs of spaces become single spaces.
+fn flatten(text: &str) -> String {
+    let mut flat = String::new();
+    for c in text.chars() {
+        match c.is_whitespace() {
+            true if flat.ends_with(' ') => {}
+            true => flat.push(' '),
+            false => flat.push(c),
+        }
+    }
+    flat
+}
@@ -0,0 +1,520 @@
+use serde::{de, Deserialize};
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use surf::Url;
+
+use crate::{
+    api::Accent,
+    bot::Bot,
+    cefr::Level,
+    cli::Format,
+    content_filter::ContentFilter,
+    crypto::Encryption,
+    daemon::Refresh,
+    desktop::Tts,
+    domains::Domain,
+    furigana, hindi, i18n, krdict,
+    labels::Label,
+    merge::MergePolicy,
+    providers::Provider,
+    routes::Route,
+    rules::Rules,
+    sherlock::{Protocol, SEARCH_WORD},
+    spelling::{self, KeyboardLayout},
+    storage,
+    wotd::Wotd,
+};
+
+/// User preferences from `config.json`. Every key is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// What to do with vulgar and offensive senses.
+    pub content_filter: ContentFilter,
+    /// Used to rank "did you mean" suggestions by likely typos.
+    pub keyboard_layout: KeyboardLayout,
+    /// Providers to try in order; the next one is used when a word isn't
+    /// found or a provider fails.
+    pub providers: Vec<Provider>,
+    /// How entries are combined when several providers know a word.
+    pub merge_policy: MergePolicy,
+    /// Rewrites and hidden senses applied to every entry.
+    pub rules: Rules,
+    /// Pronunciations to show first: "uk", "us" or "au".
+    pub accent: Option<Accent>,
+    /// Text-to-speech for words without audio.
+    pub tts: Tts,
+    /// Directory of text files `concordance` searches.
+    pub corpus: Option<PathBuf>,
+    /// Specialty glossary looked in first: "medical", "legal" or "technical".
+    pub domain: Option<Domain>,
+    /// Provider chains for queries that match a prefix, pattern, script or
+    /// length.
+    pub routes: Vec<Route>,
+    /// Roughly how many of the most common words the user knows; `gloss` and
+    /// `hardwords` don't define those.
+    pub vocabulary: Option<usize>,
+    /// Folder `sync` merges bookmarks, known words, reviews and history through.
+    pub sync_dir: Option<PathBuf>,
+    /// Encrypt the history and bookmarks with a passphrase.
+    pub encryption: Option<Encryption>,
+    /// Never write down what was looked up.
+    pub private: bool,
+    /// A DNS-over-HTTPS server's JSON endpoint, e.g.
+    /// `https://1.1.1.1/dns-query`, to resolve the sites words are looked up
+    /// on instead of the system resolver.
+    pub doh: Option<String>,
+    /// Proxy URLs by provider, e.g. `socks5h://127.0.0.1:9050` to send one
+    /// provider's lookups through Tor.
+    pub proxies: HashMap<Provider, String>,
+    /// Where "Search the web" sends a word, with `{word}` in its place, e.g.
+    /// `https://www.google.com/search?q=define:{word}`.
+    pub web_search: Option<String>,
+    /// Most megabytes of downloaded pronunciations to keep.
+    pub audio_cache_mb: Option<u64>,
+    /// Connect over IPv4 only.
+    pub ipv4_only: bool,
+    /// Seconds a whole request may take.
+    pub timeout: Option<u64>,
+    /// How often `daemon` refreshes the most looked-up words, and how many.
+    pub refresh: Refresh,
+    /// The Matrix room and the IRC channel `bot` answers in, and how often
+    /// each user may ask.
+    pub bot: Bot,
+    /// Where `wotd publish` writes the word of the day's feed and who it's
+    /// mailed to.
+    pub wotd: Wotd,
+    /// How numbers and dates are written, e.g. `de_DE`, instead of the
+    /// locale the environment sets.
+    pub locale: Option<String>,
+    /// The language tiles and terminal output are labelled in, as with
+    /// `--ui-lang`, instead of the one the environment sets.
+    pub ui_lang: Option<String>,
+    /// Put right-to-left text between Unicode isolation marks.
+    pub isolate_rtl: bool,
+    /// How Japanese headwords show their reading.
+    pub furigana: furigana::Style,
+    /// Show Chinese readings in zhuyin (bopomofo) rather than pinyin.
+    pub zhuyin: bool,
+    /// Color each syllable of a Chinese reading by its tone.
+    pub tone_colors: bool,
+    /// The krdict API key, from https://krdict.korean.go.kr/openApi.
+    pub krdict_key: Option<String>,
+    /// How Korean pronunciations are romanized.
+    pub korean_romanization: krdict::Romanization,
+    /// How Hindi words are romanized.
+    pub hindi_romanization: hindi::Romanization,
+    /// Dictionary language, as with `--lang`.
+    pub lang: Option<String>,
+    /// How results are printed, as with `--format`.
+    pub format: Option<Format>,
+    /// The version of Sherlock's pipe format to print, as with `--protocol`.
+    pub protocol: Protocol,
+    /// Simple English by default, as with `--simple`.
+    pub simple: bool,
+    /// Tag senses with an estimated CEFR level, as with `--cefr`.
+    pub cefr: bool,
+    /// Hide senses harder than this CEFR level, as with `--max-level`.
+    pub max_level: Option<Level>,
+    /// Hide senses with these usage labels, as with `--exclude-labels`.
+    pub exclude_labels: Vec<Label>,
+    /// Named sets of the keys above, e.g. `"work"` or `"study"`. The one
+    /// picked with `--profile` or `SHERLOCK_DICT_PROFILE` replaces the keys it
+    /// sets for that run.
+    pub profiles: HashMap<String, serde_json::Value>,
+}
+/// Picks a profile when `--profile` isn't given.
+const PROFILE_VAR: &str = "SHERLOCK_DICT_PROFILE";
+
+/// Chooses the profile for this run, from `--profile` or else the
+/// environment. Has to happen before the config is first loaded.
+pub fn select_profile(name: Option<String>) {
+    let _ = PROFILE.set(name.or_else(profile_from_env));
+}
+
+/// The profile chosen for this run, if any.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get_or_init(profile_from_env).as_deref()
+}
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+fn profile_from_env() -> Option<String> {
+    std::env::var(PROFILE_VAR)
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        storage::config_dir().join("config.json")
+    }
+
+    /// The config file, falling back to defaults if it is unreadable or
+    /// malformed. A missing file is not an error. It's read once per run, the
+    /// first time something asks for it, and its problems are warned about on
+    /// stderr then.
+    pub fn load() -> &'static Self {
+        static LOADED: OnceLock<Config> = OnceLock::new();
+        LOADED.get_or_init(|| {
+            let (config, problems) = match Self::read() {
+                Ok((config, problems)) => (config, problems),
+                Err(problem) => (Self::default(), vec![problem]),
+            };
+            for problem in &problems {
+                eprintln!("Warning: {}", problem);
+            }
+            config
+        })
+    }
+
+    /// The config file with the chosen profile and the environment applied,
+    /// and what's wrong with them; or why the file can't be used at all. A
+    /// missing file is read as an empty one.
+    pub fn read() -> Result<(Self, Vec<Problem>), Problem> {
+        let path = Self::path();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::from("{}"),
+            Err(e) => return Err(Problem::new(&path, None, format!("can't be read: {}", e))),
+        };
+        let config: Self = serde_json::from_str(&text).map_err(|e| {
+            // serde_json puts the position at the end of the message; it's
+            // given up front instead, like a compiler would.
+            let message = e.to_string();
+            let suffix = format!(" at line {} column {}", e.line(), e.column());
+            let message = message.strip_suffix(&suffix).unwrap_or(&message);
+            Problem::new(
+                &path,
+                Some((e.line(), e.column())),
+                format!("{}; using the defaults", message),
+            )
+        })?;
+        let mut problems = config.problems(&path, &text);
+
+        // Each layer is checked on its own, so a mistake in one leaves the
+        // others working.
+        let mut keys: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&text).unwrap_or_default();
+        let mut layered = false;
+        if let Some((name, overrides)) =
+            profile().and_then(|name| Some((name, config.profiles.get(name)?)))
+        {
+            let profiled = match overrides {
+                serde_json::Value::Object(overrides) => {
+                    let mut profiled = keys.clone();
+                    profiled.extend(overrides.clone());
+                    parse(&profiled).map(|_| profiled)
+                }
+                _ => Err(de::Error::custom("a profile has to be an object of keys")),
+            };
+            match profiled {
+                Ok(profiled) => {
+                    keys = profiled;
+                    layered = true;
+                }
+                Err(e) => problems.push(Problem::new(
+                    &path,
+                    position(&text, name),
+                    format!("ignoring profile \"{}\": {}", name, e),
+                )),
+            }
+        }
+        for (variable, key, value) in environment() {
+            match env_value(&keys, key, &value) {
+                Ok(value) => {
+                    keys.insert(key.to_string(), value);
+                    layered = true;
+                }
+                Err(e) => problems.push(Problem {
+                    origin: variable,
+                    position: None,
+                    message: format!("ignoring \"{}\": {}", value, e),
+                }),
+            }
+        }
+        // Parsing again compiles every pattern again, so it's only done when
+        // a layer changed something.
+        let config = match layered {
+            true => parse(&keys).unwrap_or(config),
+            false => config,
+        };
+        Ok((config, problems))
+    }
+
+    /// Mistakes that still leave a usable config: misspelled keys, which
+    /// would otherwise be ignored, and values that can't work.
+    fn problems(&self, path: &Path, text: &str) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let mut problem = |key: &str, message: String| {
+            problems.push(Problem::new(path, position(text, key), message));
+        };
+
+        let keys = known_keys();
+        let top: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(text).unwrap_or_default();
+        let profiles = self.profiles.values().filter_map(|p| p.as_object());
+        for map in [&top].into_iter().chain(profiles) {
+            for key in map.keys().filter(|key| !keys.contains(&key.as_str())) {
+                let closest = keys
+                    .iter()
+                    .min_by_key(|known| spelling::distance(key, known))
+                    .filter(|known| spelling::distance(key, known) <= 2);
+                match closest {
+                    Some(known) => problem(
+                        key,
+                        format!("unknown key \"{}\"; did you mean \"{}\"?", key, known),
+                    ),
+                    None => problem(key, format!("unknown key \"{}\"", key)),
+                }
+            }
+        }
+
+        if let Some(doh) = &self.doh {
+            match Url::parse(doh) {
+                Ok(url) if url.scheme() == "https" => {}
+                Ok(_) => problem("doh", format!("\"{}\" isn't an https:// URL", doh)),
+                Err(e) => problem("doh", format!("\"{}\" isn't a URL: {}", doh, e)),
+            }
+        }
+        if let Some(search) = &self.web_search {
+            match Url::parse(&search.replace(SEARCH_WORD, "word")) {
+                Ok(_) if !search.contains(SEARCH_WORD) => problem(
+                    "web_search",
+                    format!("\"{}\" has no {} for the word", search, SEARCH_WORD),
+                ),
+                Ok(_) => {}
+                Err(e) => problem("web_search", format!("\"{}\" isn't a URL: {}", search, e)),
+            }
+        }
+        if let Some(ui_lang) = &self.ui_lang {
+            if i18n::language(ui_lang).is_none() {
+                problem(
+                    "ui_lang",
+                    format!(
+                        "no translation for \"{}\"; there are {}",
+                        ui_lang,
+                        i18n::languages().join(", ")
+                    ),
+                );
+            }
+        }
+        let routed = self.routes.iter().flat_map(|route| &route.providers);
+        if self.krdict_key.is_none()
+            && self
+                .providers
+                .iter()
+                .chain(routed)
+                .any(|p| *p == Provider::Krdict)
+        {
+            problem(
+                "providers",
+                String::from("krdict needs an API key; set krdict_key"),
+            );
+        }
+        let mut proxies: Vec<_> = self.proxies.iter().collect();
+        proxies.sort_by_key(|(provider, _)| provider.name());
+        for (provider, proxy) in proxies {
+            match Url::parse(proxy) {
+                Ok(url) if PROXY_SCHEMES.contains(&url.scheme()) => {}
+                Ok(url) => problem(
+                    provider.name(),
+                    format!(
+                        "{}'s proxy has an unknown scheme \"{}\"; use one of {}",
+                        provider,
+                        url.scheme(),
+                        PROXY_SCHEMES.join(", ")
+                    ),
+                ),
+                Err(e) => problem(
+                    provider.name(),
+                    format!("{}'s proxy \"{}\" isn't a URL: {}", provider, proxy, e),
+                ),
+            }
+        }
+        for (key, dir) in [("corpus", &self.corpus), ("sync_dir", &self.sync_dir)] {
+            if let Some(dir) = dir {
+                if !storage::expand_home(dir).is_dir() {
+                    problem(key, format!("{} isn't a folder", dir.display()));
+                }
+            }
+        }
+        if let Some(words) = &self.wotd.words {
+            if !storage::expand_home(words).is_file() {
+                problem("words", format!("{} isn't a file", words.display()));
+            }
+        }
+        if let Some(encryption) = &self.encryption {
+            if encryption.passphrase_command.trim().is_empty() {
+                problem(
+                    "passphrase_command",
+                    String::from("the passphrase command is empty"),
+                );
+            }
+        }
+        problems
+    }
+}
+
+/// Proxy URL schemes curl understands.
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks4", "socks4a", "socks5", "socks5h"];
+
+/// Something wrong with the config file or a `SHERLOCK_DICT_*` variable, and
+/// where, when that's known.
+#[derive(Debug)]
+pub struct Problem {
+    /// The config file's path or the variable's name.
+    origin: String,
+    /// Line and column, both from 1.
+    position: Option<(usize, usize)>,
+    message: String,
+}
+impl Problem {
+    fn new(path: &Path, position: Option<(usize, usize)>, message: String) -> Self {
+        Self {
+            origin: path.display().to_string(),
+            position,
+            message,
+        }
+    }
+}
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => {
+                write!(f, "{}:{}:{}: {}", self.origin, line, column, self.message)
+            }
+            None => write!(f, "{}: {}", self.origin, self.message),
+        }
+    }
+}
+
+/// Each config key can also be set for a run with `SHERLOCK_DICT_` and the
+/// key in capitals, e.g. `SHERLOCK_DICT_PROVIDERS=urban`.
+const ENV_PREFIX: &str = "SHERLOCK_DICT_";
+
+/// The `SHERLOCK_DICT_*` variables that are set, with the keys they stand for.
+fn environment() -> Vec<(String, &'static str, String)> {
+    known_keys()
+        .iter()
+        .filter_map(|key| {
+            let variable = format!("{}{}", ENV_PREFIX, key.to_uppercase());
+            let value = std::env::var(&variable).ok().filter(|v| !v.is_empty())?;
+            Some((variable, *key, value))
+        })
+        .collect()
+}
+
+/// What an environment variable sets `key` to. It's read as JSON when it is
+/// JSON (`true`, `5000`, `["urban"]`), as a string otherwise, and as a
+/// comma-separated list for keys that take one, so `urban,offline` works
+/// without the brackets and quotes.
+fn env_value(
+    keys: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    value: &str,
+) -> serde_json::Result<serde_json::Value> {
+    let value = serde_json::from_str(value)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    let with = |value: &serde_json::Value| {
+        let mut keys = keys.clone();
+        keys.insert(key.to_string(), value.clone());
+        parse(&keys)
+    };
+    let e = match with(&value) {
+        Ok(_) => return Ok(value),
+        Err(e) => e,
+    };
+    let serde_json::Value::String(text) = &value else {
+        return Err(e);
+    };
+    let list = serde_json::Value::Array(
+        text.split(',')
+            .map(|item| serde_json::Value::String(item.trim().to_string()))
+            .collect(),
+    );
+    match with(&list) {
+        Ok(_) => Ok(list),
+        // When the key takes a list, what's wrong with its items is the
+        // useful error.
+        Err(list_error) if e.to_string().contains("expected a sequence") => Err(list_error),
+        Err(_) => Err(e),
+    }
+}
+
+fn parse(keys: &serde_json::Map<String, serde_json::Value>) -> serde_json::Result<Config> {
+    serde_json::from_value(serde_json::Value::Object(keys.clone()))
+}
+
+/// Where `"key":` first appears in `text`. Good enough to point at, since
+/// keys are rarely repeated in a config this small.
+fn position(text: &str, key: &str) -> Option<(usize, usize)> {
+    let quoted = format!("\"{}\"", key);
+    let offset = text.match_indices(&quoted).find_map(|(offset, _)| {
+        let rest = text[offset + quoted.len()..].trim_start();
+        rest.starts_with(':').then_some(offset)
+    })?;
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or(before).chars().count() + 1;
+    Some((line, column))
+}
+
+/// The keys [`Config`] reads, taken from its `Deserialize` impl so the list
+/// can't fall behind the struct: deserializing from [`FieldNames`] hands
+/// them over and then gives up.
+fn known_keys() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = Config::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("only struct fields are collected"))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("only struct fields are collected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+        identifier ignored_any
+    }
+}
+
+/// `config check`: says whether the config file has mistakes, and exits with
+/// 1 if it does. The mistakes themselves were already listed on stderr when
+/// the config was loaded.
+pub fn run_check() {
+    let path = Config::path();
+    let problems = match Config::read() {
+        Ok((_, problems)) => problems.len(),
+        Err(_) => 1,
+    };
+    match (problems, path.is_file()) {
+        (0, true) => println!("{} is fine.", path.display()),
+        (0, false) => println!("There's no {}; the defaults are used.", path.display()),
+        (1, _) => println!("1 problem in the config."),
+        (n, _) => println!("{} problems in the config.", n),
+    }
+    if problems > 0 {
+        std::process::exit(1);
+    }
+}
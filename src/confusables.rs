@@ -0,0 +1,188 @@
+/// Commonly confused pairs and how to tell them apart in one line.
+const PAIRS: &[(&str, &str, &str)] = &[
+    (
+        "affect",
+        "effect",
+        "affect is usually the verb (to influence), effect the noun (a result)",
+    ),
+    (
+        "its",
+        "it's",
+        "its is possessive (its tail), it's means it is or it has",
+    ),
+    (
+        "lay",
+        "lie",
+        "you lay something down (takes an object); you lie down yourself",
+    ),
+    ("your", "you're", "your is possessive, you're means you are"),
+    ("their", "there", "their is possessive, there is a place"),
+    (
+        "their",
+        "they're",
+        "their is possessive, they're means they are",
+    ),
+    ("then", "than", "then is about time, than compares"),
+    (
+        "loose",
+        "lose",
+        "loose is not tight, lose is to misplace or fail to win",
+    ),
+    (
+        "accept",
+        "except",
+        "accept is to receive, except means excluding",
+    ),
+    ("advice", "advise", "advice is the noun, advise the verb"),
+    (
+        "principal",
+        "principle",
+        "a principal is a head or main thing, a principle a rule or belief",
+    ),
+    (
+        "complement",
+        "compliment",
+        "a complement completes something, a compliment is praise",
+    ),
+    (
+        "stationary",
+        "stationery",
+        "stationary means not moving, stationery is paper and pens",
+    ),
+    (
+        "discreet",
+        "discrete",
+        "discreet is tactful, discrete is separate",
+    ),
+    (
+        "ensure",
+        "insure",
+        "ensure is to make certain, insure is to take out insurance",
+    ),
+    (
+        "farther",
+        "further",
+        "farther is physical distance, further is figurative or additional",
+    ),
+    (
+        "fewer",
+        "less",
+        "fewer for things you count, less for amounts",
+    ),
+    ("imply", "infer", "the speaker implies, the listener infers"),
+    (
+        "emigrate",
+        "immigrate",
+        "you emigrate from a country and immigrate to one",
+    ),
+    (
+        "eminent",
+        "imminent",
+        "eminent is distinguished, imminent is about to happen",
+    ),
+    (
+        "elicit",
+        "illicit",
+        "elicit is to draw out, illicit is unlawful",
+    ),
+    (
+        "allude",
+        "elude",
+        "allude is to hint at, elude is to escape",
+    ),
+    ("breath", "breathe", "breath is the noun, breathe the verb"),
+    (
+        "desert",
+        "dessert",
+        "a desert is dry land, dessert is the sweet course",
+    ),
+    (
+        "capital",
+        "capitol",
+        "a capital is a city or money, a capitol a legislative building",
+    ),
+    (
+        "compose",
+        "comprise",
+        "parts compose the whole; the whole comprises its parts",
+    ),
+    (
+        "historic",
+        "historical",
+        "historic means important in history, historical means about the past",
+    ),
+    (
+        "economic",
+        "economical",
+        "economic is about the economy, economical means thrifty",
+    ),
+    (
+        "who's",
+        "whose",
+        "who's means who is or who has, whose is possessive",
+    ),
+    (
+        "to",
+        "too",
+        "to is a preposition, too means also or excessively",
+    ),
+    (
+        "weather",
+        "whether",
+        "weather is rain and sun, whether introduces alternatives",
+    ),
+    ("peak", "peek", "a peak is a summit, a peek a quick look"),
+    (
+        "peak",
+        "pique",
+        "a peak is a summit; to pique is to arouse interest",
+    ),
+    ("site", "cite", "a site is a place, to cite is to quote"),
+    (
+        "sight",
+        "site",
+        "a sight is something seen, a site is a place",
+    ),
+    (
+        "definitely",
+        "defiantly",
+        "definitely is certainly, defiantly is in open resistance",
+    ),
+    (
+        "flaunt",
+        "flout",
+        "flaunt is to show off, flout is to disregard a rule",
+    ),
+    (
+        "averse",
+        "adverse",
+        "averse describes a person's dislike, adverse harmful conditions",
+    ),
+    (
+        "council",
+        "counsel",
+        "a council is a body of people, counsel is advice or a lawyer",
+    ),
+    (
+        "moral",
+        "morale",
+        "moral is about right and wrong, morale is confidence",
+    ),
+];
+
+/// The words `word` is often confused with, each with a hint that tells them
+/// apart. The table is English-only.
+pub fn confused_with(word: &str, lang: &str) -> Vec<(&'static str, &'static str)> {
+    if lang != "en" {
+        return Vec::new();
+    }
+    let word = word.trim().to_lowercase().replace('’', "'");
+    PAIRS
+        .iter()
+        .filter_map(|&(a, b, hint)| match word.as_str() {
+            w if w == a => Some((b, hint)),
+            w if w == b => Some((a, hint)),
+            _ => None,
+        })
+        .collect()
+}
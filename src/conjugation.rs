@@ -0,0 +1,1450 @@
+/// The languages [`conjugate`] knows.
+pub const LANGUAGES: &[&str] = &["de", "fr", "es"];
+
+/// A verb's conjugation, labelled in its own language, as learners of it
+/// know the tenses.
+pub struct Conjugation {
+    /// The infinitive, the participles and the auxiliary.
+    pub forms: Vec<(&'static str, String)>,
+    pub persons: [&'static str; 6],
+    /// Each tense with its form for each person, in the order they're
+    /// taught. Persons a tense doesn't have, like the imperative's first,
+    /// are empty.
+    pub tenses: Vec<(&'static str, [String; 6])>,
+}
+
+/// The conjugation of `verb` in `lang`, if it looks like an infinitive of
+/// that language. Regular verbs follow the rules, spelling changes
+/// included; the common irregular ones, and verbs made from them with a
+/// prefix, come from tables.
+pub fn conjugate(verb: &str, lang: &str) -> Option<Conjugation> {
+    match lang {
+        "de" => german(verb),
+        "fr" => french(verb),
+        "es" => spanish(verb),
+        _ => None,
+    }
+}
+
+/// The six words of a table cell list.
+fn six(words: &str) -> [String; 6] {
+    let words: Vec<String> = words.split_whitespace().map(String::from).collect();
+    words.try_into().unwrap_or_default()
+}
+
+/// `stem` with each of `endings`.
+fn with(stem: &str, endings: [&str; 6]) -> [String; 6] {
+    endings.map(|ending| format!("{}{}", stem, ending))
+}
+
+/// Each of `forms` after `before`, with a space between.
+fn after(before: &[String; 6], forms: impl Fn(usize) -> String) -> [String; 6] {
+    std::array::from_fn(|i| format!("{} {}", before[i], forms(i)))
+}
+
+/// `prefix` before each form.
+fn prefixed(prefix: &str, forms: &[String; 6]) -> [String; 6] {
+    forms.clone().map(|form| format!("{}{}", prefix, form))
+}
+
+/// The entry of `table` for `verb`: its own, or that of the verb it's made
+/// from with a prefix when that one's infinitive starts with `+`, along with
+/// the prefix. Fields are separated by `|`.
+fn irregular<'a>(table: &[&'a str], verb: &'a str) -> Option<(&'a str, Vec<&'a str>)> {
+    let fields = |entry: &'a str| entry.trim_start_matches('+').split('|').collect::<Vec<_>>();
+    if let Some(entry) = table.iter().find(|entry| fields(entry)[0] == verb) {
+        return Some(("", fields(entry)));
+    }
+    table
+        .iter()
+        .filter(|entry| entry.starts_with('+'))
+        .find_map(|entry| {
+            let fields = fields(entry);
+            let prefix = verb.strip_suffix(fields[0]).filter(|p| !p.is_empty())?;
+            Some((prefix, fields))
+        })
+}
+
+// French
+
+const FRENCH_PERSONS: [&str; 6] = ["je/j'", "tu", "il/elle", "nous", "vous", "ils/elles"];
+
+/// French verbs that don't follow the rules: the infinitive, the present,
+/// the imperfect's stem, the first person of the simple past, the future's
+/// stem, the present subjunctive, the participles and the auxiliary. A `+`
+/// marks one whose prefixed verbs go the same way, like "revenir" and
+/// "comprendre"; some are only ever used with a prefix.
+const FRENCH: &[&str] = &[
+    "être|suis es est sommes êtes sont|ét|fus|ser|sois sois soit soyons soyez soient|étant|été|avoir",
+    "avoir|ai as a avons avez ont|av|eus|aur|aie aies ait ayons ayez aient|ayant|eu|avoir",
+    "aller|vais vas va allons allez vont|all|allai|ir|aille ailles aille allions alliez aillent|allant|allé|être",
+    "+faire|fais fais fait faisons faites font|fais|fis|fer|fasse fasses fasse fassions fassiez fassent|faisant|fait|avoir",
+    "dire|dis dis dit disons dites disent|dis|dis|dir|dise dises dise disions disiez disent|disant|dit|avoir",
+    "pouvoir|peux peux peut pouvons pouvez peuvent|pouv|pus|pourr|puisse puisses puisse puissions puissiez puissent|pouvant|pu|avoir",
+    "vouloir|veux veux veut voulons voulez veulent|voul|voulus|voudr|veuille veuilles veuille voulions vouliez veuillent|voulant|voulu|avoir",
+    "savoir|sais sais sait savons savez savent|sav|sus|saur|sache saches sache sachions sachiez sachent|sachant|su|avoir",
+    "devoir|dois dois doit devons devez doivent|dev|dus|devr|doive doives doive devions deviez doivent|devant|dû|avoir",
+    "+cevoir|çois çois çoit cevons cevez çoivent|cev|çus|cevr|çoive çoives çoive cevions ceviez çoivent|cevant|çu|avoir",
+    "+voir|vois vois voit voyons voyez voient|voy|vis|verr|voie voies voie voyions voyiez voient|voyant|vu|avoir",
+    "croire|crois crois croit croyons croyez croient|croy|crus|croir|croie croies croie croyions croyiez croient|croyant|cru|avoir",
+    "+venir|viens viens vient venons venez viennent|ven|vins|viendr|vienne viennes vienne venions veniez viennent|venant|venu|être",
+    "+tenir|tiens tiens tient tenons tenez tiennent|ten|tins|tiendr|tienne tiennes tienne tenions teniez tiennent|tenant|tenu|avoir",
+    "+prendre|prends prends prend prenons prenez prennent|pren|pris|prendr|prenne prennes prenne prenions preniez prennent|prenant|pris|avoir",
+    "+mettre|mets mets met mettons mettez mettent|mett|mis|mettr|mette mettes mette mettions mettiez mettent|mettant|mis|avoir",
+    "+battre|bats bats bat battons battez battent|batt|battis|battr|batte battes batte battions battiez battent|battant|battu|avoir",
+    "+rompre|romps romps rompt rompons rompez rompent|romp|rompis|rompr|rompe rompes rompe rompions rompiez rompent|rompant|rompu|avoir",
+    "+naître|nais nais naît naissons naissez naissent|naiss|naquis|naîtr|naisse naisses naisse naissions naissiez naissent|naissant|né|être",
+    "+aître|ais ais aît aissons aissez aissent|aiss|us|aîtr|aisse aisses aisse aissions aissiez aissent|aissant|u|avoir",
+    "+crire|cris cris crit crivons crivez crivent|criv|crivis|crir|crive crives crive crivions criviez crivent|crivant|crit|avoir",
+    "+lire|lis lis lit lisons lisez lisent|lis|lus|lir|lise lises lise lisions lisiez lisent|lisant|lu|avoir",
+    "boire|bois bois boit buvons buvez boivent|buv|bus|boir|boive boives boive buvions buviez boivent|buvant|bu|avoir",
+    "+vivre|vis vis vit vivons vivez vivent|viv|vécus|vivr|vive vives vive vivions viviez vivent|vivant|vécu|avoir",
+    "+suivre|suis suis suit suivons suivez suivent|suiv|suivis|suivr|suive suives suive suivions suiviez suivent|suivant|suivi|avoir",
+    "+partir|pars pars part partons partez partent|part|partis|partir|parte partes parte partions partiez partent|partant|parti|être",
+    "sortir|sors sors sort sortons sortez sortent|sort|sortis|sortir|sorte sortes sorte sortions sortiez sortent|sortant|sorti|être",
+    "+dormir|dors dors dort dormons dormez dorment|dorm|dormis|dormir|dorme dormes dorme dormions dormiez dorment|dormant|dormi|avoir",
+    "+sentir|sens sens sent sentons sentez sentent|sent|sentis|sentir|sente sentes sente sentions sentiez sentent|sentant|senti|avoir",
+    "+servir|sers sers sert servons servez servent|serv|servis|servir|serve serves serve servions serviez servent|servant|servi|avoir",
+    "mentir|mens mens ment mentons mentez mentent|ment|mentis|mentir|mente mentes mente mentions mentiez mentent|mentant|menti|avoir",
+    "+ouvrir|ouvre ouvres ouvre ouvrons ouvrez ouvrent|ouvr|ouvris|ouvrir|ouvre ouvres ouvre ouvrions ouvriez ouvrent|ouvrant|ouvert|avoir",
+    "+couvrir|couvre couvres couvre couvrons couvrez couvrent|couvr|couvris|couvrir|couvre couvres couvre couvrions couvriez couvrent|couvrant|couvert|avoir",
+    "offrir|offre offres offre offrons offrez offrent|offr|offris|offrir|offre offres offre offrions offriez offrent|offrant|offert|avoir",
+    "souffrir|souffre souffres souffre souffrons souffrez souffrent|souffr|souffris|souffrir|souffre souffres souffre souffrions souffriez souffrent|souffrant|souffert|avoir",
+    "+courir|cours cours court courons courez courent|cour|courus|courr|coure coures coure courions couriez courent|courant|couru|avoir",
+    "mourir|meurs meurs meurt mourons mourez meurent|mour|mourus|mourr|meure meures meure mourions mouriez meurent|mourant|mort|être",
+    "+envoyer|envoie envoies envoie envoyons envoyez envoient|envoy|envoyai|enverr|envoie envoies envoie envoyions envoyiez envoient|envoyant|envoyé|avoir",
+    "+aindre|ains ains aint aignons aignez aignent|aign|aignis|aindr|aigne aignes aigne aignions aigniez aignent|aignant|aint|avoir",
+    "+eindre|eins eins eint eignons eignez eignent|eign|eignis|eindr|eigne eignes eigne eignions eigniez eignent|eignant|eint|avoir",
+    "+oindre|oins oins oint oignons oignez oignent|oign|oignis|oindr|oigne oignes oigne oignions oigniez oignent|oignant|oint|avoir",
+    "+uire|uis uis uit uisons uisez uisent|uis|uisis|uir|uise uises uise uisions uisiez uisent|uisant|uit|avoir",
+];
+
+/// Verbs whose imperative is their subjunctive's.
+const FRENCH_IMPERATIVES: &[(&str, &str)] = &[
+    ("être", "sois soyons soyez"),
+    ("avoir", "aie ayons ayez"),
+    ("savoir", "sache sachons sachez"),
+    ("vouloir", "veuille veuillons veuillez"),
+];
+
+/// Regular verbs that take être: the verbs of motion and change of state.
+const FRENCH_ETRE: &[&str] = &[
+    "arriver",
+    "entrer",
+    "rentrer",
+    "rester",
+    "tomber",
+    "retomber",
+    "retourner",
+    "monter",
+    "descendre",
+    "décéder",
+];
+
+/// -eler and -eter verbs that double the consonant rather than taking a
+/// grave accent: j'appelle, je jette.
+const FRENCH_DOUBLING: &[&str] = &["appeler", "jeter", "épeler", "renouveler", "feuilleter"];
+
+struct French {
+    present: [String; 6],
+    imperfect: [String; 6],
+    simple_past: [String; 6],
+    /// What the future and conditional endings go on.
+    future: String,
+    subjunctive: [String; 6],
+    present_participle: String,
+    past_participle: String,
+    auxiliary: &'static str,
+}
+
+fn french(verb: &str) -> Option<Conjugation> {
+    let parts = match irregular(FRENCH, verb) {
+        Some((prefix, fields)) => {
+            let imperfect = with(fields[2], ["ais", "ais", "ait", "ions", "iez", "aient"]);
+            French {
+                present: prefixed(prefix, &six(fields[1])),
+                imperfect: prefixed(prefix, &imperfect),
+                simple_past: prefixed(prefix, &french_simple_past(fields[3])),
+                future: format!("{}{}", prefix, fields[4]),
+                subjunctive: prefixed(prefix, &six(fields[5])),
+                present_participle: format!("{}{}", prefix, fields[6]),
+                past_participle: format!("{}{}", prefix, fields[7]),
+                auxiliary: match fields[8] {
+                    "être" => "être",
+                    _ => "avoir",
+                },
+            }
+        }
+        None => french_regular(verb)?,
+    };
+
+    let present = |aux: &str| match aux {
+        "être" => six("suis es est sommes êtes sont"),
+        _ => six("ai as a avons avez ont"),
+    };
+    let imperfect = |aux: &str| match aux {
+        "être" => six("étais étais était étions étiez étaient"),
+        _ => six("avais avais avait avions aviez avaient"),
+    };
+    // With être, the participle agrees with the subject.
+    let participle = |i: usize| match (parts.auxiliary, i) {
+        ("être", 0..=2) => format!("{}(e)", parts.past_participle),
+        ("être", _) => format!("{}(e)s", parts.past_participle),
+        _ => parts.past_participle.clone(),
+    };
+    let imperative = match FRENCH_IMPERATIVES.iter().find(|(v, _)| *v == verb) {
+        Some((_, forms)) => forms.split_whitespace().map(String::from).collect(),
+        None => {
+            let tu = &parts.present[1];
+            let tu = match tu.ends_with("es") || tu.ends_with("as") {
+                true => tu[..tu.len() - 1].to_string(),
+                false => tu.clone(),
+            };
+            vec![tu, parts.present[3].clone(), parts.present[4].clone()]
+        }
+    };
+    let future = with(&parts.future, ["ai", "as", "a", "ons", "ez", "ont"]);
+    let conditional = with(&parts.future, ["ais", "ais", "ait", "ions", "iez", "aient"]);
+    let tenses = vec![
+        ("présent", parts.present.clone()),
+        ("imparfait", parts.imperfect.clone()),
+        ("passé simple", parts.simple_past.clone()),
+        ("futur", future),
+        ("conditionnel", conditional),
+        ("subjonctif", parts.subjunctive.clone()),
+        (
+            "passé composé",
+            after(&present(parts.auxiliary), participle),
+        ),
+        (
+            "plus-que-parfait",
+            after(&imperfect(parts.auxiliary), participle),
+        ),
+        ("impératif", persons_of(&imperative, [1, 3, 4])),
+    ];
+    Some(Conjugation {
+        forms: vec![
+            ("infinitif", verb.to_string()),
+            ("participe présent", parts.present_participle),
+            ("participe passé", parts.past_participle),
+            ("auxiliaire", parts.auxiliary.to_string()),
+        ],
+        persons: FRENCH_PERSONS,
+        tenses,
+    })
+}
+
+/// `forms` in the cells of `persons`, the other cells empty.
+fn persons_of(forms: &[String], persons: [usize; 3]) -> [String; 6] {
+    let mut cells = <[String; 6]>::default();
+    for (form, person) in forms.iter().zip(persons) {
+        cells[person] = form.clone();
+    }
+    cells
+}
+
+/// The simple past from its first person: "-ai" verbs go like parler, the
+/// others like finir, voulus or vins.
+fn french_simple_past(first: &str) -> [String; 6] {
+    if let Some(stem) = first.strip_suffix("ai") {
+        return with(stem, ["ai", "as", "a", "âmes", "âtes", "èrent"]);
+    }
+    let types = [
+        ("ins", ["ins", "ins", "int", "înmes", "întes", "inrent"]),
+        ("is", ["is", "is", "it", "îmes", "îtes", "irent"]),
+        ("us", ["us", "us", "ut", "ûmes", "ûtes", "urent"]),
+    ];
+    types
+        .into_iter()
+        .find_map(|(ending, endings)| Some(with(first.strip_suffix(ending)?, endings)))
+        .unwrap_or_default()
+}
+
+/// The first group (parler), the second (finir) and the -dre verbs of the
+/// third (vendre).
+fn french_regular(verb: &str) -> Option<French> {
+    let auxiliary = match FRENCH_ETRE.contains(&verb) {
+        true => "être",
+        false => "avoir",
+    };
+    if let Some(stem) = verb.strip_suffix("er") {
+        // Before a and o: commençons, mangeons.
+        let soft = match (stem.strip_suffix('c'), stem.ends_with('g')) {
+            (Some(base), _) => format!("{}ç", base),
+            (None, true) => format!("{}e", stem),
+            (None, false) => stem.to_string(),
+        };
+        // Before an e that isn't pronounced: paie, appelle, lève, préfère.
+        // The future keeps préférer's é.
+        let (strong, future) = if let Some(base) = stem.strip_suffix('y') {
+            let strong = format!("{}i", base);
+            (strong.clone(), format!("{}er", strong))
+        } else if FRENCH_DOUBLING.iter().any(|v| verb.ends_with(v)) {
+            let strong = format!("{}{}", stem, stem.chars().last()?);
+            (strong.clone(), format!("{}er", strong))
+        } else {
+            match french_grave(stem) {
+                Some((strong, false)) => (strong.clone(), format!("{}er", strong)),
+                Some((strong, true)) => (strong, verb.to_string()),
+                None => (stem.to_string(), verb.to_string()),
+            }
+        };
+        return Some(French {
+            present: [
+                format!("{}e", strong),
+                format!("{}es", strong),
+                format!("{}e", strong),
+                format!("{}ons", soft),
+                format!("{}ez", stem),
+                format!("{}ent", strong),
+            ],
+            imperfect: [
+                format!("{}ais", soft),
+                format!("{}ais", soft),
+                format!("{}ait", soft),
+                format!("{}ions", stem),
+                format!("{}iez", stem),
+                format!("{}aient", soft),
+            ],
+            simple_past: [
+                format!("{}ai", soft),
+                format!("{}as", soft),
+                format!("{}a", soft),
+                format!("{}âmes", soft),
+                format!("{}âtes", soft),
+                format!("{}èrent", stem),
+            ],
+            future,
+            subjunctive: [
+                format!("{}e", strong),
+                format!("{}es", strong),
+                format!("{}e", strong),
+                format!("{}ions", stem),
+                format!("{}iez", stem),
+                format!("{}ent", strong),
+            ],
+            present_participle: format!("{}ant", soft),
+            past_participle: format!("{}é", stem),
+            auxiliary,
+        });
+    }
+    if let Some(stem) = verb.strip_suffix("ir") {
+        let long = format!("{}iss", stem);
+        return Some(French {
+            present: with(stem, ["is", "is", "it", "issons", "issez", "issent"]),
+            imperfect: with(&long, ["ais", "ais", "ait", "ions", "iez", "aient"]),
+            simple_past: with(stem, ["is", "is", "it", "îmes", "îtes", "irent"]),
+            future: verb.to_string(),
+            subjunctive: with(&long, ["e", "es", "e", "ions", "iez", "ent"]),
+            present_participle: format!("{}ant", long),
+            past_participle: format!("{}i", stem),
+            auxiliary,
+        });
+    }
+    let stem = verb.strip_suffix("re").filter(|stem| stem.ends_with('d'))?;
+    Some(French {
+        present: with(stem, ["s", "s", "", "ons", "ez", "ent"]),
+        imperfect: with(stem, ["ais", "ais", "ait", "ions", "iez", "aient"]),
+        simple_past: with(stem, ["is", "is", "it", "îmes", "îtes", "irent"]),
+        future: format!("{}r", stem),
+        subjunctive: with(stem, ["e", "es", "e", "ions", "iez", "ent"]),
+        present_participle: format!("{}ant", stem),
+        past_participle: format!("{}u", stem),
+        auxiliary,
+    })
+}
+
+/// `stem` with the e or é of its last syllable made è, when that syllable
+/// ends in one consonant (or a pair like "br" or "gn"): lev → lèv, préfér →
+/// préfèr. Whether the vowel was é, too.
+fn french_grave(stem: &str) -> Option<(String, bool)> {
+    let vowels = [
+        'a', 'e', 'é', 'è', 'ê', 'i', 'o', 'u', 'y', 'â', 'î', 'ô', 'û',
+    ];
+    let split = stem.rfind(|c| vowels.contains(&c))?;
+    let vowel = stem[split..].chars().next()?;
+    let consonants = &stem[split + vowel.len_utf8()..];
+    let single = consonants.chars().count() == 1
+        || ["gn", "ch"].contains(&consonants)
+        || (consonants.chars().count() == 2 && consonants.ends_with(['l', 'r']));
+    match (vowel, single) {
+        ('e' | 'é', true) => Some((format!("{}è{}", &stem[..split], consonants), vowel == 'é')),
+        _ => None,
+    }
+}
+
+// Spanish
+
+const SPANISH_PERSONS: [&str; 6] = [
+    "yo",
+    "tú",
+    "él/ella/usted",
+    "nosotros",
+    "vosotros",
+    "ellos/ustedes",
+];
+
+/// Spanish verbs that don't follow the rules: the infinitive, the present,
+/// the preterite, the present subjunctive, the imperfect, the future's stem,
+/// the gerund, the participle and the "tú" imperative. Empty fields follow
+/// the rules. A `+` marks one whose prefixed verbs go the same way, like
+/// "obtener" and "suponer".
+const SPANISH: &[&str] = &[
+    "ser|soy eres es somos sois son|fui fuiste fue fuimos fuisteis fueron|sea seas sea seamos seáis sean|era eras era éramos erais eran||siendo||sé",
+    "estar|estoy estás está estamos estáis están|estuve estuviste estuvo estuvimos estuvisteis estuvieron|esté estés esté estemos estéis estén|||||está",
+    "ir|voy vas va vamos vais van|fui fuiste fue fuimos fuisteis fueron|vaya vayas vaya vayamos vayáis vayan|iba ibas iba íbamos ibais iban||yendo|ido|ve",
+    "haber|he has ha hemos habéis han|hube hubiste hubo hubimos hubisteis hubieron|haya hayas haya hayamos hayáis hayan||habr|||he",
+    "+tener|tengo tienes tiene tenemos tenéis tienen|tuve tuviste tuvo tuvimos tuvisteis tuvieron|tenga tengas tenga tengamos tengáis tengan||tendr|||ten",
+    "+hacer|hago haces hace hacemos hacéis hacen|hice hiciste hizo hicimos hicisteis hicieron|haga hagas haga hagamos hagáis hagan||har||hecho|haz",
+    "poder|puedo puedes puede podemos podéis pueden|pude pudiste pudo pudimos pudisteis pudieron|pueda puedas pueda podamos podáis puedan||podr|pudiendo||puede",
+    "+decir|digo dices dice decimos decís dicen|dije dijiste dijo dijimos dijisteis dijeron|diga digas diga digamos digáis digan||dir|diciendo|dicho|di",
+    "querer|quiero quieres quiere queremos queréis quieren|quise quisiste quiso quisimos quisisteis quisieron|quiera quieras quiera queramos queráis quieran||querr|||quiere",
+    "saber|sé sabes sabe sabemos sabéis saben|supe supiste supo supimos supisteis supieron|sepa sepas sepa sepamos sepáis sepan||sabr|||sabe",
+    "+venir|vengo vienes viene venimos venís vienen|vine viniste vino vinimos vinisteis vinieron|venga vengas venga vengamos vengáis vengan||vendr|viniendo||ven",
+    "+poner|pongo pones pone ponemos ponéis ponen|puse pusiste puso pusimos pusisteis pusieron|ponga pongas ponga pongamos pongáis pongan||pondr||puesto|pon",
+    "dar|doy das da damos dais dan|di diste dio dimos disteis dieron|dé des dé demos deis den|||||da",
+    "ver|veo ves ve vemos veis ven|vi viste vio vimos visteis vieron|vea veas vea veamos veáis vean|veía veías veía veíamos veíais veían|||visto|ve",
+    "salir|salgo sales sale salimos salís salen||salga salgas salga salgamos salgáis salgan||saldr|||sal",
+    "+traer|traigo traes trae traemos traéis traen|traje trajiste trajo trajimos trajisteis trajeron|traiga traigas traiga traigamos traigáis traigan|||trayendo|traído|trae",
+    "caer|caigo caes cae caemos caéis caen|caí caíste cayó caímos caísteis cayeron|caiga caigas caiga caigamos caigáis caigan|||cayendo|caído|cae",
+    "oír|oigo oyes oye oímos oís oyen|oí oíste oyó oímos oísteis oyeron|oiga oigas oiga oigamos oigáis oigan||oir|oyendo|oído|oye",
+    "+reír|río ríes ríe reímos reís ríen|reí reíste rió reímos reísteis rieron|ría rías ría riamos riáis rían|||riendo|reído|ríe",
+    "valer|valgo vales vale valemos valéis valen||valga valgas valga valgamos valgáis valgan||valdr|||vale",
+];
+
+/// Verbs whose stressed stem vowel changes, and how: e → ie, o → ue, or
+/// e → i. Verbs made from them with a prefix change too.
+const SPANISH_STEM_CHANGES: &[(&str, &str)] = &[
+    ("pensar", "ie"),
+    ("cerrar", "ie"),
+    ("empezar", "ie"),
+    ("comenzar", "ie"),
+    ("despertar", "ie"),
+    ("sentar", "ie"),
+    ("calentar", "ie"),
+    ("nevar", "ie"),
+    ("negar", "ie"),
+    ("tender", "ie"),
+    ("perder", "ie"),
+    ("encender", "ie"),
+    ("defender", "ie"),
+    ("preferir", "ie"),
+    ("sentir", "ie"),
+    ("mentir", "ie"),
+    ("divertir", "ie"),
+    ("sugerir", "ie"),
+    ("contar", "ue"),
+    ("encontrar", "ue"),
+    ("costar", "ue"),
+    ("mostrar", "ue"),
+    ("recordar", "ue"),
+    ("probar", "ue"),
+    ("soñar", "ue"),
+    ("volar", "ue"),
+    ("sonar", "ue"),
+    ("almorzar", "ue"),
+    ("colgar", "ue"),
+    ("rogar", "ue"),
+    ("volver", "ue"),
+    ("mover", "ue"),
+    ("llover", "ue"),
+    ("doler", "ue"),
+    ("solver", "ue"),
+    ("dormir", "ue"),
+    ("morir", "ue"),
+    ("jugar", "ue"),
+    ("pedir", "i"),
+    ("servir", "i"),
+    ("repetir", "i"),
+    ("seguir", "i"),
+    ("vestir", "i"),
+    ("medir", "i"),
+    ("elegir", "i"),
+    ("corregir", "i"),
+];
+
+/// Participles that don't end in -ado or -ido, by the end of the infinitive.
+const SPANISH_PARTICIPLES: &[(&str, &str)] = &[
+    ("abrir", "abierto"),
+    ("cubrir", "cubierto"),
+    ("escribir", "escrito"),
+    ("romper", "roto"),
+    ("volver", "vuelto"),
+    ("solver", "suelto"),
+    ("morir", "muerto"),
+    ("imprimir", "impreso"),
+];
+
+struct Spanish {
+    present: [String; 6],
+    imperfect: [String; 6],
+    preterite: [String; 6],
+    /// What the future and conditional endings go on.
+    future: String,
+    subjunctive: [String; 6],
+    gerund: String,
+    participle: String,
+    /// The "tú" imperative.
+    imperative: String,
+}
+
+fn spanish(verb: &str) -> Option<Conjugation> {
+    let class = ["ar", "er", "ir", "ír"]
+        .into_iter()
+        .find(|class| verb.ends_with(class))?;
+    let stem = &verb[..verb.len() - class.len()];
+    let regular = spanish_regular(verb)?;
+    let parts = match irregular(SPANISH, verb) {
+        Some((prefix, fields)) => {
+            let or = |field: &str, regular: [String; 6]| match field {
+                "" => regular,
+                field => prefixed(prefix, &six(field)),
+            };
+            let or_one = |field: &str, regular: String| match field {
+                "" => regular,
+                field => format!("{}{}", prefix, field),
+            };
+            // Prefixed, a one-syllable imperative takes an accent: obtén.
+            let imperative = match (prefix, fields[8].strip_suffix('n')) {
+                ("", _) | (_, None) => or_one(fields[8], regular.imperative.clone()),
+                (_, Some(base)) => format!("{}{}n", prefix, acute(base)),
+            };
+            Spanish {
+                present: or(fields[1], regular.present),
+                preterite: or(fields[2], regular.preterite),
+                subjunctive: or(fields[3], regular.subjunctive),
+                imperfect: or(fields[4], regular.imperfect),
+                future: or_one(fields[5], regular.future),
+                gerund: or_one(fields[6], regular.gerund),
+                participle: or_one(fields[7], regular.participle),
+                imperative,
+            }
+        }
+        None => regular,
+    };
+
+    let future = with(&parts.future, ["é", "ás", "á", "emos", "éis", "án"]);
+    let conditional = with(&parts.future, ["ía", "ías", "ía", "íamos", "íais", "ían"]);
+    let perfect = after(&six("he has ha hemos habéis han"), |_| {
+        parts.participle.clone()
+    });
+    let pluperfect = after(
+        &six("había habías había habíamos habíais habían"),
+        |_| parts.participle.clone(),
+    );
+    let subjunctive = &parts.subjunctive;
+    let vosotros = match stem {
+        "" => String::from("id"),
+        _ => format!("{}d", &verb[..verb.len() - 1]),
+    };
+    let imperative = [
+        String::new(),
+        parts.imperative.clone(),
+        subjunctive[2].clone(),
+        subjunctive[3].clone(),
+        vosotros,
+        subjunctive[5].clone(),
+    ];
+    let tenses = vec![
+        ("presente", parts.present.clone()),
+        ("imperfecto", parts.imperfect.clone()),
+        ("indefinido", parts.preterite.clone()),
+        ("futuro", future),
+        ("condicional", conditional),
+        ("subjuntivo", parts.subjunctive.clone()),
+        ("perfecto", perfect),
+        ("pluscuamperfecto", pluperfect),
+        ("imperativo", imperative),
+    ];
+    Some(Conjugation {
+        forms: vec![
+            ("infinitivo", verb.to_string()),
+            ("gerundio", parts.gerund),
+            ("participio", parts.participle),
+        ],
+        persons: SPANISH_PERSONS,
+        tenses,
+    })
+}
+
+/// `syllable` with an acute accent on its vowel.
+fn acute(syllable: &str) -> String {
+    syllable
+        .chars()
+        .map(|c| match c {
+            'a' => 'á',
+            'e' => 'é',
+            'i' => 'í',
+            'o' => 'ó',
+            'u' => 'ú',
+            c => c,
+        })
+        .collect()
+}
+
+/// A regular -ar, -er or -ir verb, with its stem change if it has one.
+fn spanish_regular(verb: &str) -> Option<Spanish> {
+    let (stem, class) = match verb.strip_suffix("ír") {
+        Some(stem) => (stem, "ir"),
+        None => (&verb[..verb.len().checked_sub(2)?], &verb[verb.len() - 2..]),
+    };
+    let (change, short) = match SPANISH_STEM_CHANGES
+        .iter()
+        .find(|(base, _)| verb.ends_with(base))
+        .map(|(_, change)| *change)
+    {
+        // -ir verbs change in the preterite, gerund and some of the
+        // subjunctive too, to i or u: prefirió, durmiendo.
+        Some("ie") => ("ie", (class == "ir").then_some("i")),
+        Some("ue") => ("ue", (class == "ir").then_some("u")),
+        Some(_) => ("i", Some("i")),
+        None => ("", None),
+    };
+    // The vowel that changes: the last e or o of the stem, or jugar's u.
+    let changed = |to: &str| -> String {
+        let from = match (to, verb) {
+            (_, "jugar") => 'u',
+            ("ue" | "u", _) => 'o',
+            _ => 'e',
+        };
+        match stem.rfind(from) {
+            Some(i) => format!("{}{}{}", &stem[..i], to, &stem[i + 1..]),
+            None => stem.to_string(),
+        }
+    };
+    let (strong, weak) = match change {
+        "" => (stem.to_string(), stem.to_string()),
+        change => (changed(change), short.map_or(stem.to_string(), changed)),
+    };
+    // Stressed stems in the singular and third plural of the presents.
+    let present_stem = |person: usize| match person {
+        3 | 4 => stem,
+        _ => strong.as_str(),
+    };
+    let subjunctive_stem = |person: usize| match person {
+        3 | 4 => weak.as_str(),
+        _ => strong.as_str(),
+    };
+    let (present, subjunctive, imperfect, preterite) = match class {
+        "ar" => (
+            ["o", "as", "a", "amos", "áis", "an"],
+            ["e", "es", "e", "emos", "éis", "en"],
+            ["aba", "abas", "aba", "ábamos", "abais", "aban"],
+            ["é", "aste", "ó", "amos", "asteis", "aron"],
+        ),
+        "er" => (
+            ["o", "es", "e", "emos", "éis", "en"],
+            ["a", "as", "a", "amos", "áis", "an"],
+            ["ía", "ías", "ía", "íamos", "íais", "ían"],
+            ["í", "iste", "ió", "imos", "isteis", "ieron"],
+        ),
+        _ => (
+            ["o", "es", "e", "imos", "ís", "en"],
+            ["a", "as", "a", "amos", "áis", "an"],
+            ["ía", "ías", "ía", "íamos", "íais", "ían"],
+            ["í", "iste", "ió", "imos", "isteis", "ieron"],
+        ),
+    };
+    let present: [String; 6] = std::array::from_fn(|i| attach(present_stem(i), present[i], verb));
+    let subjunctive: [String; 6] =
+        std::array::from_fn(|i| attach(subjunctive_stem(i), subjunctive[i], verb));
+    let imperfect = imperfect.map(|ending| attach(stem, ending, verb));
+    let preterite: [String; 6] = match verb.strip_suffix("ducir") {
+        // conduje, tradujiste.
+        Some(base) => with(
+            &format!("{}duj", base),
+            ["e", "iste", "o", "imos", "isteis", "eron"],
+        ),
+        None => std::array::from_fn(|i| {
+            let stem = match i {
+                2 | 5 => weak.as_str(),
+                _ => stem,
+            };
+            attach(stem, preterite[i], verb)
+        }),
+    };
+    let participle = SPANISH_PARTICIPLES
+        .iter()
+        .find_map(|(ending, participle)| {
+            let base = verb.strip_suffix(ending)?;
+            let participle = match (ending, base.is_empty()) {
+                // Volver is vuelto, but devolver devuelto.
+                (_, true) => participle.to_string(),
+                (_, false) => format!("{}{}", base, participle),
+            };
+            Some(participle)
+        })
+        .unwrap_or_else(|| {
+            attach(
+                stem,
+                match class {
+                    "ar" => "ado",
+                    _ => "ido",
+                },
+                verb,
+            )
+        });
+    let gerund = attach(
+        &weak,
+        match class {
+            "ar" => "ando",
+            _ => "iendo",
+        },
+        verb,
+    );
+    Some(Spanish {
+        imperative: present[2].clone(),
+        present,
+        imperfect,
+        preterite,
+        future: verb.replace('í', "i"),
+        subjunctive,
+        gerund,
+        participle,
+    })
+}
+
+/// `stem` and `ending` joined with the spelling changes that keep the
+/// stem's sound: busqué, pagué, empecé, cojo, sigo, conozco, construyo,
+/// leyó, leímos.
+fn attach(stem: &str, ending: &str, verb: &str) -> String {
+    let first = ending.chars().next().unwrap_or_default();
+    let front = matches!(first, 'e' | 'é');
+    let back = matches!(first, 'a' | 'o' | 'á' | 'ó');
+    let last = stem.chars().last().unwrap_or_default();
+    let base = &stem[..stem.len() - last.len_utf8().min(stem.len())];
+    let stem = match (verb, front, back) {
+        (v, true, _) if v.ends_with("car") => format!("{}qu", base),
+        (v, true, _) if v.ends_with("gar") => format!("{}gu", base),
+        (v, true, _) if v.ends_with("zar") => format!("{}c", base),
+        (v, _, true) if v.ends_with("ger") || v.ends_with("gir") => format!("{}j", base),
+        (v, _, true) if v.ends_with("guir") => base.to_string(),
+        (v, _, true) if v.ends_with("cer") || v.ends_with("cir") => {
+            match base.ends_with(['a', 'e', 'i', 'o', 'u']) {
+                true => format!("{}zc", base),
+                false => format!("{}z", base),
+            }
+        }
+        (v, _, _) if v.ends_with("uir") && !v.ends_with("guir") && (front || back) => {
+            format!("{}y", stem)
+        }
+        _ => stem.to_string(),
+    };
+    // An unstressed i between vowels is y (leyó, construyendo); a stressed
+    // one after a, e or o takes an accent (leímos, caído).
+    let vowel_before =
+        stem.ends_with(['a', 'e', 'o', 'u']) && !stem.ends_with("gu") && !stem.ends_with("qu");
+    let mut chars = ending.chars();
+    match (chars.next(), chars.next()) {
+        (Some('i'), Some(next)) if vowel_before && "aeoó".contains(next) => {
+            format!("{}y{}", stem, &ending[1..])
+        }
+        (Some('i'), _) if stem.ends_with(['a', 'e', 'o']) => format!("{}í{}", stem, &ending[1..]),
+        _ => format!("{}{}", stem, ending),
+    }
+}
+
+// German
+
+const GERMAN_PERSONS: [&str; 6] = ["ich", "du", "er/sie/es", "wir", "ihr", "sie/Sie"];
+
+/// German strong, mixed and irregular verbs: the infinitive, the present
+/// (its six forms when irregular throughout, else the stem the second and
+/// third person singular change to, if they do), the first person of the
+/// past, the past participle, the auxiliary and the first person of the
+/// Konjunktiv II.
+const GERMAN: &[&str] = &[
+    "sein|bin bist ist sind seid sind|war|gewesen|sein|wäre",
+    "haben|habe hast hat haben habt haben|hatte|gehabt|haben|hätte",
+    "werden|werde wirst wird werden werdet werden|wurde|geworden|sein|würde",
+    "wissen|weiß weißt weiß wissen wisst wissen|wusste|gewusst|haben|wüsste",
+    "tun|tue tust tut tun tut tun|tat|getan|haben|täte",
+    "können|kann kannst kann können könnt können|konnte|gekonnt|haben|könnte",
+    "müssen|muss musst muss müssen müsst müssen|musste|gemusst|haben|müsste",
+    "dürfen|darf darfst darf dürfen dürft dürfen|durfte|gedurft|haben|dürfte",
+    "mögen|mag magst mag mögen mögt mögen|mochte|gemocht|haben|möchte",
+    "sollen|soll sollst soll sollen sollt sollen|sollte|gesollt|haben|sollte",
+    "wollen|will willst will wollen wollt wollen|wollte|gewollt|haben|wollte",
+    "gehen||ging|gegangen|sein|ginge",
+    "kommen||kam|gekommen|sein|käme",
+    "fahren|fähr|fuhr|gefahren|sein|führe",
+    "geben|gib|gab|gegeben|haben|gäbe",
+    "nehmen|nimm|nahm|genommen|haben|nähme",
+    "sehen|sieh|sah|gesehen|haben|sähe",
+    "lesen|lies|las|gelesen|haben|läse",
+    "essen|iss|aß|gegessen|haben|äße",
+    "vergessen|vergiss|vergaß|vergessen|haben|vergäße",
+    "sprechen|sprich|sprach|gesprochen|haben|spräche",
+    "helfen|hilf|half|geholfen|haben|hülfe",
+    "treffen|triff|traf|getroffen|haben|träfe",
+    "sterben|stirb|starb|gestorben|sein|stürbe",
+    "werfen|wirf|warf|geworfen|haben|würfe",
+    "brechen|brich|brach|gebrochen|haben|bräche",
+    "empfehlen|empfiehl|empfahl|empfohlen|haben|empfähle",
+    "stehlen|stiehl|stahl|gestohlen|haben|stähle",
+    "treten|tritt|trat|getreten|sein|träte",
+    "schlafen|schläf|schlief|geschlafen|haben|schliefe",
+    "laufen|läuf|lief|gelaufen|sein|liefe",
+    "halten|hält|hielt|gehalten|haben|hielte",
+    "lassen|läss|ließ|gelassen|haben|ließe",
+    "fallen|fäll|fiel|gefallen|sein|fiele",
+    "fangen|fäng|fing|gefangen|haben|finge",
+    "tragen|träg|trug|getragen|haben|trüge",
+    "schlagen|schläg|schlug|geschlagen|haben|schlüge",
+    "waschen|wäsch|wusch|gewaschen|haben|wüsche",
+    "wachsen|wächs|wuchs|gewachsen|sein|wüchse",
+    "laden|läd|lud|geladen|haben|lüde",
+    "finden||fand|gefunden|haben|fände",
+    "trinken||trank|getrunken|haben|tränke",
+    "singen||sang|gesungen|haben|sänge",
+    "springen||sprang|gesprungen|sein|spränge",
+    "beginnen||begann|begonnen|haben|begänne",
+    "gewinnen||gewann|gewonnen|haben|gewänne",
+    "schwimmen||schwamm|geschwommen|sein|schwämme",
+    "bleiben||blieb|geblieben|sein|bliebe",
+    "schreiben||schrieb|geschrieben|haben|schriebe",
+    "steigen||stieg|gestiegen|sein|stiege",
+    "scheinen||schien|geschienen|haben|schiene",
+    "schneiden||schnitt|geschnitten|haben|schnitte",
+    "greifen||griff|gegriffen|haben|griffe",
+    "fliegen||flog|geflogen|sein|flöge",
+    "ziehen||zog|gezogen|haben|zöge",
+    "lügen||log|gelogen|haben|löge",
+    "verlieren||verlor|verloren|haben|verlöre",
+    "schließen||schloss|geschlossen|haben|schlösse",
+    "bieten||bot|geboten|haben|böte",
+    "stehen||stand|gestanden|haben|stünde",
+    "liegen||lag|gelegen|haben|läge",
+    "sitzen||saß|gesessen|haben|säße",
+    "bitten||bat|gebeten|haben|bäte",
+    "rufen||rief|gerufen|haben|riefe",
+    "heißen||hieß|geheißen|haben|hieße",
+    "denken||dachte|gedacht|haben|dächte",
+    "bringen||brachte|gebracht|haben|brächte",
+    "kennen||kannte|gekannt|haben|kennte",
+    "nennen||nannte|genannt|haben|nennte",
+    "rennen||rannte|gerannt|sein|rennte",
+];
+
+/// Imperatives of the verbs with an irregular present, for du and ihr.
+/// Modal verbs have none.
+const GERMAN_IMPERATIVES: &[(&str, &str)] = &[
+    ("sein", "sei seid"),
+    ("haben", "hab habt"),
+    ("werden", "werde werdet"),
+    ("wissen", "wisse wisst"),
+    ("tun", "tu tut"),
+];
+
+/// Prefixes split off the verb in a main clause, longest first.
+const SEPARABLE: &[&str] = &[
+    "zusammen", "zurück", "vorbei", "heraus", "herein", "hinaus", "hinein", "weiter", "fest",
+    "fern", "frei", "fort", "weg", "her", "hin", "los", "mit", "nach", "vor", "ab", "an", "auf",
+    "aus", "bei", "ein", "zu",
+];
+
+/// Prefixes that stay on the verb and leave ge- off its participle.
+const INSEPARABLE: &[&str] = &["miss", "emp", "ent", "zer", "ver", "be", "er", "ge"];
+
+/// Weak verbs that separable prefixes are often put on.
+const GERMAN_WEAK: &[&str] = &[
+    "machen",
+    "kaufen",
+    "hören",
+    "stellen",
+    "legen",
+    "holen",
+    "räumen",
+    "passen",
+    "richten",
+    "füllen",
+    "zahlen",
+    "drücken",
+    "setzen",
+    "schalten",
+    "wachen",
+    "kochen",
+    "packen",
+    "probieren",
+    "suchen",
+    "sagen",
+    "zeigen",
+    "spielen",
+    "arbeiten",
+    "bauen",
+    "teilen",
+    "lernen",
+    "wählen",
+    "hören",
+    "hängen",
+    "melden",
+    "reisen",
+    "ziehen",
+];
+
+/// Verbs that look prefixed but aren't.
+const NOT_PREFIXED: &[&str] = &["beten", "erben", "ernten", "gelten", "gähnen"];
+
+/// Verbs of motion and change of state that take sein, beyond those in
+/// [`GERMAN`].
+const GERMAN_SEIN: &[&str] = &[
+    "aufstehen",
+    "einschlafen",
+    "aufwachen",
+    "entstehen",
+    "verschwinden",
+    "gelingen",
+    "passieren",
+    "reisen",
+    "wandern",
+    "begegnen",
+    "folgen",
+];
+
+struct German {
+    present: [String; 6],
+    past: [String; 6],
+    subjunctive: [String; 6],
+    participle: String,
+    auxiliary: &'static str,
+    /// For du and ihr, if it has one.
+    imperative: Option<(String, String)>,
+}
+
+fn german(verb: &str) -> Option<Conjugation> {
+    if !verb.ends_with('n') || verb.chars().any(|c| !c.is_alphabetic()) {
+        return None;
+    }
+    // A known verb inside a separable prefix: anfangen, aufräumen.
+    let separable = SEPARABLE.iter().find_map(|prefix| {
+        let base = verb.strip_prefix(prefix)?;
+        let known = irregular(GERMAN, base).is_some_and(|(p, _)| p.is_empty())
+            || GERMAN_WEAK.contains(&base);
+        known.then_some((*prefix, base))
+    });
+    let (prefix, base) = separable.unwrap_or(("", verb));
+    let mut parts = german_parts(base)?;
+    if GERMAN_SEIN.contains(&verb) {
+        parts.auxiliary = "sein";
+    }
+
+    let split = |form: &String| match prefix {
+        "" => form.clone(),
+        prefix => format!("{} {}", form, prefix),
+    };
+    let participle = format!("{}{}", prefix, parts.participle);
+    let present = parts.present.clone().map(|form| split(&form));
+    let past = parts.past.clone().map(|form| split(&form));
+    let subjunctive = parts.subjunctive.clone().map(|form| split(&form));
+    let (aux_present, aux_past) = match parts.auxiliary {
+        "sein" => (
+            six("bin bist ist sind seid sind"),
+            six("war warst war waren wart waren"),
+        ),
+        _ => (
+            six("habe hast hat haben habt haben"),
+            six("hatte hattest hatte hatten hattet hatten"),
+        ),
+    };
+    let future = after(&six("werde wirst wird werden werdet werden"), |_| {
+        verb.to_string()
+    });
+    // "Seien Sie", and "fangen Sie an" with the prefix after.
+    let sie = match base {
+        "sein" => String::from("seien Sie"),
+        base => format!("{} Sie", base),
+    };
+    let imperative = match &parts.imperative {
+        Some((du, ihr)) => [
+            String::new(),
+            split(du),
+            String::new(),
+            String::new(),
+            split(ihr),
+            split(&sie),
+        ],
+        None => Default::default(),
+    };
+    let tenses = vec![
+        ("Präsens", present),
+        ("Präteritum", past),
+        ("Perfekt", after(&aux_present, |_| participle.clone())),
+        ("Plusquamperfekt", after(&aux_past, |_| participle.clone())),
+        ("Futur I", future),
+        ("Konjunktiv II", subjunctive),
+        ("Imperativ", imperative),
+    ];
+    Some(Conjugation {
+        forms: vec![
+            ("Infinitiv", verb.to_string()),
+            ("Partizip I", participle_one(verb)),
+            ("Partizip II", participle),
+            ("Hilfsverb", parts.auxiliary.to_string()),
+        ],
+        persons: GERMAN_PERSONS,
+        tenses,
+    })
+}
+
+/// The forms of `verb` without a separable prefix: from [`GERMAN`], from its
+/// entry there with an inseparable prefix (verstehen, bekommen), or by the
+/// rules for weak verbs.
+fn german_parts(verb: &str) -> Option<German> {
+    let inseparable = INSEPARABLE.iter().find_map(|prefix| {
+        let base = verb.strip_prefix(prefix)?;
+        let known = irregular(GERMAN, base).is_some_and(|(p, _)| p.is_empty());
+        (known && !NOT_PREFIXED.contains(&verb)).then_some((*prefix, base))
+    });
+    let exact = irregular(GERMAN, verb).filter(|(p, _)| p.is_empty());
+    let (prefix, fields) = match (exact, inseparable) {
+        (Some((_, fields)), _) => ("", fields),
+        (None, Some((prefix, base))) => (prefix, irregular(GERMAN, base)?.1),
+        (None, None) => return german_weak(verb),
+    };
+    let base = fields[0];
+    let stem = german_stem(base);
+    let extra_e = needs_e(stem);
+    let sibilant = stem.ends_with(['s', 'ß', 'x', 'z']);
+    let present: [String; 6] = match fields[1] {
+        forms if forms.contains(' ') => six(forms),
+        change => {
+            let (du, er) = match change {
+                "" => (
+                    match (extra_e, sibilant) {
+                        (true, _) => format!("{}est", stem),
+                        (false, true) => format!("{}t", stem),
+                        (false, false) => format!("{}st", stem),
+                    },
+                    match extra_e {
+                        true => format!("{}et", stem),
+                        false => format!("{}t", stem),
+                    },
+                ),
+                change => (
+                    match change.ends_with(['s', 'ß', 'x', 'z']) {
+                        true => format!("{}t", change),
+                        false => format!("{}st", change),
+                    },
+                    match change.ends_with('t') {
+                        true => change.to_string(),
+                        false => format!("{}t", change),
+                    },
+                ),
+            };
+            [
+                format!("{}e", stem),
+                du,
+                er,
+                base.to_string(),
+                match extra_e {
+                    true => format!("{}et", stem),
+                    false => format!("{}t", stem),
+                },
+                base.to_string(),
+            ]
+        }
+    };
+    let past = match fields[2].strip_suffix('e') {
+        Some(stem) => with(stem, ["e", "est", "e", "en", "et", "en"]),
+        None => {
+            let past = fields[2];
+            let dental = past.ends_with(['t', 'd']);
+            let du = match (dental, past.ends_with(['s', 'ß', 'z'])) {
+                (true, _) | (_, true) => "est",
+                _ => "st",
+            };
+            with(
+                past,
+                ["", du, "", "en", if dental { "et" } else { "t" }, "en"],
+            )
+        }
+    };
+    let subjunctive = {
+        let stem = fields[5].strip_suffix('e').unwrap_or(fields[5]);
+        with(stem, ["e", "est", "e", "en", "et", "en"])
+    };
+    let imperative = match GERMAN_IMPERATIVES.iter().find(|(v, _)| *v == base) {
+        Some((_, forms)) => forms
+            .split_once(' ')
+            .map(|(du, ihr)| (du.to_string(), ihr.to_string())),
+        // Modal verbs.
+        None if fields[1].contains(' ') => None,
+        None => {
+            // Only e → i and e → ie carry over: gib, lies, but fahr.
+            let du = match fields[1] {
+                change if change.contains('i') && !stem.contains('i') => change.to_string(),
+                _ if extra_e => format!("{}e", stem),
+                _ => stem.to_string(),
+            };
+            Some((du, present[4].clone()))
+        }
+    };
+    let participle = match prefix {
+        "" => fields[3].to_string(),
+        prefix => format!(
+            "{}{}",
+            prefix,
+            fields[3].strip_prefix("ge").unwrap_or(fields[3])
+        ),
+    };
+    Some(German {
+        present: prefixed(prefix, &present),
+        past: prefixed(prefix, &past),
+        subjunctive: prefixed(prefix, &subjunctive),
+        participle,
+        auxiliary: match (prefix, fields[4]) {
+            ("", "sein") => "sein",
+            _ => "haben",
+        },
+        imperative: imperative
+            .map(|(du, ihr)| (format!("{}{}", prefix, du), format!("{}{}", prefix, ihr))),
+    })
+}
+
+/// The present participle: machend, but seiend and tuend.
+fn participle_one(verb: &str) -> String {
+    match verb.ends_with("en") || verb.ends_with("ln") || verb.ends_with("rn") {
+        true => format!("{}d", verb),
+        false => format!("{}end", &verb[..verb.len() - 1]),
+    }
+}
+
+/// The stem the endings go on: mach of machen, wander of wandern.
+fn german_stem(verb: &str) -> &str {
+    match verb.ends_with("eln") || verb.ends_with("ern") {
+        true => &verb[..verb.len() - 1],
+        false => verb.strip_suffix("en").unwrap_or(&verb[..verb.len() - 1]),
+    }
+}
+
+/// Whether endings starting with s or t need an e before them: arbeitest,
+/// atmet, rechnet.
+fn needs_e(stem: &str) -> bool {
+    let mut chars = stem.chars().rev();
+    match (chars.next(), chars.next()) {
+        (Some('t' | 'd'), _) => true,
+        (Some('m' | 'n'), Some(before)) => {
+            !"aeiouäöülrhmn".contains(before) || stem.ends_with("chn") || stem.ends_with("chm")
+        }
+        _ => false,
+    }
+}
+
+/// A weak verb: machen, machte, gemacht. No ge- after an inseparable prefix
+/// or on -ieren verbs.
+fn german_weak(verb: &str) -> Option<German> {
+    let stem = german_stem(verb);
+    if stem.is_empty() {
+        return None;
+    }
+    let extra_e = needs_e(stem);
+    let e = match extra_e {
+        true => "e",
+        false => "",
+    };
+    let du = match (extra_e, stem.ends_with(['s', 'ß', 'x', 'z'])) {
+        (false, true) => "t",
+        _ => "st",
+    };
+    let plural = match verb.ends_with("eln") || verb.ends_with("ern") {
+        true => "n",
+        false => "en",
+    };
+    // Lächeln drops the e of its stem before another: ich lächle.
+    let first = match stem.strip_suffix("el") {
+        Some(base) => format!("{}le", base),
+        None => format!("{}e", stem),
+    };
+    let present = [
+        first.clone(),
+        format!("{}{}{}", stem, e, du),
+        format!("{}{}t", stem, e),
+        format!("{}{}", stem, plural),
+        format!("{}{}t", stem, e),
+        format!("{}{}", stem, plural),
+    ];
+    let past = with(
+        &format!("{}{}t", stem, e),
+        ["e", "est", "e", "en", "et", "en"],
+    );
+    let prefixed = INSEPARABLE
+        .iter()
+        .any(|prefix| verb.starts_with(prefix) && verb.len() > prefix.len() + 3)
+        && !NOT_PREFIXED.contains(&verb);
+    let participle = match prefixed || verb.ends_with("ieren") {
+        true => format!("{}{}t", stem, e),
+        false => format!("ge{}{}t", stem, e),
+    };
+    let imperative = match plural == "n" || extra_e {
+        true => first,
+        false => stem.to_string(),
+    };
+    Some(German {
+        subjunctive: past.clone(),
+        imperative: Some((imperative, present[4].clone())),
+        present,
+        past,
+        participle,
+        auxiliary: "haben",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The forms of `verb` in `tense`, the persons it doesn't have left out.
+    fn tense(verb: &str, lang: &str, tense: &str) -> String {
+        let conjugation = conjugate(verb, lang).unwrap();
+        let (_, forms) = conjugation
+            .tenses
+            .iter()
+            .find(|(name, _)| *name == tense)
+            .unwrap();
+        let forms: Vec<&str> = forms
+            .iter()
+            .map(String::as_str)
+            .filter(|form| !form.is_empty())
+            .collect();
+        forms.join(", ")
+    }
+
+    /// The infinitive, participles and auxiliary of `verb`.
+    fn forms(verb: &str, lang: &str) -> Vec<String> {
+        let conjugation = conjugate(verb, lang).unwrap();
+        conjugation
+            .forms
+            .into_iter()
+            .map(|(_, form)| form)
+            .collect()
+    }
+
+    #[test]
+    fn french() {
+        for (verb, present_tense, future, composed) in [
+            (
+                "parler",
+                "parle, parles, parle, parlons, parlez, parlent",
+                "parlerai, parleras, parlera, parlerons, parlerez, parleront",
+                "ai parlé",
+            ),
+            (
+                "finir",
+                "finis, finis, finit, finissons, finissez, finissent",
+                "finirai, finiras, finira, finirons, finirez, finiront",
+                "ai fini",
+            ),
+            (
+                "manger",
+                "mange, manges, mange, mangeons, mangez, mangent",
+                "mangerai, mangeras, mangera, mangerons, mangerez, mangeront",
+                "ai mangé",
+            ),
+            (
+                "appeler",
+                "appelle, appelles, appelle, appelons, appelez, appellent",
+                "appellerai, appelleras, appellera, appellerons, appellerez, appelleront",
+                "ai appelé",
+            ),
+            (
+                "acheter",
+                "achète, achètes, achète, achetons, achetez, achètent",
+                "achèterai, achèteras, achètera, achèterons, achèterez, achèteront",
+                "ai acheté",
+            ),
+            (
+                "préférer",
+                "préfère, préfères, préfère, préférons, préférez, préfèrent",
+                "préférerai, préféreras, préférera, préférerons, préférerez, préféreront",
+                "ai préféré",
+            ),
+            (
+                "être",
+                "suis, es, est, sommes, êtes, sont",
+                "serai, seras, sera, serons, serez, seront",
+                "ai été",
+            ),
+            (
+                "aller",
+                "vais, vas, va, allons, allez, vont",
+                "irai, iras, ira, irons, irez, iront",
+                "suis allé(e)",
+            ),
+            (
+                "venir",
+                "viens, viens, vient, venons, venez, viennent",
+                "viendrai, viendras, viendra, viendrons, viendrez, viendront",
+                "suis venu(e)",
+            ),
+        ] {
+            assert_eq!(tense(verb, "fr", "présent"), present_tense, "{verb}");
+            assert_eq!(tense(verb, "fr", "futur"), future, "{verb}");
+            assert!(tense(verb, "fr", "passé composé").starts_with(composed));
+        }
+        assert_eq!(
+            tense("manger", "fr", "imparfait"),
+            "mangeais, mangeais, mangeait, mangions, mangiez, mangeaient"
+        );
+        assert_eq!(
+            tense("finir", "fr", "subjonctif"),
+            "finisse, finisses, finisse, finissions, finissiez, finissent"
+        );
+        assert_eq!(
+            tense("venir", "fr", "passé simple"),
+            "vins, vins, vint, vînmes, vîntes, vinrent"
+        );
+        assert_eq!(tense("être", "fr", "impératif"), "sois, soyons, soyez");
+        assert_eq!(forms("venir", "fr"), ["venir", "venant", "venu", "être"]);
+        assert!(conjugate("table", "fr").is_none());
+    }
+
+    #[test]
+    fn spanish() {
+        for (verb, present_tense, preterite) in [
+            (
+                "hablar",
+                "hablo, hablas, habla, hablamos, habláis, hablan",
+                "hablé, hablaste, habló, hablamos, hablasteis, hablaron",
+            ),
+            (
+                "comer",
+                "como, comes, come, comemos, coméis, comen",
+                "comí, comiste, comió, comimos, comisteis, comieron",
+            ),
+            (
+                "vivir",
+                "vivo, vives, vive, vivimos, vivís, viven",
+                "viví, viviste, vivió, vivimos, vivisteis, vivieron",
+            ),
+            (
+                "pensar",
+                "pienso, piensas, piensa, pensamos, pensáis, piensan",
+                "pensé, pensaste, pensó, pensamos, pensasteis, pensaron",
+            ),
+            (
+                "dormir",
+                "duermo, duermes, duerme, dormimos, dormís, duermen",
+                "dormí, dormiste, durmió, dormimos, dormisteis, durmieron",
+            ),
+            (
+                "pedir",
+                "pido, pides, pide, pedimos, pedís, piden",
+                "pedí, pediste, pidió, pedimos, pedisteis, pidieron",
+            ),
+            (
+                "tener",
+                "tengo, tienes, tiene, tenemos, tenéis, tienen",
+                "tuve, tuviste, tuvo, tuvimos, tuvisteis, tuvieron",
+            ),
+            (
+                "ser",
+                "soy, eres, es, somos, sois, son",
+                "fui, fuiste, fue, fuimos, fuisteis, fueron",
+            ),
+            (
+                "ir",
+                "voy, vas, va, vamos, vais, van",
+                "fui, fuiste, fue, fuimos, fuisteis, fueron",
+            ),
+            (
+                "obtener",
+                "obtengo, obtienes, obtiene, obtenemos, obtenéis, obtienen",
+                "obtuve, obtuviste, obtuvo, obtuvimos, obtuvisteis, obtuvieron",
+            ),
+        ] {
+            assert_eq!(tense(verb, "es", "presente"), present_tense, "{verb}");
+            assert_eq!(tense(verb, "es", "indefinido"), preterite, "{verb}");
+        }
+        assert_eq!(
+            tense("dormir", "es", "subjuntivo"),
+            "duerma, duermas, duerma, durmamos, durmáis, duerman"
+        );
+        assert_eq!(
+            tense("tener", "es", "futuro"),
+            "tendré, tendrás, tendrá, tendremos, tendréis, tendrán"
+        );
+        assert_eq!(
+            tense("ir", "es", "imperfecto"),
+            "iba, ibas, iba, íbamos, ibais, iban"
+        );
+        assert_eq!(
+            tense("obtener", "es", "imperativo"),
+            "obtén, obtenga, obtengamos, obtened, obtengan"
+        );
+        assert_eq!(
+            tense("ser", "es", "imperativo"),
+            "sé, sea, seamos, sed, sean"
+        );
+        assert_eq!(forms("dormir", "es"), ["dormir", "durmiendo", "dormido"]);
+        assert_eq!(forms("ir", "es"), ["ir", "yendo", "ido"]);
+    }
+
+    #[test]
+    fn german() {
+        for (verb, present_tense, past, participle) in [
+            (
+                "machen",
+                "mache, machst, macht, machen, macht, machen",
+                "machte, machtest, machte, machten, machtet, machten",
+                "gemacht",
+            ),
+            (
+                "arbeiten",
+                "arbeite, arbeitest, arbeitet, arbeiten, arbeitet, arbeiten",
+                "arbeitete, arbeitetest, arbeitete, arbeiteten, arbeitetet, arbeiteten",
+                "gearbeitet",
+            ),
+            (
+                "singen",
+                "singe, singst, singt, singen, singt, singen",
+                "sang, sangst, sang, sangen, sangt, sangen",
+                "gesungen",
+            ),
+            (
+                "anfangen",
+                "fange an, fängst an, fängt an, fangen an, fangt an, fangen an",
+                "fing an, fingst an, fing an, fingen an, fingt an, fingen an",
+                "angefangen",
+            ),
+            (
+                "verstehen",
+                "verstehe, verstehst, versteht, verstehen, versteht, verstehen",
+                "verstand, verstandest, verstand, verstanden, verstandet, verstanden",
+                "verstanden",
+            ),
+            (
+                "sein",
+                "bin, bist, ist, sind, seid, sind",
+                "war, warst, war, waren, wart, waren",
+                "gewesen",
+            ),
+            (
+                "haben",
+                "habe, hast, hat, haben, habt, haben",
+                "hatte, hattest, hatte, hatten, hattet, hatten",
+                "gehabt",
+            ),
+        ] {
+            assert_eq!(tense(verb, "de", "Präsens"), present_tense, "{verb}");
+            assert_eq!(tense(verb, "de", "Präteritum"), past, "{verb}");
+            assert_eq!(forms(verb, "de")[2], participle, "{verb}");
+        }
+        assert_eq!(
+            tense("singen", "de", "Konjunktiv II"),
+            "sänge, sängest, sänge, sängen, sänget, sängen"
+        );
+        assert_eq!(
+            tense("sein", "de", "Perfekt"),
+            "bin gewesen, bist gewesen, ist gewesen, sind gewesen, seid gewesen, sind gewesen"
+        );
+        assert_eq!(
+            tense("anfangen", "de", "Imperativ"),
+            "fang an, fangt an, fangen Sie an"
+        );
+        assert_eq!(
+            forms("anfangen", "de"),
+            ["anfangen", "anfangend", "angefangen", "haben"]
+        );
+        assert!(conjugate("Haus", "de").is_none());
+    }
+}
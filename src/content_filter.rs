@@ -0,0 +1,116 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+use crate::{
+    api::{Definition, DefinitionResponse},
+    labels::Label,
+};
+
+/// Shown in place of a sense the filter blurred.
+pub const MASK: &str = "[vulgar or offensive sense hidden]";
+
+/// Usage labels dictionaries put in front of crude senses, e.g. "(vulgar, slang) …".
+static LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*\([^)]*\b(vulgar|offensive|obscene|derogatory|ethnic slur|slur|profane|sexual slang)\b[^)]*\)")
+        .unwrap()
+});
+
+/// Words that make a sense unsuitable for a shared screen wherever they appear.
+const PROFANITY: &[&str] = &[
+    "fuck",
+    "fucking",
+    "fucked",
+    "fucker",
+    "shit",
+    "shitty",
+    "cunt",
+    "motherfucker",
+    "bullshit",
+    "asshole",
+    "arsehole",
+    "whore",
+    "slut",
+    "twat",
+    "wanker",
+    "nigger",
+    "faggot",
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFilter {
+    /// Show everything the provider returns.
+    #[default]
+    Off,
+    /// Replace crude senses with a placeholder; the full text stays available
+    /// in the detail view.
+    Blur,
+    /// Drop crude senses entirely.
+    Hide,
+}
+
+fn is_profane(text: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|word| PROFANITY.contains(&word.to_lowercase().as_str()))
+}
+
+/// Whether a sense is labelled or worded as vulgar.
+pub fn is_vulgar(definition: &Definition) -> bool {
+    definition
+        .labels
+        .iter()
+        .any(|label| matches!(label, Label::Vulgar | Label::Offensive))
+        || LABEL.is_match(&definition.definition)
+        || is_profane(&definition.definition)
+        || definition.example.as_deref().is_some_and(is_profane)
+}
+
+/// The entries as they should be displayed under `filter`. An entry whose
+/// headword is itself profane counts as vulgar in every sense.
+pub fn apply(
+    mut entries: Vec<DefinitionResponse>,
+    filter: ContentFilter,
+) -> Vec<DefinitionResponse> {
+    if filter == ContentFilter::Off {
+        return entries;
+    }
+    for entry in &mut entries {
+        let crude_headword = is_profane(&entry.word);
+        for meaning in &mut entry.meanings {
+            let mut filtered = false;
+            match filter {
+                ContentFilter::Off => {}
+                ContentFilter::Hide => meaning.definitions.retain(|def| {
+                    let keep = !crude_headword && !is_vulgar(def);
+                    filtered |= !keep;
+                    keep
+                }),
+                ContentFilter::Blur => {
+                    for def in &mut meaning.definitions {
+                        if crude_headword || is_vulgar(def) {
+                            filtered = true;
+                            *def = Definition {
+                                definition: MASK.to_string(),
+                                example: None,
+                                synonyms: None,
+                                antonyms: None,
+                                level: def.level,
+                                region: def.region.clone(),
+                                labels: def.labels.clone(),
+                            };
+                        }
+                    }
+                }
+            }
+            // Meaning-level synonyms of a sense we filtered are probably crude too.
+            if filtered {
+                meaning.synonyms = None;
+                meaning.antonyms = None;
+            }
+        }
+        entry.meanings.retain(|m| !m.definitions.is_empty());
+    }
+    entries.retain(|e| !e.meanings.is_empty());
+    entries
+}
@@ -0,0 +1,301 @@
+#[cfg(feature = "encryption")]
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use serde::Deserialize;
+#[cfg(feature = "encryption")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "encryption")]
+use std::fs;
+#[cfg(feature = "encryption")]
+use std::sync::Mutex;
+use std::{io, process, sync::OnceLock};
+
+use crate::config::Config;
+#[cfg(feature = "encryption")]
+use crate::storage;
+
+/// Starts every encrypted file, ahead of the salt, the nonce and the
+/// ciphertext.
+pub const MAGIC: &[u8] = b"sherlock-dictionary encrypted 2\n";
+/// Started the files sealed with the salt in the data dir, ahead of the
+/// nonce and the ciphertext. They're still read, and saved as [`MAGIC`] files.
+const MAGIC_V1: &[u8] = b"sherlock-dictionary encrypted 1\n";
+#[cfg(feature = "encryption")]
+const NONCE_LEN: usize = 12;
+#[cfg(feature = "encryption")]
+const SALT_LEN: usize = 16;
+/// PBKDF2 rounds: enough to make guessing a passphrase slow, few enough not
+/// to hold up a lookup on every keystroke.
+#[cfg(feature = "encryption")]
+const ROUNDS: u32 = 100_000;
+/// Takes the passphrase from the environment rather than the config's command.
+const PASSPHRASE_VAR: &str = "SHERLOCK_DICT_PASSPHRASE";
+
+/// How the files that say what the user looked up are encrypted.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Encryption {
+    /// A shell command that prints the passphrase, e.g. `pass show
+    /// sherlock-dictionary` or a `secret-tool lookup`.
+    pub passphrase_command: String,
+}
+
+/// The passphrase the private files are encrypted with, and the keys derived
+/// from it. Each file carries its own salt, so one written on another machine
+/// of the `sync_dir` opens with the same passphrase.
+pub struct Key {
+    #[cfg(feature = "encryption")]
+    passphrase: String,
+    /// The salts seen this run and their keys, so each is only derived once.
+    #[cfg(feature = "encryption")]
+    derived: Mutex<Vec<([u8; SALT_LEN], [u8; 32])>>,
+}
+
+/// The key the private files are encrypted with, when encryption is set up.
+pub fn key() -> Option<&'static Key> {
+    static KEY: OnceLock<Option<Key>> = OnceLock::new();
+    KEY.get_or_init(|| Key::new(passphrase()?)).as_ref()
+}
+
+/// Whether `bytes` are a file written by [`seal`].
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC) || bytes.starts_with(MAGIC_V1)
+}
+
+#[cfg(feature = "encryption")]
+impl Key {
+    fn new(passphrase: String) -> Option<Self> {
+        Some(Self {
+            passphrase,
+            derived: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// The key for the files sealed with `salt`.
+    fn derive(&self, salt: &[u8; SALT_LEN]) -> [u8; 32] {
+        let mut derived = self.derived.lock().unwrap();
+        if let Some((_, key)) = derived.iter().find(|(seen, _)| seen == salt) {
+            return *key;
+        }
+        let key = pbkdf2(self.passphrase.as_bytes(), salt, ROUNDS);
+        derived.push((*salt, key));
+        key
+    }
+
+    /// The salt to seal with: the first one derived this run, so saving what
+    /// was just read doesn't derive a second key, or else a new one.
+    fn sealing_salt(&self) -> io::Result<[u8; SALT_LEN]> {
+        if let Some((salt, _)) = self.derived.lock().unwrap().first() {
+            return Ok(*salt);
+        }
+        let mut salt = [0; SALT_LEN];
+        getrandom::getrandom(&mut salt).map_err(io::Error::other)?;
+        Ok(salt)
+    }
+}
+
+/// Without the cipher there's no key, so encrypted files stay as they are:
+/// they can't be read, and aren't overwritten either.
+#[cfg(not(feature = "encryption"))]
+impl Key {
+    fn new(_: String) -> Option<Self> {
+        eprintln!(
+            "Warning: this build has no \"encryption\" feature, so the history isn't encrypted"
+        );
+        None
+    }
+}
+
+/// `plaintext` encrypted with `key`, ready to be written to a file.
+#[cfg(feature = "encryption")]
+pub fn seal(key: &Key, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+    let salt = key.sealing_salt()?;
+    let mut nonce = [0; NONCE_LEN];
+    getrandom::getrandom(&mut nonce).map_err(io::Error::other)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key.derive(&salt)));
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), plaintext)
+        .map_err(|_| io::Error::other("encryption failed"))?;
+    Ok([MAGIC, &salt, &nonce, &ciphertext].concat())
+}
+
+/// The plaintext of a file written by [`seal`], or `None` if `key` isn't
+/// the one it was sealed with.
+#[cfg(feature = "encryption")]
+pub fn open(key: &Key, sealed: &[u8]) -> Option<Vec<u8>> {
+    let (salt, rest) = match sealed.strip_prefix(MAGIC) {
+        Some(rest) if rest.len() >= SALT_LEN => {
+            let (salt, rest) = rest.split_at(SALT_LEN);
+            (salt.try_into().unwrap(), rest)
+        }
+        Some(_) => return None,
+        None => (legacy_salt()?, sealed.strip_prefix(MAGIC_V1)?),
+    };
+    if rest.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key.derive(&salt)));
+    cipher
+        .decrypt(GenericArray::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn seal(_: &Key, _: &[u8]) -> io::Result<Vec<u8>> {
+    Err(io::Error::other("this build has no \"encryption\" feature"))
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn open(_: &Key, _: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+
+fn passphrase() -> Option<String> {
+    if let Some(passphrase) = std::env::var(PASSPHRASE_VAR).ok().filter(|p| !p.is_empty()) {
+        return Some(passphrase);
+    }
+    let command = &Config::load().encryption.as_ref()?.passphrase_command;
+    let output = match shell(command).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            eprintln!(
+                "Warning: '{}' failed: {}",
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(e) => {
+            eprintln!("Warning: couldn't run '{}': {}", command, e);
+            return None;
+        }
+    };
+    let passphrase = String::from_utf8_lossy(&output.stdout);
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']);
+    (!passphrase.is_empty()).then(|| passphrase.to_string())
+}
+
+/// `command` run the way a terminal on this platform would run it.
+pub fn shell(command: &str) -> process::Command {
+    let (shell, flag) = match cfg!(windows) {
+        true => ("cmd", "/C"),
+        false => ("sh", "-c"),
+    };
+    let mut shell = process::Command::new(shell);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+/// The salt the [`MAGIC_V1`] files of this machine were sealed with.
+#[cfg(feature = "encryption")]
+fn legacy_salt() -> Option<[u8; SALT_LEN]> {
+    fs::read(storage::data_dir().join("salt"))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// PBKDF2-HMAC-SHA256 with a single 32-byte block of output.
+#[cfg(feature = "encryption")]
+fn pbkdf2(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut key = [0u8; BLOCK];
+    match password.len() > BLOCK {
+        true => key[..32].copy_from_slice(&Sha256::digest(password)),
+        false => key[..password.len()].copy_from_slice(password),
+    }
+    // Every round's HMAC starts from the same two padded keys, so they are
+    // hashed once and the states cloned.
+    let pad = |byte: u8| -> Sha256 {
+        let padded: Vec<u8> = key.iter().map(|b| b ^ byte).collect();
+        Sha256::new().chain_update(padded)
+    };
+    let (inner, outer) = (pad(0x36), pad(0x5c));
+    let hmac = |message: &[u8]| -> [u8; 32] {
+        let digest = inner.clone().chain_update(message).finalize();
+        outer.clone().chain_update(digest).finalize().into()
+    };
+
+    let mut block = hmac(&[salt, &1u32.to_be_bytes()].concat());
+    let mut out = block;
+    for _ in 1..rounds {
+        block = hmac(&block);
+        for (o, b) in out.iter_mut().zip(block) {
+            *o ^= b;
+        }
+    }
+    out
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// RFC 7914's and the widely used PBKDF2-HMAC-SHA256 vectors, cut to 32
+    /// bytes, and a password longer than a block.
+    #[test]
+    fn pbkdf2_vectors() {
+        let vectors: &[(&[u8], &[u8], u32, &str)] = &[
+            (
+                b"password",
+                b"salt",
+                1,
+                "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+            ),
+            (
+                b"password",
+                b"salt",
+                2,
+                "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43",
+            ),
+            (
+                b"password",
+                b"salt",
+                4096,
+                "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a",
+            ),
+            (
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1",
+            ),
+            (
+                b"passwd",
+                b"salt",
+                1,
+                "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc",
+            ),
+            (
+                &[b'x'; 100],
+                b"salt",
+                3,
+                "59bfa49750dd5462ce38370a1e7abe0736ff334cf1c2f0d84f23a03435d660d1",
+            ),
+        ];
+        for (password, salt, rounds, expected) in vectors {
+            assert_eq!(hex(&pbkdf2(password, salt, *rounds)), *expected);
+        }
+    }
+
+    /// What another machine sealed, with its own salt, opens with the same
+    /// passphrase.
+    #[test]
+    fn opens_what_another_machine_sealed() {
+        let a = Key::new(String::from("hunter2")).unwrap();
+        let b = Key::new(String::from("hunter2")).unwrap();
+        let sealed = seal(&a, b"{\"words\": []}").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(open(&b, &sealed).unwrap(), b"{\"words\": []}");
+        let resealed = seal(&b, b"[]").unwrap();
+        assert_eq!(open(&a, &resealed).unwrap(), b"[]");
+        let wrong = Key::new(String::from("hunter3")).unwrap();
+        assert_eq!(open(&wrong, &sealed), None);
+    }
+}
@@ -0,0 +1,321 @@
+use serde::Deserialize;
+use std::{env, fs, io, path::PathBuf, time::Duration};
+use tokio::time::Instant;
+
+use crate::{
+    history::{self, History},
+    http,
+    providers::Settings,
+    storage,
+    wotd::{self, Wotd},
+};
+
+/// What systemd names the units `install-service` writes, and the socket.
+const UNIT: &str = "sherlock-dictionary";
+
+/// How `daemon` keeps the cache warm, from the `refresh` section of
+/// `config.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Refresh {
+    /// Hours between refreshes.
+    pub every_hours: u64,
+    /// How many of the most looked-up words are refreshed each time.
+    pub words: usize,
+    /// Seconds a lookup under way when the daemon is stopped may take to
+    /// finish.
+    pub drain_secs: u64,
+    /// Minutes without a lookup after which a daemon systemd started on
+    /// demand exits, to be started again by the next one.
+    pub idle_minutes: u64,
+}
+impl Default for Refresh {
+    fn default() -> Self {
+        Self {
+            every_hours: 6,
+            words: 100,
+            drain_secs: 10,
+            idle_minutes: 30,
+        }
+    }
+}
+
+/// `daemon`: looks the most looked-up words, and the word of the day, up
+/// again every `every_hours`, asking each site whether its entry changed, so
+/// typing one of them is
+/// answered from a cache entry that's still fresh instead of waiting on the
+/// network. The schedule carries over from one daemon to the next, so one
+/// started soon after another's round waits for the next one. Runs until it's
+/// stopped with Ctrl+C or SIGTERM, which lets the
+/// lookup under way finish first, for up to `drain_secs`. Started by systemd
+/// through the socket `install-service` sets up, it also stops once no word
+/// has been looked up for `idle_timeout` minutes, or `idle_minutes`, which
+/// frees what it holds until the next lookup starts it again.
+pub async fn run(
+    settings: &Settings,
+    refresh: &Refresh,
+    wotd: &Wotd,
+    idle_timeout: Option<u64>,
+) -> ! {
+    if settings.private {
+        eprintln!("Error: private lookups leave nothing in the cache to refresh");
+        std::process::exit(1);
+    }
+    http::always_revalidate();
+    // Every few seconds would only hammer the sites.
+    let every = Duration::from_secs(refresh.every_hours.max(1) * 60 * 60);
+    let drain = Duration::from_secs(refresh.drain_secs);
+    let idle_minutes = idle_timeout.unwrap_or(refresh.idle_minutes);
+    let idle = Duration::from_secs(idle_minutes.max(1) * 60);
+    let activation = Activation::inherited();
+    if idle_timeout.is_some() && activation.is_none() {
+        eprintln!("Warning: --idle-timeout only applies when systemd starts the daemon through its socket");
+    }
+    let mut idle_until = Instant::now() + idle;
+    // A daemon systemd starts on demand would otherwise start with a round
+    // every time, however recent the last one.
+    let mut next_round = Instant::now() + until_due(every);
+    let stop = stopped();
+    tokio::pin!(stop);
+    loop {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next_round) => break,
+                signal = &mut stop => {
+                    eprintln!("{}", signal);
+                    shut_down();
+                }
+                _ = woken(&activation) => idle_until = Instant::now() + idle,
+                _ = tokio::time::sleep_until(idle_until), if activation.is_some() => {
+                    match idle.as_secs() / 60 {
+                        1 => eprintln!("No lookups for a minute"),
+                        n => eprintln!("No lookups for {} minutes", n),
+                    }
+                    shut_down();
+                }
+            }
+        }
+        let mut words: Vec<String> = History::load()
+            .most_looked_up(refresh.words)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        // A new day's word is picked, and so looked up, on the first round
+        // of the day.
+        match wotd::today(settings, wotd).await {
+            Ok(word) if !words.contains(&word) => words.push(word),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: couldn't pick the word of the day: {}", e),
+        }
+        let mut failed = 0;
+        for word in &words {
+            let lookup = settings.resolve(word);
+            tokio::pin!(lookup);
+            let result = tokio::select! {
+                result = &mut lookup => result,
+                signal = &mut stop => {
+                    // Every file is written whole or not at all, so all a
+                    // lookup that doesn't finish in time loses is its entry.
+                    eprintln!("{}: finishing the lookup of '{}'", signal, word);
+                    if tokio::time::timeout(drain, &mut lookup).await.is_err() {
+                        eprintln!("Warning: gave up on '{}' after {}s", word, drain.as_secs());
+                    }
+                    shut_down();
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("Warning: couldn't refresh '{}': {}", word, e);
+                failed += 1;
+            }
+        }
+        match (words.len(), failed) {
+            (1, 0) => println!("Refreshed 1 word."),
+            (n, 0) => println!("Refreshed {} words.", n),
+            (n, failed) => println!("Refreshed {} of {} words.", n - failed, n),
+        }
+        if let Err(e) = storage::save_json(&last_round_path(), &history::now()) {
+            eprintln!("Warning: couldn't note the time of the refresh: {}", e);
+        }
+        next_round = Instant::now() + every;
+    }
+}
+
+/// Where the time of the last round is kept, in seconds since the Unix
+/// epoch, for the daemons started after it.
+fn last_round_path() -> PathBuf {
+    storage::cache_dir().join("refreshed.json")
+}
+
+/// How long until the next round is due, `every` after the last one: none
+/// when there hasn't been one.
+fn until_due(every: Duration) -> Duration {
+    let last: u64 = storage::load_json(&last_round_path());
+    let since = Duration::from_secs(history::now().saturating_sub(last));
+    every.saturating_sub(since)
+}
+
+/// The listening socket systemd started the daemon on, when it did.
+struct Activation {
+    #[cfg(unix)]
+    listener: tokio::net::UnixListener,
+}
+impl Activation {
+    /// The first socket passed with the `LISTEN_FDS` protocol (see
+    /// `sd_listen_fds(3)`), meant for this process.
+    #[cfg(unix)]
+    fn inherited() -> Option<Self> {
+        use std::os::{fd::FromRawFd, unix::net::UnixListener};
+        // Passed sockets start after stdin, stdout and stderr.
+        const FIRST_FD: i32 = 3;
+        let pid: u32 = env::var("LISTEN_PID").ok()?.parse().ok()?;
+        let fds: u32 = env::var("LISTEN_FDS").ok()?.parse().ok()?;
+        if pid != std::process::id() || fds == 0 {
+            return None;
+        }
+        // SAFETY: systemd hands the process this open socket and nothing
+        // else in it owns the descriptor.
+        let listener = unsafe { UnixListener::from_raw_fd(FIRST_FD) };
+        listener.set_nonblocking(true).ok()?;
+        let listener = tokio::net::UnixListener::from_std(listener).ok()?;
+        Some(Self { listener })
+    }
+
+    #[cfg(not(unix))]
+    fn inherited() -> Option<Self> {
+        None
+    }
+}
+
+/// Waits for a lookup to connect to the daemon's socket; never, when
+/// systemd didn't start it.
+async fn woken(activation: &Option<Activation>) {
+    match activation {
+        #[cfg(unix)]
+        Some(activation) => {
+            // The connection is only a nudge; nothing is read from it.
+            let _ = activation.listener.accept().await;
+        }
+        _ => std::future::pending().await,
+    }
+}
+
+/// The socket lookups nudge the daemon through, in the user's runtime dir.
+fn socket_path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty())?;
+    Some(PathBuf::from(dir).join(format!("{}.sock", UNIT)))
+}
+
+/// Tells the daemon a word was looked up, which has systemd start it when
+/// `install-service` set it up and keeps it from idling out. Without the
+/// socket this costs one failed `connect`.
+pub fn wake() {
+    #[cfg(unix)]
+    if let Some(path) = socket_path() {
+        let _ = std::os::unix::net::UnixStream::connect(path);
+    }
+}
+
+/// `install-service`: writes a systemd user service that runs `daemon` with
+/// this run's provider chain and language, and a socket that starts it the
+/// first time a word is looked up.
+pub fn install_service(settings: &Settings) -> io::Result<()> {
+    if !cfg!(target_os = "linux") {
+        eprintln!("Error: 'install-service' sets up a systemd service, which only Linux has");
+        std::process::exit(1);
+    }
+    // Next to the app's own config folder: ~/.config/systemd/user.
+    let dir = storage::config_dir()
+        .parent()
+        .map(|config| config.join("systemd/user"))
+        .unwrap_or_default();
+    fs::create_dir_all(&dir)?;
+    let exe = env::current_exe()?;
+    let providers = settings.providers_name();
+    let mut args = vec![
+        exe.to_string_lossy().into_owned(),
+        String::from("--"),
+        String::from("--provider"),
+        providers,
+        String::from("--lang"),
+        settings.lang.clone(),
+    ];
+    if let Some(profile) = &settings.profile {
+        args.extend([String::from("--profile"), profile.clone()]);
+    }
+    args.push(String::from("daemon"));
+    let exec: Vec<String> = args.iter().map(|arg| systemd_quote(arg)).collect();
+
+    let socket = dir.join(format!("{}.socket", UNIT));
+    fs::write(
+        &socket,
+        format!(
+            "[Unit]\n\
+             Description=Starts sherlock-dictionary's cache refresher when a word is looked up\n\
+             \n\
+             [Socket]\n\
+             ListenStream=%t/{}.sock\n\
+             \n\
+             [Install]\n\
+             WantedBy=sockets.target\n",
+            UNIT
+        ),
+    )?;
+    let service = dir.join(format!("{}.service", UNIT));
+    fs::write(
+        &service,
+        format!(
+            "[Unit]\n\
+             Description=Keeps sherlock-dictionary's most looked-up words fresh\n\
+             Requires={}.socket\n\
+             \n\
+             [Service]\n\
+             ExecStart={}\n",
+            UNIT,
+            exec.join(" ")
+        ),
+    )?;
+    println!(
+        "Wrote {} and {}. Start it with:\n  systemctl --user daemon-reload\n  systemctl --user enable --now {}.socket",
+        socket.display(),
+        service.display(),
+        UNIT
+    );
+    Ok(())
+}
+
+/// `arg` as one word of an `ExecStart=` line: double-quoted, with systemd's
+/// `%` specifiers escaped.
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+/// Waits for Ctrl+C, or on Unix for SIGTERM, the signal service managers
+/// stop daemons with, and says which it was.
+async fn stopped() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+            _ = terminate.recv() => "SIGTERM",
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl+C"
+    }
+}
+
+fn shut_down() -> ! {
+    println!("Stopped.");
+    std::process::exit(0);
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{cmudict::Pronunciation, http};
+
+/// A query to Datamuse's word finder, https://www.datamuse.com/api/. Every
+/// answer comes with its pronunciation, in ARPABET and in IPA.
+#[derive(Serialize)]
+pub struct Query<'a> {
+    /// Homophones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_hom: Option<&'a str>,
+    /// Perfect rhymes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_rhy: Option<&'a str>,
+    /// Near rhymes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_nry: Option<&'a str>,
+    /// Spelled like: the word itself, for its pronunciation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sp: Option<&'a str>,
+    /// What to say about each word: "r" for pronunciations.
+    pub md: &'static str,
+    /// Pronunciations in IPA as well, when 1.
+    pub ipa: u8,
+    pub max: u16,
+}
+impl Default for Query<'_> {
+    fn default() -> Self {
+        Self {
+            rel_hom: None,
+            rel_rhy: None,
+            rel_nry: None,
+            sp: None,
+            md: "r",
+            ipa: 1,
+            max: 50,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Word {
+    pub word: String,
+    /// How well it answers the query; only comparable within one answer.
+    #[serde(default)]
+    pub score: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+impl Word {
+    pub fn ipa(&self) -> Option<String> {
+        self.tags
+            .iter()
+            .find_map(|tag| tag.strip_prefix("ipa_pron:"))
+            .map(String::from)
+    }
+
+    /// Its pronunciation as CMUdict writes them, which is where Datamuse
+    /// gets it from.
+    pub fn pronunciation(&self) -> Option<Pronunciation> {
+        let arpabet = self.tags.iter().find_map(|tag| tag.strip_prefix("pron:"))?;
+        Some(Pronunciation {
+            word: self.word.clone(),
+            phonemes: arpabet.split_whitespace().map(String::from).collect(),
+        })
+    }
+}
+
+/// The words answering `query`, best first; none when Datamuse says no.
+pub async fn words(query: &Query<'_>) -> Result<Vec<Word>, surf::Error> {
+    let mut response = http::get("https://api.datamuse.com/words")
+        .query(query)?
+        .await?;
+    match response.status().is_success() {
+        true => response.body_json().await,
+        false => Ok(Vec::new()),
+    }
+}
+
+/// How Datamuse pronounces `word`, when it knows the word.
+pub async fn pronunciation(word: &str) -> Result<Option<Word>, surf::Error> {
+    let found = words(&Query {
+        sp: Some(word),
+        max: 1,
+        ..Query::default()
+    })
+    .await?;
+    Ok(found
+        .into_iter()
+        .find(|w| w.word.eq_ignore_ascii_case(word)))
+}
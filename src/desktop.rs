@@ -0,0 +1,215 @@
+use serde::Deserialize;
+#[cfg(feature = "tui")]
+use std::{
+    env,
+    io::Write,
+    process::{Command, Stdio},
+};
+use std::{fs, io, path::PathBuf};
+
+use crate::storage;
+
+/// Clipboard tools tried in order, Wayland first.
+#[cfg(all(feature = "tui", not(any(target_os = "macos", windows))))]
+const CLIPBOARD_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+#[cfg(all(feature = "tui", target_os = "macos"))]
+const CLIPBOARD_TOOLS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+
+/// Players able to stream a URL, tried in order.
+#[cfg(all(feature = "tui", not(any(target_os = "macos", windows))))]
+const AUDIO_PLAYERS: &[(&str, &[&str])] = &[
+    ("mpv", &["--no-video", "--really-quiet"]),
+    ("ffplay", &["-nodisp", "-autoexit", "-loglevel", "quiet"]),
+    ("cvlc", &["--play-and-exit", "--quiet"]),
+];
+/// Players tried in order. `afplay` comes with macOS but only plays files,
+/// which is what a pronunciation is once it's in the audio cache.
+#[cfg(all(feature = "tui", target_os = "macos"))]
+const AUDIO_PLAYERS: &[(&str, &[&str])] = &[
+    ("mpv", &["--no-video", "--really-quiet"]),
+    ("ffplay", &["-nodisp", "-autoexit", "-loglevel", "quiet"]),
+    ("afplay", &[]),
+];
+/// Players able to stream a URL, tried in order.
+#[cfg(all(feature = "tui", windows))]
+const AUDIO_PLAYERS: &[(&str, &[&str])] = &[
+    ("mpv", &["--no-video", "--really-quiet"]),
+    ("ffplay", &["-nodisp", "-autoexit", "-loglevel", "quiet"]),
+    ("vlc", &["--intf", "dummy", "--play-and-exit"]),
+];
+
+/// Opens a URL in the default browser.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub const URL_OPENER: &str = "xdg-open";
+#[cfg(target_os = "macos")]
+pub const URL_OPENER: &str = "open";
+#[cfg(windows)]
+pub const URL_OPENER: &str = "explorer";
+
+#[cfg(all(feature = "tui", not(windows)))]
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let (program, args) = find_tool(CLIPBOARD_TOOLS, "clipboard tool")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Windows' own `clip` mangles anything that isn't in the console's code
+/// page, so the clipboard is set directly.
+#[cfg(all(feature = "tui", windows))]
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    clipboard_win::set_clipboard_string(text)
+        .map_err(|e| io::Error::other(format!("couldn't set the clipboard: {}", e)))
+}
+
+/// The first of the [`AUDIO_PLAYERS`] that's installed.
+#[cfg(feature = "tui")]
+pub fn audio_player() -> io::Result<(&'static str, &'static [&'static str])> {
+    find_tool(AUDIO_PLAYERS, "audio player")
+}
+
+/// Starts playing `url` in the background; the player outlives the call.
+#[cfg(feature = "tui")]
+pub fn play_audio(url: &str) -> io::Result<()> {
+    let (program, args) = audio_player()?;
+    Command::new(program)
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Speech synthesis for words without recorded audio, from the `tts` section
+/// of `config.json`. Off unless an engine is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Tts {
+    pub engine: Option<TtsEngine>,
+    /// espeak-ng voice such as `en-gb`; the lookup language by default.
+    pub voice: Option<String>,
+    /// Piper `.onnx` voice model, required for Piper.
+    pub model: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TtsEngine {
+    #[serde(rename = "espeak-ng")]
+    EspeakNg,
+    #[serde(rename = "piper")]
+    Piper,
+}
+
+/// Says `word` out loud with the configured engine, in the background.
+#[cfg(feature = "tui")]
+pub fn speak(word: &str, lang: &str, tts: &Tts) -> io::Result<()> {
+    match tts.engine {
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no audio, and text-to-speech isn't configured",
+        )),
+        Some(TtsEngine::EspeakNg) => {
+            let voice = tts.voice.as_deref().unwrap_or(lang);
+            Command::new("espeak-ng")
+                .args(["-v", voice, "--"])
+                .arg(word)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()?;
+            Ok(())
+        }
+        Some(TtsEngine::Piper) => {
+            let model = tts.model.as_ref().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "Piper needs a voice \"model\"")
+            })?;
+            // Piper only writes files; keep them so a word is synthesized once.
+            let wav = recording(word);
+            if !wav.is_file() {
+                if let Some(dir) = wav.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                let mut child = Command::new("piper")
+                    .arg("--model")
+                    .arg(model)
+                    .arg("--output_file")
+                    .arg(&wav)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(word.as_bytes())?;
+                }
+                if !child.wait()?.success() {
+                    return Err(io::Error::other("piper failed"));
+                }
+            }
+            play_audio(&wav.to_string_lossy())
+        }
+    }
+}
+
+/// Where Piper's recording of `word` is kept.
+fn recording(word: &str) -> PathBuf {
+    let name: String = word
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    storage::cache_dir()
+        .join("tts")
+        .join(format!("{}.wav", name))
+}
+
+/// Deletes the spoken recording of `word`, if there is one.
+pub fn forget(word: &str) -> io::Result<()> {
+    match fs::remove_file(recording(word)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn find_tool(
+    tools: &[(&'static str, &'static [&'static str])],
+    what: &str,
+) -> io::Result<(&'static str, &'static [&'static str])> {
+    tools
+        .iter()
+        .copied()
+        .find(|(program, _)| in_path(program))
+        .ok_or_else(|| {
+            let names: Vec<&str> = tools.iter().map(|(program, _)| *program).collect();
+            let names = match names.split_last() {
+                Some((last, [])) => last.to_string(),
+                Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+                None => String::new(),
+            };
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No {} ({}) found", what, names),
+            )
+        })
+}
+
+#[cfg(feature = "tui")]
+fn in_path(program: &str) -> bool {
+    let program = format!("{}{}", program, env::consts::EXE_SUFFIX);
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(&program).is_file()))
+        .unwrap_or(false)
+}
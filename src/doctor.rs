@@ -0,0 +1,270 @@
+use serde::Serialize;
+use std::{fs, io, path::Path, time::Instant};
+
+use crate::{
+    api::Lookup,
+    audio,
+    cli::Format,
+    config::Config,
+    index::WordIndex,
+    offline::{self, Dictionary},
+    providers::{Provider, Settings},
+    sherlock::SherlockPipeResponse,
+    storage, text,
+};
+
+/// Looked up in every provider and dictionary; finding nothing still shows
+/// it can be reached.
+const PROBE: &str = "test";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    /// What kind of thing was checked: "config", "provider", "dictionary",
+    /// "index" or "cache".
+    area: &'static str,
+    name: String,
+    status: Status,
+    detail: String,
+}
+impl Check {
+    fn new(area: &'static str, name: impl Into<String>, status: Status, detail: String) -> Self {
+        Self {
+            area,
+            name: name.into(),
+            status,
+            detail,
+        }
+    }
+}
+
+/// `doctor`: checks the config, that each provider in the chain can be
+/// reached, that the offline dictionaries and the word index open, and how
+/// much the caches take up. With `json`, a list of the checks for scripts.
+/// Exits with 1 when a check failed.
+pub async fn run(json: bool, settings: &Settings, format: Format) {
+    let mut checks = vec![config()];
+    checks.extend(providers(settings).await);
+    checks.extend(dictionaries());
+    checks.push(index());
+    checks.extend(caches());
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks).unwrap());
+    } else {
+        let rows: Vec<Vec<String>> = checks
+            .iter()
+            .map(|c| {
+                vec![
+                    c.status.label().to_string(),
+                    c.area.to_string(),
+                    c.name.clone(),
+                    c.detail.clone(),
+                ]
+            })
+            .collect();
+        let summary = match failed {
+            0 => format!("All {} checks passed", checks.len()),
+            1 => format!("1 of {} checks failed", checks.len()),
+            n => format!("{} of {} checks failed", n, checks.len()),
+        };
+        match format {
+            Format::Sherlock => {
+                let content = format!(
+                    "<span font_desc=\"monospace\">\n{}</span>",
+                    html_escape(&text::table(&rows))
+                );
+                let response = SherlockPipeResponse::new(summary, content, Vec::new());
+                response.print();
+            }
+            Format::Plain | Format::Sexp => print!("{}\n{}.\n", text::table(&rows), summary),
+            Format::Oneline => {
+                let problems: Vec<String> = checks
+                    .iter()
+                    .filter(|c| c.status == Status::Fail)
+                    .map(|c| format!("{} {}", c.area, c.name))
+                    .collect();
+                match problems.is_empty() {
+                    true => println!("{}", summary),
+                    false => println!("{}: {}", summary, problems.join(", ")),
+                }
+            }
+        }
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn config() -> Check {
+    let path = Config::path().display().to_string();
+    match Config::read() {
+        Ok((_, problems)) if !problems.is_empty() => {
+            let details: Vec<String> = problems.iter().map(ToString::to_string).collect();
+            Check::new("config", path, Status::Warn, details.join("; "))
+        }
+        Ok(_) if Config::path().is_file() => {
+            Check::new("config", path, Status::Ok, String::from("valid"))
+        }
+        Ok(_) => Check::new(
+            "config",
+            path,
+            Status::Ok,
+            String::from("not there; using the defaults"),
+        ),
+        Err(problem) => Check::new("config", path, Status::Fail, problem.to_string()),
+    }
+}
+
+/// Looks [`PROBE`] up in each provider of the chain, and the specialty
+/// glossary when one is set.
+async fn providers(settings: &Settings) -> Vec<Check> {
+    let mut chain = settings.providers.clone();
+    if let Some(domain) = settings.domain {
+        let domain = Provider::Domain(domain);
+        if !chain.contains(&domain) {
+            chain.insert(0, domain);
+        }
+    }
+    let mut checks = Vec::new();
+    for provider in chain {
+        if provider == Provider::Offline && offline::installed().is_empty() {
+            checks.push(Check::new(
+                "provider",
+                provider.name(),
+                Status::Warn,
+                format!(
+                    "no dictionaries in {}",
+                    offline::dictionaries_dir().display()
+                ),
+            ));
+            continue;
+        }
+        let started = Instant::now();
+        let result = provider.lookup(PROBE, &settings.lang).await;
+        let took = format!("{} ms", started.elapsed().as_millis());
+        let (status, detail) = match result {
+            Ok(Lookup::Found(_)) => (Status::Ok, format!("answered in {}", took)),
+            Ok(Lookup::NotFound) => (Status::Ok, format!("reached in {}", took)),
+            Ok(Lookup::ApiError { status, error }) => (
+                Status::Warn,
+                format!("status {} in {}: {}", status, took, error.title),
+            ),
+            Ok(Lookup::ParseError { status, .. }) => (
+                Status::Fail,
+                format!("unexpected response (status {}) in {}", status, took),
+            ),
+            Err(e) => (Status::Fail, e.to_string()),
+        };
+        checks.push(Check::new("provider", provider.name(), status, detail));
+    }
+    checks
+}
+
+/// Opens every file in the dictionaries folder and looks [`PROBE`] up in it.
+fn dictionaries() -> Vec<Check> {
+    let mut paths: Vec<_> = fs::read_dir(offline::dictionaries_dir())
+        .map(|dir| dir.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+    let mut checks = Vec::new();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let Some(opened) = Dictionary::open(&path) else {
+            continue;
+        };
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+        let (status, detail) = match opened.and_then(|d| d.lookup(PROBE, "en")) {
+            Ok(_) => (Status::Ok, text::size(size)),
+            Err(e) => (Status::Fail, e.to_string()),
+        };
+        checks.push(Check::new("dictionary", name, status, detail));
+    }
+    checks
+}
+
+/// The suggestion index, built first if it's missing or stale.
+fn index() -> Check {
+    let started = Instant::now();
+    let index = WordIndex::open();
+    let (status, detail) = match index.contains("the") {
+        true => (
+            Status::Ok,
+            format!("opened in {} ms", started.elapsed().as_millis()),
+        ),
+        false => (
+            Status::Warn,
+            String::from("the word list doesn't have 'the'"),
+        ),
+    };
+    Check::new("index", "word list", status, detail)
+}
+
+/// The size of each folder and file in the cache dir, and of the data dir.
+/// The audio cache is a warning when it's over its limit.
+fn caches() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let mut entries: Vec<_> = fs::read_dir(storage::cache_dir())
+        .map(|dir| dir.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    for path in entries {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let size = size_of(&path).unwrap_or_default();
+        let status = match name == "audio" && size > audio::max_bytes() {
+            true => Status::Warn,
+            false => Status::Ok,
+        };
+        checks.push(Check::new("cache", name, status, text::size(size)));
+    }
+    let data = size_of(&storage::data_dir()).unwrap_or_default();
+    checks.push(Check::new(
+        "cache",
+        storage::data_dir().display().to_string(),
+        Status::Ok,
+        format!("{} of history, words and dictionaries", text::size(data)),
+    ));
+    checks
+}
+
+/// The bytes taken up by `path`, following subfolders.
+fn size_of(path: &Path) -> io::Result<u64> {
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut total = 0;
+    for entry in fs::read_dir(path)?.flatten() {
+        total += size_of(&entry.path()).unwrap_or_default();
+    }
+    Ok(total)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
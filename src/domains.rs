@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, str::FromStr};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning},
+    http,
+    providers::Provider,
+    storage,
+};
+
+const LEGAL: &str = include_str!("../assets/glossaries/legal.tsv");
+const TECHNICAL: &str = include_str!("../assets/glossaries/technical.tsv");
+/// Label texts run to pages; the first sentences say what the drug is for.
+const MAX_LABEL_CHARS: usize = 300;
+/// The provider MeSH and FDA requests are made for, which picks their proxy.
+const MEDICAL: Provider = Provider::Domain(Domain::Medical);
+
+/// A field with its own vocabulary, looked up before the general dictionaries
+/// when it's chosen with `--domain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Domain {
+    /// MeSH scope notes and FDA drug labels.
+    Medical,
+    Legal,
+    /// Engineering and electronics.
+    Technical,
+}
+impl Domain {
+    pub const ALL: &'static [Domain] = &[Domain::Medical, Domain::Legal, Domain::Technical];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Domain::Medical => "medical",
+            Domain::Legal => "legal",
+            Domain::Technical => "technical",
+        }
+    }
+
+    /// The glossary shipped with the program, in English.
+    fn bundled(self) -> &'static str {
+        match self {
+            Domain::Medical => "",
+            Domain::Legal => LEGAL,
+            Domain::Technical => TECHNICAL,
+        }
+    }
+
+    /// The user's own glossary for the domain: `legal.tsv` for English,
+    /// `legal.de.tsv` for German and so on.
+    fn user_glossary(self, lang: &str) -> PathBuf {
+        let name = match lang {
+            "en" => format!("{}.tsv", self.name()),
+            _ => format!("{}.{}.tsv", self.name(), lang),
+        };
+        glossaries_dir().join(name)
+    }
+
+    /// The user's glossary first, then the sources built in for the domain.
+    pub async fn lookup(self, word: &str, lang: &str) -> Result<Lookup, surf::Error> {
+        let user = fs::read_to_string(self.user_glossary(lang)).unwrap_or_default();
+        let mut entries: Vec<DefinitionResponse> = glossary(&user, word).into_iter().collect();
+        if lang == "en" {
+            entries.extend(glossary(self.bundled(), word));
+            if self == Domain::Medical {
+                // What the glossary has is still worth showing offline.
+                match medical(word).await {
+                    Ok(found) => entries.extend(found),
+                    Err(e) if entries.is_empty() => return Err(e),
+                    Err(_) => {}
+                }
+            }
+        }
+        Ok(match entries.is_empty() {
+            true => Lookup::NotFound,
+            false => Lookup::Found(entries),
+        })
+    }
+}
+impl FromStr for Domain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|domain| domain.name() == s.to_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "Unknown domain '{}' (expected medical, legal or technical)",
+                    s
+                )
+            })
+    }
+}
+
+/// Where glossaries of the user's own go, one tab-separated file per domain.
+pub fn glossaries_dir() -> PathBuf {
+    storage::data_dir().join("glossaries")
+}
+
+/// The entry for `word` in a glossary of `term<TAB>definition[<TAB>part of
+/// speech]` lines, if it has one. A term on several lines has several senses.
+pub fn glossary(text: &str, word: &str) -> Option<DefinitionResponse> {
+    let word = word.trim().to_lowercase();
+    let mut headword = None;
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let mut fields = line.split('\t').map(str::trim);
+        let (Some(term), Some(definition)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if term.to_lowercase() != word || definition.is_empty() {
+            continue;
+        }
+        // The glossary's spelling, such as "DRY", rather than the query's.
+        headword.get_or_insert(term);
+        let part_of_speech = fields
+            .next()
+            .filter(|pos| !pos.is_empty())
+            .unwrap_or("noun");
+        let definition = sense(definition.to_string());
+        match meanings
+            .iter_mut()
+            .find(|m| m.part_of_speech == part_of_speech)
+        {
+            Some(meaning) => meaning.definitions.push(definition),
+            None => meanings.push(meaning(part_of_speech, vec![definition])),
+        }
+    }
+    Some(entry(headword?, meanings, Vec::new()))
+}
+
+fn sense(definition: String) -> Definition {
+    Definition {
+        definition,
+        example: None,
+        synonyms: None,
+        antonyms: None,
+        level: None,
+        region: None,
+        labels: Vec::new(),
+    }
+}
+
+fn meaning(part_of_speech: &str, definitions: Vec<Definition>) -> Meaning {
+    Meaning {
+        part_of_speech: part_of_speech.to_string(),
+        definitions,
+        synonyms: None,
+        antonyms: None,
+        forms: None,
+        gender: None,
+    }
+}
+
+fn entry(word: &str, meanings: Vec<Meaning>, urls: Vec<String>) -> DefinitionResponse {
+    DefinitionResponse {
+        word: word.to_string(),
+        phonetic: None,
+        phonetics: Vec::new(),
+        meanings,
+        source_urls: Some(urls).filter(|urls| !urls.is_empty()),
+        origin: None,
+        provider: None,
+    }
+}
+
+#[derive(Serialize)]
+struct MeshLookup<'a> {
+    label: &'a str,
+    #[serde(rename = "match")]
+    kind: &'static str,
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+struct Descriptor {
+    /// `http://id.nlm.nih.gov/mesh/D001241`
+    resource: String,
+}
+
+#[derive(Serialize)]
+struct Sparql {
+    query: String,
+    format: &'static str,
+}
+
+#[derive(Deserialize)]
+struct SparqlResponse {
+    results: Bindings,
+}
+
+#[derive(Deserialize)]
+struct Bindings {
+    bindings: Vec<NoteBinding>,
+}
+
+#[derive(Deserialize)]
+struct NoteBinding {
+    note: SparqlValue,
+}
+
+#[derive(Deserialize)]
+struct SparqlValue {
+    value: String,
+}
+
+#[derive(Serialize)]
+struct LabelSearch {
+    search: String,
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+struct LabelResponse {
+    results: Vec<Label>,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    indications_and_usage: Vec<String>,
+    #[serde(default)]
+    purpose: Vec<String>,
+    set_id: Option<String>,
+}
+
+/// MeSH's note on what the heading covers, and for drugs what the FDA label
+/// says they're used for.
+async fn medical(word: &str) -> Result<Option<DefinitionResponse>, surf::Error> {
+    let mut definitions = Vec::new();
+    let mut urls = Vec::new();
+    if let Some((id, note)) = mesh(word).await? {
+        definitions.push(sense(note));
+        urls.push(format!("https://meshb.nlm.nih.gov/record/ui?ui={}", id));
+    }
+    if let Some(label) = drug_label(word).await? {
+        let text = label
+            .indications_and_usage
+            .first()
+            .or(label.purpose.first());
+        if let Some(text) = text.map(|text| first_sentences(text)) {
+            definitions.push(sense(format!("(drug) {}", text)));
+        }
+        if let Some(set_id) = label.set_id {
+            urls.push(format!(
+                "https://dailymed.nlm.nih.gov/dailymed/lookup.cfm?setid={}",
+                set_id
+            ));
+        }
+    }
+    Ok(match definitions.is_empty() {
+        true => None,
+        false => Some(entry(word, vec![meaning("noun", definitions)], urls)),
+    })
+}
+
+/// The MeSH descriptor labelled `word` and the scope note of its preferred
+/// concept.
+async fn mesh(word: &str) -> Result<Option<(String, String)>, surf::Error> {
+    let lookup = MeshLookup {
+        label: word,
+        kind: "exact",
+        limit: 1,
+    };
+    let descriptors: Vec<Descriptor> =
+        http::provider_get(MEDICAL, "https://id.nlm.nih.gov/mesh/lookup/descriptor")?
+            .query(&lookup)?
+            .recv_json()
+            .await?;
+    let Some(descriptor) = descriptors.into_iter().next() else {
+        return Ok(None);
+    };
+    let query = Sparql {
+        query: format!(
+            "PREFIX meshv: <http://id.nlm.nih.gov/mesh/vocab#> \
+             SELECT ?note WHERE {{ <{}> meshv:preferredConcept ?concept . \
+             ?concept meshv:scopeNote ?note }} LIMIT 1",
+            descriptor.resource
+        ),
+        format: "JSON",
+    };
+    let response: SparqlResponse =
+        http::provider_get(MEDICAL, "https://id.nlm.nih.gov/mesh/sparql")?
+            .query(&query)?
+            .recv_json()
+            .await?;
+    let id = descriptor.resource.rsplit('/').next().unwrap_or_default();
+    Ok(response
+        .results
+        .bindings
+        .into_iter()
+        .next()
+        .map(|binding| (id.to_string(), binding.note.value.trim().to_string())))
+}
+
+/// The FDA label of a drug with `word` as its generic or brand name.
+async fn drug_label(word: &str) -> Result<Option<Label>, surf::Error> {
+    let search = LabelSearch {
+        search: format!(
+            r#"openfda.generic_name:"{0}" openfda.brand_name:"{0}""#,
+            word.replace('"', "")
+        ),
+        limit: 1,
+    };
+    let mut response = http::provider_get(MEDICAL, "https://api.fda.gov/drug/label.json")?
+        .query(&search)?
+        .await?;
+    // No match is a 404.
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+    let labels: LabelResponse = response.body_json().await?;
+    Ok(labels.results.into_iter().next())
+}
+
+/// The start of a label section without its heading ("INDICATIONS AND
+/// USAGE", "Uses"), cut at a sentence end once it's long enough.
+fn first_sentences(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let heading = words
+        .iter()
+        .take_while(|word| {
+            !word.chars().any(char::is_lowercase) || matches!(**word, "Uses" | "Use" | "Purpose")
+        })
+        .count();
+    let mut sentences = String::new();
+    for word in &words[heading.min(words.len().saturating_sub(1))..] {
+        if !sentences.is_empty() {
+            sentences.push(' ');
+        }
+        sentences.push_str(word);
+        if word.ends_with('.') && sentences.chars().count() >= MAX_LABEL_CHARS / 2 {
+            break;
+        }
+        if sentences.chars().count() >= MAX_LABEL_CHARS {
+            sentences.push('…');
+            break;
+        }
+    }
+    let mut chars = sentences.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => sentences,
+    }
+}
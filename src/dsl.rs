@@ -0,0 +1,382 @@
+use regex::Regex;
+#[cfg(feature = "compression")]
+use std::io::Read;
+use std::{fs, io, path::Path, sync::LazyLock};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Meaning, Phonetic},
+    index::OffsetIndex,
+    storage::{self, Bytes},
+};
+
+/// A `[tag]`, `[/tag]` or `[tag attributes]` markup element.
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(/?)([a-z!*']+)[^\]]*\]").unwrap());
+/// `[s]file.wav[/s]` sound references.
+static SOUND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[s\][^\[]*\[/s\]").unwrap());
+/// `{{comments}}`, which are dropped entirely.
+static COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)\{\{.*?\}\}").unwrap());
+/// Sense numbering at the start of a line: "1.", "2)", "a)".
+static NUMBERING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d+[.)]|[a-zа-я]\))\s*").unwrap());
+
+/// Lingvo's part-of-speech labels, as they appear in `[p]…[/p]`.
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("n", "noun"),
+    ("noun", "noun"),
+    ("v", "verb"),
+    ("vt", "verb"),
+    ("vi", "verb"),
+    ("verb", "verb"),
+    ("a", "adjective"),
+    ("adj", "adjective"),
+    ("adv", "adverb"),
+    ("prep", "preposition"),
+    ("conj", "conjunction"),
+    ("pron", "pronoun"),
+    ("int", "interjection"),
+    ("interj", "interjection"),
+    ("num", "numeral"),
+    ("pl", "plural noun"),
+];
+
+/// An ABBYY Lingvo `.dsl` dictionary, optionally dictzipped (`.dsl.dz`). The
+/// format is plain text in UTF-16 more often than not, so it's decoded and
+/// indexed once, into the cache, and the copy there is mapped from then on.
+pub struct Dsl {
+    /// The dictionary in UTF-8, without its comments.
+    text: Bytes,
+    /// Lower-cased headword → where the cards with it start in `text`.
+    index: OffsetIndex,
+}
+
+#[cfg(feature = "compression")]
+fn gunzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "compression"))]
+fn gunzip(_: &[u8]) -> io::Result<Vec<u8>> {
+    Err(crate::offline::without_compression(".dsl.dz files"))
+}
+
+impl Dsl {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let sources = [path.to_path_buf()];
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = storage::cache_dir().join("dictionaries");
+        let text = storage::cached(&dir.join(format!("{}.txt", name)), &sources, || {
+            let mut bytes = fs::read(path)?;
+            if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("dz"))
+            {
+                bytes = gunzip(&bytes)?;
+            }
+            Ok(COMMENT
+                .replace_all(&decode(&bytes), "")
+                .into_owned()
+                .into_bytes())
+        })?;
+        // Named after the version, like the word index.
+        let index = format!("{}.{}.fst", name, env!("CARGO_PKG_VERSION"));
+        let index = storage::cached(&dir.join(index), &sources, || {
+            Ok(OffsetIndex::build(headwords(text.as_ref())))
+        })?;
+        Ok(Self {
+            text,
+            index: OffsetIndex::new(index)?,
+        })
+    }
+
+    /// Every headword, in the lower-cased form lookups match.
+    pub fn headwords(&self) -> Vec<String> {
+        self.index.keys()
+    }
+
+    pub fn lookup(&self, word: &str) -> Vec<DefinitionResponse> {
+        let word = word.trim().to_lowercase();
+        self.index
+            .get(&word)
+            .into_iter()
+            .filter_map(|start| {
+                let (headwords, body) = card(self.text.as_ref().get(start as usize..)?);
+                let headword = headwords
+                    .iter()
+                    .find(|h| sorting_form(h) == word)
+                    .or(headwords.first())?;
+                Some(entry(&display_form(headword), &body))
+            })
+            .filter(|entry| !entry.meanings.is_empty())
+            .collect()
+    }
+}
+
+/// Each headword in `text` and where its card starts. A card is one or more
+/// headword lines and the indented body below them, which they share.
+fn headwords(text: &[u8]) -> Vec<(String, u64)> {
+    let mut headwords = Vec::new();
+    let (mut card_start, mut in_body, mut any) = (0, false, false);
+    let mut offset = 0;
+    for line in text.split_inclusive(|&b| b == b'\n') {
+        let start = offset;
+        offset += line.len();
+        let line = String::from_utf8_lossy(line);
+        let trimmed = line.trim_end();
+        // The `#NAME`-style header comes before the first card.
+        if trimmed.is_empty() || (!any && trimmed.starts_with('#')) {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            if in_body || !any {
+                card_start = start;
+                in_body = false;
+                any = true;
+            }
+            headwords.push((sorting_form(trimmed), card_start as u64));
+        } else {
+            in_body = any;
+        }
+    }
+    headwords
+}
+
+/// The headword lines and body of the card at the start of `text`.
+fn card(text: &[u8]) -> (Vec<String>, String) {
+    let (mut headwords, mut body) = (Vec::new(), String::new());
+    for line in text.split_inclusive(|&b| b == b'\n') {
+        let line = String::from_utf8_lossy(line);
+        if line.trim_end().is_empty() {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            // The next card's headwords.
+            if !body.is_empty() {
+                break;
+            }
+            headwords.push(line.trim_end().to_string());
+        } else {
+            body.push_str(&line);
+        }
+    }
+    (headwords, body)
+}
+
+fn decode(bytes: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| from([c[0], c[1]])).collect();
+        String::from_utf16_lossy(&units)
+    };
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        // No BOM: Lingvo's own files are UTF-16LE, which shows as zero odd bytes.
+        _ if bytes.len() >= 2 && bytes.iter().skip(1).step_by(2).take(64).all(|&b| b == 0) => {
+            utf16(bytes, u16::from_le_bytes)
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Undoes `\[`-style escapes.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The headword as indexed: `{unsorted parts}` left out, lower-cased.
+fn sorting_form(headword: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut chars = headword.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next().filter(|_| depth == 0)),
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            c if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out.trim().to_lowercase()
+}
+
+/// The headword as shown: `{unsorted parts}` kept, braces gone.
+fn display_form(headword: &str) -> String {
+    let mut out = String::new();
+    let mut chars = headword.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            '{' | '}' => {}
+            c => out.push(c),
+        }
+    }
+    out.trim().to_string()
+}
+
+/// The text of a line with all markup stripped and `~` standing for `headword`.
+fn plain(line: &str, headword: &str) -> String {
+    let marked = line
+        .replace("\\~", "\u{0}")
+        .replace('~', headword)
+        .replace('\u{0}', "\\~")
+        .replace("<<", "")
+        .replace(">>", "");
+    let stripped = TAG.replace_all(&marked, "");
+    unescape(stripped.trim())
+}
+
+/// The contents of every `[tag]…[/tag]` in `line`.
+fn tagged<'a>(line: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("[{}]", tag);
+    let close = format!("[/{}]", tag);
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let end = after.find(&close).unwrap_or(after.len());
+        found.push(&after[..end]);
+        rest = &after[end..];
+    }
+    found
+}
+
+/// Builds an entry from a card body, one line at a time: `[p]` part-of-speech
+/// labels start a new meaning, `[ex]` lines are examples for the sense above,
+/// `[t]` holds the transcription and anything else with text is a sense.
+fn entry(headword: &str, body: &str) -> DefinitionResponse {
+    let mut phonetic = None;
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for line in body.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(transcription) = tagged(line, "t").first() {
+            phonetic.get_or_insert_with(|| format!("[{}]", unescape(transcription)));
+            continue;
+        }
+        // Sounds live in a separate resources archive that isn't read.
+        let line = &*SOUND.replace_all(line, "");
+
+        let labels = tagged(line, "p");
+        let text = plain(line, headword);
+        let text = NUMBERING.replace(&text, "").to_string();
+        let part_of_speech = labels
+            .iter()
+            .map(|l| plain(l, headword).trim_end_matches('.').to_lowercase())
+            .find_map(|l| PARTS_OF_SPEECH.iter().find(|(abbr, _)| *abbr == l))
+            .map(|(_, name)| *name);
+        let label_only = labels
+            .iter()
+            .map(|l| plain(l, headword))
+            .collect::<Vec<_>>()
+            .join(" ")
+            == text;
+        if let (Some(pos), true) = (part_of_speech, label_only) {
+            meanings.push(new_meaning(pos));
+            continue;
+        }
+        if text.is_empty() {
+            continue;
+        }
+        if meanings.is_empty() {
+            meanings.push(new_meaning("definition"));
+        }
+        let definitions = &mut meanings.last_mut().unwrap().definitions;
+        let is_example = line.contains("[ex]") && tagged(line, "ex").len() == 1;
+        match definitions.last_mut() {
+            Some(previous) if is_example && previous.example.is_none() => {
+                previous.example = Some(text)
+            }
+            _ => definitions.push(Definition {
+                definition: text,
+                example: None,
+                synonyms: None,
+                antonyms: None,
+                level: None,
+                region: None,
+                labels: Vec::new(),
+            }),
+        }
+    }
+    meanings.retain(|m| !m.definitions.is_empty());
+    DefinitionResponse {
+        word: headword.to_string(),
+        phonetic: phonetic.clone(),
+        phonetics: phonetic
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings,
+        source_urls: None,
+        origin: None,
+        provider: None,
+    }
+}
+
+fn new_meaning(part_of_speech: &str) -> Meaning {
+    Meaning {
+        part_of_speech: part_of_speech.to_string(),
+        definitions: Vec::new(),
+        synonyms: None,
+        antonyms: None,
+        forms: None,
+        gender: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_cards_by_any_headword() {
+        let text = "\
+#NAME \"Test\"
+#INDEX_LANGUAGE \"English\"
+
+colour
+color
+\t[p]n[/p]
+\t[m1]1. the quality of light[/m1]
+
+\t[m1][ex]a bright ~[/ex][/m1]
+{to} run
+\t[m1]to move fast[/m1]
+";
+        let text = COMMENT.replace_all(text, "").into_owned().into_bytes();
+        let index = OffsetIndex::build(headwords(&text));
+        let dsl = Dsl {
+            text: Bytes::Built(text),
+            index: OffsetIndex::new(Bytes::Built(index)).unwrap(),
+        };
+        assert_eq!(dsl.headwords(), ["color", "colour", "run"]);
+
+        let found = dsl.lookup("Color");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].word, "color");
+        let senses = &found[0].meanings[0].definitions;
+        assert_eq!(found[0].meanings[0].part_of_speech, "noun");
+        assert_eq!(senses[0].definition, "the quality of light");
+        assert_eq!(senses[0].example.as_deref(), Some("a bright color"));
+
+        assert_eq!(dsl.lookup("run")[0].word, "to run");
+        assert!(dsl.lookup("walk").is_empty());
+    }
+}
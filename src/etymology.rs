@@ -0,0 +1,159 @@
+use crate::{
+    cli::Format,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    wiktionary,
+};
+
+/// Templates that name the form a word came from: inherited, derived and
+/// borrowed, with their `+` variants.
+const ANCESTRY: &[&str] = &[
+    "inh", "inh+", "der", "der+", "uder", "bor", "bor+", "lbor", "lbor+", "slbor",
+];
+/// Where the lineage ends and asides about relatives begin.
+const ASIDES: &[&str] = &[
+    "cognate",
+    "Cognate",
+    "compare",
+    "Compare",
+    "doublet",
+    "Doublet",
+    "{{cog",
+    "{{doublet",
+];
+
+/// One step in a word's history.
+pub struct Ancestor {
+    language: String,
+    form: String,
+}
+
+/// `etymology <word>`: where an English word came from, oldest form first.
+pub async fn run(word: &str, format: Format) -> Result<(), surf::Error> {
+    let word = word.trim();
+    let lineages = lineages(word).await?;
+
+    if lineages.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No etymology found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No etymology found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let trees: Vec<String> = lineages.iter().map(|lineage| tree(lineage)).collect();
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            content.push_str(&trees.join("\n"));
+            content.push_str("</span>");
+            let mut actions = Vec::new();
+            let mut copied: Vec<&str> = Vec::new();
+            for ancestor in lineages.iter().flat_map(|l| &l[..l.len() - 1]) {
+                if !copied.contains(&ancestor.form.as_str()) {
+                    copied.push(&ancestor.form);
+                    actions.push(ApplicationAction::copy(
+                        format!("{} {}", ancestor.language, ancestor.form),
+                        ancestor.form.clone(),
+                    ));
+                }
+            }
+            let response =
+                SherlockPipeResponse::new(format!(r#"Etymology of "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => print!("{}", trees.join("\n")),
+        Format::Oneline => {
+            let steps: Vec<String> = lineages[0]
+                .iter()
+                .rev()
+                .map(|a| match a.language.as_str() {
+                    "English" => a.form.clone(),
+                    _ => format!("{} {}", a.language, a.form),
+                })
+                .collect();
+            println!("{}", steps.join(" ← "));
+        }
+    }
+    Ok(())
+}
+
+/// Each etymology Wiktionary gives the English `word`, as the forms it came
+/// from, oldest first; none when it has no English entry.
+pub async fn lineages(word: &str) -> Result<Vec<Vec<Ancestor>>, surf::Error> {
+    let page = wiktionary::fetch_page(word).await?;
+    Ok(page
+        .as_deref()
+        .and_then(|page| wiktionary::language_section(page, "English"))
+        .map(|english| {
+            wiktionary::sections(english, "Etymology")
+                .into_iter()
+                .map(|section| lineage(word, section))
+                .filter(|lineage| lineage.len() > 1)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The ancestors named in the first paragraph of an etymology section, oldest
+/// first and ending with `word` itself. Relatives mentioned after "compare"
+/// or "cognate with" aren't ancestors and are left out.
+fn lineage(word: &str, section: &str) -> Vec<Ancestor> {
+    let paragraph = section
+        .trim_start()
+        .split("\n\n")
+        .next()
+        .unwrap_or_default();
+    let end = ASIDES
+        .iter()
+        .filter_map(|aside| paragraph.find(aside))
+        .min()
+        .unwrap_or(paragraph.len());
+
+    let mut ancestors: Vec<Ancestor> = Vec::new();
+    for (_, template) in wiktionary::templates_named(&paragraph[..end], ANCESTRY) {
+        let (Some(code), Some(term)) = (template.positional.get(1), template.positional.get(2))
+        else {
+            continue;
+        };
+        let form = match template.positional.get(3) {
+            Some(alt) if !alt.is_empty() => alt,
+            _ => term,
+        };
+        let form = wiktionary::plain(form);
+        if form.is_empty() || form == "-" {
+            continue;
+        }
+        let language = wiktionary::language_name(code).unwrap_or(code).to_string();
+        // "from Latin nātiōnem, accusative of nātiō": one step, not two.
+        if ancestors.last().is_some_and(|a| a.language == language) {
+            continue;
+        }
+        ancestors.push(Ancestor { language, form });
+    }
+    ancestors.reverse();
+    ancestors.push(Ancestor {
+        language: String::from("English"),
+        form: word.to_string(),
+    });
+    ancestors
+}
+
+/// The lineage drawn as a tree, each descendant indented under its source.
+pub fn tree(lineage: &[Ancestor]) -> String {
+    let mut lines = String::new();
+    for (depth, ancestor) in lineage.iter().enumerate() {
+        if depth > 0 {
+            lines.push_str(&"   ".repeat(depth - 1));
+            lines.push_str("└─ ");
+        }
+        lines.push_str(&format!("{} {}\n", ancestor.language, ancestor.form));
+    }
+    lines
+}
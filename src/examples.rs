@@ -0,0 +1,246 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+};
+
+use crate::{
+    cli::Format,
+    http,
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    storage, wiktionary,
+};
+
+/// Sentences shown per word.
+const LIMIT: usize = 8;
+/// Shorter sentences rarely show how a word is used.
+const MIN_WORDS: usize = 3;
+/// Longer ones are hard to take in at a glance.
+const MAX_CHARS: usize = 120;
+
+/// A real sentence that uses the word, and its translations.
+struct Sentence {
+    text: String,
+    /// Three-letter language codes and the translation in each.
+    translations: Vec<(String, String)>,
+}
+
+/// Where a downloaded Tatoeba export goes: `sentences.csv`, plus `links.csv`
+/// for translations. When it's there the API isn't used.
+fn dump_dir() -> PathBuf {
+    storage::data_dir().join("tatoeba")
+}
+
+/// Tatoeba identifies languages by their three-letter codes.
+fn iso3(code: &str) -> &str {
+    wiktionary::LANGUAGES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map_or(code, |(_, iso3, _)| iso3)
+}
+
+/// `examples <word>`: sentences from Tatoeba that use the word, with
+/// translations into the `to` languages.
+pub async fn run(
+    word: &str,
+    settings: &Settings,
+    to: &[String],
+    format: Format,
+) -> Result<(), surf::Error> {
+    let word = word.trim();
+    let from = iso3(&settings.lang);
+    let to: Vec<&str> = to.iter().map(|code| iso3(code)).collect();
+    let sentences = match dump_dir().join("sentences.csv").exists() {
+        true => from_dump(word, from, &to)?,
+        false => from_api(word, from, &to).await?,
+    };
+    let sentences = select(sentences);
+
+    if sentences.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No examples found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No example sentences found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        Format::Sherlock => {
+            let pattern = word_pattern(word);
+            let mut content = String::new();
+            for (i, sentence) in sentences.iter().enumerate() {
+                let text = pattern.replace_all(&sentence.text, "<b>$0</b>");
+                content.push_str(&format!("{}. {}\n", i + 1, text));
+                for (_, translation) in &sentence.translations {
+                    content.push_str(&format!("    <i>→ {}</i>\n", translation));
+                }
+            }
+            let actions = sentences
+                .iter()
+                .map(|s| ApplicationAction::copy(s.text.clone(), s.text.clone()))
+                .collect();
+            let response =
+                SherlockPipeResponse::new(format!(r#"Examples of "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            for (i, sentence) in sentences.iter().enumerate() {
+                println!("{}. {}", i + 1, sentence.text);
+                for (lang, translation) in &sentence.translations {
+                    println!("    → [{}] {}", lang, translation);
+                }
+            }
+        }
+        Format::Oneline => println!("{}", sentences[0].text),
+    }
+    Ok(())
+}
+
+/// `word` as a whole word, in any case.
+fn word_pattern(word: &str) -> Regex {
+    Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word))).unwrap()
+}
+
+/// Drops sentences that are too short or too long and near-duplicates that
+/// differ only in case or punctuation, then keeps the shortest.
+fn select(mut sentences: Vec<Sentence>) -> Vec<Sentence> {
+    let mut seen = HashSet::new();
+    sentences.retain(|sentence| {
+        let words = sentence.text.split_whitespace().count();
+        let key: String = sentence
+            .text
+            .chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+        words >= MIN_WORDS && sentence.text.chars().count() <= MAX_CHARS && seen.insert(key)
+    });
+    sentences.sort_by_key(|sentence| sentence.text.chars().count());
+    sentences.truncate(LIMIT);
+    sentences
+}
+
+#[derive(Serialize)]
+struct SearchQuery<'a> {
+    from: &'a str,
+    query: String,
+    /// Only sentences translated into this language.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<&'a str>,
+    orphans: &'static str,
+    unapproved: &'static str,
+    sort: &'static str,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    text: String,
+    /// Direct translations, then translations of translations.
+    #[serde(default)]
+    translations: Vec<Vec<SearchTranslation>>,
+}
+
+#[derive(Deserialize)]
+struct SearchTranslation {
+    text: String,
+    lang: Option<String>,
+}
+
+async fn from_api(word: &str, from: &str, to: &[&str]) -> Result<Vec<Sentence>, surf::Error> {
+    let query = SearchQuery {
+        from,
+        // An exact-word match rather than Tatoeba's default stemming.
+        query: format!("={}", word),
+        to: to.first().copied(),
+        orphans: "no",
+        unapproved: "no",
+        sort: "relevance",
+    };
+    let mut response = http::get("https://tatoeba.org/en/api_v0/search")
+        .query(&query)?
+        .await?;
+    if !response.status().is_success() {
+        return Ok(Vec::new());
+    }
+    let body: SearchResponse = response.body_json().await?;
+    Ok(body
+        .results
+        .into_iter()
+        .map(|result| Sentence {
+            text: result.text,
+            translations: result
+                .translations
+                .into_iter()
+                .flatten()
+                .filter_map(|t| Some((t.lang?, t.text)))
+                .filter(|(lang, _)| to.contains(&lang.as_str()))
+                .collect(),
+        })
+        .collect())
+}
+
+/// Scans the local export: one pass over the sentences for matches and their
+/// possible translations, one over the links to pair them up.
+fn from_dump(word: &str, from: &str, to: &[&str]) -> io::Result<Vec<Sentence>> {
+    let pattern = word_pattern(word);
+    let mut matches: Vec<(u64, Sentence)> = Vec::new();
+    let mut targets: HashMap<u64, (String, String)> = HashMap::new();
+    for line in BufReader::new(File::open(dump_dir().join("sentences.csv"))?).lines() {
+        let line = line?;
+        let mut fields = line.splitn(3, '\t');
+        let (Some(id), Some(lang), Some(text)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let Ok(id) = id.parse() else {
+            continue;
+        };
+        if lang == from && pattern.is_match(text) {
+            let sentence = Sentence {
+                text: text.to_string(),
+                translations: Vec::new(),
+            };
+            matches.push((id, sentence));
+        } else if to.contains(&lang) {
+            targets.insert(id, (lang.to_string(), text.to_string()));
+        }
+    }
+
+    let links = dump_dir().join("links.csv");
+    if !to.is_empty() && links.exists() {
+        let positions: HashMap<u64, usize> = matches
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, i))
+            .collect();
+        for line in BufReader::new(File::open(links)?).lines() {
+            let line = line?;
+            let Some((source, target)) = line.split_once('\t') else {
+                continue;
+            };
+            let (Ok(source), Ok(target)) = (source.parse::<u64>(), target.trim().parse()) else {
+                continue;
+            };
+            if let (Some(&i), Some(translation)) = (positions.get(&source), targets.get(&target)) {
+                matches[i].1.translations.push(translation.clone());
+            }
+        }
+    }
+    Ok(matches.into_iter().map(|(_, sentence)| sentence).collect())
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    history::{self, DAY},
+    srs::Deck,
+};
+
+/// How far ahead `export ics` schedules review sessions.
+const HORIZON_DAYS: u64 = 30;
+/// Length of a review session in the calendar.
+const SESSION_MINUTES: u64 = 15;
+
+/// What `export` writes.
+#[derive(Debug)]
+pub enum Kind {
+    /// An iCalendar file of upcoming review sessions.
+    Ics,
+}
+
+pub fn run(kind: Kind) {
+    match kind {
+        Kind::Ics => print!("{}", ics(&Deck::load(), history::now())),
+    }
+}
+
+/// A calendar with one event per day that has words due in the next
+/// [`HORIZON_DAYS`], starting when the first of them is due (words already
+/// overdue are due now) and with a reminder at the start.
+fn ics(deck: &Deck, now: u64) -> String {
+    let mut sessions: BTreeMap<u64, (u64, Vec<&str>)> = BTreeMap::new();
+    for card in &deck.cards {
+        let due = card.due.max(now);
+        if due >= now + HORIZON_DAYS * DAY {
+            continue;
+        }
+        let session = sessions.entry(due / DAY).or_insert((due, Vec::new()));
+        session.0 = session.0.min(due);
+        session.1.push(&card.word);
+    }
+
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//sherlock-dictionary//reviews//EN"),
+        String::from("X-WR-CALNAME:Word reviews"),
+    ];
+    for (day, (start, words)) in &sessions {
+        let summary = match words.len() {
+            1 => String::from("Review 1 word"),
+            n => format!("Review {} words", n),
+        };
+        lines.extend([
+            String::from("BEGIN:VEVENT"),
+            format!("UID:review-{}@sherlock-dictionary", day),
+            format!("DTSTAMP:{}", timestamp(now)),
+            format!("DTSTART:{}", timestamp(*start)),
+            format!("DTEND:{}", timestamp(start + SESSION_MINUTES * 60)),
+            format!("SUMMARY:{}", escape(&summary)),
+            format!("DESCRIPTION:{}", escape(&words.join(", "))),
+            String::from("BEGIN:VALARM"),
+            String::from("ACTION:DISPLAY"),
+            format!("DESCRIPTION:{}", escape(&summary)),
+            String::from("TRIGGER:PT0S"),
+            String::from("END:VALARM"),
+            String::from("END:VEVENT"),
+        ]);
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// `timestamp` as an iCalendar UTC date-time, e.g. `20261014T073015Z`.
+fn timestamp(timestamp: u64) -> String {
+    let (year, month, day) = history::civil(timestamp / DAY);
+    let seconds = timestamp % DAY;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Escapes the characters that are special in iCalendar text values.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits `line` into lines of at most 75 bytes, each continuation starting
+/// with a space, as iCalendar requires.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out
+}
@@ -0,0 +1,180 @@
+use crate::{
+    cli::Format,
+    hunspell::Hunspell,
+    index::WordIndex,
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Derivational suffixes, the ones that make new words rather than
+/// inflections of the same one.
+const SUFFIXES: &[&str] = &[
+    "al", "ally", "ize", "ise", "ization", "isation", "ity", "ness", "ly", "ful", "less", "ment",
+    "ation", "ion", "er", "or", "ist", "ism", "able", "ible", "ive", "ous", "ic", "ical", "ship",
+    "hood", "ify", "ance", "ence", "ant", "ent", "y", "en", "ish", "dom", "ery",
+];
+/// Prefixes that flip or repeat a word's meaning.
+const PREFIXES: &[&str] = &["un", "in", "im", "dis", "re", "non", "mis", "over", "under"];
+/// How many suffixes deep derivations go: nation → national → nationalize.
+const DEPTH: usize = 2;
+
+/// A word in the family and how it's built from the base.
+struct Member {
+    word: String,
+    /// "nation + al + ize"
+    derivation: String,
+}
+
+/// `family <word>`: words derived from the same base, each one clickable.
+pub fn run(word: &str, settings: &Settings, format: Format) {
+    let word = word.trim().to_lowercase();
+    let known = |candidate: &str| is_word(candidate, &settings.lang);
+    let base = root(&word, &known).unwrap_or_else(|| word.clone());
+    let members = family(&base, &known);
+
+    if members.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response = SherlockPipeResponse::message(
+                    "No word family found".to_string(),
+                    String::new(),
+                );
+                response.print();
+            }
+            _ => {
+                eprintln!("No derived words found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut rows = vec![vec![base.clone(), String::from("base")]];
+    rows.extend(
+        members
+            .iter()
+            .map(|m| vec![m.word.clone(), m.derivation.clone()]),
+    );
+    match format {
+        Format::Sherlock => {
+            let content = format!(
+                "<span font_desc=\"monospace\">\n{}</span>",
+                text::table(&rows)
+            );
+            let actions = std::iter::once(&base)
+                .chain(members.iter().map(|m| &m.word))
+                .filter(|w| **w != word)
+                .map(|w| ApplicationAction::lookup_word("Define", w, settings))
+                .collect();
+            let response = SherlockPipeResponse::new(
+                format!(r#"Word family of "{}""#, base),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => print!("{}", text::table(&rows)),
+        Format::Oneline => {
+            let words: Vec<&str> = members.iter().map(|m| m.word.as_str()).collect();
+            println!("{}: {}", base, words.join(", "));
+        }
+    }
+}
+
+/// Whether `word` is a real word: known to the word list, or to Hunspell when
+/// a dictionary for the language is installed.
+fn is_word(word: &str, lang: &str) -> bool {
+    WordIndex::shared().contains(word) || Hunspell::shared(lang).is_some_and(|h| h.check(word))
+}
+
+/// The shortest real word `word` is derived from, if it is derived at all:
+/// nationality → nation.
+fn root(word: &str, known: &impl Fn(&str) -> bool) -> Option<String> {
+    let mut current = word.to_string();
+    let mut found = None;
+    'strip: for _ in 0..DEPTH {
+        for suffix in SUFFIXES {
+            let Some(stem) = current.strip_suffix(suffix) else {
+                continue;
+            };
+            if stem.chars().count() < 3 {
+                continue;
+            }
+            let mut candidates = vec![stem.to_string(), format!("{}e", stem)];
+            candidates.extend(stem.strip_suffix('i').map(|s| format!("{}y", s)));
+            // Undo a doubled consonant: runn(er) → run.
+            let mut chars = stem.chars().rev();
+            if let (Some(last), true) = (chars.next(), chars.next() == stem.chars().last()) {
+                candidates.push(stem[..stem.len() - last.len_utf8()].to_string());
+            }
+            if let Some(base) = candidates.into_iter().find(|c| known(c)) {
+                current = base;
+                found = Some(current.clone());
+                continue 'strip;
+            }
+        }
+        break;
+    }
+    found
+}
+
+/// Spellings of `stem` + `suffix`: as is, with a final "e" dropped before a
+/// vowel, "y" turned into "i", or the last consonant doubled.
+fn attach(stem: &str, suffix: &str) -> Vec<String> {
+    let vowel = |c: char| "aeiou".contains(c);
+    let suffix_vowel = suffix.starts_with(vowel);
+    let mut spellings = vec![format!("{}{}", stem, suffix)];
+    if let Some(bare) = stem.strip_suffix('e') {
+        if suffix_vowel {
+            spellings.push(format!("{}{}", bare, suffix));
+        }
+    }
+    if let Some(bare) = stem.strip_suffix('y') {
+        if !suffix.starts_with('i') && !bare.ends_with(vowel) {
+            spellings.push(format!("{}i{}", bare, suffix));
+        }
+    }
+    let chars: Vec<char> = stem.chars().collect();
+    if let [.., a, b, c] = chars[..] {
+        if suffix_vowel && !vowel(a) && vowel(b) && !vowel(c) && !"wxy".contains(c) {
+            spellings.push(format!("{}{}{}", stem, c, suffix));
+        }
+    }
+    spellings
+}
+
+/// Real words made from `base` with up to [`DEPTH`] suffixes, plus prefixed
+/// forms of the base itself.
+fn family(base: &str, known: &impl Fn(&str) -> bool) -> Vec<Member> {
+    let mut members: Vec<Member> = Vec::new();
+    let mut frontier = vec![(base.to_string(), base.to_string())];
+    for _ in 0..DEPTH {
+        let mut next = Vec::new();
+        for (stem, derivation) in &frontier {
+            for suffix in SUFFIXES {
+                for word in attach(stem, suffix) {
+                    if word != base && !members.iter().any(|m| m.word == word) && known(&word) {
+                        let derivation = format!("{} + {}", derivation, suffix);
+                        members.push(Member {
+                            word: word.clone(),
+                            derivation: derivation.clone(),
+                        });
+                        next.push((word, derivation));
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+    for prefix in PREFIXES {
+        let word = format!("{}{}", prefix, base);
+        if known(&word) {
+            members.push(Member {
+                word,
+                derivation: format!("{} + {}", prefix, base),
+            });
+        }
+    }
+    members
+}
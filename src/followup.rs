@@ -0,0 +1,168 @@
+use std::iter;
+
+use crate::{
+    api::{DefinitionResponse, Lookup},
+    cli::Format,
+    etymology, examples,
+    providers::Settings,
+    sexp::Sexp,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+};
+
+/// A query about the word looked up last rather than a word of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUp {
+    Synonyms,
+    Antonyms,
+    Etymology,
+    Examples,
+}
+impl FollowUp {
+    pub fn parse(query: &str) -> Option<Self> {
+        match query.trim().to_lowercase().as_str() {
+            "syn" | "synonyms" => Some(FollowUp::Synonyms),
+            "ant" | "antonyms" => Some(FollowUp::Antonyms),
+            "ety" | "etymology" => Some(FollowUp::Etymology),
+            "ex" | "examples" => Some(FollowUp::Examples),
+            _ => None,
+        }
+    }
+}
+
+/// Answers `follow_up` about `word`.
+pub async fn run(
+    follow_up: FollowUp,
+    word: &str,
+    settings: &Settings,
+    format: Format,
+) -> Result<(), surf::Error> {
+    match follow_up {
+        FollowUp::Etymology => etymology::run(word, format).await,
+        FollowUp::Examples => examples::run(word, settings, &[], format).await,
+        FollowUp::Synonyms | FollowUp::Antonyms => {
+            let entries = match settings.lookup(word).await? {
+                Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+                _ => Vec::new(),
+            };
+            related(
+                follow_up == FollowUp::Synonyms,
+                word,
+                &entries,
+                settings,
+                format,
+            );
+            Ok(())
+        }
+    }
+}
+
+/// The synonyms or antonyms of each part of speech, each one clickable.
+fn related(
+    synonyms: bool,
+    word: &str,
+    entries: &[DefinitionResponse],
+    settings: &Settings,
+    format: Format,
+) {
+    let kind = match synonyms {
+        true => "Synonyms",
+        false => "Antonyms",
+    };
+    let groups = groups(synonyms, entries);
+    let mut all: Vec<&str> = Vec::new();
+    for related in groups.iter().flat_map(|(_, words)| words) {
+        if !all.contains(related) {
+            all.push(related);
+        }
+    }
+
+    if groups.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response = SherlockPipeResponse::message(
+                    format!("No {} found", kind.to_lowercase()),
+                    String::new(),
+                );
+                response.print();
+            }
+            _ => {
+                eprintln!("No {} found for '{}'.", kind.to_lowercase(), word);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::new();
+            for (pos, words) in &groups {
+                content.push_str(&format!("─── <b><i>{}</i></b> ───\n", pos));
+                content.push_str(&format!("{}\n\n", words.join(", ")));
+            }
+            let actions = all
+                .iter()
+                .map(|related| ApplicationAction::lookup_word("Define", related, settings))
+                .collect();
+            let response = SherlockPipeResponse::new(
+                format!(r#"{} of "{}""#, kind, word),
+                content.trim_end().to_string(),
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain => {
+            for (pos, words) in &groups {
+                println!("{}: {}", pos, words.join(", "));
+            }
+        }
+        Format::Oneline => println!("{}: {}", word, all.join(", ")),
+        // An alist from each part of speech to its words.
+        Format::Sexp => {
+            let alist = groups.iter().map(|(pos, words)| {
+                let words = words.iter().map(|word| Sexp::string(word));
+                Sexp::List(iter::once(Sexp::symbol(pos)).chain(words).collect())
+            });
+            println!("{}", Sexp::List(alist.collect()));
+        }
+    }
+}
+
+/// The synonyms, or antonyms, of each part of speech in `entries`, those of
+/// its senses included, without repeats.
+pub fn groups(synonyms: bool, entries: &[DefinitionResponse]) -> Vec<(&str, Vec<&str>)> {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for meaning in entries.iter().flat_map(|entry| &entry.meanings) {
+        let lists: Vec<&Option<Vec<String>>> = match synonyms {
+            true => std::iter::once(&meaning.synonyms)
+                .chain(meaning.definitions.iter().map(|d| &d.synonyms))
+                .collect(),
+            false => std::iter::once(&meaning.antonyms)
+                .chain(meaning.definitions.iter().map(|d| &d.antonyms))
+                .collect(),
+        };
+        let words: Vec<&str> = lists
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let group = match groups
+            .iter_mut()
+            .position(|(pos, _)| *pos == meaning.part_of_speech)
+        {
+            Some(i) => &mut groups[i].1,
+            None => {
+                groups.push((meaning.part_of_speech.as_str(), Vec::new()));
+                &mut groups.last_mut().unwrap().1
+            }
+        };
+        for word in words {
+            if !group.contains(&word) {
+                group.push(word);
+            }
+        }
+    }
+    groups.retain(|(_, words)| !words.is_empty());
+    groups
+}
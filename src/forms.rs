@@ -0,0 +1,349 @@
+use crate::{
+    api::Lookup,
+    cli::Format,
+    conjugation::{self, Conjugation},
+    morphology::{self, Comparison, Plural, VerbForms},
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text, wiktionary,
+};
+
+/// Inflection tables for one part of speech.
+struct Section {
+    part_of_speech: &'static str,
+    rows: Vec<Vec<String>>,
+    /// Distinct word forms worth a copy action.
+    forms: Vec<String>,
+}
+
+/// `forms <word>`: plurals, comparatives and conjugation tables.
+pub async fn run(word: &str, settings: &Settings, format: Format) -> Result<(), surf::Error> {
+    let word = word.trim().to_lowercase();
+    if conjugation::LANGUAGES.contains(&settings.lang.as_str()) {
+        return conjugated(&word, settings, format).await;
+    }
+    // The dictionary tells us which parts of speech to inflect; Wiktionary's
+    // headword templates know the irregular forms. Either may be missing.
+    let (lookup, page) = tokio::join!(settings.lookup(&word), wiktionary::fetch_page(&word));
+    let page = page.ok().flatten();
+    let wiktionary_forms = page
+        .as_deref()
+        .and_then(|page| wiktionary::language_section(page, "English"))
+        .map(|english| morphology::from_wiktionary(&word, english))
+        .unwrap_or_default();
+
+    let mut parts: Vec<&str> = match &lookup? {
+        Lookup::Found(entries) => entries
+            .iter()
+            .flat_map(|e| &e.meanings)
+            .map(|m| m.part_of_speech.as_str())
+            .filter_map(|pos| match pos {
+                "verb" => Some("verb"),
+                "noun" => Some("noun"),
+                "adjective" => Some("adjective"),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if wiktionary_forms.verb.is_some() {
+        parts.push("verb");
+    }
+    if wiktionary_forms.plural.is_some() {
+        parts.push("noun");
+    }
+    if wiktionary_forms.comparison.is_some() {
+        parts.push("adjective");
+    }
+    // Verbs first: their table is the one people usually come for.
+    parts.sort_by_key(|pos| ["verb", "noun", "adjective"].iter().position(|p| p == pos));
+    parts.dedup();
+
+    let sections: Vec<Section> = parts
+        .into_iter()
+        .map(|pos| match pos {
+            "verb" => verb_section(
+                wiktionary_forms
+                    .verb
+                    .clone()
+                    .unwrap_or_else(|| morphology::verb(&word)),
+            ),
+            "noun" => noun_section(
+                &word,
+                wiktionary_forms
+                    .plural
+                    .clone()
+                    .unwrap_or_else(|| morphology::plural(&word)),
+            ),
+            _ => adjective_section(
+                &word,
+                wiktionary_forms
+                    .comparison
+                    .clone()
+                    .unwrap_or_else(|| morphology::comparison(&word)),
+            ),
+        })
+        .collect();
+
+    print_sections(&word, &sections, format);
+    Ok(())
+}
+
+/// The conjugation tables of a German, French or Spanish verb. The rules
+/// don't know a verb from a noun that looks like one, so words the
+/// dictionary has, but not as a verb, aren't conjugated.
+async fn conjugated(word: &str, settings: &Settings, format: Format) -> Result<(), surf::Error> {
+    let is_verb = match settings.lookup(word).await {
+        Ok(Lookup::Found(entries)) => entries
+            .iter()
+            .flat_map(|e| &e.meanings)
+            .any(|m| m.part_of_speech == "verb"),
+        _ => true,
+    };
+    let sections: Vec<Section> = conjugation::conjugate(word, &settings.lang)
+        .filter(|_| is_verb)
+        .map(conjugation_section)
+        .into_iter()
+        .collect();
+    print_sections(word, &sections, format);
+    Ok(())
+}
+
+fn print_sections(word: &str, sections: &[Section], format: Format) {
+    if sections.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No forms found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No forms found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            let mut actions = Vec::new();
+            let mut copied: Vec<&str> = Vec::new();
+            for section in sections {
+                content.push_str(&format!(
+                    "─── <b><i>{}</i></b> ───\n\n",
+                    section.part_of_speech
+                ));
+                content.push_str(&text::table(&section.rows));
+                content.push('\n');
+                for form in &section.forms {
+                    if !copied.contains(&form.as_str()) {
+                        copied.push(form);
+                        actions.push(ApplicationAction::copy(form.clone(), form.clone()));
+                    }
+                }
+            }
+            content.push_str("────────────\n</span>");
+            let response =
+                SherlockPipeResponse::new(format!(r#"Forms of "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            for section in sections {
+                println!("─── {} ───\n", section.part_of_speech);
+                print!("{}", text::table(&section.rows));
+                println!();
+            }
+        }
+        Format::Oneline => {
+            let mut forms: Vec<&str> = Vec::new();
+            for form in sections.iter().flat_map(|s| &s.forms) {
+                if !forms.contains(&form.as_str()) {
+                    forms.push(form);
+                }
+            }
+            println!("{}: {}", word, forms.join(", "));
+        }
+    }
+}
+
+fn row(cells: &[&str]) -> Vec<String> {
+    cells.iter().map(|c| c.to_string()).collect()
+}
+
+fn verb_section(forms: VerbForms) -> Section {
+    let base = forms.base.as_str();
+    let ing = forms.present_participle.as_str();
+    let pp = forms.past_participle.as_str();
+    let is_be = base == "be";
+
+    let (present_i, present_you) = if is_be { ("am", "are") } else { (base, base) };
+    let past_you = if is_be { "were" } else { forms.past.as_str() };
+
+    let mut rows = vec![
+        row(&["infinitive", &format!("to {}", base)]),
+        row(&["3rd person singular", &forms.third_person]),
+        row(&["present participle", ing]),
+        row(&["simple past", &forms.past]),
+        row(&["past participle", pp]),
+        Vec::new(),
+        row(&["tense", "I", "you/we/they", "he/she/it"]),
+        row(&["present", present_i, present_you, &forms.third_person]),
+        row(&["past", &forms.past, past_you, &forms.past]),
+    ];
+    let compound: [(&str, [String; 3]); 6] = [
+        (
+            "present progressive",
+            [
+                format!("am {}", ing),
+                format!("are {}", ing),
+                format!("is {}", ing),
+            ],
+        ),
+        (
+            "past progressive",
+            [
+                format!("was {}", ing),
+                format!("were {}", ing),
+                format!("was {}", ing),
+            ],
+        ),
+        (
+            "present perfect",
+            [
+                format!("have {}", pp),
+                format!("have {}", pp),
+                format!("has {}", pp),
+            ],
+        ),
+        (
+            "past perfect",
+            [
+                format!("had {}", pp),
+                format!("had {}", pp),
+                format!("had {}", pp),
+            ],
+        ),
+        (
+            "future",
+            [
+                format!("will {}", base),
+                format!("will {}", base),
+                format!("will {}", base),
+            ],
+        ),
+        (
+            "conditional",
+            [
+                format!("would {}", base),
+                format!("would {}", base),
+                format!("would {}", base),
+            ],
+        ),
+    ];
+    for (tense, [i, you, he]) in compound {
+        rows.push(vec![tense.to_string(), i, you, he]);
+    }
+
+    let mut distinct = vec![
+        forms.base.clone(),
+        forms.third_person.clone(),
+        forms.present_participle.clone(),
+        forms.past.clone(),
+        forms.past_participle.clone(),
+    ];
+    if is_be {
+        distinct.extend(["am", "are", "were"].map(String::from));
+    }
+    distinct.dedup();
+    Section {
+        part_of_speech: "verb",
+        rows,
+        forms: distinct,
+    }
+}
+
+fn conjugation_section(conjugation: Conjugation) -> Section {
+    let mut rows: Vec<Vec<String>> = conjugation
+        .forms
+        .iter()
+        .map(|(label, form)| row(&[label, form]))
+        .collect();
+    // Three tenses side by side keep the table narrow enough for a tile.
+    for tenses in conjugation.tenses.chunks(3) {
+        rows.push(Vec::new());
+        rows.push(
+            std::iter::once(String::new())
+                .chain(tenses.iter().map(|(tense, _)| tense.to_string()))
+                .collect(),
+        );
+        for (i, person) in conjugation.persons.iter().enumerate() {
+            rows.push(
+                std::iter::once(person.to_string())
+                    .chain(tenses.iter().map(|(_, forms)| forms[i].clone()))
+                    .collect(),
+            );
+        }
+    }
+
+    // The auxiliary isn't a form of the verb.
+    let mut distinct: Vec<String> = conjugation
+        .forms
+        .iter()
+        .take(3)
+        .map(|(_, form)| form.clone())
+        .collect();
+    if let Some((_, present)) = conjugation.tenses.first() {
+        for form in present {
+            if !distinct.contains(form) {
+                distinct.push(form.clone());
+            }
+        }
+    }
+    Section {
+        part_of_speech: "verb",
+        rows,
+        forms: distinct,
+    }
+}
+
+fn noun_section(word: &str, plural: Plural) -> Section {
+    let (plural_cell, forms) = match plural {
+        Plural::Countable(plural) => (plural.clone(), vec![word.to_string(), plural]),
+        Plural::Uncountable => (String::from("(uncountable)"), vec![word.to_string()]),
+    };
+    Section {
+        part_of_speech: "noun",
+        rows: vec![row(&["singular", word]), row(&["plural", &plural_cell])],
+        forms,
+    }
+}
+
+fn adjective_section(word: &str, comparison: Comparison) -> Section {
+    let (rows, forms) = match comparison {
+        Comparison::Graded {
+            comparative,
+            superlative,
+        } => (
+            vec![
+                row(&["positive", word]),
+                row(&["comparative", &comparative]),
+                row(&["superlative", &superlative]),
+            ],
+            vec![word.to_string(), comparative, superlative],
+        ),
+        Comparison::NotComparable => (
+            vec![
+                row(&["positive", word]),
+                row(&["comparative", "(not comparable)"]),
+            ],
+            vec![word.to_string()],
+        ),
+    };
+    Section {
+        part_of_speech: "adjective",
+        rows,
+        forms,
+    }
+}
@@ -0,0 +1,93 @@
+use serde::Deserialize;
+
+use crate::{api::DefinitionResponse, config::Config, text};
+
+/// How a Japanese headword's reading is shown, from `furigana` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Style {
+    /// On a line of its own over the kanji it reads, as ruby is printed.
+    #[default]
+    Above,
+    /// In brackets after the kanji: 食（た）べる.
+    Inline,
+    /// Only as the entry's pronunciation, the way other words' IPA is.
+    Off,
+}
+
+/// A headword with kanji and its reading, split where the reading applies:
+/// `食べる` read `たべる` is `食` read `た`, with `べる` after it.
+pub struct Ruby<'a> {
+    before: &'a str,
+    base: &'a str,
+    after: &'a str,
+    reading: &'a str,
+    style: Style,
+}
+impl<'a> Ruby<'a> {
+    /// The ruby for `entry`, when its headword has kanji, its pronunciation is
+    /// in kana, as Jisho's readings are, and the config doesn't turn it off.
+    pub fn of(entry: &'a DefinitionResponse) -> Option<Self> {
+        let style = Config::load().furigana;
+        if style == Style::Off {
+            return None;
+        }
+        let (word, reading) = (entry.word.as_str(), entry.ipa()?);
+        if !word.chars().any(text::is_ideograph) || !reading.chars().all(is_kana) {
+            return None;
+        }
+        // Kana the word and its reading share at either end is okurigana,
+        // or a prefix like お, and needs no reading of its own.
+        let prefix: usize = word
+            .chars()
+            .zip(reading.chars())
+            .take_while(|(w, r)| w == r && is_kana(*w))
+            .map(|(w, _)| w.len_utf8())
+            .sum();
+        let suffix: usize = word[prefix..]
+            .chars()
+            .rev()
+            .zip(reading[prefix..].chars().rev())
+            .take_while(|(w, r)| w == r && is_kana(*w))
+            .map(|(w, _)| w.len_utf8())
+            .sum();
+        let base = &word[prefix..word.len() - suffix];
+        let reading = &reading[prefix..reading.len() - suffix];
+        if base.is_empty() || reading.is_empty() {
+            return None;
+        }
+        Some(Self {
+            before: &word[..prefix],
+            base,
+            after: &word[word.len() - suffix..],
+            reading,
+            style,
+        })
+    }
+
+    /// The headword's lines, the word itself last: with [`Style::Above`], the
+    /// reading padded to sit centered over the kanji, then the word, padded
+    /// too when the reading is the wider.
+    pub fn lines(&self) -> Vec<String> {
+        let word = format!("{}{}{}", self.before, self.base, self.after);
+        if self.style == Style::Inline {
+            return vec![format!(
+                "{}{}（{}）{}",
+                self.before, self.base, self.reading, self.after
+            )];
+        }
+        // Where the reading starts, in columns from where the word does.
+        let start = (text::width(self.before) + text::width(self.base) / 2) as isize
+            - (text::width(self.reading) / 2) as isize;
+        let shift = (-start).max(0) as usize;
+        vec![
+            format!("{}{}", " ".repeat(start.max(0) as usize), self.reading),
+            format!("{}{}", " ".repeat(shift), word),
+        ]
+    }
+}
+
+/// Hiragana, katakana and the long vowel mark.
+fn is_kana(c: char) -> bool {
+    matches!(u32::from(c), 0x3041..=0x30FF)
+}
@@ -0,0 +1,174 @@
+use serde::Serialize;
+
+use crate::api::DefinitionResponse;
+
+/// French nouns starting with an "aspirated" h, which keep their article
+/// whole: le héros, not l'héros.
+const ASPIRATED: &[&str] = &[
+    "hache",
+    "haie",
+    "haine",
+    "hall",
+    "hamac",
+    "hameau",
+    "hamster",
+    "hanche",
+    "handicap",
+    "hangar",
+    "hareng",
+    "haricot",
+    "harpe",
+    "hasard",
+    "hausse",
+    "haut",
+    "hauteur",
+    "héros",
+    "hérisson",
+    "hibou",
+    "hiérarchie",
+    "hockey",
+    "homard",
+    "honte",
+    "hoquet",
+    "hors-d'œuvre",
+    "housse",
+    "hublot",
+    "huit",
+    "hurlement",
+    "hutte",
+];
+
+/// Feminine Spanish nouns starting with a stressed a, which take "el" in
+/// the singular: el agua.
+const STRESSED_A: &[&str] = &[
+    "agua", "águila", "ala", "alba", "alma", "ama", "ancla", "ansia", "arca", "área", "arma",
+    "arpa", "asa", "aula", "ave", "haba", "habla", "hacha", "hada", "hambre",
+];
+
+/// A noun's grammatical gender, from providers that record it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+    /// Masculine and feminine merged into one, as in Dutch and the
+    /// Scandinavian languages.
+    Common,
+    /// Either, depending on who's meant: el estudiante, la estudiante.
+    MasculineFeminine,
+}
+impl Gender {
+    /// Reads a gender as dictionaries abbreviate it: "m", "f.", "neut",
+    /// "m or f" or Wiktionary's "m-p" (masculine plural) and "mf".
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code
+            .trim()
+            .trim_end_matches('.')
+            .to_lowercase()
+            .replace(" or ", "/");
+        let code = code
+            .split_once(['-', ' '])
+            .map_or(code.as_str(), |(gender, _)| gender);
+        match code {
+            "m" | "masc" | "masculine" => Some(Gender::Masculine),
+            "f" | "fem" | "feminine" => Some(Gender::Feminine),
+            "n" | "nt" | "neut" | "neuter" => Some(Gender::Neuter),
+            "c" | "com" | "common" => Some(Gender::Common),
+            "mf" | "m/f" | "f/m" | "mfbysense" => Some(Gender::MasculineFeminine),
+            _ => None,
+        }
+    }
+
+    /// How dictionaries tag it: "m", "f", "n", "c" or "m/f".
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Gender::Masculine => "m",
+            Gender::Feminine => "f",
+            Gender::Neuter => "n",
+            Gender::Common => "c",
+            Gender::MasculineFeminine => "m/f",
+        }
+    }
+}
+
+/// The gender of `entry`'s first noun with one, the noun its title is about.
+pub fn of(entry: &DefinitionResponse) -> Option<Gender> {
+    entry.meanings.iter().find_map(|meaning| meaning.gender)
+}
+
+/// `word` with the definite article a noun of `gender` takes in `lang`:
+/// "der Hund", "l'homme", "lo zio". The Scandinavian languages put theirs
+/// at the end of the word, so it gets the indefinite one: "en bil". In
+/// languages without articles, and for genders a language doesn't have,
+/// `word` is as it was.
+pub fn with_article(word: &str, gender: Gender, lang: &str) -> String {
+    let lower = word.to_lowercase();
+    let vowel = lower.starts_with(|c: char| "aeiouàâäéèêëîïôöùûüœæ".contains(c));
+    let article = match (lang, gender) {
+        ("de", Gender::Masculine) => "der",
+        ("de", Gender::Feminine) => "die",
+        ("de", Gender::Neuter) => "das",
+        ("de", Gender::MasculineFeminine) => "der/die",
+        ("fr", Gender::Masculine | Gender::Feminine | Gender::MasculineFeminine)
+            if vowel || (lower.starts_with('h') && !ASPIRATED.contains(&lower.as_str())) =>
+        {
+            "l'"
+        }
+        ("fr", Gender::Masculine) => "le",
+        ("fr", Gender::Feminine) => "la",
+        ("fr", Gender::MasculineFeminine) => "le/la",
+        ("es", Gender::Masculine) => "el",
+        ("es", Gender::Feminine) if STRESSED_A.contains(&lower.as_str()) => "el",
+        ("es", Gender::Feminine) => "la",
+        ("es", Gender::MasculineFeminine) => "el/la",
+        ("it", Gender::Masculine | Gender::Feminine | Gender::MasculineFeminine) if vowel => "l'",
+        ("it", Gender::Masculine) if takes_lo(&lower) => "lo",
+        ("it", Gender::Masculine) => "il",
+        ("it", Gender::Feminine) => "la",
+        ("it", Gender::MasculineFeminine) => "il/la",
+        ("pt", Gender::Masculine) => "o",
+        ("pt", Gender::Feminine) => "a",
+        ("pt", Gender::MasculineFeminine) => "o/a",
+        ("nl", Gender::Neuter) => "het",
+        ("nl", _) => "de",
+        ("sv", Gender::Neuter) => "ett",
+        ("sv", _) => "en",
+        ("da", Gender::Neuter) => "et",
+        ("da", _) => "en",
+        ("nb" | "no", Gender::Feminine) => "ei",
+        ("nb" | "no", Gender::Neuter) => "et",
+        ("nb" | "no", _) => "en",
+        _ => return word.to_string(),
+    };
+    match article.ends_with('\'') {
+        true => format!("{}{}", article, word),
+        false => format!("{} {}", article, word),
+    }
+}
+
+/// Whether a masculine Italian noun takes "lo": before s and a consonant,
+/// z, gn, ps, pn, x and y.
+fn takes_lo(word: &str) -> bool {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some('s'), Some(next)) => !"aeiouàèéìòù".contains(next),
+        (Some('z' | 'x' | 'y'), _) => true,
+        _ => ["gn", "ps", "pn"]
+            .iter()
+            .any(|start| word.starts_with(start)),
+    }
+}
+
+/// `entry`'s headword as a title line shows it: with its article and gender
+/// when a provider gave one, "der Hund (m)", and as it is otherwise.
+pub fn headline(entry: &DefinitionResponse, lang: &str) -> String {
+    match of(entry) {
+        Some(gender) => format!(
+            "{} ({})",
+            with_article(&entry.word, gender, lang),
+            gender.abbreviation()
+        ),
+        None => entry.word.clone(),
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Mutex,
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    api::Lookup,
+    cli::Format,
+    history,
+    known::Known,
+    providers::Settings,
+    segment,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    stats::Counters,
+    storage, text,
+    wordlist::WordList,
+};
+
+/// Words this common are taken as known and not glossed, unless the user set
+/// their vocabulary size.
+const TRIVIAL_RANK: usize = 300;
+/// Lookups in flight at once; enough to be quick, few enough to be polite.
+const CONCURRENCY: usize = 6;
+/// Glosses longer than this are cut, so the table stays one line per word.
+const MAX_CHARS: usize = 70;
+/// Seconds a cached gloss is used before it's looked up again.
+const MAX_AGE: u64 = 30 * 24 * 60 * 60;
+
+/// A word's short definition, for listing many words at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gloss {
+    /// The entry looked up, e.g. "run" for "running".
+    pub headword: String,
+    /// Interned: a big cache has the same few dozen for every word.
+    #[serde(deserialize_with = "interned")]
+    pub part_of_speech: Cow<'static, str>,
+    pub definition: String,
+}
+
+/// The one copy of `text` every gloss shares. Parts of speech are a small,
+/// closed set, so the copies kept for the rest of the run stay few.
+fn intern(text: &str) -> &'static str {
+    static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut interned = INTERNED.lock().unwrap();
+    match interned.get(text) {
+        Some(text) => text,
+        None => {
+            let text: &'static str = Box::leak(text.into());
+            interned.insert(text);
+            text
+        }
+    }
+}
+
+fn interned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cow<'static, str>, D::Error> {
+    let text = Cow::<str>::deserialize(deserializer)?;
+    Ok(Cow::Borrowed(intern(&text)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cached {
+    /// `None` when nothing was found, so misses aren't asked again either.
+    gloss: Option<Gloss>,
+    timestamp: u64,
+}
+
+/// Where glosses are cached, one file per provider chain and language since
+/// each gives different definitions.
+fn cache_path(settings: &Settings) -> PathBuf {
+    storage::cache_dir().join("gloss").join(format!(
+        "{}-{}.json",
+        settings.providers_name().replace(',', "+"),
+        settings.lang
+    ))
+}
+
+/// `gloss <sentence>`: a short definition of every word in the sentence that
+/// isn't among the most common or known to the user, in the order they appear.
+pub async fn run(sentence: &str, settings: &Settings, format: Format) -> Result<(), surf::Error> {
+    let list = WordList::shared();
+    let known = Known::load();
+    let trivial = settings.vocabulary.unwrap_or(TRIVIAL_RANK);
+    let words: Vec<String> = segment::words(sentence)
+        .into_iter()
+        .filter(|word| list.rank(word).is_none_or(|rank| rank >= trivial))
+        .filter(|word| !known.contains(word))
+        .collect();
+
+    let found = glosses(&words, settings).await;
+    let glosses: Vec<(&String, &Gloss)> = words
+        .iter()
+        .filter_map(|word| Some((word, found.get(word)?)))
+        .filter(|(_, gloss)| !known.contains(&gloss.headword))
+        .collect();
+    if glosses.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("Nothing to gloss".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No uncommon words with definitions in that sentence.");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = glosses
+        .iter()
+        .map(|(word, gloss)| {
+            let word = match **word == gloss.headword {
+                true => word.to_string(),
+                false => format!("{} ({})", word, gloss.headword),
+            };
+            vec![
+                word,
+                gloss.part_of_speech.to_string(),
+                gloss.definition.clone(),
+            ]
+        })
+        .collect();
+    match format {
+        Format::Sherlock => {
+            let content = format!(
+                "<span font_desc=\"monospace\">\n{}</span>",
+                text::table(&rows)
+            );
+            let actions = glosses
+                .iter()
+                .map(|(_, gloss)| {
+                    ApplicationAction::lookup_word("Define", &gloss.headword, settings)
+                })
+                .collect();
+            let response = SherlockPipeResponse::new(
+                format!(r#"Gloss of "{}""#, sentence.trim()),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => print!("{}", text::table(&rows)),
+        Format::Oneline => {
+            let parts: Vec<String> = glosses
+                .iter()
+                .map(|(word, gloss)| format!("{}: {}", word, gloss.definition))
+                .collect();
+            println!("{}", parts.join("; "));
+        }
+    }
+    Ok(())
+}
+
+/// The glosses of those `words` that have entries, from the cache where
+/// it's fresh and otherwise looked up a few at a time.
+pub async fn glosses(words: &[String], settings: &Settings) -> HashMap<String, Gloss> {
+    let path = cache_path(settings);
+    let mut cache: HashMap<String, Cached> = storage::load_json(&path);
+    let now = history::now();
+    let missing: Vec<String> = words
+        .iter()
+        .filter(|word| {
+            cache
+                .get(*word)
+                .is_none_or(|cached| now.saturating_sub(cached.timestamp) >= MAX_AGE)
+        })
+        .cloned()
+        .collect();
+    if !settings.private {
+        Counters::record_cache(words.len() - missing.len(), missing.len());
+    }
+    let mut found = HashMap::new();
+    for batch in missing.chunks(CONCURRENCY) {
+        let mut lookups = JoinSet::new();
+        for word in batch {
+            let (settings, word) = (settings.clone(), word.clone());
+            lookups.spawn(async move {
+                let gloss = gloss(&word, &settings).await;
+                (word, gloss)
+            });
+        }
+        while let Some(result) = lookups.join_next().await {
+            let Ok((word, gloss)) = result else {
+                continue;
+            };
+            // Failed lookups are left out of the cache and tried next time.
+            if let Ok(gloss) = gloss {
+                found.insert(
+                    word,
+                    Cached {
+                        gloss,
+                        timestamp: now,
+                    },
+                );
+            }
+        }
+    }
+    if !found.is_empty() && !settings.private {
+        // Added to the file as it is now, which another run may have added
+        // its own glosses to while these were looked up.
+        let saved = storage::with_lock(&path, || {
+            let mut on_disk: HashMap<String, Cached> = storage::load_json(&path);
+            on_disk.extend(
+                found
+                    .iter()
+                    .map(|(word, cached)| (word.clone(), cached.clone())),
+            );
+            storage::save_json(&path, &on_disk)
+        });
+        if let Err(e) = saved {
+            eprintln!("Warning: couldn't save the gloss cache: {}", e);
+        }
+    }
+    cache.extend(found);
+
+    words
+        .iter()
+        .filter_map(|word| Some((word.clone(), cache.remove(word)?.gloss?)))
+        .collect()
+}
+
+/// Drops `word` from every gloss cache.
+pub fn forget(word: &str) -> std::io::Result<()> {
+    let Ok(files) = std::fs::read_dir(storage::cache_dir().join("gloss")) else {
+        return Ok(());
+    };
+    let word = word.trim().to_lowercase();
+    for file in files.flatten() {
+        let path = file.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        storage::with_lock(&path, || {
+            let mut cache: HashMap<String, Cached> = storage::load_json(&path);
+            match cache.remove(&word) {
+                Some(_) => storage::save_json(&path, &cache),
+                None => Ok(()),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// The first sense of `word`, or of its dictionary form.
+async fn gloss(word: &str, settings: &Settings) -> Result<Option<Gloss>, surf::Error> {
+    let resolved = settings.resolve(word).await?;
+    let Lookup::Found(entries) = resolved.lookup else {
+        return Ok(None);
+    };
+    let entries = settings.prepare(entries, settings.content_filter);
+    let Some((meaning, definition)) = entries
+        .iter()
+        .flat_map(|entry| &entry.meanings)
+        .find_map(|meaning| Some((meaning, meaning.definitions.first()?)))
+    else {
+        return Ok(None);
+    };
+    let mut text = definition
+        .definition
+        .trim()
+        .trim_end_matches('.')
+        .to_string();
+    if text.chars().count() > MAX_CHARS {
+        text = text.chars().take(MAX_CHARS - 1).collect::<String>() + "…";
+    }
+    Ok(Some(Gloss {
+        headword: resolved.word,
+        part_of_speech: Cow::Borrowed(intern(&meaning.part_of_speech)),
+        definition: text,
+    }))
+}
@@ -0,0 +1,101 @@
+use std::io::{self, Read};
+
+use crate::{
+    cli::Format,
+    gloss::{self, Gloss},
+    known::Known,
+    locale,
+    providers::Settings,
+    segment,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+    wordlist::WordList,
+};
+
+/// `hardwords`: the words of the text on stdin rarer than the `rarer_than`
+/// most common ones and not on the user's known-words list, each with a
+/// short definition, in the order they first appear.
+pub async fn run(
+    rarer_than: usize,
+    settings: &Settings,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let list = WordList::shared();
+    let known = Known::load();
+    let words: Vec<String> = segment::words(&input)
+        .into_iter()
+        .filter(|word| word.chars().all(char::is_alphabetic))
+        .filter(|word| list.rank(word).is_none_or(|rank| rank >= rarer_than))
+        .filter(|word| !known.contains(word))
+        .collect();
+
+    let found = gloss::glosses(&words, settings).await;
+    // "running" is as familiar as "run" to someone who knows "run".
+    let hard: Vec<(&String, &Gloss)> = words
+        .iter()
+        .filter_map(|word| Some((word, found.get(word)?)))
+        .filter(|(_, gloss)| !known.contains(&gloss.headword))
+        .collect();
+    if hard.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No hard words".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No hard words with definitions in the text.");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        Format::Sherlock => {
+            let rows: Vec<Vec<String>> = hard
+                .iter()
+                .map(|(word, gloss)| {
+                    vec![
+                        word.to_string(),
+                        gloss.part_of_speech.to_string(),
+                        gloss.definition.clone(),
+                    ]
+                })
+                .collect();
+            let content = format!(
+                "<span font_desc=\"monospace\">\n{}</span>",
+                text::table(&rows)
+            );
+            let actions = hard
+                .iter()
+                .map(|(_, gloss)| {
+                    ApplicationAction::lookup_word("Define", &gloss.headword, settings)
+                })
+                .collect();
+            let title = match hard.len() {
+                1 => String::from("1 hard word"),
+                n => format!("{} hard words", locale::number(n as u64)),
+            };
+            let response = SherlockPipeResponse::new(title, content, actions);
+            response.print();
+        }
+        // Tab-separated, so the list can go on to `cut`, `sort` or a spreadsheet.
+        Format::Plain | Format::Sexp => {
+            for (word, gloss) in &hard {
+                println!("{}\t{}\t{}", word, gloss.part_of_speech, gloss.definition);
+            }
+        }
+        Format::Oneline => {
+            let parts: Vec<String> = hard
+                .iter()
+                .map(|(word, gloss)| format!("{}: {}", word, gloss.definition))
+                .collect();
+            println!("{}", parts.join("; "));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,395 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    config::Config,
+    gender::Gender,
+    http,
+    providers::Provider,
+    wiktionary,
+};
+
+/// The part-of-speech headings Wiktionary gives Hindi entries, in the order
+/// their senses are shown.
+const PARTS_OF_SPEECH: &[&str] = &[
+    "Noun",
+    "Proper noun",
+    "Verb",
+    "Adjective",
+    "Adverb",
+    "Pronoun",
+    "Postposition",
+    "Conjunction",
+    "Interjection",
+    "Particle",
+    "Numeral",
+    "Determiner",
+    "Phrase",
+    "Proverb",
+];
+
+/// A template call with nothing nested in it.
+static TEMPLATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{([^{}|]+)((?:\|[^{}]*)?)\}\}").unwrap());
+
+/// Vowels, independent and as signs on a consonant, in IAST and ISO 15919.
+const VOWELS: &[(char, Option<char>, &str, &str)] = &[
+    ('अ', None, "a", "a"),
+    ('आ', Some('ा'), "ā", "ā"),
+    ('इ', Some('ि'), "i", "i"),
+    ('ई', Some('ी'), "ī", "ī"),
+    ('उ', Some('ु'), "u", "u"),
+    ('ऊ', Some('ू'), "ū", "ū"),
+    ('ऋ', Some('ृ'), "ṛ", "r̥"),
+    ('ए', Some('े'), "e", "ē"),
+    ('ऐ', Some('ै'), "ai", "ai"),
+    ('ओ', Some('ो'), "o", "ō"),
+    ('औ', Some('ौ'), "au", "au"),
+    ('ऑ', Some('ॉ'), "ô", "ô"),
+    ('ऍ', Some('ॅ'), "ê", "ê"),
+];
+
+/// Consonants, which both systems write alike.
+const CONSONANTS: &[(char, &str)] = &[
+    ('क', "k"),
+    ('ख', "kh"),
+    ('ग', "g"),
+    ('घ', "gh"),
+    ('ङ', "ṅ"),
+    ('च', "c"),
+    ('छ', "ch"),
+    ('ज', "j"),
+    ('झ', "jh"),
+    ('ञ', "ñ"),
+    ('ट', "ṭ"),
+    ('ठ', "ṭh"),
+    ('ड', "ḍ"),
+    ('ढ', "ḍh"),
+    ('ण', "ṇ"),
+    ('त', "t"),
+    ('थ', "th"),
+    ('द', "d"),
+    ('ध', "dh"),
+    ('न', "n"),
+    ('प', "p"),
+    ('फ', "ph"),
+    ('ब', "b"),
+    ('भ', "bh"),
+    ('म', "m"),
+    ('य', "y"),
+    ('र', "r"),
+    ('ल', "l"),
+    ('व', "v"),
+    ('श', "ś"),
+    ('ष', "ṣ"),
+    ('स', "s"),
+    ('ह', "h"),
+];
+
+/// Consonants with a nukta, for sounds from Persian, Arabic and English: the
+/// consonant the dot goes under, the precomposed letter, and the letters in
+/// IAST and ISO 15919.
+const NUKTA: &[(char, char, &str, &str)] = &[
+    ('क', '\u{0958}', "q", "q"),
+    ('ख', '\u{0959}', "x", "k͟h"),
+    ('ग', '\u{095a}', "ġ", "ġ"),
+    ('ज', '\u{095b}', "z", "z"),
+    ('ड', '\u{095c}', "ṛ", "ṛ"),
+    ('ढ', '\u{095d}', "ṛh", "ṛh"),
+    ('फ', '\u{095e}', "f", "f"),
+    ('य', '\u{095f}', "ẏ", "ẏ"),
+];
+
+const VIRAMA: char = '\u{94d}';
+const NUKTA_SIGN: char = '\u{93c}';
+
+/// How Hindi words are romanized, from `hindi_romanization` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Romanization {
+    /// The International Alphabet of Sanskrit Transliteration: पानी is
+    /// "pānī", देश "deś".
+    #[default]
+    Iast,
+    /// ISO 15919, which marks long e and o: देश is "dēś".
+    Iso,
+    /// Only the word in Devanagari.
+    Off,
+}
+
+/// Hindi words from their entries on the English Wiktionary, with English
+/// glosses and the word romanized as its pronunciation.
+pub async fn lookup(word: &str) -> Result<Lookup, surf::Error> {
+    let mut response = http::provider_get(Provider::Hindi, wiktionary::page_url(word))?.await?;
+    if !response.status().is_success() {
+        return Ok(Lookup::NotFound);
+    }
+    let page = response.body_string().await?;
+    let Some(hindi) = wiktionary::language_section(&page, "Hindi") else {
+        return Ok(Lookup::NotFound);
+    };
+
+    let mut meanings = Vec::new();
+    for part_of_speech in PARTS_OF_SPEECH {
+        let sections = wiktionary::sections(hindi, part_of_speech);
+        let definitions: Vec<Definition> = sections.iter().copied().flat_map(definitions).collect();
+        if !definitions.is_empty() {
+            meanings.push(Meaning {
+                part_of_speech: part_of_speech.to_lowercase(),
+                definitions,
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender: sections.iter().find_map(|section| gender(section)),
+            });
+        }
+    }
+    if meanings.is_empty() {
+        return Ok(Lookup::NotFound);
+    }
+    let romanized = Some(romanize(word, Config::load().hindi_romanization))
+        .filter(|romanized| romanized != word);
+    Ok(Lookup::Found(vec![DefinitionResponse {
+        word: word.to_string(),
+        phonetic: romanized.clone(),
+        phonetics: romanized
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings,
+        source_urls: Some(vec![format!(
+            "https://en.wiktionary.org/wiki/{}#Hindi",
+            word.replace(' ', "_")
+        )]),
+        origin: None,
+        provider: None,
+    }]))
+}
+
+/// The gender a noun's headword template gives it: `{{hi-noun|g=m}}`.
+fn gender(section: &str) -> Option<Gender> {
+    wiktionary::templates_named(section, &["hi-noun", "hi-proper noun", "head"])
+        .into_iter()
+        .find_map(|(_, template)| Gender::parse(template.param("g")?))
+}
+
+/// The senses under a part-of-speech heading: its `#` lines, each with the
+/// first usage example under it.
+fn definitions(section: &str) -> Vec<Definition> {
+    let mut definitions: Vec<Definition> = Vec::new();
+    for line in section.lines() {
+        if let Some(example) = line.strip_prefix("#:") {
+            if let Some(last) = definitions.last_mut().filter(|d| d.example.is_none()) {
+                last.example = usage_example(example);
+            }
+            continue;
+        }
+        let Some(gloss) = line
+            .strip_prefix('#')
+            .filter(|rest| !rest.starts_with(['*', ':', '#']))
+        else {
+            continue;
+        };
+        let gloss = expand(gloss);
+        if gloss.is_empty() {
+            continue;
+        }
+        definitions.push(Definition {
+            definition: gloss,
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        });
+    }
+    definitions
+}
+
+/// A `{{ux|hi|…|…}}` example with its translation after a dash, or the line
+/// as it is when it isn't in a template.
+fn usage_example(line: &str) -> Option<String> {
+    let example = match wiktionary::templates_named(line, &["ux", "uxi"]).first() {
+        Some((_, template)) => {
+            let text = wiktionary::plain(template.positional.get(1)?);
+            let translation = template
+                .positional
+                .get(2)
+                .copied()
+                .or_else(|| template.param("t"))
+                .or_else(|| template.param("translation"))
+                .map(wiktionary::plain)
+                .filter(|t| !t.is_empty());
+            match translation {
+                Some(translation) => format!("{} — {}", text, translation),
+                None => text,
+            }
+        }
+        None => expand(line),
+    };
+    Some(example).filter(|example| !example.is_empty())
+}
+
+/// A gloss with its links, labels and qualifiers written out, and the
+/// templates that show nothing readable dropped.
+fn expand(wikitext: &str) -> String {
+    let mut text = wikitext.to_string();
+    // Inner templates first, so labels can hold links.
+    while TEMPLATE.is_match(&text) {
+        text = TEMPLATE
+            .replace_all(&text, |caps: &regex::Captures| {
+                let params: Vec<&str> = caps[2]
+                    .split('|')
+                    .skip(1)
+                    .filter(|param| !param.contains('='))
+                    .map(str::trim)
+                    .collect();
+                let param = |i: usize| params.get(i).copied().unwrap_or_default();
+                match caps[1].trim() {
+                    "l" | "m" | "ll" | "l-lite" => match param(2) {
+                        "" => param(1).to_string(),
+                        shown => shown.to_string(),
+                    },
+                    "w" | "ng" | "n-g" | "non-gloss" | "non-gloss definition" => {
+                        param(0).to_string()
+                    }
+                    "lb" | "lbl" | "label" => {
+                        let labels: Vec<&str> = params
+                            .iter()
+                            .skip(1)
+                            .copied()
+                            .filter(|label| *label != "_")
+                            .collect();
+                        format!("({})", labels.join(", "))
+                    }
+                    "gloss" | "gl" | "q" | "qual" | "qualifier" | "i" | "sense" => {
+                        format!("({})", params.join(", "))
+                    }
+                    _ => String::new(),
+                }
+            })
+            .into_owned();
+    }
+    let text = wiktionary::plain(&text).replace("()", "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// One letter of a word in Devanagari.
+enum Letter {
+    /// A consonant, with its vowel sign: `None` for the inherent a, spoken
+    /// or not, and `Some("")` after a virama.
+    Consonant(&'static str, Option<&'static str>),
+    /// A vowel written on its own.
+    Vowel(&'static str),
+    /// Nasalization, a digit or anything else kept as it is.
+    Other(String),
+}
+
+/// `text` in Latin letters. Hindi doesn't pronounce the inherent a at the end
+/// of a word or between a vowel-and-consonant and a consonant-and-vowel, so
+/// neither is written: कमल is "kamal", समझना "samajhnā" and दिलचस्प
+/// "dilcasp", as Wiktionary romanizes them.
+fn romanize(text: &str, scheme: Romanization) -> String {
+    let pick = |iast: &'static str, iso: &'static str| match scheme {
+        Romanization::Iso => iso,
+        _ => iast,
+    };
+    if scheme == Romanization::Off {
+        return text.to_string();
+    }
+    let mut words: Vec<Vec<Letter>> = vec![Vec::new()];
+    for c in text.chars() {
+        let word = words.last_mut().unwrap();
+        let sign = VOWELS.iter().find(|(_, sign, _, _)| *sign == Some(c));
+        if let Some((_, letters)) = CONSONANTS.iter().find(|(letter, _)| *letter == c) {
+            word.push(Letter::Consonant(letters, None));
+        } else if let Some((_, _, iast, iso)) = NUKTA.iter().find(|(_, nukta, _, _)| *nukta == c) {
+            word.push(Letter::Consonant(pick(iast, iso), None));
+        } else if let (NUKTA_SIGN, Some(Letter::Consonant(letters, _))) = (c, word.last_mut()) {
+            let base = CONSONANTS.iter().find(|(_, l)| l == letters);
+            if let Some((_, _, iast, iso)) = base
+                .and_then(|(base, _)| NUKTA.iter().find(|(consonant, _, _, _)| consonant == base))
+            {
+                *letters = pick(iast, iso);
+            }
+        } else if let (VIRAMA, Some(Letter::Consonant(_, vowel))) = (c, word.last_mut()) {
+            *vowel = Some("");
+        } else if let Some((_, _, iast, iso)) = sign {
+            match word.last_mut() {
+                Some(Letter::Consonant(_, vowel @ None)) => *vowel = Some(pick(iast, iso)),
+                _ => word.push(Letter::Vowel(pick(iast, iso))),
+            }
+        } else if let Some((_, _, iast, iso)) = VOWELS.iter().find(|(vowel, _, _, _)| *vowel == c) {
+            word.push(Letter::Vowel(pick(iast, iso)));
+        } else {
+            let other = match c {
+                'ं' => pick("ṃ", "ṁ").to_string(),
+                'ँ' => String::from("m̐"),
+                'ः' => String::from("ḥ"),
+                '।' => String::from("."),
+                '०'..='९' => (u32::from(c) - u32::from('०')).to_string(),
+                NUKTA_SIGN | VIRAMA => String::new(),
+                c => {
+                    // Anything else is between words.
+                    words.push(vec![Letter::Other(c.to_string())]);
+                    words.push(Vec::new());
+                    continue;
+                }
+            };
+            word.push(Letter::Other(other));
+        }
+    }
+
+    let mut out = String::new();
+    for word in &words {
+        for (letter, schwa) in word.iter().zip(spoken_schwas(word)) {
+            match letter {
+                Letter::Consonant(letters, vowel) => {
+                    out.push_str(letters);
+                    out.push_str(vowel.unwrap_or(match schwa {
+                        true => "a",
+                        false => "",
+                    }));
+                }
+                Letter::Vowel(letters) => out.push_str(letters),
+                Letter::Other(text) => out.push_str(text),
+            }
+        }
+    }
+    out
+}
+
+/// Which of a word's letters have an inherent a that is pronounced, working
+/// from the end of the word as schwa deletion does.
+fn spoken_schwas(word: &[Letter]) -> Vec<bool> {
+    let inherent = |letter: &Letter| matches!(letter, Letter::Consonant(_, None));
+    let mut spoken: Vec<bool> = word.iter().map(inherent).collect();
+    let has_vowel = |i: usize, spoken: &[bool]| match &word[i] {
+        Letter::Consonant(_, Some(vowel)) => !vowel.is_empty(),
+        Letter::Consonant(_, None) => spoken[i],
+        Letter::Vowel(_) => true,
+        Letter::Other(_) => false,
+    };
+    let syllables = (0..word.len()).filter(|&i| has_vowel(i, &spoken)).count();
+    for i in (0..word.len()).rev() {
+        if !inherent(&word[i]) || syllables < 2 {
+            continue;
+        }
+        let last = i + 1 == word.len();
+        let after_vowel = i > 0 && has_vowel(i - 1, &spoken);
+        let before_vowel =
+            !last && matches!(word[i + 1], Letter::Consonant(..)) && has_vowel(i + 1, &spoken);
+        if last || (after_vowel && before_vowel) {
+            spoken[i] = false;
+        }
+    }
+    spoken
+}
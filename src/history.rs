@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::storage;
+
+pub const DAY: u64 = 24 * 60 * 60;
+/// Upper bound on stored lookups; the oldest entries are dropped first.
+pub const MAX_HISTORY: usize = 10_000;
+/// Seconds a looked-up word stays the subject of follow-up queries.
+const SESSION_EXPIRY: u64 = 10 * 60;
+/// Recent words kept for follow-ups.
+const SESSION_WORDS: usize = 5;
+/// Seconds between lookups made while the query is still being typed.
+const TYPING_GAP: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub word: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Previously looked-up words, oldest first.
+pub struct History {
+    path: PathBuf,
+    pub entries: Vec<HistoryEntry>,
+}
+impl History {
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("history.json");
+        let entries = storage::load_private(&path);
+        Self { path, entries }
+    }
+
+    /// Loads the history, lets `change` change it and saves it, without
+    /// another process saving in between and one of the two losing its entry.
+    pub fn update<R>(change: impl FnOnce(&mut Self) -> R) -> io::Result<R> {
+        storage::with_lock(&storage::data_dir().join("history.json"), || {
+            let mut history = Self::load();
+            let changed = change(&mut history);
+            history.save().map(|()| changed)
+        })
+    }
+
+    pub fn record(&mut self, word: &str) {
+        // Looking the same word up twice in a row isn't worth a second entry.
+        if self.entries.last().is_some_and(|last| last.word == word) {
+            return;
+        }
+        self.entries.push(HistoryEntry {
+            word: word.to_string(),
+            timestamp: now(),
+        });
+        if self.entries.len() > MAX_HISTORY {
+            let excess = self.entries.len() - MAX_HISTORY;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Like [`History::record`], for lookups made as the query is typed: a
+    /// word that goes on from the one just before ("seren", then
+    /// "serendipity") or backspaces from it replaces it, instead of every
+    /// keystroke counting as a lookup.
+    pub fn record_typed(&mut self, word: &str) {
+        let now = now();
+        if let Some(last) = self.entries.last_mut() {
+            let typing = now.saturating_sub(last.timestamp) < TYPING_GAP
+                && (word.starts_with(&last.word) || last.word.starts_with(word));
+            if typing {
+                last.word = word.to_string();
+                last.timestamp = now;
+                return;
+            }
+        }
+        self.record(word);
+    }
+
+    /// The `n` words looked up most often and how many times, most first.
+    pub fn most_looked_up(&self, n: usize) -> Vec<(String, usize)> {
+        let mut by_word: HashMap<&str, usize> = HashMap::new();
+        for entry in &self.entries {
+            *by_word.entry(entry.word.as_str()).or_default() += 1;
+        }
+        let mut top: Vec<(String, usize)> = by_word
+            .into_iter()
+            .map(|(word, count)| (word.to_string(), count))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(n);
+        top
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_private(&self.path, &self.entries)
+    }
+}
+
+/// Words the user starred, in the order they were added.
+pub struct Bookmarks {
+    path: PathBuf,
+    pub words: Vec<String>,
+}
+impl Bookmarks {
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("bookmarks.json");
+        let words = storage::load_private(&path);
+        Self { path, words }
+    }
+
+    /// Like [`History::update`], for the bookmarks.
+    pub fn update<R>(change: impl FnOnce(&mut Self) -> R) -> io::Result<R> {
+        storage::with_lock(&storage::data_dir().join("bookmarks.json"), || {
+            let mut bookmarks = Self::load();
+            let changed = change(&mut bookmarks);
+            bookmarks.save().map(|()| changed)
+        })
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+
+    /// Adds or removes `word`, returning whether it is now bookmarked.
+    #[cfg(feature = "tui")]
+    pub fn toggle(&mut self, word: &str) -> bool {
+        if let Some(pos) = self.words.iter().position(|w| w == word) {
+            self.words.remove(pos);
+            false
+        } else {
+            self.words.push(word.to_string());
+            true
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_private(&self.path, &self.words)
+    }
+}
+
+/// The last few words found, newest last, so a follow-up such as "syn" in
+/// the next run knows which word it's about. Kept in the cache, since each
+/// launcher query is a new process.
+pub struct Session {
+    path: PathBuf,
+    words: Vec<HistoryEntry>,
+}
+impl Session {
+    pub fn load() -> Self {
+        let path = storage::cache_dir().join("session.json");
+        let words = storage::load_private(&path);
+        Self { path, words }
+    }
+
+    /// Like [`History::update`], for the session.
+    pub fn update<R>(change: impl FnOnce(&mut Self) -> R) -> io::Result<R> {
+        storage::with_lock(&storage::cache_dir().join("session.json"), || {
+            let mut session = Self::load();
+            let changed = change(&mut session);
+            session.save().map(|()| changed)
+        })
+    }
+
+    pub fn record(&mut self, word: &str) {
+        self.words.retain(|entry| entry.word != word);
+        self.words.push(HistoryEntry {
+            word: word.to_string(),
+            timestamp: now(),
+        });
+        if self.words.len() > SESSION_WORDS {
+            self.words.remove(0);
+        }
+    }
+
+    /// The newest recent word that `typed` is a follow-up to. The launcher
+    /// runs a lookup per keystroke, so words that are just the start of what
+    /// was typed ("s" and "sy" on the way to "syn") don't count.
+    pub fn subject(&self, typed: &str) -> Option<&str> {
+        let typed = typed.to_lowercase();
+        self.words
+            .iter()
+            .rev()
+            .filter(|entry| now().saturating_sub(entry.timestamp) < SESSION_EXPIRY)
+            .find(|entry| !typed.starts_with(&entry.word.to_lowercase()))
+            .map(|entry| entry.word.as_str())
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_private(&self.path, &self.words)
+    }
+}
+
+/// Removes every lookup of `word` from the history, the session and the
+/// `--interactive` prompt's line history, returning how many history entries
+/// there were.
+pub fn redact(word: &str) -> io::Result<usize> {
+    let matches = |other: &str| other.trim().eq_ignore_ascii_case(word.trim());
+
+    let removed = History::update(|history| {
+        let before = history.entries.len();
+        history.entries.retain(|entry| !matches(&entry.word));
+        before - history.entries.len()
+    })?;
+    Session::update(|session| session.words.retain(|entry| !matches(&entry.word)))?;
+
+    let path = storage::data_dir().join("repl_history.txt");
+    if let Ok(text) = fs::read_to_string(&path) {
+        let kept: Vec<&str> = text.lines().filter(|line| !matches(line)).collect();
+        fs::write(&path, kept.join("\n") + "\n")?;
+    }
+    Ok(removed)
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The UTC date of `timestamp` as `YYYY-MM-DD`.
+pub fn date(timestamp: u64) -> String {
+    let (year, month, day) = civil(timestamp / DAY);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Year, month and day of the `days`th day since 1970-01-01, after Howard
+/// Hinnant's `civil_from_days`.
+pub fn civil(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
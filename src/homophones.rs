@@ -0,0 +1,256 @@
+use std::{collections::HashMap, io};
+
+use crate::{
+    cli::Format,
+    cmudict::{self, Pronunciation},
+    datamuse::{self, Query, Word},
+    index::WordIndex,
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Minimal pairs shown; common words have dozens.
+const PAIRS: usize = 20;
+
+/// A word and how it's pronounced, when that's known.
+struct Sound {
+    word: String,
+    ipa: Option<String>,
+}
+
+/// A word one sound away from the looked-up one: "bat" and "pat" differ in
+/// "b → p".
+struct Pair {
+    sound: Sound,
+    contrast: String,
+}
+
+/// What `homophones` found for a word.
+#[derive(Default)]
+struct Found {
+    ipa: Option<String>,
+    /// Its ARPABET, syllables and stress, when CMUdict has it.
+    details: Option<String>,
+    homophones: Vec<Sound>,
+    /// Only CMUdict has these.
+    pairs: Vec<Pair>,
+}
+
+/// `homophones <word>`: words pronounced the same, for pronunciation
+/// learners and pun writers, from CMUdict when it's installed and Datamuse
+/// otherwise. CMUdict also gives the minimal pairs, words one sound away.
+/// English only.
+pub async fn run(word: &str, settings: &Settings, format: Format) -> Result<(), surf::Error> {
+    let word = word.trim().to_lowercase();
+    if settings.lang != "en" {
+        return Err(surf::Error::from_str(
+            surf::StatusCode::NotImplemented,
+            "Homophones are only available in English",
+        ));
+    }
+    let found = match cmudict::is_installed() {
+        true => from_cmudict(&word)?,
+        false => from_datamuse(&word).await?,
+    };
+
+    if found.homophones.is_empty() && found.pairs.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No homophones found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No homophones found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let mut heading = match &found.ipa {
+        Some(ipa) => format!("{}  /{}/", word, ipa),
+        None => word.clone(),
+    };
+    if let Some(details) = &found.details {
+        heading.push_str(&format!("\n{}", details));
+    }
+    let homophones: Vec<Vec<String>> = found
+        .homophones
+        .iter()
+        .map(|sound| vec![sound.word.clone(), slashed(&sound.ipa)])
+        .collect();
+    let pairs: Vec<Vec<String>> = found
+        .pairs
+        .iter()
+        .map(|pair| {
+            vec![
+                pair.sound.word.clone(),
+                slashed(&pair.sound.ipa),
+                pair.contrast.clone(),
+            ]
+        })
+        .collect();
+    match format {
+        Format::Sherlock => {
+            let mut content = format!("<span font_desc=\"monospace\">\n<b>{}</b>\n\n", heading);
+            if !homophones.is_empty() {
+                content.push_str("─── <b><i>homophones</i></b> ───\n\n");
+                content.push_str(&text::table(&homophones));
+                content.push('\n');
+            }
+            if !pairs.is_empty() {
+                content.push_str("─── <b><i>minimal pairs</i></b> ───\n\n");
+                content.push_str(&text::table(&pairs));
+                content.push('\n');
+            }
+            content.push_str("────────────\n</span>");
+            let actions = found
+                .homophones
+                .iter()
+                .chain(found.pairs.iter().map(|pair| &pair.sound))
+                .map(|sound| ApplicationAction::lookup_word("Define", &sound.word, settings))
+                .collect();
+            let response =
+                SherlockPipeResponse::new(format!(r#"Homophones of "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            println!("{}\n", heading);
+            if !homophones.is_empty() {
+                println!("─── homophones ───");
+                println!("{}", text::table(&homophones));
+            }
+            if !pairs.is_empty() {
+                println!("─── minimal pairs ───");
+                println!("{}", text::table(&pairs));
+            }
+        }
+        Format::Oneline => {
+            let words: Vec<&str> = found.homophones.iter().map(|s| s.word.as_str()).collect();
+            println!("{}: {}", word, words.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// An IPA pronunciation between slashes, or nothing when there's none.
+pub fn slashed(ipa: &Option<String>) -> String {
+    ipa.as_ref()
+        .map(|ipa| format!("/{}/", ipa))
+        .unwrap_or_default()
+}
+
+async fn from_datamuse(word: &str) -> Result<Found, surf::Error> {
+    let homophones = datamuse::words(&Query {
+        rel_hom: Some(word),
+        ..Query::default()
+    })
+    .await?;
+    let own = datamuse::pronunciation(word).await?;
+    let mut found = Found {
+        ipa: own.as_ref().and_then(Word::ipa),
+        homophones: homophones
+            .iter()
+            .filter(|w| !w.word.eq_ignore_ascii_case(word))
+            .map(|w| Sound {
+                word: w.word.clone(),
+                ipa: w.ipa(),
+            })
+            .collect(),
+        details: None,
+        pairs: Vec::new(),
+    };
+    by_rank(&mut found.homophones);
+    Ok(found)
+}
+
+fn from_cmudict(word: &str) -> io::Result<Found> {
+    let dictionary = cmudict::Dictionary::shared()?;
+    let own = dictionary.lookup(word);
+    let Some(first) = own.first() else {
+        return Ok(Found::default());
+    };
+    let mut homophones: HashMap<&str, &Pronunciation> = HashMap::new();
+    let mut pairs: HashMap<&str, (&Pronunciation, String)> = HashMap::new();
+    for other in dictionary.entries() {
+        if other.word == word {
+            continue;
+        }
+        for pronunciation in &own {
+            if other.phonemes == pronunciation.phonemes {
+                homophones.entry(&other.word).or_insert(other);
+            } else if let Some(contrast) = contrast(&pronunciation.phonemes, &other.phonemes) {
+                pairs.entry(&other.word).or_insert((other, contrast));
+            }
+        }
+    }
+    // With two pronunciations a word can be both.
+    pairs.retain(|word, _| !homophones.contains_key(word));
+    let mut found = Found {
+        ipa: Some(first.ipa()),
+        details: Some(format!(
+            "{} · {} · stress {}",
+            first.arpabet(),
+            match first.syllables() {
+                1 => String::from("1 syllable"),
+                n => format!("{} syllables", n),
+            },
+            first.stress()
+        )),
+        homophones: homophones
+            .into_values()
+            .map(|pronunciation| Sound {
+                word: pronunciation.word.clone(),
+                ipa: Some(pronunciation.ipa()),
+            })
+            .collect(),
+        pairs: pairs
+            .into_values()
+            .map(|(pronunciation, contrast)| Pair {
+                sound: Sound {
+                    word: pronunciation.word.clone(),
+                    ipa: Some(pronunciation.ipa()),
+                },
+                contrast,
+            })
+            .collect(),
+    };
+    by_rank(&mut found.homophones);
+    found
+        .pairs
+        .sort_by_cached_key(|pair| rank(&pair.sound.word));
+    found.pairs.truncate(PAIRS);
+    Ok(found)
+}
+
+/// The one sound `a` and `b` differ in, "b → p", when they're otherwise the
+/// same. Stress isn't a sound of its own.
+fn contrast(a: &[String], b: &[String]) -> Option<String> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let mut differences = a
+        .iter()
+        .zip(b)
+        .filter(|(x, y)| cmudict::unstressed(x) != cmudict::unstressed(y));
+    let (x, y) = differences.next()?;
+    match differences.next() {
+        Some(_) => None,
+        None => Some(format!("{} → {}", cmudict::phoneme(x), cmudict::phoneme(y))),
+    }
+}
+
+fn by_rank(sounds: &mut [Sound]) {
+    sounds.sort_by_cached_key(|sound| rank(&sound.word));
+}
+
+/// What to sort words by to have common ones first, and the rest
+/// alphabetically.
+pub fn rank(word: &str) -> (usize, String) {
+    (
+        WordIndex::shared().rank(word).unwrap_or(usize::MAX),
+        word.to_string(),
+    )
+}
@@ -0,0 +1,140 @@
+use regex::Regex;
+use std::{ops::Range, sync::LazyLock};
+
+/// An opening, closing or self-closing tag.
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9_:-]*)\b[^>]*?(/?)>").unwrap());
+static INVISIBLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<!--.*?-->|<(script|style)\b[^>]*>.*?</(script|style)>").unwrap()
+});
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+
+/// Tags that never have a closing tag.
+const VOID: &[&str] = &["br", "hr", "img", "input", "meta", "link", "wbr", "source"];
+
+/// The readable text of an HTML fragment on one line: tags dropped, entities
+/// decoded, whitespace collapsed.
+pub fn text(html: &str) -> String {
+    let html = INVISIBLE.replace_all(html, "");
+    let stripped = TAG.replace_all(&html, " ");
+    let decoded = ENTITY.replace_all(&stripped, |caps: &regex::Captures| {
+        decode_entity(&caps[1]).unwrap_or_else(|| caps[0].to_string())
+    });
+    let words: Vec<&str> = decoded.split_whitespace().collect();
+    // Tags become spaces, which leaves "word ," behind inline markup.
+    words
+        .join(" ")
+        .replace(" ,", ",")
+        .replace(" .", ".")
+        .replace(" ;", ";")
+        .replace(" :", ":")
+        .replace("( ", "(")
+        .replace(" )", ")")
+}
+
+fn decode_entity(name: &str) -> Option<String> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).map(String::from);
+    }
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "middot" => '·',
+        _ => return None,
+    };
+    Some(c.to_string())
+}
+
+/// Byte ranges of the outermost `tag` elements in `html`, each from its
+/// opening `<` to the end of its closing tag. An unclosed element runs to the
+/// end of the input.
+pub fn elements(html: &str, tag: &str) -> Vec<Range<usize>> {
+    if VOID.contains(&tag) {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for caps in TAG.captures_iter(html) {
+        let name = &caps[2];
+        if !name.eq_ignore_ascii_case(tag) || !caps[3].is_empty() {
+            continue;
+        }
+        let whole = caps.get(0).unwrap();
+        if caps[1].is_empty() {
+            if depth == 0 {
+                start = whole.start();
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                found.push(start..whole.end());
+            }
+        }
+    }
+    if depth > 0 {
+        found.push(start..html.len());
+    }
+    found
+}
+
+/// The content between an element's opening and closing tags.
+pub fn inner(element: &str) -> &str {
+    let open_end = element.find('>').map_or(0, |i| i + 1);
+    let close_start = element.rfind("</").filter(|&i| i >= open_end);
+    &element[open_end..close_start.unwrap_or(element.len())]
+}
+
+/// `html` with every `tag` element removed, contents included.
+pub fn without(html: &str, tag: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for range in elements(html, tag) {
+        out.push_str(&html[last..range.start]);
+        last = range.end;
+    }
+    out.push_str(&html[last..]);
+    out
+}
+
+/// The direct `<li>` children of a list's content (nested lists stay inside
+/// their parent item).
+pub fn list_items(list: &str) -> Vec<&str> {
+    elements(inner(list), "li")
+        .into_iter()
+        .map(|range| inner(&inner(list)[range]))
+        .collect()
+}
+
+/// Every `<hN>` heading as (level, text, byte range), in document order.
+pub fn headings(html: &str) -> Vec<(u8, String, Range<usize>)> {
+    static HEADING: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]\s*>").unwrap());
+    HEADING
+        .captures_iter(html)
+        .map(|caps| {
+            let level = caps[1].as_bytes()[0] - b'0';
+            let range = caps.get(0).unwrap().range();
+            // MediaWiki exports keep the "[edit]" links in headings.
+            let title = text(&caps[2]).trim_end_matches("[edit]").trim().to_string();
+            (level, title, range)
+        })
+        .collect()
+}
@@ -0,0 +1,632 @@
+use http_client::isahc::IsahcClient;
+use isahc::{
+    auth::Credentials,
+    config::{Configurable, IpVersion, ResolveMap},
+    HttpClientBuilder,
+};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs, io, mem,
+    net::IpAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
+use surf::{
+    http::Method,
+    middleware::{Middleware, Next},
+    Client, Request, RequestBuilder, Response, StatusCode, Url,
+};
+
+use tokio::{runtime::Handle, task::JoinHandle};
+
+use crate::{
+    breaker::Breaker,
+    config::Config,
+    history,
+    providers::{Provider, Settings},
+    storage,
+};
+
+/// DNS record types asked for, in order, with their numeric codes.
+const RECORD_TYPES: [(&str, u16); 2] = [("A", 1), ("AAAA", 28)];
+/// How long resolving a host and connecting to it may take. curl already
+/// races IPv6 against IPv4 ("happy eyeballs"); this bounds the wait when
+/// neither gets through.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a whole request may take unless `timeout` says otherwise, as
+/// with surf's own client.
+const TIMEOUT: Duration = Duration::from_secs(60);
+/// How long a cached response is used without asking the site about it.
+/// Dictionary entries hardly ever change.
+const FRESH_SECS: u64 = 24 * 60 * 60;
+/// How long an older cached response waits on the site's answer before it's
+/// used anyway, while the site is asked in the background.
+const RACE: Duration = Duration::from_millis(250);
+
+static NETWORK: OnceLock<Network> = OnceLock::new();
+
+/// How this run reaches the network, from the config and the settings.
+struct Network {
+    doh: Option<String>,
+    proxies: HashMap<Provider, String>,
+    /// Leave nothing in the response cache.
+    private: bool,
+    ipv4_only: bool,
+    /// Answer from the response cache alone.
+    cached: bool,
+    timeout: Duration,
+}
+impl Network {
+    fn new(settings: &Settings) -> Self {
+        let config = Config::load();
+        Self {
+            doh: config.doh.clone(),
+            proxies: config.proxies.clone(),
+            private: settings.private,
+            ipv4_only: settings.ipv4_only,
+            cached: settings.cached,
+            timeout: config.timeout.map_or(TIMEOUT, Duration::from_secs),
+        }
+    }
+}
+
+/// Applies the run's settings to every request made after it. Called once,
+/// before the first request.
+pub fn configure(settings: &Settings) {
+    let _ = NETWORK.set(Network::new(settings));
+}
+
+fn network() -> &'static Network {
+    NETWORK.get_or_init(|| Network::new(&Settings::default()))
+}
+
+/// The isahc client every client starts from.
+fn builder() -> HttpClientBuilder {
+    untimed().timeout(network().timeout)
+}
+
+/// [`builder`] without the limit on the whole request, which counts the time
+/// spent reading the body too.
+fn untimed() -> HttpClientBuilder {
+    let ip_version = match network().ipv4_only {
+        true => IpVersion::V4,
+        false => IpVersion::Any,
+    };
+    isahc::HttpClient::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .ip_version(ip_version)
+}
+
+fn surf_client(builder: HttpClientBuilder) -> surf::Result<Client> {
+    Ok(Client::with_http_client(IsahcClient::from_client(
+        builder.build()?,
+    )))
+}
+
+/// `client` with the middleware requests go through, outermost first: the
+/// provider's circuit breaker, the response cache, then DNS-over-HTTPS
+/// unless a proxy resolves the hosts instead.
+fn layered(client: Client, provider: Option<Provider>, proxied: bool) -> Client {
+    let below = match &network().doh {
+        Some(resolver) if !proxied => client.clone().with(Doh {
+            resolver: resolver.clone(),
+            clients: Mutex::default(),
+        }),
+        _ => client.clone(),
+    };
+    let client = match provider {
+        Some(provider) => client.with(Breaker { provider }),
+        None => client,
+    };
+    client.with(Revalidate { below })
+}
+
+/// The client for requests that aren't made for a provider, such as
+/// Wiktionary pages and downloads.
+pub fn client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let client = surf_client(builder()).expect("couldn't set up the HTTP client");
+            layered(client, None, false)
+        })
+        .clone()
+}
+
+/// Like [`client`], for downloading dictionary packs, which can take hours:
+/// only connecting is timed, so the caller has to notice a stalled transfer.
+pub fn downloads() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let client = surf_client(untimed()).expect("couldn't set up the HTTP client");
+            layered(client, None, false)
+        })
+        .clone()
+}
+
+/// The client for services whose answers aren't dictionary entries, like the
+/// Matrix homeserver `bot` talks to: DNS-over-HTTPS when the config sets it,
+/// but no response cache, so `--cached` doesn't apply either.
+pub fn uncached() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let client = surf_client(builder()).expect("couldn't set up the HTTP client");
+            match &network().doh {
+                Some(resolver) => client.with(Doh {
+                    resolver: resolver.clone(),
+                    clients: Mutex::default(),
+                }),
+                None => client,
+            }
+        })
+        .clone()
+}
+
+/// A GET request for `url` through [`client`].
+pub fn get(url: impl AsRef<str>) -> RequestBuilder {
+    client().get(url)
+}
+
+/// A GET request for `url` on behalf of `provider`, through its circuit
+/// breaker and the proxy the config gives it. A proxy that can't be used is
+/// an error rather than a reason to connect directly.
+pub fn provider_get(provider: Provider, url: impl AsRef<str>) -> surf::Result<RequestBuilder> {
+    static CLIENTS: OnceLock<Mutex<HashMap<Provider, Client>>> = OnceLock::new();
+    let mut clients = CLIENTS.get_or_init(Mutex::default).lock().unwrap();
+    let client = match clients.get(&provider) {
+        Some(client) => client.clone(),
+        None => {
+            let client = match network().proxies.get(&provider) {
+                Some(proxy) => proxied(provider, proxy).map_err(|e| {
+                    surf::Error::from_str(
+                        StatusCode::BadGateway,
+                        format!("Can't use the proxy '{}' for {}: {}", proxy, provider, e),
+                    )
+                })?,
+                None => layered(surf_client(builder())?, Some(provider), false),
+            };
+            clients.insert(provider, client.clone());
+            client
+        }
+    };
+    Ok(client.get(url))
+}
+
+/// A client whose requests all go through `proxy`. The proxy resolves the
+/// hosts, so DNS-over-HTTPS isn't used. SOCKS proxies are sent the provider's
+/// name as the username, which Tor takes as a reason to give each provider a
+/// circuit of its own, so the sites can't be linked by exit node.
+fn proxied(provider: Provider, proxy: &str) -> Result<Client, Box<dyn std::error::Error>> {
+    let uri: isahc::http::Uri = proxy.parse()?;
+    let mut builder = builder().proxy(Some(uri.clone()));
+    let socks = uri
+        .scheme_str()
+        .is_some_and(|scheme| scheme.starts_with("socks"));
+    let has_credentials = uri.authority().is_some_and(|a| a.as_str().contains('@'));
+    if socks && !has_credentials {
+        builder = builder.proxy_credentials(Credentials::new(provider.name(), provider.name()));
+    }
+    Ok(layered(surf_client(builder)?, Some(provider), true))
+}
+
+/// What's kept alongside a cached response body.
+#[derive(Serialize, Deserialize)]
+struct Cached {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: String,
+    /// Unix time the site last sent or confirmed it; entries from before
+    /// this was kept count as stale.
+    #[serde(default)]
+    fetched: u64,
+}
+impl Cached {
+    fn load(url: &str) -> Option<Self> {
+        let (meta, _) = cache_paths(url);
+        serde_json::from_slice(&fs::read(meta).ok()?).ok()
+    }
+
+    fn is_fresh(&self) -> bool {
+        history::now().saturating_sub(self.fetched) < FRESH_SECS
+    }
+
+    fn response(&self) -> Option<Response> {
+        let (_, body) = cache_paths(&self.url);
+        let mut response = surf::http::Response::new(StatusCode::Ok);
+        response.insert_header("Content-Type", self.content_type.as_str());
+        response.set_body(fs::read(body).ok()?);
+        Some(response.into())
+    }
+}
+
+/// Set by [`always_revalidate`].
+static ALWAYS_REVALIDATE: AtomicBool = AtomicBool::new(false);
+
+/// Makes every cached response be checked with the site before it's used,
+/// however fresh, for runs that are there to bring the cache up to date.
+pub fn always_revalidate() {
+    ALWAYS_REVALIDATE.store(true, Ordering::Relaxed);
+}
+
+/// Every URL requested so far, for [`has_cached_copy`].
+static REQUESTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Refreshes of stale cache entries still running, waited for by [`settle`].
+static REFRESHES: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Keeps JSON and text responses that carry an `ETag` or `Last-Modified`.
+/// One the site sent or confirmed in the last day is used as it is. An older
+/// one is used too, but the site is asked whether it changed at the same
+/// time: an answer within [`RACE`] wins, and otherwise the cached copy is
+/// returned and the answer updates the cache in the background. An
+/// unchanged entry costs a `304` instead of the whole body. Compression is
+/// negotiated by curl, which asks for gzip and deflate (and brotli, when the
+/// system libcurl has it) and decompresses the response.
+struct Revalidate {
+    /// Sends the requests, with the layers below this one. The client surf
+    /// hands middleware has none, and a refresh outlives the middleware
+    /// chain it started in.
+    below: Client,
+}
+
+#[surf::utils::async_trait]
+impl Middleware for Revalidate {
+    async fn handle(&self, req: Request, _: Client, _: Next<'_>) -> surf::Result<Response> {
+        let url = req.url().to_string();
+        if network().cached {
+            return cached(&url).ok_or_else(|| {
+                surf::Error::from_str(
+                    StatusCode::ServiceUnavailable,
+                    format!("{} isn't in the cache", url),
+                )
+            });
+        }
+        {
+            let mut requested = REQUESTED.lock().unwrap();
+            // The daemon asks for the same words every round.
+            if !requested.contains(&url) {
+                requested.push(url.clone());
+            }
+        }
+        // Resumed downloads want part of a file, not a cached copy of it.
+        if req.method() != Method::Get || req.header("Range").is_some() || network().private {
+            return self.below.send(req).await;
+        }
+        let cached = match Cached::load(&url) {
+            Some(cached) if !ALWAYS_REVALIDATE.load(Ordering::Relaxed) => cached,
+            cached => return fetch(&self.below, req, cached).await,
+        };
+        let Some(response) = cached.response() else {
+            return fetch(&self.below, req, Some(cached)).await;
+        };
+        if cached.is_fresh() {
+            return Ok(response);
+        }
+        // Without a runtime to refresh it on, such as a `blocking` lookup's,
+        // the site's answer is waited for.
+        let Ok(runtime) = Handle::try_current() else {
+            return Ok(fetch(&self.below, req, Some(cached))
+                .await
+                .unwrap_or(response));
+        };
+        let below = self.below.clone();
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let refresh = runtime.spawn(async move {
+            let _ = sender.send(fetch(&below, req, Some(cached)).await);
+        });
+        match tokio::time::timeout(RACE, receiver).await {
+            Ok(Ok(Ok(answer))) => Ok(answer),
+            // A site that can't be reached leaves the cached copy.
+            Ok(_) => Ok(response),
+            Err(_) => {
+                let mut refreshes = REFRESHES.lock().unwrap();
+                // A long gloss or daemon run would otherwise keep every one.
+                refreshes.retain(|refresh| !refresh.is_finished());
+                refreshes.push(refresh);
+                Ok(response)
+            }
+        }
+    }
+}
+
+/// Sends `req` through `client`, asking only for what changed since `cached`,
+/// and keeps the answer in the cache.
+async fn fetch(
+    client: &Client,
+    mut req: Request,
+    cached: Option<Cached>,
+) -> surf::Result<Response> {
+    let url = req.url().to_string();
+    // Without the body there's nothing to answer a 304 with, so the request
+    // is only made conditional when it's there.
+    let cached = cached.and_then(|cached| Some((cached.response()?, cached)));
+    if let Some((_, cached)) = &cached {
+        if let Some(etag) = &cached.etag {
+            req.insert_header("If-None-Match", etag.as_str());
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            req.insert_header("If-Modified-Since", last_modified.as_str());
+        }
+    }
+    let mut response = client.send(req).await?;
+
+    let (meta, body) = cache_paths(&url);
+    if let (StatusCode::NotModified, Some((unchanged, cached))) = (response.status(), cached) {
+        let cached = Cached {
+            fetched: history::now(),
+            ..cached
+        };
+        let _ = storage::save_json(&meta, &cached);
+        return Ok(unchanged);
+    }
+    let header = |name: &str| response.header(name).map(|v| v.last().to_string());
+    let (etag, last_modified) = (header("ETag"), header("Last-Modified"));
+    let content_type = response
+        .content_type()
+        .map(|mime| mime.to_string())
+        .unwrap_or_default();
+    // Audio and dictionary packs have caches of their own.
+    let textual = content_type.starts_with("text/") || content_type.contains("json");
+    if response.status() != StatusCode::Ok
+        || (etag.is_none() && last_modified.is_none())
+        || !textual
+    {
+        return Ok(response);
+    }
+    let bytes = response.body_bytes().await?;
+    let cached = Cached {
+        url,
+        etag,
+        last_modified,
+        content_type,
+        fetched: history::now(),
+    };
+    // A cache that can't be written only costs the next lookup a download.
+    if storage::replace_file(&body, &bytes).is_ok() {
+        let _ = storage::save_json(&meta, &cached);
+    }
+    response.set_body(bytes);
+    Ok(response)
+}
+
+/// Waits for the cache refreshes still running, so a run that printed its
+/// answer from a stale entry still leaves the cache up to date.
+pub async fn settle() {
+    let refreshes = mem::take(&mut *REFRESHES.lock().unwrap());
+    for refresh in refreshes {
+        let _ = refresh.await;
+    }
+}
+
+/// The cached response for `url`, if there is one, without asking the site
+/// whether it's still current.
+pub fn cached(url: &str) -> Option<Response> {
+    Cached::load(url)?.response()
+}
+
+/// Whether any request made so far has a cached response, however old, for
+/// `--cached` to answer with when a site failed.
+pub fn has_cached_copy() -> bool {
+    let requested = REQUESTED.lock().unwrap();
+    requested.iter().any(|url| cache_paths(url).1.is_file())
+}
+
+/// Where the metadata and the body of the response for `url` are cached.
+fn cache_paths(url: &str) -> (PathBuf, PathBuf) {
+    let digest = Sha256::digest(url.as_bytes());
+    let name: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    let dir = cache_dir();
+    (
+        dir.join(format!("{}.json", name)),
+        dir.join(format!("{}.body", name)),
+    )
+}
+
+fn cache_dir() -> PathBuf {
+    storage::cache_dir().join("http")
+}
+
+/// Drops the cached responses of every request made for `word`.
+pub fn forget(word: &str) -> io::Result<()> {
+    let Ok(files) = fs::read_dir(cache_dir()) else {
+        return Ok(());
+    };
+    let word = word.trim().to_lowercase();
+    for file in files.flatten() {
+        let path = file.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let Ok(bytes) = fs::read(&path) else {
+            continue;
+        };
+        let Ok(cached) = serde_json::from_slice::<Cached>(&bytes) else {
+            continue;
+        };
+        if asks_for(&cached.url, &word) {
+            let (meta, body) = cache_paths(&cached.url);
+            fs::remove_file(body).ok();
+            fs::remove_file(meta)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the request for `url` looks `word` up: the word is the path's
+/// last segment or one of the query's values, nothing else in it, so "en"
+/// or "dev" don't match every dictionaryapi.dev URL, nor "cat" "category".
+fn asks_for(url: &str, word: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    // Spaces in a word are `+`, `_` or `%20` depending on the site.
+    let decode = |part: &str| {
+        percent_decode_str(&part.replace(['+', '_'], " "))
+            .decode_utf8_lossy()
+            .to_lowercase()
+    };
+    let last = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()));
+    let values = url.query().into_iter().flat_map(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('=').map(|(_, value)| value))
+    });
+    last.into_iter()
+        .chain(values)
+        .any(|part| decode(part) == word)
+}
+
+/// Resolves the host of each request with a DNS-over-HTTPS server instead of
+/// the system resolver, so the sites a word is looked up on don't show in
+/// plaintext DNS, and sends the request with a client pinned to the answer.
+struct Doh {
+    /// A JSON DoH endpoint such as `https://1.1.1.1/dns-query`.
+    resolver: String,
+    /// Pinned clients by host and port, so each host is resolved once a run.
+    clients: Mutex<HashMap<(String, u16), Client>>,
+}
+
+#[surf::utils::async_trait]
+impl Middleware for Doh {
+    async fn handle(&self, req: Request, client: Client, next: Next<'_>) -> surf::Result<Response> {
+        let Some((host, port)) = domain(req.url()) else {
+            // IP addresses have nothing to resolve.
+            return next.run(req, client).await;
+        };
+        let key = (host.clone(), port);
+        let cached = self.clients.lock().unwrap().get(&key).cloned();
+        let pinned = match cached {
+            Some(pinned) => pinned,
+            None => {
+                let addr = resolve(&self.resolver, &host).await?;
+                let pinned =
+                    surf_client(builder().dns_resolve(ResolveMap::new().add(&host, port, addr)))?;
+                self.clients.lock().unwrap().insert(key, pinned.clone());
+                pinned
+            }
+        };
+        pinned.send(req).await
+    }
+}
+
+fn domain(url: &Url) -> Option<(String, u16)> {
+    let host = url.host_str()?;
+    // IPv6 addresses come bracketed.
+    if host.starts_with('[') || host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    Some((host.to_string(), url.port_or_known_default()?))
+}
+
+#[derive(Serialize)]
+struct Question<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    kind: &'a str,
+}
+
+#[derive(Deserialize)]
+struct DnsResponse {
+    #[serde(rename = "Answer", default)]
+    answer: Vec<Record>,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    #[serde(rename = "type")]
+    kind: u16,
+    data: String,
+}
+
+/// The first address `resolver` gives for `host`, IPv4 first and only IPv4
+/// with `ipv4_only`. Failing here fails the request rather than falling back
+/// to plaintext DNS.
+async fn resolve(resolver: &str, host: &str) -> surf::Result<IpAddr> {
+    let types = match network().ipv4_only {
+        true => &RECORD_TYPES[..1],
+        false => &RECORD_TYPES[..],
+    };
+    let client = surf_client(builder())?;
+    for &(name, code) in types {
+        let response: DnsResponse = client
+            .get(resolver)
+            .query(&Question {
+                name: host,
+                kind: name,
+            })?
+            .header("Accept", "application/dns-json")
+            .recv_json()
+            .await?;
+        let addr = response
+            .answer
+            .iter()
+            .filter(|record| record.kind == code)
+            .find_map(|record| record.data.parse().ok());
+        if let Some(addr) = addr {
+            return Ok(addr);
+        }
+    }
+    Err(surf::Error::from_str(
+        StatusCode::BadGateway,
+        format!("{} has no address for {}", resolver, host),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asks_for_the_word_only() {
+        let url = "https://api.dictionaryapi.dev/api/v2/entries/en/cat";
+        assert!(asks_for(url, "cat"));
+        for other in ["en", "dev", "api", "entries", "ca"] {
+            assert!(!asks_for(url, other), "{}", other);
+        }
+        assert!(!asks_for(
+            "https://api.dictionaryapi.dev/api/v2/entries/en/category",
+            "cat"
+        ));
+        assert!(!asks_for(
+            "https://api.dictionaryapi.dev/api/v2/entries/en/scatter",
+            "cat"
+        ));
+    }
+
+    #[test]
+    fn asks_for_decodes_spaces_and_case() {
+        let word = "ice cream";
+        assert!(asks_for(
+            "https://api.dictionaryapi.dev/api/v2/entries/en/ice%20cream",
+            word
+        ));
+        assert!(asks_for(
+            "https://en.wikipedia.org/api/rest_v1/page/summary/Ice_cream",
+            word
+        ));
+        assert!(asks_for(
+            "https://api.datamuse.com/words?rel_syn=ice+cream&max=10",
+            word
+        ));
+        assert!(asks_for(
+            "https://en.wiktionary.org/w/index.php?title=ice_cream&action=raw",
+            word
+        ));
+        assert!(!asks_for(
+            "https://api.datamuse.com/words?rel_syn=ice+creamer",
+            word
+        ));
+    }
+}
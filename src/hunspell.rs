@@ -0,0 +1,443 @@
+use encoding_rs::Encoding;
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::storage;
+
+/// Where distributions install Hunspell/MySpell dictionaries.
+const SYSTEM_DIRS: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+    "/usr/local/share/hunspell",
+];
+
+/// How the `.aff` file encodes affix flags (its `FLAG` directive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagMode {
+    /// One character per flag (the default).
+    Char,
+    /// Two characters per flag.
+    Long,
+    /// Comma-separated decimal numbers.
+    Num,
+}
+
+type Flag = String;
+
+fn parse_flags(flags: &str, mode: FlagMode) -> Vec<Flag> {
+    match mode {
+        FlagMode::Char => flags.chars().map(String::from).collect(),
+        FlagMode::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            chars.chunks(2).map(|c| c.iter().collect()).collect()
+        }
+        FlagMode::Num => flags.split(',').map(|f| f.trim().to_string()).collect(),
+    }
+}
+
+/// One `PFX`/`SFX` rule line: remove `strip`, attach `add`, if the stem matches
+/// `condition`.
+#[derive(Debug)]
+struct Affix {
+    flag: Flag,
+    /// Whether it combines with affixes of the other kind (the `Y` in the header).
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Regex,
+}
+
+/// A word broken down into its dictionary stem and the affixes applied to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub stem: String,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+/// An offline Hunspell-format dictionary: enough of the format to check words,
+/// reduce them to their stems and suggest corrections. Compounding and the
+/// finer `.aff` options are ignored.
+pub struct Hunspell {
+    stems: HashMap<String, Vec<Flag>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// Lower-cased stems, for case-insensitive lookups and suggestions.
+    lowercase: HashMap<String, String>,
+}
+
+impl Hunspell {
+    /// The dictionary for `lang` ("en" or "en_GB"), loaded once per process.
+    pub fn shared(lang: &str) -> Option<&'static Hunspell> {
+        static LOADED: OnceLock<std::sync::Mutex<HashMap<String, Option<&'static Hunspell>>>> =
+            OnceLock::new();
+        let mut loaded = LOADED.get_or_init(Default::default).lock().unwrap();
+        *loaded.entry(lang.to_string()).or_insert_with(|| {
+            let (aff, dic) = find(lang)?;
+            let hunspell = Hunspell::load(&aff, &dic)?;
+            Some(Box::leak(Box::new(hunspell)))
+        })
+    }
+
+    pub fn load(aff: &Path, dic: &Path) -> Option<Self> {
+        let aff = fs::read(aff).ok()?;
+        let dic = fs::read(dic).ok()?;
+        let encoding = encoding(&aff);
+        let aff = decode(&aff, encoding);
+        let dic = decode(&dic, encoding);
+
+        let mut mode = FlagMode::Char;
+        let mut prefixes = Vec::new();
+        let mut suffixes = Vec::new();
+        // Cross-product setting of each affix class, from its header line.
+        let mut cross: HashMap<(String, Flag), bool> = HashMap::new();
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => mode = FlagMode::Long,
+                ["FLAG", "num", ..] => mode = FlagMode::Num,
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    cross.insert((kind.to_string(), flag.to_string()), *cross_product == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let condition = rest.first().copied().unwrap_or(".");
+                    let pattern = if *kind == "PFX" {
+                        format!("^{}", condition)
+                    } else {
+                        format!("{}$", condition)
+                    };
+                    let Ok(condition) = Regex::new(&pattern) else {
+                        continue;
+                    };
+                    let zero = |s: &str| {
+                        if s == "0" {
+                            String::new()
+                        } else {
+                            s.to_string()
+                        }
+                    };
+                    let affix = Affix {
+                        flag: flag.to_string(),
+                        cross_product: cross
+                            .get(&(kind.to_string(), flag.to_string()))
+                            .copied()
+                            .unwrap_or(false),
+                        strip: zero(strip),
+                        // Continuation flags ("ing/S") aren't supported.
+                        add: zero(add.split('/').next().unwrap_or_default()),
+                        condition,
+                    };
+                    if *kind == "PFX" {
+                        prefixes.push(affix);
+                    } else {
+                        suffixes.push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut stems = HashMap::new();
+        let mut lowercase = HashMap::new();
+        // The first line is the word count.
+        for line in dic.lines().skip(1) {
+            // Morphological fields follow the word after whitespace.
+            let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            if word.is_empty() {
+                continue;
+            }
+            lowercase
+                .entry(word.to_lowercase())
+                .or_insert_with(|| word.to_string());
+            stems.insert(word.to_string(), parse_flags(flags, mode));
+        }
+        Some(Self {
+            stems,
+            prefixes,
+            suffixes,
+            lowercase,
+        })
+    }
+
+    fn has_flag(&self, stem: &str, flag: &str) -> bool {
+        self.stems
+            .get(stem)
+            .is_some_and(|flags| flags.iter().any(|f| f == flag))
+    }
+
+    /// Stems `word` could come from by undoing `affix`.
+    fn strip_suffix(word: &str, affix: &Affix) -> Option<String> {
+        let base = word.strip_suffix(affix.add.as_str())?;
+        let stem = format!("{}{}", base, affix.strip);
+        (!stem.is_empty() && affix.condition.is_match(&stem)).then_some(stem)
+    }
+
+    fn strip_prefix(word: &str, affix: &Affix) -> Option<String> {
+        let base = word.strip_prefix(affix.add.as_str())?;
+        let stem = format!("{}{}", affix.strip, base);
+        (!stem.is_empty() && affix.condition.is_match(&stem)).then_some(stem)
+    }
+
+    /// Every way `word` decomposes into a dictionary stem plus affixes.
+    pub fn analyze(&self, word: &str) -> Vec<Analysis> {
+        let mut found = Vec::new();
+        for candidate in [word.to_string(), word.to_lowercase()] {
+            if self.stems.contains_key(&candidate) {
+                found.push(Analysis {
+                    stem: candidate.clone(),
+                    prefix: None,
+                    suffix: None,
+                });
+            }
+            for sfx in &self.suffixes {
+                let Some(stem) = Self::strip_suffix(&candidate, sfx) else {
+                    continue;
+                };
+                if self.has_flag(&stem, &sfx.flag) {
+                    found.push(Analysis {
+                        stem: stem.clone(),
+                        prefix: None,
+                        suffix: Some(sfx.add.clone()),
+                    });
+                }
+                if !sfx.cross_product {
+                    continue;
+                }
+                for pfx in self.prefixes.iter().filter(|p| p.cross_product) {
+                    let Some(root) = Self::strip_prefix(&stem, pfx) else {
+                        continue;
+                    };
+                    if self.has_flag(&root, &sfx.flag) && self.has_flag(&root, &pfx.flag) {
+                        found.push(Analysis {
+                            stem: root,
+                            prefix: Some(pfx.add.clone()),
+                            suffix: Some(sfx.add.clone()),
+                        });
+                    }
+                }
+            }
+            for pfx in &self.prefixes {
+                if let Some(stem) = Self::strip_prefix(&candidate, pfx) {
+                    if self.has_flag(&stem, &pfx.flag) {
+                        found.push(Analysis {
+                            stem,
+                            prefix: Some(pfx.add.clone()),
+                            suffix: None,
+                        });
+                    }
+                }
+            }
+        }
+        found.dedup();
+        found
+    }
+
+    /// Whether `word` is spelled correctly.
+    pub fn check(&self, word: &str) -> bool {
+        !self.analyze(word).is_empty()
+    }
+
+    /// The dictionary form of an inflected word ("running" → "run"), if it
+    /// differs from the word itself.
+    pub fn lemma(&self, word: &str) -> Option<String> {
+        let analyses = self.analyze(word);
+        // An exact stem match means the word is its own lemma.
+        if analyses
+            .iter()
+            .any(|a| a.prefix.is_none() && a.suffix.is_none())
+        {
+            return None;
+        }
+        // Prefixes change the meaning ("unlock"), so they stay in the lemma.
+        analyses
+            .into_iter()
+            .find(|a| a.suffix.is_some())
+            .map(|a| format!("{}{}", a.prefix.unwrap_or_default(), a.stem))
+    }
+
+    /// Corrections for a misspelled `word`: spelled-correctly words one edit
+    /// away (affixed forms included), then stems two edits away.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let word = word.to_lowercase();
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+        let near = edits(&word);
+        for candidate in &near {
+            if self.check(candidate) && seen.insert(candidate.clone()) {
+                suggestions.push(candidate.clone());
+            }
+        }
+        for candidate in near.iter().flat_map(|c| edits(c)) {
+            if let Some(stem) = self.lowercase.get(&candidate) {
+                if seen.insert(candidate.clone()) {
+                    suggestions.push(stem.clone());
+                }
+            }
+        }
+        suggestions.retain(|s| s.to_lowercase() != word);
+        suggestions
+    }
+}
+
+/// The encoding the `.aff` file's `SET` directive gives both files in:
+/// `UTF-8`, `ISO8859-1` to `-15`, `KOI8-R`, `microsoft-cp1251` and the like.
+/// Without one, files that aren't UTF-8 are read as Latin-1, Hunspell's
+/// default.
+fn encoding(aff: &[u8]) -> Option<&'static Encoding> {
+    let set = aff.split(|&b| b == b'\n').find_map(|line| {
+        let line = String::from_utf8_lossy(line);
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some("SET")).then(|| fields.next().map(String::from))?
+    })?;
+    let label = match set.strip_prefix("microsoft-cp") {
+        Some(number) => format!("windows-{}", number),
+        None if set.starts_with("TIS620") => String::from("tis-620"),
+        None => set.clone(),
+    };
+    let encoding = Encoding::for_label(label.as_bytes());
+    if encoding.is_none() {
+        eprintln!(
+            "Warning: Hunspell encoding {} isn't supported; reading the dictionary as UTF-8",
+            set
+        );
+    }
+    encoding.or(Some(encoding_rs::UTF_8))
+}
+
+fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> String {
+    let encoding = encoding.unwrap_or(match std::str::from_utf8(bytes) {
+        Ok(_) => encoding_rs::UTF_8,
+        Err(_) => encoding_rs::WINDOWS_1252,
+    });
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// Every string one deletion, transposition, replacement or insertion away.
+pub fn edits(word: &str) -> Vec<String> {
+    const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+    let chars: Vec<char> = word.chars().collect();
+    let mut out = Vec::new();
+    for i in 0..=chars.len() {
+        let (left, right) = chars.split_at(i);
+        let left: String = left.iter().collect();
+        if let Some((_, rest)) = right.split_first() {
+            out.push(format!("{}{}", left, rest.iter().collect::<String>()));
+            if let Some((second, rest2)) = rest.split_first() {
+                out.push(format!(
+                    "{}{}{}{}",
+                    left,
+                    second,
+                    right[0],
+                    rest2.iter().collect::<String>()
+                ));
+            }
+            for c in LETTERS.chars() {
+                out.push(format!("{}{}{}", left, c, rest.iter().collect::<String>()));
+            }
+        }
+        let right: String = right.iter().collect();
+        for c in LETTERS.chars() {
+            out.push(format!("{}{}{}", left, c, right));
+        }
+    }
+    out
+}
+
+/// Finds `<lang>.aff`/`.dic`: the data dir first, then `$DICPATH`, then the
+/// system dirs. A bare language like "en" matches the first regional variant,
+/// preferring `en_US`.
+fn find(lang: &str) -> Option<(PathBuf, PathBuf)> {
+    let mut dirs = vec![storage::data_dir().join("hunspell")];
+    if let Some(paths) = env::var_os("DICPATH") {
+        dirs.extend(env::split_paths(&paths));
+    }
+    dirs.extend(SYSTEM_DIRS.iter().map(PathBuf::from));
+
+    let preferred = [
+        lang.to_string(),
+        format!("{}_{}", lang, lang.to_uppercase()),
+        format!("{}_US", lang),
+    ];
+    for dir in &dirs {
+        let pair = |name: &str| {
+            let aff = dir.join(format!("{}.aff", name));
+            let dic = dir.join(format!("{}.dic", name));
+            (aff.is_file() && dic.is_file()).then_some((aff, dic))
+        };
+        if let Some(found) = preferred.iter().find_map(|name| pair(name)) {
+            return Some(found);
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut regional: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                e.file_name()
+                    .to_str()?
+                    .strip_suffix(".dic")
+                    .map(String::from)
+            })
+            .filter(|name| name.starts_with(&format!("{}_", lang)))
+            .collect();
+        regional.sort();
+        if let Some(found) = regional.iter().find_map(|name| pair(name)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads a dictionary written to temporary files as `aff` and `dic`.
+    fn load(name: &str, aff: &[u8], dic: &[u8]) -> Hunspell {
+        let dir = env::temp_dir().join(format!("sherlock-hunspell-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (aff_path, dic_path) = (
+            dir.join(format!("{name}.aff")),
+            dir.join(format!("{name}.dic")),
+        );
+        fs::write(&aff_path, aff).unwrap();
+        fs::write(&dic_path, dic).unwrap();
+        let hunspell = Hunspell::load(&aff_path, &dic_path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        hunspell
+    }
+
+    #[test]
+    fn reads_the_set_encoding() {
+        let latin1 = load(
+            "latin1",
+            b"SET ISO8859-1\nSFX S Y 1\nSFX S 0 s .\n",
+            b"1\ncaf\xe9/S\n",
+        );
+        assert!(latin1.check("café"));
+        assert_eq!(latin1.lemma("cafés").as_deref(), Some("café"));
+
+        // "мир" and its plural ending in KOI8-R.
+        let koi8 = load(
+            "koi8",
+            b"SET KOI8-R\nSFX A Y 1\nSFX A 0 \xd9 .\n",
+            b"1\n\xcd\xc9\xd2/A\n",
+        );
+        assert_eq!(koi8.lemma("миры").as_deref(), Some("мир"));
+
+        // Without SET, UTF-8 if the files are, Latin-1 otherwise.
+        let utf8 = load("utf8", b"", "1\nnaïve\n".as_bytes());
+        assert!(utf8.check("naïve"));
+        let unset = load("unset", b"", b"1\nna\xefve\n");
+        assert!(unset.check("naïve"));
+    }
+}
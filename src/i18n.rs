@@ -0,0 +1,93 @@
+use std::{env, sync::OnceLock};
+
+use crate::config::Config;
+
+/// The languages the definition tile and terminal output are translated
+/// into, by code. English comes first and has every message.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../assets/locales/en.ftl")),
+    ("de", include_str!("../assets/locales/de.ftl")),
+    ("es", include_str!("../assets/locales/es.ftl")),
+    ("fr", include_str!("../assets/locales/fr.ftl")),
+];
+
+static UI_LANG: OnceLock<&'static str> = OnceLock::new();
+
+/// The codes `--ui-lang` and `ui_lang` take.
+pub fn languages() -> Vec<&'static str> {
+    CATALOGS.iter().map(|(code, _)| *code).collect()
+}
+
+/// The catalog's code for `name`, a code or a locale like `de_DE.UTF-8`.
+pub fn language(name: &str) -> Option<&'static str> {
+    let lang = name.split(['_', '-', '.', '@']).next().unwrap_or_default();
+    CATALOGS
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| code.eq_ignore_ascii_case(lang))
+}
+
+/// Chooses the language for the rest of the run: `ui_lang` when given, else
+/// the config's `ui_lang`, else the language of the locale the environment
+/// sets for messages, else English.
+pub fn select(ui_lang: Option<&str>) {
+    let _ = UI_LANG.set(chosen(ui_lang));
+}
+
+fn chosen(ui_lang: Option<&str>) -> &'static str {
+    let config = Config::load();
+    let environment = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty());
+    ui_lang
+        .or(config.ui_lang.as_deref())
+        .map(String::from)
+        .or(environment)
+        .and_then(|name| language(&name))
+        .unwrap_or("en")
+}
+
+/// The message `id` without placeables, in the chosen language, or English
+/// where the translation has none. `id` itself if neither does.
+pub fn text(id: &str) -> &str {
+    let lang = *UI_LANG.get_or_init(|| chosen(None));
+    [lang, "en"]
+        .into_iter()
+        .find_map(|lang| message(lang, id))
+        .unwrap_or(id)
+}
+
+/// The message `id` in the chosen language, with each `{ $name }` replaced
+/// by its value in `args`.
+pub fn tr(id: &str, args: &[(&str, &str)]) -> String {
+    let mut rest = text(id);
+    let mut out = String::with_capacity(rest.len());
+    // Values go in as they are, even ones that look like placeables.
+    while let Some(start) = rest.find("{ $") {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = rest[start + 3..start + end].trim();
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 1]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Message `id` of the `lang` catalog, if it has one.
+fn message(lang: &str, id: &str) -> Option<&'static str> {
+    let (_, catalog) = CATALOGS.iter().find(|(code, _)| *code == lang)?;
+    catalog
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == id)
+        .map(|(_, value)| value.trim())
+}
@@ -0,0 +1,241 @@
+use std::{io, path::PathBuf, sync::OnceLock};
+
+use fst::{automaton::Levenshtein, Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+
+use crate::{
+    offline,
+    storage::{self, Bytes},
+    wordlist::{self, WordList},
+};
+
+/// Value stored for words that come from an unranked source.
+const UNRANKED: u64 = u64::MAX;
+
+/// The word list compiled into an FST and cached on disk, so suggestions don't
+/// need the whole list parsed into memory first. Each word maps to its
+/// frequency rank.
+pub struct WordIndex {
+    map: Map<Bytes>,
+}
+
+impl WordIndex {
+    /// The index opened once per process, for features that only read it.
+    pub fn shared() -> &'static WordIndex {
+        static INDEX: OnceLock<WordIndex> = OnceLock::new();
+        INDEX.get_or_init(WordIndex::open)
+    }
+
+    /// Opens the cached index, building it first if it's missing or older than
+    /// any of the word list's sources or the offline dictionaries. The cache is
+    /// memory-mapped, so opening it costs next to nothing however big the list
+    /// is.
+    pub fn open() -> Self {
+        let path = path();
+        let sources = [wordlist::source_paths().to_vec(), offline::sources()].concat();
+        if storage::is_fresh(&path, &sources) {
+            if let Some(map) = storage::map_file(&path)
+                .ok()
+                .and_then(|mmap| Map::new(Bytes::Mapped(mmap)).ok())
+            {
+                return Self { map };
+            }
+        }
+        let built = build(WordList::shared(), &headwords());
+        if let Err(e) = storage::replace_file(&path, &built) {
+            eprintln!(
+                "Warning: could not cache the word index in {}: {}",
+                path.display(),
+                e
+            );
+        }
+        let map = Map::new(Bytes::Built(built)).expect("freshly built FST is valid");
+        Self { map }
+    }
+
+    /// Frequency rank of `word` (0 is the most common), if it is ranked.
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.map
+            .get(word)
+            .filter(|&rank| rank != UNRANKED)
+            .map(|rank| rank as usize)
+    }
+
+    /// Whether the word list has `word` at all, ranked or not.
+    pub fn contains(&self, word: &str) -> bool {
+        self.map.contains_key(word)
+    }
+
+    /// Up to `limit` words starting with `prefix`, in frequency order.
+    #[cfg(any(test, feature = "tui", feature = "interactive"))]
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        self.ranked(fst::automaton::Str::new(&prefix).starts_with(), limit)
+    }
+
+    /// Words within `distance` edits of `word`, or `None` if the automaton for
+    /// it would be too large to build.
+    pub fn fuzzy(&self, word: &str, distance: u32) -> Option<Vec<String>> {
+        let automaton = Levenshtein::new(word, distance).ok()?;
+        Some(self.ranked(automaton, usize::MAX))
+    }
+
+    fn ranked<A: Automaton>(&self, automaton: A, limit: usize) -> Vec<String> {
+        let mut stream = self.map.search(automaton).into_stream();
+        let mut matches = Vec::new();
+        while let Some((word, rank)) = stream.next() {
+            matches.push((rank, String::from_utf8_lossy(word).into_owned()));
+        }
+        // Stable, so unranked words stay alphabetical.
+        matches.sort_by_key(|(rank, _)| *rank);
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, word)| word)
+            .collect()
+    }
+}
+
+/// Where the entries for each key are in a dictionary's text, for the
+/// formats that have no index of their own. Each key maps to every offset
+/// it's at, so words with several entries keep them all, in text order.
+pub struct OffsetIndex {
+    map: Map<Bytes>,
+}
+
+impl OffsetIndex {
+    /// An index of `entries`, (key, offset) pairs in any order.
+    pub fn build(mut entries: Vec<(String, u64)>) -> Vec<u8> {
+        entries.sort_unstable();
+        entries.dedup();
+        let mut builder = MapBuilder::memory();
+        for (key, offset) in entries {
+            builder
+                .insert(entry_key(&key, offset), offset)
+                .expect("offset index keys are sorted and unique");
+        }
+        builder.into_inner().expect("in-memory writes can't fail")
+    }
+
+    pub fn new(bytes: Bytes) -> io::Result<Self> {
+        let map = Map::new(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self { map })
+    }
+
+    /// The offsets of `key`'s entries, first in the text first.
+    pub fn get(&self, key: &str) -> Vec<u64> {
+        let from = [key.as_bytes(), &[0]].concat();
+        let to = [key.as_bytes(), &[1]].concat();
+        let mut stream = self.map.range().ge(from).lt(to).into_stream();
+        let mut offsets = Vec::new();
+        while let Some((_, offset)) = stream.next() {
+            offsets.push(offset);
+        }
+        offsets
+    }
+
+    /// Every key, once.
+    pub fn keys(&self) -> Vec<String> {
+        let mut stream = self.map.stream();
+        let mut keys: Vec<String> = Vec::new();
+        while let Some((key, _)) = stream.next() {
+            let key = String::from_utf8_lossy(&key[..key.len() - 9]);
+            if keys.last().is_none_or(|last| *last != key) {
+                keys.push(key.into_owned());
+            }
+        }
+        keys
+    }
+}
+
+/// A key followed by a NUL and its offset, big-endian so a key's offsets
+/// sort in text order.
+fn entry_key(key: &str, offset: u64) -> Vec<u8> {
+    [key.as_bytes(), &[0], &offset.to_be_bytes()].concat()
+}
+
+/// Named after the version, since the embedded list can change between releases.
+fn path() -> PathBuf {
+    storage::cache_dir().join(format!("words-{}.fst", env!("CARGO_PKG_VERSION")))
+}
+
+/// The installed offline dictionaries' headwords, lower-cased like the word
+/// list. A dictionary that can't be read is left out, with a warning.
+fn headwords() -> Vec<String> {
+    let mut headwords = Vec::new();
+    for (path, dictionary) in offline::installed() {
+        match dictionary.headwords() {
+            Ok(words) => headwords.extend(
+                words
+                    .iter()
+                    .map(|word| word.trim().to_lowercase())
+                    .filter(|word| !word.is_empty()),
+            ),
+            Err(e) => eprintln!(
+                "Warning: leaving {} out of the suggestions: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    headwords
+}
+
+/// The word list's words with their ranks, and `headwords` the list doesn't
+/// have, unranked.
+fn build(words: &WordList, headwords: &[String]) -> Vec<u8> {
+    let mut sorted: Vec<(&str, u64)> = words
+        .iter()
+        .map(|word| (word, words.rank(word).map_or(UNRANKED, |rank| rank as u64)))
+        .chain(headwords.iter().map(|word| (word.as_str(), UNRANKED)))
+        .collect();
+    // Ranked first, so deduplicating keeps a listed word's rank.
+    sorted.sort_unstable_by_key(|&(word, rank)| (word.as_bytes(), rank));
+    sorted.dedup_by_key(|(word, _)| *word);
+    let mut builder = MapBuilder::memory();
+    for (word, rank) in sorted {
+        // Keys are unique and sorted, which is all insert checks for.
+        builder
+            .insert(word, rank)
+            .expect("word list keys are sorted and unique");
+    }
+    builder.into_inner().expect("in-memory writes can't fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headwords_join_the_word_list() {
+        let words = WordList::shared();
+        let the = words.rank("the");
+        let headwords = [String::from("zorpification"), String::from("the")];
+        let index = WordIndex {
+            map: Map::new(Bytes::Built(build(words, &headwords))).unwrap(),
+        };
+        assert!(index.contains("zorpification"));
+        assert_eq!(index.rank("zorpification"), None);
+        // A headword the list has keeps the list's rank.
+        assert_eq!(index.rank("the"), the);
+        assert_eq!(index.suggest("zorpif", 5), ["zorpification"]);
+    }
+
+    #[test]
+    fn offsets_come_back_in_text_order() {
+        let entries = vec![
+            (String::from("set"), 300),
+            (String::from("sets"), 10),
+            (String::from("set"), 20),
+            (String::from("set"), 20),
+            (String::from("se"), 5),
+        ];
+        let index = OffsetIndex::new(Bytes::Built(OffsetIndex::build(entries))).unwrap();
+        assert_eq!(index.get("set"), [20, 300]);
+        assert_eq!(index.get("se"), [5]);
+        assert!(index.get("s").is_empty());
+        assert_eq!(index.keys(), ["se", "set", "sets"]);
+    }
+}
@@ -0,0 +1,190 @@
+use regex::Regex;
+use std::{fs, io, path::PathBuf, sync::LazyLock};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    domains, storage,
+};
+
+/// Programming terms the Jargon File predates or never had.
+const DEV_TERMS: &str = include_str!("../assets/glossaries/jargon.tsv");
+
+/// The Jargon File's part-of-speech abbreviations.
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("n.", "noun"),
+    ("v.", "verb"),
+    ("vt.", "verb"),
+    ("vi.", "verb"),
+    ("adj.", "adjective"),
+    ("adv.", "adverb"),
+    ("interj.", "interjection"),
+    ("pref.", "prefix"),
+    ("suff.", "suffix"),
+];
+
+/// `:headword: body`, the line an entry starts on in the text releases.
+static HEADWORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^:([^:\s][^:]*):\s*(.*)").unwrap());
+/// A leading `/pronunciation/` and `[etymology]`.
+static PREAMBLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:/([^/]+)/\s*,?\s*)?(?:\[([^\]]*)\]\s*)?").unwrap());
+
+/// A plain-text copy of the Jargon File, e.g. `jarg447.txt` renamed.
+pub fn jargon_file() -> PathBuf {
+    storage::data_dir().join("jargon.txt")
+}
+
+/// The curated developer terms first, then the Jargon File's entry when
+/// there's a copy of it.
+pub fn lookup(word: &str, lang: &str) -> io::Result<Lookup> {
+    if lang != "en" {
+        return Ok(Lookup::NotFound);
+    }
+    let mut entries: Vec<DefinitionResponse> =
+        domains::glossary(DEV_TERMS, word).into_iter().collect();
+    match fs::read_to_string(jargon_file()) {
+        Ok(text) => entries.extend(entry(&text, word)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    Ok(match entries.is_empty() {
+        true => Lookup::NotFound,
+        false => Lookup::Found(entries),
+    })
+}
+
+/// "Yak-shaving" and "yak shaving" are the same entry.
+fn normalize(term: &str) -> String {
+    term.trim().to_lowercase().replace('-', " ")
+}
+
+/// The body of the entry headed `word`, its lines joined and `{cross
+/// references}` unwrapped.
+fn body(text: &str, word: &str) -> Option<String> {
+    let word = normalize(word);
+    let mut lines = text.lines();
+    let first = lines.find_map(|line| {
+        let captures = HEADWORD.captures(line)?;
+        (normalize(&captures[1]) == word).then(|| captures[2].to_string())
+    })?;
+    let mut body = first;
+    for line in lines.take_while(|line| !HEADWORD.is_match(line)) {
+        body.push(' ');
+        body.push_str(line.trim());
+    }
+    let body = body.replace(['{', '}'], "");
+    Some(body.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// The entry for `word` in the Jargon File, split into numbered senses
+/// grouped by part of speech.
+fn entry(text: &str, word: &str) -> Option<DefinitionResponse> {
+    let body = body(text, word)?;
+    let (ipa, mut origin, rest) = preamble(&body);
+    let ipa = ipa.map(|ipa| format!("/{}/", ipa));
+
+    // A part of speech before the first number applies to every sense, and
+    // the etymology can come after it.
+    let (shared, rest) = part_of_speech(rest);
+    let (_, later_origin, rest) = preamble(rest);
+    origin = origin.or(later_origin);
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for sense in senses(rest) {
+        let (own, definition) = part_of_speech(sense);
+        let definition = preamble(definition).2.trim().to_string();
+        if definition.is_empty() {
+            continue;
+        }
+        let part_of_speech = own.or(shared).unwrap_or("jargon");
+        let definition = Definition {
+            definition,
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        };
+        match meanings
+            .iter_mut()
+            .find(|m| m.part_of_speech == part_of_speech)
+        {
+            Some(meaning) => meaning.definitions.push(definition),
+            None => meanings.push(Meaning {
+                part_of_speech: part_of_speech.to_string(),
+                definitions: vec![definition],
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender: None,
+            }),
+        }
+    }
+    if meanings.is_empty() {
+        return None;
+    }
+    let phonetics = ipa
+        .iter()
+        .map(|text| Phonetic {
+            text: Some(text.clone()),
+            audio: None,
+            source_url: None,
+            license: None,
+        })
+        .collect();
+    Some(DefinitionResponse {
+        word: word.trim().to_string(),
+        phonetic: ipa,
+        phonetics,
+        meanings,
+        source_urls: None,
+        origin: origin.map(str::to_string),
+        provider: None,
+    })
+}
+
+/// Splits off a leading `/pronunciation/` and `[etymology]`.
+fn preamble(text: &str) -> (Option<&str>, Option<&str>, &str) {
+    let text = text.trim_start();
+    match PREAMBLE.captures(text) {
+        Some(captures) => (
+            captures.get(1).map(|m| m.as_str()),
+            captures.get(2).map(|m| m.as_str()),
+            &text[captures.get(0).map_or(0, |m| m.end())..],
+        ),
+        None => (None, None, text),
+    }
+}
+
+/// Splits off a leading abbreviation such as `n.` or `vt.`.
+fn part_of_speech(text: &str) -> (Option<&'static str>, &str) {
+    let text = text.trim_start();
+    let (first, rest) = text.split_once(' ').unwrap_or((text, ""));
+    match PARTS_OF_SPEECH
+        .iter()
+        .find(|(abbreviation, _)| *abbreviation == first.trim_end_matches(','))
+    {
+        Some((_, name)) => (Some(name), rest),
+        None => (None, text),
+    }
+}
+
+/// The text of each numbered sense ("1. … 2. …"), or the whole text when
+/// the entry has only one. Numbers are taken in order, so "version 2. " in a
+/// sense doesn't start a new one unless it's the next number.
+fn senses(text: &str) -> Vec<&str> {
+    let marker = |n: usize| format!("{}. ", n);
+    if !text.starts_with(&marker(1)) {
+        return vec![text];
+    }
+    let mut senses = Vec::new();
+    let mut start = marker(1).len();
+    let mut n = 2;
+    while let Some(offset) = text[start..].find(&format!(" {}", marker(n))) {
+        senses.push(&text[start..start + offset]);
+        start += offset + 1 + marker(n).len();
+        n += 1;
+    }
+    senses.push(&text[start..]);
+    senses
+}
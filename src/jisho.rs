@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    http,
+    providers::Provider,
+};
+
+/// Words shown per lookup. An English keyword matches many Japanese words,
+/// and the first few are the common ones.
+const LIMIT: usize = 3;
+
+#[derive(Serialize)]
+struct Query<'a> {
+    keyword: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: Vec<Word>,
+}
+
+#[derive(Deserialize)]
+struct Word {
+    slug: String,
+    #[serde(default)]
+    japanese: Vec<Writing>,
+    #[serde(default)]
+    senses: Vec<Sense>,
+}
+
+#[derive(Deserialize)]
+struct Writing {
+    word: Option<String>,
+    reading: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Sense {
+    #[serde(default)]
+    english_definitions: Vec<String>,
+    #[serde(default)]
+    parts_of_speech: Vec<String>,
+}
+
+/// Japanese words from Jisho, looked up by kanji, kana, romaji or English.
+/// Each word's reading is shown as its pronunciation.
+pub async fn lookup(word: &str) -> Result<Lookup, surf::Error> {
+    let response: Response =
+        http::provider_get(Provider::Jisho, "https://jisho.org/api/v1/search/words")?
+            .query(&Query { keyword: word })?
+            .recv_json()
+            .await?;
+    let entries: Vec<DefinitionResponse> = response
+        .data
+        .into_iter()
+        .filter_map(entry)
+        .take(LIMIT)
+        .collect();
+    Ok(match entries.is_empty() {
+        true => Lookup::NotFound,
+        false => Lookup::Found(entries),
+    })
+}
+
+fn entry(word: Word) -> Option<DefinitionResponse> {
+    let writing = word.japanese.first()?;
+    let reading = writing.reading.clone();
+    let headword = writing.word.clone().or_else(|| reading.clone())?;
+
+    let mut meanings: Vec<Meaning> = Vec::new();
+    let mut part_of_speech = String::from("word");
+    for sense in word.senses {
+        // Senses without parts of speech share the previous sense's.
+        if let Some(pos) = sense.parts_of_speech.first() {
+            part_of_speech = pos.to_lowercase();
+        }
+        if sense.english_definitions.is_empty() {
+            continue;
+        }
+        let definition = Definition {
+            definition: sense.english_definitions.join("; "),
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        };
+        match meanings
+            .iter_mut()
+            .find(|m| m.part_of_speech == part_of_speech)
+        {
+            Some(meaning) => meaning.definitions.push(definition),
+            None => meanings.push(Meaning {
+                part_of_speech: part_of_speech.clone(),
+                definitions: vec![definition],
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender: None,
+            }),
+        }
+    }
+    if meanings.is_empty() {
+        return None;
+    }
+    // Kana-only words are their own reading.
+    let reading = reading.filter(|reading| *reading != headword);
+    Some(DefinitionResponse {
+        word: headword,
+        phonetic: reading.clone(),
+        phonetics: reading
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings,
+        source_urls: Some(vec![format!("https://jisho.org/word/{}", word.slug)]),
+        origin: None,
+        provider: None,
+    })
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{html, storage};
+
+/// What `known` does to the known-words list.
+#[derive(Debug)]
+pub enum Action {
+    List,
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    /// A CSV file or an Anki "notes in plain text" export, one word a row in
+    /// the first column.
+    Import(PathBuf),
+}
+
+/// Words the user already knows, which the commands that pick out
+/// unfamiliar words leave out.
+#[derive(Debug, Default)]
+pub struct Known {
+    pub words: BTreeSet<String>,
+}
+
+impl Known {
+    /// Reads `known.txt` from the data dir, one word per line. A missing file
+    /// means no words are known yet.
+    pub fn load() -> Self {
+        let text = fs::read_to_string(path()).unwrap_or_default();
+        let words = text
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        Self { words }
+    }
+
+    /// Like [`History::update`](crate::history::History::update), for the
+    /// known words.
+    pub fn update<R>(change: impl FnOnce(&mut Self) -> R) -> std::io::Result<R> {
+        storage::with_lock(&path(), || {
+            let mut known = Self::load();
+            let changed = change(&mut known);
+            known.save().map(|()| changed)
+        })
+    }
+
+    /// Writes the list back, sorted, so it stays easy to edit by hand.
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut text = String::new();
+        for word in &self.words {
+            text.push_str(word);
+            text.push('\n');
+        }
+        storage::replace_file(&path(), text.as_bytes())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    /// Adds `word`, returning whether it wasn't known before.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let word = word.trim().to_lowercase();
+        !word.is_empty() && self.words.insert(word)
+    }
+
+    /// Removes `word`, returning whether it was known.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.words.remove(&word.trim().to_lowercase())
+    }
+}
+
+pub fn run(action: Action) -> Result<(), Box<dyn Error>> {
+    match action {
+        Action::List => {
+            for word in &Known::load().words {
+                println!("{}", word);
+            }
+        }
+        Action::Add(words) => {
+            let added =
+                Known::update(|known| words.iter().filter(|word| known.insert(word)).count())?;
+            println!("Added {} of {} words.", added, words.len());
+        }
+        Action::Remove(words) => {
+            let removed =
+                Known::update(|known| words.iter().filter(|word| known.remove(word)).count())?;
+            println!("Removed {} of {} words.", removed, words.len());
+        }
+        Action::Import(path) => {
+            let words = import(&path)?;
+            let added =
+                Known::update(|known| words.iter().filter(|word| known.insert(word)).count())?;
+            println!(
+                "Imported {} new words of {} in {}.",
+                added,
+                words.len(),
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The first column of each row of a CSV or tab-separated file. Anki's
+/// `#separator:tab`-style header lines are skipped and card HTML is stripped.
+fn import(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let words = text
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let field = match line.contains('\t') {
+                true => line.split('\t').next()?,
+                false => line.split(',').next()?,
+            };
+            let word = html::text(field.trim().trim_matches('"'));
+            (!word.is_empty()).then_some(word)
+        })
+        .collect();
+    Ok(words)
+}
+
+fn path() -> PathBuf {
+    storage::data_dir().join("known.txt")
+}
@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use surf::StatusCode;
+
+use crate::{
+    api::{ApiErrorResponse, Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    config::Config,
+    html, http,
+    providers::Provider,
+};
+
+/// Words shown per lookup; homographs beyond the first few are rare ones.
+const LIMIT: usize = 5;
+
+/// The languages krdict translates its definitions into, by the number its
+/// `trans_lang` takes.
+const TRANSLATIONS: &[(&str, u8)] = &[
+    ("en", 1),
+    ("ja", 2),
+    ("fr", 3),
+    ("es", 4),
+    ("ar", 5),
+    ("mn", 6),
+    ("vi", 7),
+    ("th", 8),
+    ("id", 9),
+    ("ru", 10),
+    ("zh", 11),
+];
+
+/// Its parts of speech, in Korean.
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("명사", "noun"),
+    ("의존 명사", "bound noun"),
+    ("대명사", "pronoun"),
+    ("수사", "numeral"),
+    ("동사", "verb"),
+    ("보조 동사", "auxiliary verb"),
+    ("형용사", "adjective"),
+    ("보조 형용사", "auxiliary adjective"),
+    ("관형사", "determiner"),
+    ("부사", "adverb"),
+    ("감탄사", "interjection"),
+    ("조사", "particle"),
+    ("어미", "ending"),
+    ("접사", "affix"),
+];
+
+/// Revised Romanization of the 19 initial consonants, 21 vowels and 28
+/// finals (the first being none) hangul syllables are made of. Finals are
+/// written as they're pronounced at the end of a syllable.
+const REVISED: [&[&str]; 3] = [
+    &[
+        "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t",
+        "p", "h",
+    ],
+    &[
+        "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo",
+        "we", "wi", "yu", "eu", "ui", "i",
+    ],
+    &[
+        "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p",
+        "p", "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+    ],
+];
+
+/// Yale romanization of the same, letter for letter as the word is spelled.
+const YALE: [&[&str]; 3] = [
+    &[
+        "k", "kk", "n", "t", "tt", "l", "m", "p", "pp", "s", "ss", "", "c", "cc", "ch", "kh", "th",
+        "ph", "h",
+    ],
+    &[
+        "a", "ay", "ya", "yay", "e", "ey", "ye", "yey", "o", "wa", "way", "oy", "yo", "wu", "we",
+        "wey", "wi", "yu", "u", "uy", "i",
+    ],
+    &[
+        "", "k", "kk", "ks", "n", "nc", "nh", "t", "l", "lk", "lm", "lp", "ls", "lth", "lph", "lh",
+        "m", "p", "ps", "s", "ss", "ng", "c", "ch", "kh", "th", "ph", "h",
+    ],
+];
+
+/// How Korean words' pronunciations are romanized, from
+/// `korean_romanization` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Romanization {
+    /// South Korea's official system, from how the word is pronounced:
+    /// 읽다 is "iktta".
+    #[default]
+    Revised,
+    /// Yale, letter for letter as the word is spelled, as linguists write
+    /// it: 읽다 is "ilkta".
+    Yale,
+    /// Only the pronunciation in hangul.
+    Off,
+}
+
+#[derive(Serialize)]
+struct Query<'a> {
+    key: &'a str,
+    q: &'a str,
+    part: &'a str,
+    translated: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trans_lang: Option<u8>,
+}
+
+/// Korean words from the National Institute of Korean Language's Basic
+/// Korean Dictionary, with definitions translated into `lang` when krdict
+/// has it in that language, in Korean for `ko` and in English otherwise.
+/// Needs `krdict_key` in the config.
+pub async fn lookup(word: &str, lang: &str) -> Result<Lookup, surf::Error> {
+    let config = Config::load();
+    let Some(key) = config.krdict_key.as_deref() else {
+        return Err(surf::Error::from_str(
+            StatusCode::Unauthorized,
+            "krdict needs an API key: get one at https://krdict.korean.go.kr/openApi/openApiInfo \
+             and put it in the config as krdict_key",
+        ));
+    };
+    let trans_lang = match lang {
+        "ko" => None,
+        lang => Some(
+            TRANSLATIONS
+                .iter()
+                .find(|(code, _)| *code == lang)
+                .map_or(1, |(_, n)| *n),
+        ),
+    };
+    let mut response =
+        http::provider_get(Provider::Krdict, "https://krdict.korean.go.kr/api/search")?
+            .query(&Query {
+                key,
+                q: word,
+                part: "word",
+                translated: match trans_lang.is_some() {
+                    true => "y",
+                    false => "n",
+                },
+                trans_lang,
+            })?
+            .await?;
+    let status = response.status();
+    let xml = response.body_string().await?;
+    // Text comes in CDATA sections, which aren't tags to the HTML helpers.
+    let xml = xml.replace("<![CDATA[", "").replace("]]>", "");
+    if let Some(error) = html::elements(&xml, "error").into_iter().next() {
+        let error = &xml[error];
+        return Ok(Lookup::ApiError {
+            status,
+            error: ApiErrorResponse {
+                title: format!(
+                    "krdict error {}",
+                    first("error_code", error).unwrap_or_default()
+                ),
+                message: first("message", error).unwrap_or_default(),
+                resolution: String::from("Check krdict_key in the config."),
+            },
+        });
+    }
+    let entries: Vec<DefinitionResponse> = html::elements(&xml, "item")
+        .into_iter()
+        .filter_map(|range| entry(&xml[range], trans_lang.is_some()))
+        .take(LIMIT)
+        .collect();
+    Ok(match entries.is_empty() {
+        true => Lookup::NotFound,
+        false => Lookup::Found(entries),
+    })
+}
+
+/// Converts one `<item>`: a word, its pronunciation in hangul and its
+/// senses, each with a Korean definition and, when `translated`, a
+/// translation of the word and of the definition.
+fn entry(item: &str, translated: bool) -> Option<DefinitionResponse> {
+    let word = first("word", item)?;
+    let part_of_speech = first("pos", item)
+        .map(|pos| {
+            PARTS_OF_SPEECH
+                .iter()
+                .find(|(korean, _)| *korean == pos)
+                .map_or(pos, |(_, english)| english.to_string())
+        })
+        .unwrap_or_else(|| String::from("word"));
+
+    let mut definitions = Vec::new();
+    for sense in html::elements(item, "sense") {
+        let sense = &item[sense];
+        let translation = html::elements(sense, "translation")
+            .into_iter()
+            .next()
+            .map(|range| &sense[range]);
+        let definition = match translation.filter(|_| translated) {
+            Some(translation) => {
+                match (
+                    first("trans_word", translation),
+                    first("trans_dfn", translation),
+                ) {
+                    (Some(word), Some(definition)) => Some(format!("{} — {}", word, definition)),
+                    (word, definition) => word.or(definition),
+                }
+            }
+            None => first("definition", sense),
+        };
+        if let Some(definition) = definition {
+            definitions.push(Definition {
+                definition,
+                example: None,
+                synonyms: None,
+                antonyms: None,
+                level: None,
+                region: None,
+                labels: Vec::new(),
+            });
+        }
+    }
+    if definitions.is_empty() {
+        return None;
+    }
+
+    // krdict marks long vowels with a colon; several pronunciations are
+    // separated by slashes.
+    let pronunciation = first("pronunciation", item)
+        .and_then(|p| p.split('/').next().map(|p| p.trim().to_string()))
+        .filter(|p| !p.is_empty());
+    let romanized = match Config::load().korean_romanization {
+        Romanization::Revised => Some(romanize(
+            &pronunciation.as_deref().unwrap_or(&word).replace(':', ""),
+            Romanization::Revised,
+        )),
+        Romanization::Yale => Some(romanize(&word, Romanization::Yale)),
+        Romanization::Off => None,
+    };
+    let hangul = pronunciation
+        .filter(|p| *p != word)
+        .map(|p| format!("[{}]", p));
+    let phonetics: Vec<String> = hangul.iter().chain(&romanized).cloned().collect();
+    Some(DefinitionResponse {
+        phonetic: romanized.or(hangul),
+        phonetics: phonetics
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        word,
+        meanings: vec![Meaning {
+            part_of_speech,
+            definitions,
+            synonyms: None,
+            antonyms: None,
+            forms: None,
+            gender: None,
+        }],
+        source_urls: first("link", item).map(|link| vec![link]),
+        origin: None,
+        provider: None,
+    })
+}
+
+/// The text of the first `tag` element in `within`, if it has any.
+fn first(tag: &str, within: &str) -> Option<String> {
+    html::elements(within, tag)
+        .into_iter()
+        .next()
+        .map(|range| html::text(&within[range]))
+        .filter(|text| !text.is_empty())
+}
+
+/// `hangul` romanized with `scheme`, keeping what isn't a hangul syllable.
+/// In the Revised system a ㄹ after a final ㄹ is "l", and "r" otherwise; in
+/// Yale ㅜ is "u" after ㅁ, ㅂ, ㅃ and ㅍ, which are followed by no other
+/// rounded vowel.
+fn romanize(hangul: &str, scheme: Romanization) -> String {
+    let letters = match scheme {
+        Romanization::Revised => &REVISED,
+        Romanization::Yale => &YALE,
+        Romanization::Off => return hangul.to_string(),
+    };
+    let mut out = String::new();
+    let mut last_final = None;
+    for c in hangul.chars() {
+        let Some(index) = u32::from(c)
+            .checked_sub(0xAC00)
+            .filter(|&i| i < 19 * 21 * 28)
+        else {
+            out.push(c);
+            last_final = None;
+            continue;
+        };
+        let (initial, vowel, last) = (index / 588, index % 588 / 28, index % 28);
+        let initial_letters = match (scheme, initial, last_final) {
+            (Romanization::Revised, 5, Some(8)) => "l",
+            (_, initial, _) => letters[0][initial as usize],
+        };
+        let vowel_letters = match (scheme, initial, vowel) {
+            (Romanization::Yale, 6 | 7 | 8 | 17, 13) => "u",
+            (_, _, vowel) => letters[1][vowel as usize],
+        };
+        out.push_str(initial_letters);
+        out.push_str(vowel_letters);
+        out.push_str(letters[2][last as usize]);
+        last_final = Some(last);
+    }
+    out
+}
@@ -0,0 +1,253 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{self, IsTerminal},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+use crate::api::DefinitionResponse;
+
+/// A leading "(informal, humorous)" label list, and the rest of the sense.
+static LIST: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\(([^)]*)\)\s*(.*)$").unwrap());
+/// Hedges in front of a label, as in "now rare".
+static HEDGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(chiefly|mainly|mostly|now|often|usually|sometimes|somewhat|especially)\s+")
+        .unwrap()
+});
+
+/// How a sense is used rather than what it means: its register, its age,
+/// whether it's crude and where in a language it belongs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Label {
+    Informal,
+    Slang,
+    Formal,
+    Literary,
+    Humorous,
+    Archaic,
+    Dated,
+    Obsolete,
+    Historical,
+    Rare,
+    Vulgar,
+    Offensive,
+    Dialectal,
+    Nonstandard,
+    Technical,
+}
+impl Label {
+    pub const ALL: [Label; 15] = [
+        Label::Informal,
+        Label::Slang,
+        Label::Formal,
+        Label::Literary,
+        Label::Humorous,
+        Label::Archaic,
+        Label::Dated,
+        Label::Obsolete,
+        Label::Historical,
+        Label::Rare,
+        Label::Vulgar,
+        Label::Offensive,
+        Label::Dialectal,
+        Label::Nonstandard,
+        Label::Technical,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Label::Informal => "informal",
+            Label::Slang => "slang",
+            Label::Formal => "formal",
+            Label::Literary => "literary",
+            Label::Humorous => "humorous",
+            Label::Archaic => "archaic",
+            Label::Dated => "dated",
+            Label::Obsolete => "obsolete",
+            Label::Historical => "historical",
+            Label::Rare => "rare",
+            Label::Vulgar => "vulgar",
+            Label::Offensive => "offensive",
+            Label::Dialectal => "dialectal",
+            Label::Nonstandard => "nonstandard",
+            Label::Technical => "technical",
+        }
+    }
+
+    /// The other ways dictionaries write it.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Label::Informal => &["colloquial", "familiar", "casual"],
+            Label::Slang => &["internet slang", "street slang"],
+            Label::Formal => &[],
+            Label::Literary => &["poetic", "poetical"],
+            Label::Humorous => &["jocular", "facetious"],
+            Label::Archaic => &["archaism"],
+            Label::Dated => &["old-fashioned"],
+            Label::Obsolete => &[],
+            Label::Historical => &[],
+            Label::Rare => &["uncommon"],
+            Label::Vulgar => &["coarse", "crude", "obscene", "profane"],
+            Label::Offensive => &[
+                "derogatory",
+                "pejorative",
+                "disparaging",
+                "slur",
+                "ethnic slur",
+            ],
+            Label::Dialectal => &["dialect", "regional", "provincial"],
+            Label::Nonstandard => &["non-standard", "substandard", "proscribed"],
+            Label::Technical => &["jargon", "specialist"],
+        }
+    }
+
+    /// Its tag's color: blue for informal registers, purple for elevated
+    /// ones, brown for old or rare senses, red for crude ones and green for
+    /// dialect, as Pango and ratatui take it.
+    pub fn color(self) -> &'static str {
+        match self {
+            Label::Informal | Label::Slang | Label::Humorous => "#1e6fd9",
+            Label::Formal | Label::Literary => "#8e44ad",
+            Label::Archaic | Label::Dated | Label::Obsolete | Label::Historical | Label::Rare => {
+                "#9a6a1f"
+            }
+            Label::Vulgar | Label::Offensive => "#d11a2a",
+            Label::Dialectal | Label::Nonstandard => "#1f8a3b",
+            Label::Technical => "#11808a",
+        }
+    }
+
+    /// The same color as a terminal takes it.
+    fn ansi_color(self) -> &'static str {
+        match self {
+            Label::Informal | Label::Slang | Label::Humorous => "34",
+            Label::Formal | Label::Literary => "35",
+            Label::Archaic | Label::Dated | Label::Obsolete | Label::Historical | Label::Rare => {
+                "33"
+            }
+            Label::Vulgar | Label::Offensive => "31",
+            Label::Dialectal | Label::Nonstandard => "32",
+            Label::Technical => "36",
+        }
+    }
+
+    /// The label a dictionary's "now rare" or "colloquial" stands for.
+    fn of(label: &str) -> Option<Self> {
+        let label = HEDGE.replace(label.trim(), "").to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|l| l.name() == label || l.aliases().contains(&label.as_str()))
+    }
+}
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl FromStr for Label {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|label| label.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|l| l.name()).collect();
+                format!(
+                    "Unknown usage label '{}' (expected one of {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Reads a comma-separated list of labels, as `--exclude-labels` takes it.
+pub fn parse_list(list: &str) -> Result<Vec<Label>, String> {
+    list.split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// `labels` as bracketed tags in their colors, as Pango markup: "[informal] ".
+pub fn pango(labels: &[Label]) -> String {
+    labels
+        .iter()
+        .map(|label| {
+            format!(
+                "<span foreground=\"{}\"><small>[{}]</small></span> ",
+                label.color(),
+                label
+            )
+        })
+        .collect()
+}
+
+/// `labels` as bracketed tags, in their colors when they're printed to a
+/// terminal.
+pub fn ansi(labels: &[Label]) -> String {
+    let colored = io::stdout().is_terminal();
+    labels
+        .iter()
+        .map(|label| match colored {
+            true => format!("\x1b[{}m[{}]\x1b[0m ", label.ansi_color(), label),
+            false => format!("[{}] ", label),
+        })
+        .collect()
+}
+
+/// Moves the usage labels it knows out of each sense's label list into its
+/// `labels`: "(informal, sport) a game" becomes "(sport) a game" tagged
+/// informal. Subject labels such as "sport" stay in the text.
+pub fn annotate(entries: &mut [DefinitionResponse]) {
+    let definitions = entries
+        .iter_mut()
+        .flat_map(|e| &mut e.meanings)
+        .flat_map(|m| &mut m.definitions);
+    for definition in definitions {
+        let Some(captures) = LIST.captures(&definition.definition) else {
+            continue;
+        };
+        let mut labels = Vec::new();
+        let mut others = Vec::new();
+        for label in captures[1].split([',', ';']) {
+            match Label::of(label) {
+                Some(label) if !labels.contains(&label) => labels.push(label),
+                Some(_) => {}
+                None => others.push(label.trim()),
+            }
+        }
+        if labels.is_empty() {
+            continue;
+        }
+        let rest = &captures[2];
+        let text = match others.is_empty() {
+            true => rest.to_string(),
+            false => format!("({}) {}", others.join(", "), rest),
+        };
+        definition.labels = labels;
+        definition.definition = text;
+    }
+}
+
+/// The entries without the senses tagged with any of `excluded`, and
+/// without the parts of speech and entries left with none.
+pub fn exclude(
+    mut entries: Vec<DefinitionResponse>,
+    excluded: &[Label],
+) -> Vec<DefinitionResponse> {
+    for entry in &mut entries {
+        for meaning in &mut entry.meanings {
+            meaning
+                .definitions
+                .retain(|def| !def.labels.iter().any(|label| excluded.contains(label)));
+        }
+        entry.meanings.retain(|m| !m.definitions.is_empty());
+    }
+    entries.retain(|e| !e.meanings.is_empty());
+    entries
+}
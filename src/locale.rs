@@ -0,0 +1,155 @@
+use std::{env, sync::OnceLock};
+
+use crate::{
+    config::Config,
+    history::{self, DAY},
+};
+
+/// How numbers and dates are written where the user lives.
+#[derive(Debug, Clone, Copy)]
+struct Conventions {
+    /// Between groups of three digits; empty for none.
+    group: &'static str,
+    decimal: char,
+    date: DateOrder,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DateOrder {
+    /// 2024-03-14
+    Iso,
+    /// 03/14/2024
+    MonthDayYear,
+    /// 14/03/2024
+    DaySlash,
+    /// 14.03.2024
+    DayDot,
+    /// 14-03-2024
+    DayDash,
+    /// 2024/03/14
+    YearSlash,
+}
+
+/// What the "C" locale and languages not listed here get: digits without
+/// grouping, a point and ISO dates, as before there were locales.
+const PLAIN: Conventions = Conventions {
+    group: "",
+    decimal: '.',
+    date: DateOrder::Iso,
+};
+
+/// `locale` from the config, else the locale the environment sets for
+/// `category` (`LC_NUMERIC` or `LC_TIME`), with `LC_ALL` and `LANG` as
+/// POSIX has them.
+fn name(category: &str) -> Option<String> {
+    if let Some(locale) = &Config::load().locale {
+        return Some(locale.clone());
+    }
+    ["LC_ALL", category, "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// The conventions of a locale name such as `de_DE.UTF-8`, `en-GB` or `fr`.
+fn conventions(name: &str) -> Conventions {
+    // The encoding and modifier say nothing about either.
+    let name = name.split(['.', '@']).next().unwrap_or_default();
+    let (lang, region) = name.split_once(['_', '-']).unwrap_or((name, ""));
+    let (lang, region) = (lang.to_lowercase(), region.to_uppercase());
+    let (group, decimal) = match (lang.as_str(), region.as_str()) {
+        ("de" | "it" | "fr" | "rm", "CH" | "LI") => ("'", '.'),
+        ("en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ga" | "cy" | "ms", _) => (",", '.'),
+        (
+            "de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "sl" | "hr"
+            | "sr" | "ca" | "eu" | "gl" | "is",
+            _,
+        ) => (".", ','),
+        (
+            "fr" | "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "sv" | "nb" | "nn" | "no" | "fi"
+            | "hu" | "bg" | "et" | "lv" | "lt" | "kk",
+            _,
+        ) => ("\u{a0}", ','),
+        _ => return PLAIN,
+    };
+    let date = match (lang.as_str(), region.as_str()) {
+        ("en", "US" | "PH" | "") => DateOrder::MonthDayYear,
+        ("en", "CA") | ("sv" | "lt", _) => DateOrder::Iso,
+        ("ja" | "zh" | "ko" | "hu", _) => DateOrder::YearSlash,
+        ("nl", _) => DateOrder::DayDash,
+        (
+            "de" | "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "no" | "da"
+            | "tr" | "ro" | "bg" | "et" | "lv" | "sl" | "hr" | "sr" | "is" | "kk" | "rm",
+            _,
+        ) => DateOrder::DayDot,
+        _ => DateOrder::DaySlash,
+    };
+    Conventions {
+        group,
+        decimal,
+        date,
+    }
+}
+
+fn numeric() -> Conventions {
+    static NUMERIC: OnceLock<Conventions> = OnceLock::new();
+    *NUMERIC.get_or_init(|| name("LC_NUMERIC").map_or(PLAIN, |name| conventions(&name)))
+}
+
+fn time() -> Conventions {
+    static TIME: OnceLock<Conventions> = OnceLock::new();
+    *TIME.get_or_init(|| name("LC_TIME").map_or(PLAIN, |name| conventions(&name)))
+}
+
+/// `n` with its digits grouped the local way: 12,345 or 12.345.
+pub fn number(n: u64) -> String {
+    group(&n.to_string(), numeric().group)
+}
+
+/// `value` with `places` decimals and the local decimal mark: 3.5 or 3,5.
+pub fn decimal(value: f64, places: usize) -> String {
+    let conventions = numeric();
+    let text = format!("{:.*}", places, value);
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.as_str()),
+    };
+    match text.split_once('.') {
+        Some((whole, fraction)) => format!(
+            "{}{}{}{}",
+            sign,
+            group(whole, conventions.group),
+            conventions.decimal,
+            fraction
+        ),
+        None => format!("{}{}", sign, group(text, conventions.group)),
+    }
+}
+
+/// The UTC date of `timestamp`, in the local order.
+pub fn date(timestamp: u64) -> String {
+    let (year, month, day) = history::civil(timestamp / DAY);
+    match time().date {
+        DateOrder::Iso => history::date(timestamp),
+        DateOrder::MonthDayYear => format!("{:02}/{:02}/{}", month, day, year),
+        DateOrder::DaySlash => format!("{:02}/{:02}/{}", day, month, year),
+        DateOrder::DayDot => format!("{:02}.{:02}.{}", day, month, year),
+        DateOrder::DayDash => format!("{:02}-{:02}-{}", day, month, year),
+        DateOrder::YearSlash => format!("{}/{:02}/{:02}", year, month, day),
+    }
+}
+
+/// `digits` with `separator` between each group of three, from the right.
+fn group(digits: &str, separator: &str) -> String {
+    if separator.is_empty() {
+        return digits.to_string();
+    }
+    let mut grouped = String::with_capacity(digits.len() * 2);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
@@ -0,0 +1,344 @@
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+use tokio::sync::mpsc;
+
+use crate::{api::Lookup, providers::Settings, text};
+
+/// Senses shown per part of speech in a hover; editors show hovers in a small
+/// popup.
+const SENSES: usize = 3;
+
+/// JSON-RPC's error codes, as the Language Server Protocol and MCP use them.
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// `--lsp`: a language server on stdin and stdout whose only feature is
+/// hovers, the definition of the word under the cursor in any document. The
+/// lookups go through the provider chain and the caches like any other, with
+/// `--lang`, `--provider` and the rest, but aren't kept in the history: an
+/// editor asks about every word the pointer rests on.
+pub async fn run(settings: Settings) -> io::Result<()> {
+    let (sender, mut messages) = mpsc::unbounded_channel();
+    // Reading stdin blocks, so it's kept off the runtime's only thread.
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let message = match read_message(&mut stdin) {
+                Ok(Some(body)) => Ok(body),
+                Ok(None) => break,
+                // Only a message without its length can be skipped.
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => Err(e),
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    break;
+                }
+            };
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut documents: HashMap<String, String> = HashMap::new();
+    let mut shut_down = false;
+    while let Some(message) = messages.recv().await {
+        let message: Value = match message.and_then(|body| Ok(serde_json::from_slice(&body)?)) {
+            Ok(message) => message,
+            Err(e) => {
+                send(&error(Value::Null, PARSE_ERROR, &e.to_string()))?;
+                continue;
+            }
+        };
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let Some(method) = message["method"].as_str() else {
+            // Answers to requests of the server's; it makes none.
+            continue;
+        };
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "hoverProvider": true,
+                    // Documents are sent whole on every change.
+                    "textDocumentSync": { "openClose": true, "change": 1 },
+                },
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    documents.insert(uri.to_string(), text.to_string());
+                }
+                continue;
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    documents.insert(uri.to_string(), text.to_string());
+                }
+                continue;
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    documents.remove(uri);
+                }
+                continue;
+            }
+            "textDocument/hover" => {
+                let document = params["textDocument"]["uri"]
+                    .as_str()
+                    .and_then(|uri| documents.get(uri));
+                let position = &params["position"];
+                let word = match (
+                    document,
+                    position["line"].as_u64(),
+                    position["character"].as_u64(),
+                ) {
+                    (Some(document), Some(line), Some(character)) => {
+                        word_at(document, line as usize, character as usize)
+                    }
+                    _ => None,
+                };
+                match word {
+                    Some(word) => hover(&settings, word).await,
+                    None => Value::Null,
+                }
+            }
+            "shutdown" => {
+                shut_down = true;
+                Value::Null
+            }
+            // A client that exits without `shutdown` first has failed.
+            "exit" => std::process::exit(match shut_down {
+                true => 0,
+                false => 1,
+            }),
+            _ if id.is_none() => continue,
+            other => {
+                let message = format!("'{}' isn't supported", other);
+                send(&error(id.unwrap(), METHOD_NOT_FOUND, &message))?;
+                continue;
+            }
+        };
+        match id {
+            Some(id) => send(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))?,
+            None => send(&error(
+                Value::Null,
+                INVALID_REQUEST,
+                &format!("'{}' is a request and needs an id", method),
+            ))?,
+        }
+    }
+    Ok(())
+}
+
+/// A word of a line and where it is, in the UTF-16 code units LSP positions
+/// count in.
+struct Word {
+    text: String,
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// The word at `character` of `line` of `document`. Only letters and
+/// apostrophes make words, so `snake_case` names are looked up a word at a
+/// time; `camelCase` ones are split where a capital follows a small letter.
+fn word_at(document: &str, line: usize, character: usize) -> Option<Word> {
+    let text = document.lines().nth(line)?;
+    // Each char with the UTF-16 offset it starts at.
+    let mut offset = 0;
+    let chars: Vec<(usize, char)> = text
+        .chars()
+        .map(|c| {
+            let start = offset;
+            offset += c.len_utf16();
+            (start, c)
+        })
+        .collect();
+    let is_letter = |c: char| c.is_alphabetic() || c == '\'';
+    let cursor = chars
+        .iter()
+        .rposition(|&(start, _)| start <= character)
+        .filter(|&i| is_letter(chars[i].1))?;
+    let mut first = cursor;
+    while first > 0
+        && is_letter(chars[first - 1].1)
+        && !(chars[first].1.is_uppercase() && chars[first - 1].1.is_lowercase())
+    {
+        first -= 1;
+    }
+    let mut last = cursor;
+    while last + 1 < chars.len()
+        && is_letter(chars[last + 1].1)
+        && !(chars[last + 1].1.is_uppercase() && chars[last].1.is_lowercase())
+    {
+        last += 1;
+    }
+    // Quotes around the word aren't part of it.
+    while first < last && chars[first].1 == '\'' {
+        first += 1;
+    }
+    while last > first && chars[last].1 == '\'' {
+        last -= 1;
+    }
+    let word: String = chars[first..=last].iter().map(|&(_, c)| c).collect();
+    if word == "'" {
+        return None;
+    }
+    // A capital starting the part of a camelCase name isn't a proper noun's.
+    let split = first > 0 && chars[first - 1].1.is_alphabetic();
+    Some(Word {
+        text: match split {
+            true => word.to_lowercase(),
+            false => word,
+        },
+        line,
+        start: chars[first].0,
+        end: chars[last].0 + chars[last].1.len_utf16(),
+    })
+}
+
+/// The hover for `word`: its entries, or Wikipedia's summary for a name, in
+/// Markdown; null when there's neither, which editors show as no hover.
+async fn hover(settings: &Settings, word: Word) -> Value {
+    let resolved = match settings.resolve(&word.text).await {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("Error: couldn't look up '{}': {}", word.text, e);
+            return Value::Null;
+        }
+    };
+    let markdown = match (resolved.lookup, &resolved.summary) {
+        (Lookup::Found(entries), _) => {
+            let entries = settings.prepare(entries, settings.content_filter);
+            text::markdown(&entries, &settings.lang, SENSES)
+        }
+        (_, Some(summary)) => summary.markdown(),
+        _ => String::new(),
+    };
+    if markdown.is_empty() {
+        return Value::Null;
+    }
+    json!({
+        "contents": { "kind": "markdown", "value": markdown },
+        "range": {
+            "start": { "line": word.line, "character": word.start },
+            "end": { "line": word.line, "character": word.end },
+        },
+    })
+}
+
+/// The body of the next message on `input`, after its `Content-Length`
+/// header; `None` once the client closes it.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length: usize = length.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "a message came without a Content-Length",
+        )
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn send(message: &Value) -> io::Result<()> {
+    let body = serde_json::to_string(message)?;
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    stdout.flush()
+}
+
+/// A JSON-RPC error response to the request `id`.
+pub fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The word at a position, with where it starts and ends.
+    fn at(document: &str, line: usize, character: usize) -> Option<(String, usize, usize)> {
+        let word = word_at(document, line, character)?;
+        assert_eq!(word.line, line);
+        Some((word.text, word.start, word.end))
+    }
+
+    fn word(text: &str, start: usize, end: usize) -> Option<(String, usize, usize)> {
+        Some((text.to_string(), start, end))
+    }
+
+    #[test]
+    fn finds_the_word_under_the_cursor() {
+        assert_eq!(at("hello world", 0, 7), word("world", 6, 11));
+        assert_eq!(at("hello world", 0, 6), word("world", 6, 11));
+        assert_eq!(at("hello world", 0, 5), None);
+        assert_eq!(
+            at("fn main() {}\n  let snake_case = 1;", 1, 12),
+            word("case", 12, 16)
+        );
+        assert_eq!(at("one\ntwo", 2, 0), None);
+    }
+
+    #[test]
+    fn counts_in_utf16() {
+        // The emoji takes two code units, so "naïve" starts at 3.
+        let line = "😀 naïve café";
+        assert_eq!(at(line, 0, 5), word("naïve", 3, 8));
+        assert_eq!(at(line, 0, 10), word("café", 9, 13));
+        assert_eq!(at(line, 0, 1), None);
+    }
+
+    #[test]
+    fn splits_camel_case() {
+        assert_eq!(at("getUserName()", 0, 0), word("get", 0, 3));
+        assert_eq!(at("getUserName()", 0, 4), word("user", 3, 7));
+        assert_eq!(at("getUserName()", 0, 9), word("name", 7, 11));
+        // A capital that starts a sentence is kept.
+        assert_eq!(at("London calling", 0, 2), word("London", 0, 6));
+    }
+
+    #[test]
+    fn leaves_quotes_out() {
+        assert_eq!(at("say 'hello' now", 0, 6), word("hello", 5, 10));
+        assert_eq!(at("say 'hello' now", 0, 4), word("hello", 5, 10));
+        assert_eq!(at("don't", 0, 2), word("don't", 0, 5));
+        assert_eq!(at("a ' b", 0, 2), None);
+    }
+}
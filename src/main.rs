@@ -1,332 +1,557 @@
-use regex::Regex;
-use serde::{Deserialize, Serialize};
-use std::{env, vec};
-use surf;
-use tokio;
-#[derive(Debug, Serialize, Deserialize)]
-struct DefinitionResponse {
-    word: String,
-    phonetic: Option<String>,
-    phonetics: Vec<Phonetic>,
-    meanings: Vec<Meaning>,
-    source_urls: Option<Vec<String>>,
-    origin: Option<String>,
-}
+mod analyze;
+mod api;
+mod audio;
+#[cfg(test)]
+mod bench;
+mod bot;
+mod breaker;
+mod cedict;
+mod cefr;
+mod cli;
+mod cmudict;
+mod cognates;
+mod compare;
+mod concordance;
+mod config;
+mod confusables;
+mod conjugation;
+mod content_filter;
+mod crypto;
+mod daemon;
+mod datamuse;
+mod desktop;
+mod doctor;
+mod domains;
+mod dsl;
+mod etymology;
+mod examples;
+mod export;
+mod family;
+mod followup;
+mod forms;
+mod furigana;
+mod gender;
+mod gloss;
+mod hardwords;
+mod hindi;
+mod history;
+mod homophones;
+mod html;
+mod http;
+mod hunspell;
+mod i18n;
+mod index;
+mod jargon;
+mod jisho;
+mod known;
+mod krdict;
+mod labels;
+mod locale;
+mod lsp;
+mod mcp;
+mod mdict;
+mod merge;
+mod meter;
+mod morphology;
+mod msgpack;
+mod offline;
+mod packs;
+mod providers;
+mod query;
+mod quiz;
+mod regional;
+mod related;
+#[cfg(feature = "interactive")]
+mod repl;
+mod rhymes;
+mod routes;
+mod rules;
+mod schema;
+mod segment;
+mod sexp;
+mod sherlock;
+mod simple;
+mod simple_wiktionary;
+mod slob;
+mod spelling;
+mod srs;
+mod stats;
+mod storage;
+mod syllables;
+mod sync;
+mod tei;
+mod text;
+mod timing;
+mod translations;
+#[cfg(feature = "tui")]
+mod tui;
+mod urban;
+mod wikidata;
+mod wikipedia;
+mod wiktionary;
+mod wordlist;
+mod wotd;
+mod zim;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Phonetic {
-    text: Option<String>,
-    audio: Option<String>,
-    source_url: Option<String>,
-    license: Option<License>,
-}
+use std::{
+    env,
+    ops::ControlFlow,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use api::{DefinitionResponse, Lookup};
+use cli::{Command, Format};
+use config::Config;
+use followup::FollowUp;
+use history::{Bookmarks, History, Session};
+use providers::{Provider, Resolved, Settings};
+use timing::Stopwatch;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct License {
-    name: Option<String>,
-    url: Option<String>,
+// Almost every run is one lookup, and curl does the waiting on a thread of its
+// own, so worker threads would only add to the start-up time.
+#[cfg(not(feature = "blocking"))]
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (started, cli, config) = start();
+    let result = run(started, cli, config).await;
+    // Answers from stale cache entries are printed before the sites reply;
+    // the cache is brought up to date before the process exits.
+    http::settle().await;
+    result
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Meaning {
-    #[serde(rename = "partOfSpeech")]
-    part_of_speech: String,
-    definitions: Vec<Definition>,
-    synonyms: Option<Vec<String>>,
-    antonyms: Option<Vec<String>>,
+/// With `blocking`, a lookup is run on the main thread without starting
+/// tokio at all, the way isahc's synchronous calls wait on curl's thread.
+/// Nothing is left to refresh in the background, so a stale cache entry
+/// waits for the site's answer. Every other command still gets the runtime.
+#[cfg(feature = "blocking")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (started, cli, config) = start();
+    if matches!(cli.command, Command::Define(_)) {
+        return futures_lite::future::block_on(run(started, cli, config));
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let result = run(started, cli, config).await;
+        http::settle().await;
+        result
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Definition {
-    definition: String,
-    example: Option<String>,
-    synonyms: Option<Vec<String>>,
-    antonyms: Option<Vec<String>>,
+/// The parsed command line, on top of the config and its profile, and
+/// when the run started.
+fn start() -> (Instant, cli::Cli, &'static Config) {
+    let started = Instant::now();
+    let args: Vec<String> = env::args().skip(1).collect();
+    config::select_profile(cli::profile(&args));
+    let config = Config::load();
+    if let Some(profile) = config::profile() {
+        if !config.profiles.contains_key(profile) {
+            eprintln!(
+                "Error: there's no profile '{}' in {}",
+                profile,
+                Config::path().display()
+            );
+            std::process::exit(1);
+        }
+    }
+    let cli = match cli::parse(
+        args,
+        Settings::from(config),
+        config.format.unwrap_or(Format::Sherlock),
+    ) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {}\n{}", e, cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+    (started, cli, config)
 }
-impl Definition {
-    fn to_vec(&self)->Vec<String>{
-        let mut collect: Vec<String> = Vec::with_capacity(4);
-        collect.push(self.definition.to_string());
-        if let Some(example) = &self.example {
-            collect.push(example.to_string());
+
+async fn run(
+    started: Instant,
+    cli: cli::Cli,
+    config: &'static Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    i18n::select(cli.settings.ui_lang.as_deref());
+    sherlock::select_protocol(cli.settings.protocol);
+    http::configure(&cli.settings);
+
+    let word_to_define = match cli.command {
+        #[cfg(feature = "tui")]
+        Command::Tui => return tui::run(cli.settings).await,
+        #[cfg(not(feature = "tui"))]
+        Command::Tui => without_feature("tui"),
+        #[cfg(feature = "interactive")]
+        Command::Interactive => return repl::run(cli.settings).await,
+        #[cfg(not(feature = "interactive"))]
+        Command::Interactive => without_feature("interactive"),
+        Command::Lsp => return Ok(lsp::run(cli.settings).await?),
+        Command::MsgpackRpc => return Ok(msgpack::run(cli.settings).await?),
+        Command::Forms(word) => return Ok(forms::run(&word, &cli.settings, cli.format).await?),
+        Command::Compare(first, second) => {
+            return Ok(compare::run(&first, &second, &cli.settings, cli.format).await?)
+        }
+        Command::Family(word) => {
+            family::run(&word, &cli.settings, cli.format);
+            return Ok(());
+        }
+        Command::Cognates(word, languages) => {
+            return Ok(cognates::run(&word, &languages, cli.format).await?)
+        }
+        Command::Translations(word, to) => {
+            return Ok(translations::run(&word, &to, cli.format).await?)
+        }
+        Command::Examples(word, to) => {
+            return Ok(examples::run(&word, &cli.settings, &to, cli.format).await?)
+        }
+        Command::Concordance(word) => {
+            concordance::run(&word, cli.settings.corpus.as_deref(), cli.format)?;
+            return Ok(());
+        }
+        Command::Analyze(path, top) => {
+            return analyze::run(&path, top, &cli.settings, cli.format).await
+        }
+        Command::HardWords(rarer_than) => {
+            return hardwords::run(rarer_than, &cli.settings, cli.format).await
         }
-        if let Some(synonyms) = &self.synonyms {
-            collect.push(synonyms.join(", "));
+        Command::Gloss(sentence) => {
+            return Ok(gloss::run(&sentence, &cli.settings, cli.format).await?)
         }
-        if let Some(antonyms) = &self.antonyms {
-            collect.push(antonyms.join(", "));
+        Command::Etymology(word) => return Ok(etymology::run(&word, cli.format).await?),
+        Command::Homophones(word) => {
+            return Ok(homophones::run(&word, &cli.settings, cli.format).await?)
+        }
+        Command::Rhymes(word) => return Ok(rhymes::run(&word, &cli.settings, cli.format).await?),
+        Command::Meter(line) => return Ok(meter::run(&line, cli.format).await?),
+        Command::Syllables(text, json) => {
+            syllables::run(&text, json, cli.format);
+            return Ok(());
+        }
+        Command::Quiz(answer) => return quiz::run(answer, &cli.settings, cli.format).await,
+        Command::Stats(waybar) => {
+            stats::run(waybar, cli.format);
+            return Ok(());
+        }
+        Command::Export(kind) => {
+            export::run(kind);
+            return Ok(());
+        }
+        Command::Schema(kind) => {
+            schema::run(kind);
+            return Ok(());
+        }
+        Command::Sync => return sync::run(config.sync_dir.as_deref()),
+        Command::Daemon(idle_timeout) => {
+            daemon::run(&cli.settings, &config.refresh, &config.wotd, idle_timeout).await
+        }
+        Command::InstallService => return Ok(daemon::install_service(&cli.settings)?),
+        Command::Mcp => return Ok(mcp::run(cli.settings).await?),
+        Command::Bot => return bot::run(&cli.settings, &config.bot).await,
+        Command::Wotd(true) => return wotd::publish(&cli.settings, &config.wotd).await,
+        Command::Redact(word) => {
+            redact(&word)?;
+            return Ok(());
+        }
+        Command::Encrypt => {
+            encrypt()?;
+            return Ok(());
+        }
+        Command::Doctor(json) => {
+            doctor::run(json, &cli.settings, cli.format).await;
+            return Ok(());
+        }
+        Command::ConfigCheck => {
+            config::run_check();
+            return Ok(());
+        }
+        Command::PruneAudio(max_mb) => {
+            audio::run_prune(max_mb)?;
+            return Ok(());
+        }
+        Command::Idle => {
+            quiz::reminder(&cli.settings);
+            return Ok(());
+        }
+        Command::Dict(action) => return packs::run(action).await,
+        Command::Known(action) => return known::run(action),
+        Command::Wotd(false) => wotd::today(&cli.settings, &config.wotd).await?,
+        Command::Define(word) => word,
+    };
+
+    let mut stopwatch = Stopwatch::new(started, cli.bench);
+    let (settings, word_to_define) = cli.settings.interpret(&word_to_define);
+    // "syn", "ety" and the like are about the word looked up just before.
+    let session = Session::load();
+    if let Some(follow_up) = FollowUp::parse(&word_to_define).filter(|_| !settings.exact) {
+        if let Some(subject) = session.subject(&word_to_define) {
+            return Ok(followup::run(follow_up, subject, &settings, cli.format).await?);
         }
-        collect
     }
-}
+    stopwatch.lap("start-up");
+    let streamed = match cli.format {
+        Format::Plain | Format::Oneline if settings.streams() => {
+            Some(stream(&settings, cli.format))
+        }
+        _ => None,
+    };
+    let resolved = match settings.resolve(&word_to_define).await {
+        Ok(resolved) => resolved,
+        // Sherlock only shows what's printed, so the error gets a tile.
+        Err(e) if cli.format == Format::Sherlock => {
+            eprintln!("Error: {}", e);
+            let response = sherlock::failed(&word_to_define, &e, &settings);
+            response.print();
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    stopwatch.lap("lookup");
+    if matches!(resolved.lookup, Lookup::Found(_)) && !settings.private {
+        // Loaded again, since other keystrokes' lookups may have saved it
+        // while this one waited on the network.
+        if let Err(e) = Session::update(|session| session.record(&resolved.word)) {
+            eprintln!("Warning: couldn't save the session: {}", e);
+        }
+        // Sherlock looks the query up on every keystroke.
+        let recorded = History::update(|history| match cli.format {
+            Format::Sherlock => history.record_typed(&resolved.word),
+            _ => history.record(&resolved.word),
+        });
+        if let Err(e) = recorded {
+            eprintln!("Warning: couldn't save the history: {}", e);
+        }
+        daemon::wake();
+    }
+    stopwatch.lap("history");
+    // Entries streamed are on screen already; only what follows them is left.
+    let streamed = streamed
+        .map(|printed| std::mem::take(&mut *printed.lock().unwrap()))
+        .filter(|printed| !printed.is_empty());
+    match (cli.format, &resolved.summary) {
+        (Format::Plain, None) if streamed.is_some() => {
+            print!("{}", text::plain_end(&streamed.unwrap(), &settings.lang))
+        }
+        (Format::Oneline, None) if streamed.is_some() => {}
+        (Format::Sexp, _) => print_sexp(resolved, &settings),
+        (Format::Plain, Some(summary)) => print!("{}", summary.plain()),
+        (Format::Oneline, Some(summary)) => println!("{}", summary.oneline()),
+        (Format::Plain, None) => {
+            print_plain(&resolved.word, resolved.lookup, &resolved.tried, &settings)
+        }
+        (Format::Oneline, None) => {
+            print_oneline(&resolved.word, resolved.lookup, &resolved.tried, &settings)
+        }
+        (Format::Sherlock, _) => {
+            let sherlock_response = sherlock::respond(resolved, &settings);
+            sherlock_response.print();
+        }
+    }
+    stopwatch.lap("output");
+    stopwatch.report();
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ApiErrorResponse {
-    title: String,
-    message: String,
-    resolution: String,
+    // Return Ok(()) to indicate successful execution.
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SherlockPipeResponse {
-    title: String,
-    content: String,
-    next_content: String,
-    actions: Vec<ApplicationAction>,
+/// `history redact <word>`: removes `word` from the history, the session,
+/// the prompt's line history and the caches that are kept per word.
+fn redact(word: &str) -> std::io::Result<()> {
+    let removed = history::redact(word)?;
+    gloss::forget(word)?;
+    desktop::forget(word)?;
+    http::forget(word)?;
+    match removed {
+        0 => println!(
+            "'{}' wasn't in the history; its cached definitions are gone.",
+            word
+        ),
+        1 => println!("Removed 1 lookup of '{}' and its cached definitions.", word),
+        n => println!(
+            "Removed {} lookups of '{}' and its cached definitions.",
+            n, word
+        ),
+    }
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApplicationAction {
-    name: Option<String>,
-    exec: Option<String>,
-    icon: Option<String>,
-    method: String,
-    exit: bool,
+/// Stops with why a command this build was made without can't run.
+#[cfg(not(all(feature = "tui", feature = "interactive")))]
+fn without_feature(feature: &str) -> ! {
+    eprintln!(
+        "Error: this build has no \"{}\" feature; build it with `--features {}`",
+        feature, feature
+    );
+    std::process::exit(1);
 }
-impl ApplicationAction {
-    fn from_definition(definition: &Definition) -> Self {
-        let name = remove_parens(&definition.definition);
-        let short = definition.to_vec().join("\n");
-        Self {
-            name: Some(name),
-            exec: Some(short),
-            icon: Some(String::from("edit-copy")),
-            method: String::from("copy"),
-            exit: true,
+
+/// `encrypt`: saves the history, bookmarks and session again, so they are
+/// encrypted (or decrypted, with encryption turned off) straight away rather
+/// than the next time they change.
+fn encrypt() -> std::io::Result<()> {
+    History::update(|_| ())?;
+    Bookmarks::update(|_| ())?;
+    Session::update(|_| ())?;
+    match crypto::key() {
+        Some(_) => println!("History and bookmarks are encrypted."),
+        None => {
+            println!("Encryption isn't set up, so history and bookmarks are stored as plain text.")
         }
     }
-}
-fn remove_parens(s: &str) -> String {
-    let re = Regex::new(r"\([^)]*\)\s*").unwrap();
-    let cleaned = re.replace_all(s, "");
-    cleaned
-        .split_once(',')
-        .map_or_else(
-            || cleaned.trim_end_matches('.'),
-            |(first, _)| first.trim_end_matches('.'),
-        )
-        .to_string()
+    Ok(())
 }
 
-impl DefinitionResponse {
-    fn format_content_for_sherlock(&self) -> (String, Vec<ApplicationAction>) {
-        let mut content_buffer = String::new();
-        let mut actions: Vec<ApplicationAction> = Vec::new();
+/// Exit code for a word no provider has, so scripts can tell it from a
+/// failed lookup, which exits with 1.
+const NOT_FOUND: i32 = 2;
 
-        // Iterate through each meaning and format it
-        content_buffer.push_str("<span font_desc=\"monospace\">\n");
-
-        for meaning in &self.meanings {
-            content_buffer.push_str(&format!(
-                "─── <b><i>{}</i></b> ───\n\n",
-                meaning.part_of_speech
-            ));
-            for (i, def) in meaning.definitions.iter().enumerate() {
-                actions.push(ApplicationAction::from_definition(&def));
-                content_buffer.push_str(&format!(" {:>2}. {}\n", i + 1, def.definition));
-                if let Some(example) = &def.example {
-                    content_buffer.push_str(&format!("     Example: \"{}\"\n", example));
-                }
-                if let Some(synonyms) = &def.synonyms {
-                    if !synonyms.is_empty() {
-                        content_buffer
-                            .push_str(&format!("     Synonyms: {}\n", synonyms.join(", ")));
-                    }
-                }
-                if let Some(antonyms) = &def.antonyms {
-                    if !antonyms.is_empty() {
-                        content_buffer
-                            .push_str(&format!("     Antonyms: {}\n", antonyms.join(", ")));
-                    }
-                }
-                content_buffer.push_str("\n");
+/// `--format plain` output: the entries on stdout, anything else on stderr with
+/// a failing exit code.
+fn print_plain(word: &str, lookup: Lookup, tried: &[String], settings: &Settings) {
+    match lookup {
+        Lookup::Found(entries) => {
+            let entries = settings.prepare(entries, settings.content_filter);
+            if entries.is_empty() {
+                print_oneline(word, Lookup::NotFound, tried, settings);
+            } else {
+                print!("{}", text::plain(&entries, &settings.lang));
             }
         }
-        content_buffer.push_str("────────────\n");
-        content_buffer.push_str("</span>");
-
-        (content_buffer, actions)
+        // Errors are reported the same way as for `--oneline`.
+        other => print_oneline(word, other, tried, settings),
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Error: No word provided. Usage: sherlock-dictionary <word_to_define>");
-        std::process::exit(1);
-    }
-
-    let word_to_define = &args[1];
-    let definition_url = format!(
-        "https://api.dictionaryapi.dev/api/v2/entries/en/{}",
-        word_to_define
-    );
-
-    let mut response = surf::get(&definition_url).await?;
-    let status = response.status();
-    let body_text = response.body_string().await?;
+/// Has `--format plain` and `--oneline` output printed entry by entry while
+/// dictionaryapi.dev's answer is read, for [`Settings::streams`]. A word
+/// like "set" has hundreds of senses, so the first are on screen before the
+/// rest are parsed, and `--oneline` stops reading at its line. Returns the
+/// entries printed, prepared, for what [`text::plain`] prints after them.
+fn stream(settings: &Settings, format: Format) -> Arc<Mutex<Vec<DefinitionResponse>>> {
+    let printed = Arc::new(Mutex::new(Vec::new()));
+    let (settings, sink) = (settings.clone(), Arc::clone(&printed));
+    api::stream_to(move |entry| {
+        let entry = DefinitionResponse {
+            provider: Some(Provider::DictionaryApi),
+            ..entry.clone()
+        };
+        let entries = settings.prepare(vec![entry], settings.content_filter);
+        let mut printed = sink.lock().unwrap();
+        if format == Format::Oneline {
+            let Some(line) = text::oneline(&entries, &settings.lang) else {
+                return ControlFlow::Continue(());
+            };
+            println!("{}", line);
+            printed.extend(entries);
+            return ControlFlow::Break(());
+        }
+        for entry in &entries {
+            print!("{}", text::plain_entry(entry, &settings.lang));
+        }
+        printed.extend(entries);
+        ControlFlow::Continue(())
+    });
+    printed
+}
 
-    if status.is_success() {
-        // Attempt to parse the response as a vector of DefinitionResponse (successful case).
-        match serde_json::from_str::<Vec<DefinitionResponse>>(&body_text) {
-            Ok(definitions) => {
-                if definitions.is_empty() {
-                    eprintln!("No definition found for '{}'.", word_to_define);
-                    // Output a simplified "No definition found" for Sherlock
-                    let sherlock_error_response = SherlockPipeResponse {
-                        title: "No definition found".to_string(),
-                        content: String::new(), // Empty content for a concise message
-                        next_content: String::new(),
-                        actions: vec![],
-                    };
-                    println!(
-                        "{}",
-                        serde_json::to_string(&sherlock_error_response).unwrap()
-                    );
-                } else {
-                    // Consolidate all definitions into a single content string
-                    let mut actions: Vec<ApplicationAction> = Vec::new();
-                    let mut all_definitions_content = String::new();
-                    for def_response in definitions {
-                        let (content, acts) = def_response.format_content_for_sherlock();
-                        all_definitions_content.push_str(&content);
-                        actions.extend(acts);
-                    }
+/// `--format sexp` output: a plist with the entries, or Wikipedia's summary,
+/// for Emacs to `read`. A word that isn't in the dictionaries gets one with
+/// the forms tried and the closest spellings instead, and exits with
+/// [`NOT_FOUND`]; failed lookups are reported like `--oneline`'s.
+fn print_sexp(resolved: Resolved, settings: &Settings) {
+    use sexp::Sexp;
+    let entries = match resolved.lookup {
+        Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+        Lookup::NotFound => Vec::new(),
+        other => return print_oneline(&resolved.word, other, &resolved.tried, settings),
+    };
+    let found = !entries.is_empty() || resolved.summary.is_some();
+    let suggestions = match found {
+        true => Vec::new(),
+        false => spelling::suggest(&resolved.word, settings, 5),
+    };
+    let plist = Sexp::plist(vec![
+        ("query", Some(Sexp::string(&resolved.query))),
+        ("word", Some(Sexp::string(&resolved.word))),
+        (
+            "entries",
+            Some(&entries)
+                .filter(|entries| !entries.is_empty())
+                .map(|entries| sexp::entries(entries)),
+        ),
+        ("summary", resolved.summary.as_ref().map(sexp::summary)),
+        (
+            "tried",
+            Some(&resolved.tried)
+                .filter(|tried| !tried.is_empty())
+                .map(Sexp::strings),
+        ),
+        (
+            "suggestions",
+            Some(&suggestions)
+                .filter(|suggestions| !suggestions.is_empty())
+                .map(Sexp::strings),
+        ),
+    ]);
+    println!("{}", plist);
+    if !found {
+        std::process::exit(NOT_FOUND);
+    }
+}
 
-                    // Create a single SherlockPipeResponse with all content
-                    let sherlock_response = SherlockPipeResponse {
-                        title: format!(r#"Definition of "{}""#, word_to_define),
-                        content: all_definitions_content.clone(),
-                        next_content: all_definitions_content, // Populate if Sherlock supports pagination
-                        actions,
-                    };
-                    println!("{}", serde_json::to_string(&sherlock_response).unwrap());
-                }
-            }
-            Err(e) => {
-                // If parsing as Vec<DefinitionResponse> failed, it might be an error object
-                // even if the status was 200 OK (less common, but possible for "not found"
-                // if the API returns a 200 with an error payload).
-                match serde_json::from_str::<ApiErrorResponse>(&body_text) {
-                    Ok(api_error) => {
-                        // Check if the API error indicates "No Definitions Found"
-                        if api_error.title == "No Definitions Found" {
-                            eprintln!("No definition found for '{}'.", word_to_define);
-                            let sherlock_error_response = SherlockPipeResponse {
-                                title: "No definition found".to_string(),
-                                content: String::new(), // Empty content for a concise message
-                                next_content: String::new(),
-                                actions: vec![],
-                            };
-                            println!(
-                                "{}",
-                                serde_json::to_string(&sherlock_error_response).unwrap()
-                            );
-                        } else {
-                            // For other API errors, output the detailed message
-                            eprintln!("API Error: {}", api_error.title);
-                            eprintln!("Message: {}", api_error.message);
-                            eprintln!("Resolution: {}", api_error.resolution);
-                            let sherlock_error_response = SherlockPipeResponse {
-                                title: format!("API Error: {}", api_error.title),
-                                content: format!(
-                                    "Message: {}\nResolution: {}",
-                                    api_error.message, api_error.resolution
-                                ),
-                                next_content: String::new(),
-                                actions: vec![],
-                            };
-                            println!(
-                                "{}",
-                                serde_json::to_string(&sherlock_error_response).unwrap()
-                            );
-                        }
-                    }
-                    Err(_) => {
-                        // If it's neither a definition array nor a known error object,
-                        // print the raw body and the original parsing error for debugging.
-                        eprintln!("Failed to parse API response for '{}'.", word_to_define);
-                        eprintln!("Raw response body: {}", body_text);
-                        eprintln!("Parsing error: {}", e);
-                        // Output generic parsing error as JSON for Sherlock
-                        let sherlock_error_response = SherlockPipeResponse {
-                            title: format!("Parsing Error for '{}'", word_to_define),
-                            content: format!(
-                                "Failed to parse API response. Raw body: {}",
-                                body_text
-                            ),
-                            next_content: String::new(),
-                            actions: vec![],
-                        };
-                        println!(
-                            "{}",
-                            serde_json::to_string(&sherlock_error_response).unwrap()
-                        );
-                    }
-                }
-            }
+/// `--oneline` output: the line on stdout, anything else on stderr with a
+/// failing exit code, [`NOT_FOUND`] for a word that isn't in the dictionaries.
+/// `tried` are the other forms looked up for it.
+fn print_oneline(word: &str, lookup: Lookup, tried: &[String], settings: &Settings) {
+    let line = match &lookup {
+        Lookup::Found(entries) => text::oneline(
+            &settings.prepare(entries.clone(), settings.content_filter),
+            &settings.lang,
+        ),
+        _ => None,
+    };
+    match (line, lookup) {
+        (Some(line), _) => println!("{}", line),
+        (None, Lookup::ApiError { status, error }) => {
+            let status = status.to_string();
+            eprintln!(
+                "{}",
+                i18n::tr(
+                    "api-error-status",
+                    &[("status", &status), ("title", &error.title)]
+                )
+            );
+            std::process::exit(1);
         }
-    } else {
-        // Handle non-success HTTP status codes (e.g., 404 Not Found, 500 Internal Server Error).
-        // In these cases, the body is often an error object.
-        match serde_json::from_str::<ApiErrorResponse>(&body_text) {
-            Ok(api_error) => {
-                // Check if the API error indicates "No Definitions Found"
-                if api_error.title == "No Definitions Found" {
-                    eprintln!("No definition found for '{}'.", word_to_define);
-                    let sherlock_error_response = SherlockPipeResponse {
-                        title: "No definition found".to_string(),
-                        content: String::new(), // Empty content for a concise message
-                        next_content: String::new(),
-                        actions: vec![],
-                    };
-                    println!(
-                        "{}",
-                        serde_json::to_string(&sherlock_error_response).unwrap()
-                    );
-                } else {
-                    // For other API errors, output the detailed message
-                    eprintln!("API Error (Status {}): {}", status, api_error.title);
-                    eprintln!("Message: {}", api_error.message);
-                    eprintln!("Resolution: {}", api_error.resolution);
-                    let sherlock_error_response = SherlockPipeResponse {
-                        title: format!("API Error (Status {}): {}", status, api_error.title),
-                        content: format!(
-                            "Message: {}\nResolution: {}",
-                            api_error.message, api_error.resolution
-                        ),
-                        next_content: String::new(),
-                        actions: vec![],
-                    };
-                    println!(
-                        "{}",
-                        serde_json::to_string(&sherlock_error_response).unwrap()
-                    );
-                }
-            }
-            Err(e) => {
-                // If the status is not successful, and we can't parse it into our
-                // known error format, print a generic error with the raw body.
-                eprintln!("Error fetching definition for '{}'.", word_to_define);
-                eprintln!("HTTP Status: {}", status);
-                eprintln!("Failed to parse error response: {}", e);
-                eprintln!("Raw response body: {}", body_text);
-                // Output generic HTTP error as JSON for Sherlock
-                let sherlock_error_response = SherlockPipeResponse {
-                    title: format!("HTTP Error (Status {}) for '{}'", status, word_to_define),
-                    content: format!("Failed to parse error response. Raw body: {}", body_text),
-                    next_content: String::new(),
-                    actions: vec![],
-                };
-                println!(
-                    "{}",
-                    serde_json::to_string(&sherlock_error_response).unwrap()
+        (None, Lookup::ParseError { error, .. }) => {
+            eprintln!("Failed to parse API response for '{}': {}", word, error);
+            std::process::exit(1);
+        }
+        (None, _) => {
+            let also = match tried {
+                [] => String::new(),
+                tried => format!(
+                    " ({})",
+                    i18n::tr("also-tried", &[("words", &tried.join(", "))])
+                ),
+            };
+            let not_found = i18n::tr("not-found-for", &[("word", word)]);
+            let suggestions = spelling::suggest(word, settings, 5);
+            if suggestions.is_empty() {
+                eprintln!("{}{}.", not_found, also);
+            } else {
+                eprintln!(
+                    "{}{}. {}",
+                    not_found,
+                    also,
+                    i18n::tr("did-you-mean", &[("words", &suggestions.join(", "))])
                 );
             }
+            std::process::exit(NOT_FOUND);
         }
     }
-
-    // Return Ok(()) to indicate successful execution.
-    Ok(())
 }
@@ -0,0 +1,227 @@
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use tokio::sync::mpsc;
+
+use crate::{
+    api::Lookup,
+    etymology, followup,
+    lsp::{self, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR},
+    providers::Settings,
+    spelling, text,
+};
+
+/// The protocol revisions the server speaks, newest first; a client asking
+/// for another one is offered the newest.
+const VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// The tools, what they're for as the assistant reads it, and whether they
+/// take a `lang`.
+const TOOLS: &[(&str, &str, bool)] = &[
+    (
+        "lookup",
+        "Look up a word's definitions in the user's dictionaries: pronunciation, \
+         senses by part of speech, examples, synonyms and origin.",
+        true,
+    ),
+    (
+        "synonyms",
+        "List a word's synonyms and antonyms, by part of speech.",
+        true,
+    ),
+    (
+        "etymology",
+        "Trace where an English word came from, as the forms it descends from, \
+         oldest first.",
+        false,
+    ),
+];
+
+/// `mcp`: a Model Context Protocol server on stdin and stdout, so a local
+/// assistant calls the dictionary as tools instead of making definitions up.
+/// The lookups go through the provider chain and the caches, `--cached` and
+/// offline dictionaries included, and aren't kept in the history.
+pub async fn run(settings: Settings) -> io::Result<()> {
+    let (sender, mut lines) = mpsc::unbounded_channel();
+    // Reading stdin blocks, so it's kept off the runtime's only thread.
+    std::thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Messages are a line of JSON each.
+    while let Some(line) = lines.recv().await {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                send(&lsp::error(Value::Null, PARSE_ERROR, &e.to_string()))?;
+                continue;
+            }
+        };
+        // Notifications, "notifications/initialized" among them, and
+        // answers to requests, which the server makes none of, need nothing.
+        let (Some(id), Some(method)) = (message.get("id").cloned(), message["method"].as_str())
+        else {
+            continue;
+        };
+        let params = &message["params"];
+        let result = match method {
+            "initialize" => {
+                let asked = params["protocolVersion"].as_str();
+                let version = VERSIONS
+                    .iter()
+                    .find(|version| Some(**version) == asked)
+                    .unwrap_or(&VERSIONS[0]);
+                json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                })
+            }
+            "ping" => json!({}),
+            "tools/list" => json!({ "tools": TOOLS.iter().map(tool).collect::<Vec<_>>() }),
+            "tools/call" => {
+                let name = params["name"].as_str().unwrap_or_default();
+                let word = params["arguments"]["word"]
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|word| !word.is_empty());
+                let (Some(_), Some(word)) = (TOOLS.iter().find(|(tool, ..)| *tool == name), word)
+                else {
+                    let message = match word {
+                        Some(_) => format!("There's no tool '{}'", name),
+                        None => format!("'{}' needs a word", name),
+                    };
+                    send(&lsp::error(id, INVALID_PARAMS, &message))?;
+                    continue;
+                };
+                let mut settings = settings.clone();
+                if let Some(lang) = params["arguments"]["lang"].as_str() {
+                    settings.lang = lang.to_string();
+                }
+                let answer = match name {
+                    "lookup" => lookup(&settings, word).await,
+                    "synonyms" => synonyms(&settings, word).await,
+                    _ => etymology(word).await,
+                };
+                // A failed lookup is the tool's to report, so the assistant
+                // sees why.
+                let (text, failed) = match answer {
+                    Ok(text) => (text, false),
+                    Err(e) => (format!("The lookup of '{}' failed: {}", word, e), true),
+                };
+                json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": failed,
+                })
+            }
+            other => {
+                let message = format!("'{}' isn't supported", other);
+                send(&lsp::error(id, METHOD_NOT_FOUND, &message))?;
+                continue;
+            }
+        };
+        send(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))?;
+    }
+    Ok(())
+}
+
+/// A tool as `tools/list` describes it.
+fn tool(&(name, description, lang): &(&str, &str, bool)) -> Value {
+    let mut properties = json!({
+        "word": { "type": "string", "description": "The word or phrase" },
+    });
+    if lang {
+        properties["lang"] = json!({
+            "type": "string",
+            "description": "The dictionary's language code, such as en or fr",
+        });
+    }
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": ["word"],
+        },
+    })
+}
+
+/// The entries for `word` as `--format plain` prints them, or Wikipedia's
+/// summary for a name.
+async fn lookup(settings: &Settings, word: &str) -> Result<String, surf::Error> {
+    let resolved = settings.resolve(word).await?;
+    let entries = match resolved.lookup {
+        Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+        _ => Vec::new(),
+    };
+    Ok(match (entries.is_empty(), &resolved.summary) {
+        (false, _) => text::plain(&entries, &settings.lang),
+        (true, Some(summary)) => summary.plain(),
+        (true, None) => not_found(settings, word),
+    })
+}
+
+async fn synonyms(settings: &Settings, word: &str) -> Result<String, surf::Error> {
+    let resolved = settings.resolve(word).await?;
+    let entries = match resolved.lookup {
+        Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+        _ => return Ok(not_found(settings, word)),
+    };
+    let mut out = String::new();
+    for (synonyms, kind) in [(true, "Synonyms"), (false, "Antonyms")] {
+        for (pos, words) in followup::groups(synonyms, &entries) {
+            out.push_str(&format!("{} ({}): {}\n", kind, pos, words.join(", ")));
+        }
+    }
+    Ok(match out.is_empty() {
+        true => format!(
+            "The dictionaries list no synonyms or antonyms of '{}'.",
+            word
+        ),
+        false => out,
+    })
+}
+
+async fn etymology(word: &str) -> Result<String, surf::Error> {
+    let lineages = etymology::lineages(word).await?;
+    Ok(match lineages.is_empty() {
+        true => format!(
+            "Wiktionary has no etymology for the English word '{}'.",
+            word
+        ),
+        false => {
+            let trees: Vec<String> = lineages.iter().map(|l| etymology::tree(l)).collect();
+            trees.join("\n")
+        }
+    })
+}
+
+/// Says so, with the closest spellings: an assistant's typo is often why.
+fn not_found(settings: &Settings, word: &str) -> String {
+    let suggestions = spelling::suggest(word, settings, 5);
+    match suggestions.is_empty() {
+        true => format!("'{}' isn't in the dictionaries.", word),
+        false => format!(
+            "'{}' isn't in the dictionaries. Close spellings: {}.",
+            word,
+            suggestions.join(", ")
+        ),
+    }
+}
+
+fn send(message: &Value) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", serde_json::to_string(message)?)?;
+    stdout.flush()
+}
@@ -0,0 +1,586 @@
+use regex::Regex;
+#[cfg(feature = "compression")]
+use std::io::Read;
+use std::{io, path::Path, sync::LazyLock};
+
+use memmap2::Mmap;
+
+use crate::storage;
+
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(\w+)="([^"]*)""#).unwrap());
+
+/// An MDict `.mdx` dictionary or `.mdd` resource file. Keys are stored in
+/// compressed blocks listed in a small block index, so a lookup only
+/// decompresses the key block and record block it needs.
+pub struct Mdict {
+    bytes: Mmap,
+    /// 8-byte numbers from format version 2 on, 4-byte before.
+    wide: bool,
+    utf16: bool,
+    key_blocks: Vec<KeyBlock>,
+    record_blocks: Vec<RecordBlock>,
+}
+
+struct KeyBlock {
+    /// Where the compressed block starts and how long it is.
+    offset: usize,
+    len: usize,
+    first: String,
+    last: String,
+}
+
+struct RecordBlock {
+    offset: usize,
+    len: usize,
+    /// Where this block starts in the concatenated decompressed records.
+    start: u64,
+    size: u64,
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    wide: bool,
+}
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len());
+        let Some(end) = end else {
+            return Err(invalid("MDict file is truncated"));
+        };
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// A count or size, whose width depends on the format version.
+    fn number(&mut self) -> io::Result<u64> {
+        if self.wide {
+            Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+        } else {
+            self.u32().map(u64::from)
+        }
+    }
+}
+
+impl Mdict {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let bytes = storage::map_file(path)?;
+        let mut cursor = Cursor {
+            bytes: &bytes,
+            pos: 0,
+            wide: false,
+        };
+
+        // The header is a single UTF-16 XML element of attributes.
+        let header_len = cursor.u32()? as usize;
+        let header = utf16(cursor.take(header_len)?);
+        cursor.take(4)?; // checksum
+        let attribute = |name: &str| {
+            ATTRIBUTE
+                .captures_iter(&header)
+                .find(|c| &c[1] == name)
+                .map(|c| c[2].to_string())
+        };
+        let version: f32 = attribute("GeneratedByEngineVersion")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1.0);
+        let encrypted: u32 = attribute("Encrypted")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        // Bit 0 encrypts the keys for one registered user, with a key only
+        // they have; bit 1 just scrambles the key index, with a key in the file.
+        if encrypted & 1 != 0 {
+            return Err(unsupported(
+                "MDict files encrypted for a registered user aren't supported",
+            ));
+        }
+        // Resource files have no encoding attribute and always use UTF-16 keys.
+        let is_mdd = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("mdd"));
+        let utf16_keys = is_mdd
+            || attribute("Encoding").is_some_and(|e| {
+                e.eq_ignore_ascii_case("utf-16") || e.eq_ignore_ascii_case("utf16")
+            });
+        let wide = version >= 2.0;
+        cursor.wide = wide;
+
+        let key_block_count = cursor.number()? as usize;
+        let _entry_count = cursor.number()?;
+        if wide {
+            cursor.number()?; // decompressed size of the block index
+        }
+        let index_len = cursor.number()? as usize;
+        let key_blocks_len = cursor.number()? as usize;
+        if wide {
+            cursor.take(4)?; // checksum
+        }
+        let index = cursor.take(index_len)?;
+        let index = if wide {
+            if index.get(..4) != Some(&[2, 0, 0, 0]) {
+                return Err(invalid("MDict key index isn't zlib-compressed"));
+            }
+            match encrypted & 2 != 0 {
+                true => decompress(&unscramble(index))?,
+                false => decompress(index)?,
+            }
+        } else {
+            index.to_vec()
+        };
+        let key_blocks_start = cursor.pos;
+        cursor.take(key_blocks_len)?;
+
+        let mut key_blocks = Vec::with_capacity(key_block_count);
+        let mut index = Cursor {
+            bytes: &index,
+            pos: 0,
+            wide,
+        };
+        let mut offset = key_blocks_start;
+        for _ in 0..key_block_count {
+            index.number()?; // entries in the block
+            let first = index_key(&mut index, wide, utf16_keys)?;
+            let last = index_key(&mut index, wide, utf16_keys)?;
+            let len = index.number()? as usize;
+            index.number()?; // decompressed size
+            key_blocks.push(KeyBlock {
+                offset,
+                len,
+                first,
+                last,
+            });
+            offset = offset.saturating_add(len);
+        }
+
+        let record_block_count = cursor.number()? as usize;
+        let _entry_count = cursor.number()?;
+        let _info_len = cursor.number()?;
+        let _records_len = cursor.number()?;
+        let mut sizes = Vec::with_capacity(record_block_count);
+        for _ in 0..record_block_count {
+            sizes.push((cursor.number()? as usize, cursor.number()?));
+        }
+        let mut record_blocks = Vec::with_capacity(record_block_count);
+        let (mut offset, mut start) = (cursor.pos, 0);
+        for (len, size) in sizes {
+            record_blocks.push(RecordBlock {
+                offset,
+                len,
+                start,
+                size,
+            });
+            offset = offset.saturating_add(len);
+            start += size;
+        }
+
+        Ok(Self {
+            bytes,
+            wide,
+            utf16: utf16_keys,
+            key_blocks,
+            record_blocks,
+        })
+    }
+
+    /// The records stored under `key`, ignoring case. MDX records are HTML
+    /// articles; MDD records are the raw bytes of a resource.
+    pub fn lookup(&self, key: &str) -> io::Result<Vec<Vec<u8>>> {
+        let target = sort_key(key);
+        let wanted = key.to_lowercase();
+        // Key blocks are in sorted order: start at the first one that could
+        // hold the key, and keep going while blocks can still contain it.
+        let first = self
+            .key_blocks
+            .partition_point(|block| sort_key(&block.last) < target);
+        let mut records = Vec::new();
+        for (i, block) in self.key_blocks.iter().enumerate().skip(first) {
+            if sort_key(&block.first) > target {
+                break;
+            }
+            let keys = self.keys(block)?;
+            for (j, (offset, found)) in keys.iter().enumerate() {
+                if found.to_lowercase() != wanted {
+                    continue;
+                }
+                let end = match keys.get(j + 1) {
+                    Some((next, _)) => Some(*next),
+                    None => match self.key_blocks.get(i + 1) {
+                        Some(next) => self.keys(next)?.first().map(|(offset, _)| *offset),
+                        None => None,
+                    },
+                };
+                records.push(self.record(*offset, end)?);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Every key, as stored.
+    pub fn headwords(&self) -> io::Result<Vec<String>> {
+        let mut headwords = Vec::new();
+        for block in &self.key_blocks {
+            headwords.extend(self.keys(block)?.into_iter().map(|(_, key)| key));
+        }
+        Ok(headwords)
+    }
+
+    /// The (record offset, key) pairs in a key block.
+    fn keys(&self, block: &KeyBlock) -> io::Result<Vec<(u64, String)>> {
+        let data = decompress(self.block(block.offset, block.len)?)?;
+        let mut cursor = Cursor {
+            bytes: &data,
+            pos: 0,
+            wide: self.wide,
+        };
+        let width = if self.utf16 { 2 } else { 1 };
+        let mut keys = Vec::new();
+        while cursor.pos < data.len() {
+            let offset = cursor.number()?;
+            let start = cursor.pos;
+            // Keys are NUL-terminated, with a two-byte NUL for UTF-16.
+            let mut end = start;
+            while end + width <= data.len() && data[end..end + width].iter().any(|&b| b != 0) {
+                end += width;
+            }
+            let key = match self.utf16 {
+                true => utf16(&data[start..end]),
+                false => String::from_utf8_lossy(&data[start..end]).into_owned(),
+            };
+            cursor.pos = (end + width).min(data.len());
+            keys.push((offset, key));
+        }
+        Ok(keys)
+    }
+
+    /// The `len` bytes of a block at `offset`, which a truncated file doesn't
+    /// have.
+    fn block(&self, offset: usize, len: usize) -> io::Result<&[u8]> {
+        self.bytes
+            .get(offset..offset.saturating_add(len))
+            .ok_or_else(|| invalid("MDict file is truncated"))
+    }
+
+    /// The record starting at `start` in the decompressed record stream and
+    /// running to `end`, or to the end of its block when it's the last one.
+    fn record(&self, start: u64, end: Option<u64>) -> io::Result<Vec<u8>> {
+        let i = self
+            .record_blocks
+            .partition_point(|block| block.start + block.size <= start);
+        let block = self
+            .record_blocks
+            .get(i)
+            .ok_or_else(|| invalid("MDict key points past the records"))?;
+        let data = decompress(self.block(block.offset, block.len)?)?;
+        let from = (start - block.start) as usize;
+        let to = end
+            .map(|end| (end.saturating_sub(block.start) as usize).min(data.len()))
+            .unwrap_or(data.len())
+            .max(from);
+        data.get(from..to)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| invalid("MDict record is out of bounds"))
+    }
+}
+
+/// A first or last key from the block index. Lengths count characters, and
+/// version 2 adds a terminator after each key.
+fn index_key(cursor: &mut Cursor, wide: bool, utf16_keys: bool) -> io::Result<String> {
+    let chars = if wide {
+        cursor.u16()? as usize
+    } else {
+        cursor.u8()? as usize
+    };
+    let terminator = usize::from(wide);
+    let width = if utf16_keys { 2 } else { 1 };
+    let bytes = cursor.take((chars + terminator) * width)?;
+    let bytes = &bytes[..chars * width];
+    Ok(match utf16_keys {
+        true => utf16(bytes),
+        false => String::from_utf8_lossy(bytes).into_owned(),
+    })
+}
+
+/// Unpacks a block: a 4-byte compression type and a checksum, then the data.
+fn decompress(block: &[u8]) -> io::Result<Vec<u8>> {
+    if block.len() < 8 {
+        return Err(invalid("MDict block is truncated"));
+    }
+    let mut out = Vec::new();
+    match block[0] {
+        0 => out.extend_from_slice(&block[8..]),
+        #[cfg(feature = "compression")]
+        2 => {
+            flate2::read::ZlibDecoder::new(&block[8..]).read_to_end(&mut out)?;
+        }
+        #[cfg(not(feature = "compression"))]
+        2 => return Err(crate::offline::without_compression("this MDict's blocks")),
+        1 => return Err(unsupported("LZO-compressed MDict files aren't supported")),
+        _ => return Err(invalid("unknown MDict block compression")),
+    }
+    Ok(out)
+}
+
+/// Undoes the scrambling of the key index in files with `Encrypted="2"`: the
+/// data after the compression type and checksum is XORed, byte by byte, with
+/// a key hashed from the checksum.
+fn unscramble(block: &[u8]) -> Vec<u8> {
+    let mut out = block.to_vec();
+    let Some(checksum) = block.get(4..8) else {
+        return out;
+    };
+    let mut seed = checksum.to_vec();
+    seed.extend_from_slice(&0x3695u32.to_le_bytes());
+    let key = ripemd128(&seed);
+    let mut previous = 0x36;
+    for (i, byte) in out.iter_mut().skip(8).enumerate() {
+        let plain = byte.rotate_left(4) ^ previous ^ (i as u8) ^ key[i % key.len()];
+        previous = *byte;
+        *byte = plain;
+    }
+    out
+}
+
+/// RIPEMD-128, which MDict hashes the key of a scrambled index with.
+fn ripemd128(data: &[u8]) -> [u8; 16] {
+    const R: [[usize; 16]; 4] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8],
+        [3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12],
+        [1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2],
+    ];
+    const R2: [[usize; 16]; 4] = [
+        [5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12],
+        [6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2],
+        [15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13],
+        [8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14],
+    ];
+    const S: [[u32; 16]; 4] = [
+        [11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8],
+        [7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12],
+        [11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5],
+        [11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12],
+    ];
+    const S2: [[u32; 16]; 4] = [
+        [8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6],
+        [9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11],
+        [9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5],
+        [15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8],
+    ];
+    const K: [u32; 4] = [0, 0x5a82_7999, 0x6ed9_eba1, 0x8f1b_bcdc];
+    const K2: [u32; 4] = [0x50a2_8be6, 0x5c4d_d124, 0x6d70_3ef3, 0];
+    let f = |round: usize, x: u32, y: u32, z: u32| match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        _ => (x & z) | (y & !z),
+    };
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_le_bytes());
+
+    let mut h: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+    for chunk in message.chunks_exact(64) {
+        let x: Vec<u32> = chunk
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = h;
+        let [mut a2, mut b2, mut c2, mut d2] = h;
+        for round in 0..4 {
+            for step in 0..16 {
+                let t = a
+                    .wrapping_add(f(round, b, c, d))
+                    .wrapping_add(x[R[round][step]])
+                    .wrapping_add(K[round])
+                    .rotate_left(S[round][step]);
+                (a, d, c, b) = (d, c, b, t);
+                // The parallel line runs the rounds' functions in reverse.
+                let t = a2
+                    .wrapping_add(f(3 - round, b2, c2, d2))
+                    .wrapping_add(x[R2[round][step]])
+                    .wrapping_add(K2[round])
+                    .rotate_left(S2[round][step]);
+                (a2, d2, c2, b2) = (d2, c2, b2, t);
+            }
+        }
+        h = [
+            h[1].wrapping_add(c).wrapping_add(d2),
+            h[2].wrapping_add(d).wrapping_add(a2),
+            h[3].wrapping_add(a).wrapping_add(b2),
+            h[0].wrapping_add(b).wrapping_add(c2),
+        ];
+    }
+    let mut out = [0; 16];
+    for (bytes, word) in out.chunks_exact_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+/// Keys are sorted case-insensitively with punctuation and spaces stripped.
+fn sort_key(key: &str) -> String {
+    key.chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn unsupported(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn hashes_like_ripemd128() {
+        assert_eq!(hex(&ripemd128(b"")), "cdf26213a150dc3ecb610f18f6b38b46");
+        assert_eq!(hex(&ripemd128(b"abc")), "c14a12199c66e4ba84636b0f69144c77");
+        assert_eq!(
+            hex(&ripemd128(b"message digest")),
+            "9e327b3d6e523062afc1132d7df9d1b8"
+        );
+        assert_eq!(
+            hex(&ripemd128("1234567890".repeat(8).as_bytes())),
+            "3f45ef194732c2dbb2c4a2c769795fa3"
+        );
+    }
+
+    /// A version 2 `.mdx` with one key block and one record block, its key
+    /// index scrambled when `encrypted` is 2.
+    #[cfg(feature = "compression")]
+    fn mdx(entries: &[(&str, &str)], encrypted: u32) -> Vec<u8> {
+        use std::io::Write;
+
+        let number = |n: usize| (n as u64).to_be_bytes();
+        let block = |data: &[u8]| [&[0u8; 8][..], data].concat();
+
+        let (mut keys, mut records) = (Vec::new(), Vec::new());
+        for (key, record) in entries {
+            keys.extend_from_slice(&number(records.len()));
+            keys.extend_from_slice(key.as_bytes());
+            keys.push(0);
+            records.extend_from_slice(record.as_bytes());
+        }
+        let (keys, records) = (block(&keys), block(&records));
+
+        let mut index = number(entries.len()).to_vec();
+        for key in [entries[0].0, entries[entries.len() - 1].0] {
+            index.extend_from_slice(&(key.len() as u16).to_be_bytes());
+            index.extend_from_slice(key.as_bytes());
+            index.push(0);
+        }
+        index.extend_from_slice(&number(keys.len()));
+        index.extend_from_slice(&number(keys.len() - 8));
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(&index).unwrap();
+        let mut index = [&[2, 0, 0, 0, 1, 2, 3, 4][..], &zlib.finish().unwrap()].concat();
+        if encrypted == 2 {
+            // The inverse of `unscramble`.
+            let key = ripemd128(&[1, 2, 3, 4, 0x95, 0x36, 0, 0]);
+            let mut previous = 0x36;
+            for (i, byte) in index.iter_mut().skip(8).enumerate() {
+                *byte = (*byte ^ previous ^ (i as u8) ^ key[i % key.len()]).rotate_left(4);
+                previous = *byte;
+            }
+        }
+
+        let header = format!(
+            r#"<Dictionary GeneratedByEngineVersion="2.0" Encrypted="{}" Encoding="UTF-8"/>"#,
+            encrypted
+        );
+        let header: Vec<u8> = header.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut file = (header.len() as u32).to_be_bytes().to_vec();
+        file.extend_from_slice(&header);
+        file.extend_from_slice(&[0; 4]);
+        for n in [1, entries.len(), 0, index.len(), keys.len()] {
+            file.extend_from_slice(&number(n));
+        }
+        file.extend_from_slice(&[0; 4]);
+        file.extend_from_slice(&index);
+        file.extend_from_slice(&keys);
+        for n in [
+            1,
+            entries.len(),
+            16,
+            records.len(),
+            records.len(),
+            records.len() - 8,
+        ] {
+            file.extend_from_slice(&number(n));
+        }
+        file.extend_from_slice(&records);
+        file
+    }
+
+    #[cfg(feature = "compression")]
+    fn open(name: &str, bytes: &[u8]) -> io::Result<Mdict> {
+        let path = std::env::temp_dir().join(format!("sherlock-mdict-{}.mdx", name));
+        std::fs::write(&path, bytes).unwrap();
+        let opened = Mdict::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        opened
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn reads_scrambled_key_indexes() {
+        let entries = [("cat", "<b>cat</b>"), ("dog", "<b>dog</b>")];
+        for encrypted in [0, 2] {
+            let mdx = open(&format!("plain-{}", encrypted), &mdx(&entries, encrypted)).unwrap();
+            assert_eq!(mdx.headwords().unwrap(), ["cat", "dog"]);
+            assert_eq!(mdx.lookup("Dog").unwrap(), [b"<b>dog</b>".to_vec()]);
+        }
+        assert!(open("registered", &mdx(&entries, 1)).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn truncated_files_fail_instead_of_panicking() {
+        let full = mdx(&[("cat", "<b>cat</b>")], 0);
+        for len in [full.len() - 4, full.len() / 2, 10, 0] {
+            if let Ok(mdx) = open(&format!("truncated-{}", len), &full[..len]) {
+                assert!(mdx.lookup("cat").is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+
+use crate::api::{Definition, DefinitionResponse, Meaning};
+
+/// How the answers of a provider chain are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergePolicy {
+    /// Only the first provider that knows the word.
+    #[default]
+    FirstWins,
+    /// Every provider's entries, one after the other under its name.
+    ConcatWithHeaders,
+    /// A single entry, each part of speech taking senses from every provider
+    /// in turn.
+    InterleaveByPos,
+    /// Like first-wins, but installed dictionaries go ahead of the web.
+    PreferOffline,
+}
+impl MergePolicy {
+    /// Whether every provider in the chain has to be asked.
+    pub fn asks_all(self) -> bool {
+        matches!(self, Self::ConcatWithHeaders | Self::InterleaveByPos)
+    }
+}
+
+/// Folds `entries` into one: the first entry's headword and pronunciation,
+/// then one meaning per part of speech in order of first appearance, with the
+/// senses of each provider taken in turn. Senses with the same wording are
+/// only kept once.
+pub fn interleave(entries: Vec<DefinitionResponse>) -> Vec<DefinitionResponse> {
+    let mut entries = entries.into_iter();
+    let Some(mut merged) = entries.next() else {
+        return Vec::new();
+    };
+    let rest: Vec<DefinitionResponse> = entries.collect();
+    for entry in &rest {
+        for phonetic in &entry.phonetics {
+            if !merged.phonetics.iter().any(|p| p.text == phonetic.text) {
+                merged.phonetics.push(phonetic.clone());
+            }
+        }
+        if merged.origin.is_none() {
+            merged.origin = entry.origin.clone();
+        }
+        if let Some(urls) = &entry.source_urls {
+            union(merged.source_urls.get_or_insert_with(Vec::new), urls);
+        }
+    }
+
+    // Each provider's senses for a part of speech, in chain order.
+    let mut groups: Vec<(String, Vec<Vec<Definition>>, Meaning)> = Vec::new();
+    let mut providers = Vec::new();
+    for entry in std::iter::once(&merged).chain(&rest) {
+        let column = match providers.iter().position(|p| *p == entry.provider) {
+            Some(column) => column,
+            None => {
+                providers.push(entry.provider);
+                providers.len() - 1
+            }
+        };
+        for meaning in &entry.meanings {
+            let pos = meaning.part_of_speech.to_lowercase();
+            let index = match groups.iter().position(|(p, _, _)| *p == pos) {
+                Some(index) => index,
+                None => {
+                    let empty = Meaning {
+                        definitions: Vec::new(),
+                        ..meaning.clone()
+                    };
+                    groups.push((pos, Vec::new(), empty));
+                    groups.len() - 1
+                }
+            };
+            let (_, columns, combined) = &mut groups[index];
+            if columns.len() <= column {
+                columns.resize(column + 1, Vec::new());
+            }
+            columns[column].extend(meaning.definitions.iter().cloned());
+            combined.gender = combined.gender.or(meaning.gender);
+            for (into, from) in [
+                (&mut combined.synonyms, &meaning.synonyms),
+                (&mut combined.antonyms, &meaning.antonyms),
+            ] {
+                if let Some(words) = from {
+                    union(into.get_or_insert_with(Vec::new), words);
+                }
+            }
+        }
+    }
+
+    merged.meanings = groups
+        .into_iter()
+        .map(|(_, columns, mut meaning)| {
+            let mut seen = Vec::new();
+            let longest = columns.iter().map(Vec::len).max().unwrap_or(0);
+            for i in 0..longest {
+                for definition in columns.iter().filter_map(|c| c.get(i)) {
+                    let key = definition.definition.trim().to_lowercase();
+                    if !seen.contains(&key) {
+                        seen.push(key);
+                        meaning.definitions.push(definition.clone());
+                    }
+                }
+            }
+            meaning
+        })
+        .collect();
+    vec![merged]
+}
+
+/// Appends the strings of `from` that `into` doesn't have yet.
+fn union(into: &mut Vec<String>, from: &[String]) {
+    for item in from {
+        if !into.contains(item) {
+            into.push(item.clone());
+        }
+    }
+}
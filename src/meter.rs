@@ -0,0 +1,220 @@
+use crate::{
+    analyze,
+    cli::Format,
+    cmudict::{self, Pronunciation},
+    datamuse,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Feet and their stress, 1 stressed and 0 not, in the order a tie goes.
+const FEET: &[(&str, &[u8])] = &[
+    ("iambic", &[0, 1]),
+    ("trochaic", &[1, 0]),
+    ("anapestic", &[0, 0, 1]),
+    ("dactylic", &[1, 0, 0]),
+    ("amphibrachic", &[0, 1, 0]),
+];
+
+/// Line lengths by their number of feet.
+const LENGTHS: &[&str] = &[
+    "monometer",
+    "dimeter",
+    "trimeter",
+    "tetrameter",
+    "pentameter",
+    "hexameter",
+    "heptameter",
+    "octameter",
+];
+
+/// How a syllable is stressed, as the CMUdict digit says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stress {
+    Primary,
+    Secondary,
+    None,
+    /// The word isn't in the dictionary; its syllables are counted from the
+    /// spelling.
+    Unknown,
+}
+impl Stress {
+    /// As scansion marks it: / stressed, \ secondary, x unstressed.
+    fn mark(self) -> &'static str {
+        match self {
+            Stress::Primary => "/",
+            Stress::Secondary => "\\",
+            Stress::None => "x",
+            Stress::Unknown => "?",
+        }
+    }
+}
+
+/// A word of the line and its syllables' stress.
+struct Word {
+    word: String,
+    ipa: Option<String>,
+    stresses: Vec<Stress>,
+}
+impl Word {
+    fn from_pronunciation(word: &str, pronunciation: &Pronunciation) -> Self {
+        Self {
+            word: word.to_string(),
+            ipa: Some(pronunciation.ipa()),
+            stresses: pronunciation
+                .stress()
+                .chars()
+                .map(|digit| match digit {
+                    '1' => Stress::Primary,
+                    '2' => Stress::Secondary,
+                    _ => Stress::None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether a syllable can go either way in the meter: one-syllable
+    /// words, which the dictionary stresses out of context, secondary stress
+    /// and words it doesn't know.
+    fn flexible(&self, stress: Stress) -> bool {
+        self.stresses.len() == 1 || matches!(stress, Stress::Secondary | Stress::Unknown)
+    }
+}
+
+/// The meter that fits a line best, and how well.
+struct Scansion {
+    name: String,
+    /// Syllables whose stress the meter puts where the words have it.
+    fitting: usize,
+    syllables: usize,
+}
+
+/// `meter "<line>"`: each word's stress and the line's pattern, and the
+/// metrical foot that fits it best, from CMUdict when it's installed and
+/// Datamuse otherwise.
+pub async fn run(line: &str, format: Format) -> Result<(), surf::Error> {
+    let mut words = Vec::new();
+    for word in line
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+    {
+        words.push(stresses(&word).await?);
+    }
+    if words.is_empty() {
+        return Err(surf::Error::from_str(
+            surf::StatusCode::BadRequest,
+            "'meter' needs a line of English",
+        ));
+    }
+
+    let pattern: Vec<&str> = words
+        .iter()
+        .flat_map(|w| w.stresses.iter().map(|s| s.mark()))
+        .collect();
+    let pattern = pattern.join(" ");
+    let scansion = scan(&words);
+    let summary = format!(
+        "{} ({} of {} syllables fit)",
+        scansion.name, scansion.fitting, scansion.syllables
+    );
+    let rows: Vec<Vec<String>> = words
+        .iter()
+        .map(|w| {
+            let marks: Vec<&str> = w.stresses.iter().map(|s| s.mark()).collect();
+            vec![
+                w.word.clone(),
+                marks.join(" "),
+                w.ipa
+                    .as_ref()
+                    .map(|ipa| format!("/{}/", ipa))
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    match format {
+        Format::Sherlock => {
+            let content = format!(
+                "<span font_desc=\"monospace\">\n<b>{}</b>\n{}\n\n{}</span>",
+                pattern,
+                summary,
+                text::table(&rows)
+            );
+            let actions = vec![ApplicationAction::copy(
+                String::from("Copy the pattern"),
+                pattern.clone(),
+            )];
+            let response =
+                SherlockPipeResponse::new(format!(r#"Meter of "{}""#, line), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            println!("{}\n{}\n", pattern, summary);
+            print!("{}", text::table(&rows));
+        }
+        Format::Oneline => println!("{} — {}", pattern, scansion.name),
+    }
+    Ok(())
+}
+
+/// `word` with its syllables' stress, as CMUdict or Datamuse has it, or
+/// syllables of unknown stress counted from the spelling.
+async fn stresses(word: &str) -> Result<Word, surf::Error> {
+    let mut pronunciation = None;
+    // A possessive is said like the word with an unstressed ending.
+    for spelling in [Some(word), word.strip_suffix("'s")].into_iter().flatten() {
+        pronunciation = match cmudict::is_installed() {
+            true => cmudict::Dictionary::shared()?
+                .lookup(spelling)
+                .first()
+                .map(|p| Word::from_pronunciation(word, p)),
+            false => datamuse::pronunciation(spelling)
+                .await?
+                .and_then(|w| w.pronunciation())
+                .map(|p| Word::from_pronunciation(word, &p)),
+        };
+        if pronunciation.is_some() {
+            break;
+        }
+    }
+    Ok(pronunciation
+        .filter(|w| !w.stresses.is_empty())
+        .unwrap_or_else(|| Word {
+            word: word.to_string(),
+            ipa: None,
+            stresses: vec![Stress::Unknown; analyze::syllables(word)],
+        }))
+}
+
+/// Tries each foot over the line and keeps the one most syllables fit:
+/// "iambic pentameter".
+fn scan(words: &[Word]) -> Scansion {
+    let syllables: Vec<(Stress, bool)> = words
+        .iter()
+        .flat_map(|w| w.stresses.iter().map(|&s| (s, w.flexible(s))))
+        .collect();
+    let (foot, pattern, fitting) = FEET
+        .iter()
+        .map(|(foot, pattern)| {
+            let fitting = syllables
+                .iter()
+                .zip(pattern.iter().cycle())
+                .filter(|((stress, flexible), &expected)| {
+                    *flexible || (*stress == Stress::Primary) == (expected == 1)
+                })
+                .count();
+            (foot, pattern, fitting)
+        })
+        .rev()
+        .max_by_key(|(_, _, fitting)| *fitting)
+        .unwrap();
+    let feet = (syllables.len() / pattern.len()).max(1);
+    let length = LENGTHS
+        .get(feet - 1)
+        .map_or_else(|| format!("{} feet", feet), |length| length.to_string());
+    Scansion {
+        name: format!("{} {}", foot, length),
+        fitting,
+        syllables: syllables.len(),
+    }
+}
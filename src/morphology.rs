@@ -0,0 +1,566 @@
+use crate::wiktionary::{self, Template};
+
+/// (base, simple past, past participle) for common irregular verbs.
+const IRREGULAR_VERBS: &[(&str, &str, &str)] = &[
+    ("arise", "arose", "arisen"),
+    ("awake", "awoke", "awoken"),
+    ("bear", "bore", "borne"),
+    ("beat", "beat", "beaten"),
+    ("become", "became", "become"),
+    ("begin", "began", "begun"),
+    ("bend", "bent", "bent"),
+    ("bet", "bet", "bet"),
+    ("bind", "bound", "bound"),
+    ("bite", "bit", "bitten"),
+    ("bleed", "bled", "bled"),
+    ("blow", "blew", "blown"),
+    ("break", "broke", "broken"),
+    ("breed", "bred", "bred"),
+    ("bring", "brought", "brought"),
+    ("build", "built", "built"),
+    ("burst", "burst", "burst"),
+    ("buy", "bought", "bought"),
+    ("cast", "cast", "cast"),
+    ("catch", "caught", "caught"),
+    ("choose", "chose", "chosen"),
+    ("cling", "clung", "clung"),
+    ("come", "came", "come"),
+    ("cost", "cost", "cost"),
+    ("creep", "crept", "crept"),
+    ("cut", "cut", "cut"),
+    ("deal", "dealt", "dealt"),
+    ("dig", "dug", "dug"),
+    ("do", "did", "done"),
+    ("draw", "drew", "drawn"),
+    ("drink", "drank", "drunk"),
+    ("drive", "drove", "driven"),
+    ("eat", "ate", "eaten"),
+    ("fall", "fell", "fallen"),
+    ("feed", "fed", "fed"),
+    ("feel", "felt", "felt"),
+    ("fight", "fought", "fought"),
+    ("find", "found", "found"),
+    ("flee", "fled", "fled"),
+    ("fling", "flung", "flung"),
+    ("fly", "flew", "flown"),
+    ("forbid", "forbade", "forbidden"),
+    ("forget", "forgot", "forgotten"),
+    ("forgive", "forgave", "forgiven"),
+    ("freeze", "froze", "frozen"),
+    ("get", "got", "gotten"),
+    ("give", "gave", "given"),
+    ("go", "went", "gone"),
+    ("grind", "ground", "ground"),
+    ("grow", "grew", "grown"),
+    ("hang", "hung", "hung"),
+    ("have", "had", "had"),
+    ("hear", "heard", "heard"),
+    ("hide", "hid", "hidden"),
+    ("hit", "hit", "hit"),
+    ("hold", "held", "held"),
+    ("hurt", "hurt", "hurt"),
+    ("keep", "kept", "kept"),
+    ("kneel", "knelt", "knelt"),
+    ("know", "knew", "known"),
+    ("lay", "laid", "laid"),
+    ("lead", "led", "led"),
+    ("leave", "left", "left"),
+    ("lend", "lent", "lent"),
+    ("let", "let", "let"),
+    ("lie", "lay", "lain"),
+    ("light", "lit", "lit"),
+    ("lose", "lost", "lost"),
+    ("make", "made", "made"),
+    ("mean", "meant", "meant"),
+    ("meet", "met", "met"),
+    ("pay", "paid", "paid"),
+    ("put", "put", "put"),
+    ("quit", "quit", "quit"),
+    ("read", "read", "read"),
+    ("ride", "rode", "ridden"),
+    ("ring", "rang", "rung"),
+    ("rise", "rose", "risen"),
+    ("run", "ran", "run"),
+    ("say", "said", "said"),
+    ("see", "saw", "seen"),
+    ("seek", "sought", "sought"),
+    ("sell", "sold", "sold"),
+    ("send", "sent", "sent"),
+    ("set", "set", "set"),
+    ("shake", "shook", "shaken"),
+    ("shine", "shone", "shone"),
+    ("shoot", "shot", "shot"),
+    ("show", "showed", "shown"),
+    ("shrink", "shrank", "shrunk"),
+    ("shut", "shut", "shut"),
+    ("sing", "sang", "sung"),
+    ("sink", "sank", "sunk"),
+    ("sit", "sat", "sat"),
+    ("sleep", "slept", "slept"),
+    ("slide", "slid", "slid"),
+    ("speak", "spoke", "spoken"),
+    ("spend", "spent", "spent"),
+    ("spin", "spun", "spun"),
+    ("split", "split", "split"),
+    ("spread", "spread", "spread"),
+    ("spring", "sprang", "sprung"),
+    ("stand", "stood", "stood"),
+    ("steal", "stole", "stolen"),
+    ("stick", "stuck", "stuck"),
+    ("sting", "stung", "stung"),
+    ("stink", "stank", "stunk"),
+    ("strike", "struck", "struck"),
+    ("swear", "swore", "sworn"),
+    ("sweep", "swept", "swept"),
+    ("swim", "swam", "swum"),
+    ("swing", "swung", "swung"),
+    ("take", "took", "taken"),
+    ("teach", "taught", "taught"),
+    ("tear", "tore", "torn"),
+    ("tell", "told", "told"),
+    ("think", "thought", "thought"),
+    ("throw", "threw", "thrown"),
+    ("understand", "understood", "understood"),
+    ("wake", "woke", "woken"),
+    ("wear", "wore", "worn"),
+    ("weep", "wept", "wept"),
+    ("win", "won", "won"),
+    ("wind", "wound", "wound"),
+    ("withdraw", "withdrew", "withdrawn"),
+    ("write", "wrote", "written"),
+];
+
+/// (singular, plural) for nouns that don't take -s/-es.
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[
+    ("man", "men"),
+    ("woman", "women"),
+    ("child", "children"),
+    ("person", "people"),
+    ("foot", "feet"),
+    ("tooth", "teeth"),
+    ("goose", "geese"),
+    ("mouse", "mice"),
+    ("louse", "lice"),
+    ("ox", "oxen"),
+    ("die", "dice"),
+    ("criterion", "criteria"),
+    ("phenomenon", "phenomena"),
+    ("cactus", "cacti"),
+    ("fungus", "fungi"),
+    ("nucleus", "nuclei"),
+    ("radius", "radii"),
+    ("stimulus", "stimuli"),
+    ("analysis", "analyses"),
+    ("crisis", "crises"),
+    ("thesis", "theses"),
+    ("axis", "axes"),
+    ("appendix", "appendices"),
+    ("index", "indices"),
+    ("matrix", "matrices"),
+    ("datum", "data"),
+    ("medium", "media"),
+    ("curriculum", "curricula"),
+    ("sheep", "sheep"),
+    ("deer", "deer"),
+    ("fish", "fish"),
+    ("species", "species"),
+    ("series", "series"),
+    ("aircraft", "aircraft"),
+];
+
+/// Nouns in -f/-fe that change to -ves.
+const VES_PLURALS: &[&str] = &[
+    "calf", "elf", "half", "knife", "leaf", "life", "loaf", "self", "sheaf", "shelf", "thief",
+    "wife", "wolf",
+];
+
+/// Nouns in -o that take -es.
+const OES_PLURALS: &[&str] = &[
+    "echo", "embargo", "hero", "potato", "tomato", "torpedo", "veto", "volcano",
+];
+
+/// (positive, comparative, superlative) for irregular adjectives.
+const IRREGULAR_ADJECTIVES: &[(&str, &str, &str)] = &[
+    ("good", "better", "best"),
+    ("well", "better", "best"),
+    ("bad", "worse", "worst"),
+    ("ill", "worse", "worst"),
+    ("far", "farther", "farthest"),
+    ("little", "less", "least"),
+    ("many", "more", "most"),
+    ("much", "more", "most"),
+];
+
+/// Two-syllable verbs stressed on the last syllable, which double their final
+/// consonant like one-syllable verbs do ("preferred").
+const DOUBLING_VERBS: &[&str] = &[
+    "admit", "commit", "compel", "control", "equip", "occur", "omit", "patrol", "permit", "prefer",
+    "refer", "regret", "submit", "transfer",
+];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Rough syllable count from vowel groups, with a silent final e.
+fn syllables(word: &str) -> usize {
+    let chars: Vec<char> = word.chars().collect();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for &c in &chars {
+        let vowel = is_vowel(c) || c == 'y';
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// Whether the word ends consonant-vowel-consonant, so suffixes double the
+/// last letter ("stop" → "stopping"). w, x and y never double.
+fn ends_cvc(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().rev().take(3).collect();
+    matches!(chars.as_slice(), [c3, v, c1]
+        if !is_vowel(*c3) && !matches!(c3, 'w' | 'x' | 'y') && is_vowel(*v) && !is_vowel(*c1))
+}
+
+fn doubles(word: &str) -> bool {
+    ends_cvc(word) && (syllables(word) == 1 || DOUBLING_VERBS.contains(&word))
+}
+
+/// Adds a vowel-initial suffix ("ed", "ing", "er", "est") with English spelling
+/// rules: dropped e, doubled consonant, y → i.
+fn add_suffix(word: &str, suffix: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ie").filter(|_| suffix == "ing") {
+        return format!("{}ying", stem);
+    }
+    if word.ends_with('e') && !word.ends_with("ee") && !word.ends_with("ye") {
+        if suffix == "ing" {
+            return format!("{}{}", &word[..word.len() - 1], suffix);
+        }
+        // "e" + "ed" → "ed", "e" + "er" → "er"
+        return format!("{}{}", word, &suffix[1..]);
+    }
+    if word.ends_with('e') && suffix != "ing" {
+        return format!("{}{}", word, &suffix[1..]);
+    }
+    if let Some(stem) = word.strip_suffix('y') {
+        if !stem.ends_with(is_vowel) && suffix != "ing" {
+            return format!("{}i{}", stem, suffix);
+        }
+    }
+    if doubles(word) {
+        let last = word.chars().last().unwrap();
+        return format!("{}{}{}", word, last, suffix);
+    }
+    format!("{}{}", word, suffix)
+}
+
+/// Adds -s/-es with the usual spelling changes ("box" → "boxes", "try" → "tries").
+fn add_s(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix('y') {
+        if !stem.ends_with(is_vowel) {
+            return format!("{}ies", stem);
+        }
+    }
+    if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|end| word.ends_with(end))
+    {
+        return format!("{}es", word);
+    }
+    format!("{}s", word)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerbForms {
+    pub base: String,
+    pub third_person: String,
+    pub present_participle: String,
+    pub past: String,
+    pub past_participle: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// "bigger, biggest" or "more careful, most careful".
+    Graded {
+        comparative: String,
+        superlative: String,
+    },
+    /// Absolute adjectives like "dead".
+    NotComparable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plural {
+    Countable(String),
+    Uncountable,
+}
+
+pub fn verb(word: &str) -> VerbForms {
+    if word == "be" {
+        return VerbForms {
+            base: word.to_string(),
+            third_person: String::from("is"),
+            present_participle: String::from("being"),
+            past: String::from("was"),
+            past_participle: String::from("been"),
+        };
+    }
+    let (past, past_participle) = IRREGULAR_VERBS
+        .iter()
+        .find(|(base, _, _)| *base == word)
+        .map(|(_, past, pp)| (past.to_string(), pp.to_string()))
+        .unwrap_or_else(|| (add_suffix(word, "ed"), add_suffix(word, "ed")));
+    let third_person = match word {
+        "have" => String::from("has"),
+        "do" | "go" => format!("{}es", word),
+        _ => add_s(word),
+    };
+    VerbForms {
+        base: word.to_string(),
+        third_person,
+        present_participle: add_suffix(word, "ing"),
+        past,
+        past_participle,
+    }
+}
+
+pub fn plural(word: &str) -> Plural {
+    if let Some((_, plural)) = IRREGULAR_PLURALS.iter().find(|(s, _)| *s == word) {
+        return Plural::Countable(plural.to_string());
+    }
+    if VES_PLURALS.contains(&word) {
+        let stem = word.trim_end_matches('e').trim_end_matches('f');
+        return Plural::Countable(format!("{}ves", stem));
+    }
+    if OES_PLURALS.contains(&word) {
+        return Plural::Countable(format!("{}es", word));
+    }
+    Plural::Countable(add_s(word))
+}
+
+pub fn comparison(word: &str) -> Comparison {
+    if let Some((_, comparative, superlative)) =
+        IRREGULAR_ADJECTIVES.iter().find(|(p, _, _)| *p == word)
+    {
+        return Comparison::Graded {
+            comparative: comparative.to_string(),
+            superlative: superlative.to_string(),
+        };
+    }
+    let short = syllables(word) == 1
+        || (syllables(word) == 2 && (word.ends_with('y') || word.ends_with("le")));
+    if short {
+        Comparison::Graded {
+            comparative: add_suffix(word, "er"),
+            superlative: add_suffix(word, "est"),
+        }
+    } else {
+        Comparison::Graded {
+            comparative: format!("more {}", word),
+            superlative: format!("most {}", word),
+        }
+    }
+}
+
+/// The English inflections Wiktionary's headword templates spell out, which
+/// take precedence over the rules above (they know "go" → "went").
+#[derive(Debug, Default)]
+pub struct WiktionaryForms {
+    pub verb: Option<VerbForms>,
+    pub plural: Option<Plural>,
+    pub comparison: Option<Comparison>,
+}
+
+/// Reads `{{en-verb}}`, `{{en-noun}}` and `{{en-adj}}` from an English section.
+pub fn from_wiktionary(word: &str, english: &str) -> WiktionaryForms {
+    WiktionaryForms {
+        verb: wiktionary::templates(english, "en-verb")
+            .first()
+            .map(|t| verb_from_template(word, t)),
+        plural: wiktionary::templates(english, "en-noun")
+            .first()
+            .map(|t| plural_from_template(word, t)),
+        comparison: wiktionary::templates(english, "en-adj")
+            .first()
+            .map(|t| comparison_from_template(word, t)),
+    }
+}
+
+/// Suffix codes the headword templates accept in place of a spelled-out form.
+const SHORTHAND: &[&str] = &[
+    "s", "es", "ies", "d", "ed", "ing", "er", "est", "more", "most",
+];
+
+/// An explicit form, as opposed to one of the templates' shorthand codes.
+fn explicit(param: &str) -> Option<&str> {
+    let is_word = !param.is_empty()
+        && !SHORTHAND.contains(&param)
+        && param
+            .chars()
+            .all(|c| c.is_alphabetic() || c == ' ' || c == '-');
+    is_word.then_some(param)
+}
+
+/// `{{en-verb|<3rd sg.>|<pres. part.>|<past>|<past part.>}}`; missing or
+/// shorthand slots ("+", "++", …) fall back to the rules.
+fn verb_from_template(word: &str, template: &Template) -> VerbForms {
+    let mut forms = verb(word);
+    let slot = |i: usize| template.positional.get(i).copied().and_then(explicit);
+    if let Some(form) = slot(0) {
+        forms.third_person = form.to_string();
+    }
+    if let Some(form) = slot(1) {
+        forms.present_participle = form.to_string();
+    }
+    if let Some(form) = slot(2) {
+        forms.past = form.to_string();
+        forms.past_participle = form.to_string();
+    }
+    if let Some(form) = slot(3) {
+        forms.past_participle = form.to_string();
+    }
+    forms
+}
+
+/// `{{en-noun}}`: "-" uncountable, "~" both (plural -s), "s"/"es" suffixes,
+/// anything else an explicit plural.
+fn plural_from_template(word: &str, template: &Template) -> Plural {
+    match template.positional.first().copied().unwrap_or_default() {
+        "-" => Plural::Uncountable,
+        "" | "~" | "+" => plural(word),
+        "s" => Plural::Countable(format!("{}s", word)),
+        "es" => Plural::Countable(format!("{}es", word)),
+        other => explicit(other)
+            .map(|p| Plural::Countable(p.to_string()))
+            .unwrap_or_else(|| plural(word)),
+    }
+}
+
+/// `{{en-adj}}`: "-" not comparable, "er"/"more" to choose the comparison,
+/// or explicit comparative (and superlative) forms.
+fn comparison_from_template(word: &str, template: &Template) -> Comparison {
+    let first = template.positional.first().copied().unwrap_or_default();
+    match first {
+        "-" => Comparison::NotComparable,
+        "er" => Comparison::Graded {
+            comparative: add_suffix(word, "er"),
+            superlative: add_suffix(word, "est"),
+        },
+        "more" => Comparison::Graded {
+            comparative: format!("more {}", word),
+            superlative: format!("most {}", word),
+        },
+        other => match explicit(other) {
+            Some(comparative) => Comparison::Graded {
+                comparative: comparative.to_string(),
+                superlative: template
+                    .positional
+                    .get(1)
+                    .copied()
+                    .or_else(|| template.param("sup"))
+                    .and_then(explicit)
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("most {}", word)),
+            },
+            None => comparison(word),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graded(comparative: &str, superlative: &str) -> Comparison {
+        Comparison::Graded {
+            comparative: comparative.to_string(),
+            superlative: superlative.to_string(),
+        }
+    }
+
+    #[test]
+    fn inflects_verbs() {
+        for (word, third_person, present_participle, past, past_participle) in [
+            ("walk", "walks", "walking", "walked", "walked"),
+            ("hope", "hopes", "hoping", "hoped", "hoped"),
+            ("stop", "stops", "stopping", "stopped", "stopped"),
+            ("prefer", "prefers", "preferring", "preferred", "preferred"),
+            ("visit", "visits", "visiting", "visited", "visited"),
+            ("fix", "fixes", "fixing", "fixed", "fixed"),
+            ("try", "tries", "trying", "tried", "tried"),
+            ("play", "plays", "playing", "played", "played"),
+            ("die", "dies", "dying", "died", "died"),
+            ("see", "sees", "seeing", "saw", "seen"),
+            ("go", "goes", "going", "went", "gone"),
+            ("have", "has", "having", "had", "had"),
+            ("be", "is", "being", "was", "been"),
+        ] {
+            assert_eq!(
+                verb(word),
+                VerbForms {
+                    base: word.to_string(),
+                    third_person: third_person.to_string(),
+                    present_participle: present_participle.to_string(),
+                    past: past.to_string(),
+                    past_participle: past_participle.to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn inflects_nouns_and_adjectives() {
+        for (word, expected) in [
+            ("cat", "cats"),
+            ("box", "boxes"),
+            ("church", "churches"),
+            ("city", "cities"),
+            ("day", "days"),
+            ("knife", "knives"),
+            ("potato", "potatoes"),
+            ("child", "children"),
+        ] {
+            assert_eq!(plural(word), Plural::Countable(expected.to_string()));
+        }
+        assert_eq!(comparison("big"), graded("bigger", "biggest"));
+        assert_eq!(comparison("happy"), graded("happier", "happiest"));
+        assert_eq!(comparison("simple"), graded("simpler", "simplest"));
+        assert_eq!(
+            comparison("careful"),
+            graded("more careful", "most careful")
+        );
+        assert_eq!(comparison("good"), graded("better", "best"));
+    }
+
+    #[test]
+    fn reads_headword_templates() {
+        let forms = from_wiktionary(
+            "run",
+            "{{en-verb|runs|running|ran|run}}\n{{en-noun}}\n{{en-adj|-}}",
+        );
+        let verb = forms.verb.unwrap();
+        assert_eq!(
+            (verb.past.as_str(), verb.past_participle.as_str()),
+            ("ran", "run")
+        );
+        assert_eq!(forms.plural, Some(Plural::Countable(String::from("runs"))));
+        assert_eq!(forms.comparison, Some(Comparison::NotComparable));
+
+        let forms = from_wiktionary("ox", "{{en-noun|oxen}}");
+        assert_eq!(forms.plural, Some(Plural::Countable(String::from("oxen"))));
+        // Two syllables would take "more", but the template says -er.
+        let forms = from_wiktionary("quiet", "{{en-adj|er}}");
+        assert_eq!(forms.comparison, Some(graded("quieter", "quietest")));
+        // Shorthand slots fall back to the rules.
+        let forms = from_wiktionary("stop", "{{en-verb|+|++}}\n{{en-noun|-}}");
+        assert_eq!(forms.verb.unwrap().present_participle, "stopping");
+        assert_eq!(forms.plural, Some(Plural::Uncountable));
+    }
+}
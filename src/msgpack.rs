@@ -0,0 +1,406 @@
+use serde_json::{json, Map, Number, Value};
+use std::io::{self, ErrorKind, Read, Write};
+use tokio::sync::mpsc;
+
+use crate::{api::Lookup, providers::Settings, text};
+
+/// MessagePack-RPC's message types, the first element of each message.
+const REQUEST: u64 = 0;
+const RESPONSE: u64 = 1;
+const NOTIFICATION: u64 = 2;
+
+/// `--msgpack-rpc`: answers MessagePack-RPC requests on stdin and stdout,
+/// the protocol Neovim speaks to the jobs it starts with `rpc = true`, so a
+/// plugin gets entries as Lua tables without a shell or JSON in between.
+///
+/// - `define(word)`: `{query, word, found, entries, tried, summary}`, the
+///   entries in
+///   dictionaryapi.dev's layout after the usual filters.
+/// - `oneline(word)`: `--oneline`'s line, or nil.
+///
+/// Like `--lsp`'s, its lookups aren't kept in the history.
+pub async fn run(settings: Settings) -> io::Result<()> {
+    let (sender, mut messages) = mpsc::unbounded_channel();
+    // Reading stdin blocks, so it's kept off the runtime's only thread.
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let message = read(&mut stdin);
+            let last = message.is_err();
+            if sender.send(message).is_err() || last {
+                break;
+            }
+        }
+    });
+
+    while let Some(message) = messages.recv().await {
+        let message = match message {
+            Ok(message) => message,
+            // The client closed the channel.
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        };
+        let (id, method, params) = match message.as_array().map(Vec::as_slice) {
+            Some([kind, id, method, params]) if kind.as_u64() == Some(REQUEST) => {
+                (id.clone(), method.as_str().unwrap_or_default(), params)
+            }
+            // Nothing is done for notifications, and the server sends no
+            // requests to get responses to.
+            Some([kind, ..])
+                if kind.as_u64() == Some(NOTIFICATION) || kind.as_u64() == Some(RESPONSE) =>
+            {
+                continue
+            }
+            _ => {
+                eprintln!("Error: ignored a message that isn't MessagePack-RPC");
+                continue;
+            }
+        };
+        let word = params
+            .as_array()
+            .and_then(|params| params.first())
+            .and_then(Value::as_str);
+        let answer = match (method, word) {
+            ("define", Some(word)) => define(&settings, word).await,
+            ("oneline", Some(word)) => oneline(&settings, word).await,
+            ("define" | "oneline", None) => Err(format!("'{}' needs a word", method)),
+            (method, _) => Err(format!("There's no method '{}'", method)),
+        };
+        let (error, result) = match answer {
+            Ok(result) => (Value::Null, result),
+            Err(e) => (Value::String(e), Value::Null),
+        };
+        send(&json!([RESPONSE, id, error, result]))?;
+    }
+    Ok(())
+}
+
+async fn define(settings: &Settings, word: &str) -> Result<Value, String> {
+    let resolved = settings.resolve(word).await.map_err(|e| e.to_string())?;
+    let entries = match resolved.lookup {
+        Lookup::Found(entries) => settings.prepare(entries, settings.content_filter),
+        _ => Vec::new(),
+    };
+    let summary = resolved.summary.map(|summary| {
+        json!({
+            "title": summary.title,
+            "description": summary.description,
+            "extract": summary.extract,
+            "url": summary.url(),
+        })
+    });
+    Ok(json!({
+        "query": resolved.query,
+        "word": resolved.word,
+        "found": !entries.is_empty(),
+        "entries": entries,
+        "tried": resolved.tried,
+        "summary": summary,
+    }))
+}
+
+async fn oneline(settings: &Settings, word: &str) -> Result<Value, String> {
+    let resolved = settings.resolve(word).await.map_err(|e| e.to_string())?;
+    let line = match (resolved.lookup, resolved.summary) {
+        (Lookup::Found(entries), _) => text::oneline(
+            &settings.prepare(entries, settings.content_filter),
+            &settings.lang,
+        ),
+        (_, Some(summary)) => Some(summary.oneline()),
+        _ => None,
+    };
+    Ok(line.map_or(Value::Null, Value::String))
+}
+
+fn send(message: &Value) -> io::Result<()> {
+    let mut out = Vec::new();
+    write(&mut out, message);
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&out)?;
+    stdout.flush()
+}
+
+/// Appends `value` to `out` in MessagePack, in the smallest encoding of each
+/// number, string, array and map.
+fn write(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(n), _) if n < 0x80 => out.push(n as u8),
+            (Some(n), _) if n <= u8::MAX as u64 => out.extend([0xcc, n as u8]),
+            (Some(n), _) if n <= u16::MAX as u64 => {
+                out.push(0xcd);
+                out.extend((n as u16).to_be_bytes());
+            }
+            (Some(n), _) if n <= u32::MAX as u64 => {
+                out.push(0xce);
+                out.extend((n as u32).to_be_bytes());
+            }
+            (Some(n), _) => {
+                out.push(0xcf);
+                out.extend(n.to_be_bytes());
+            }
+            (None, Some(n)) if n >= -32 => out.push(n as i8 as u8),
+            (None, Some(n)) if n >= i8::MIN as i64 => out.extend([0xd0, n as i8 as u8]),
+            (None, Some(n)) if n >= i16::MIN as i64 => {
+                out.push(0xd1);
+                out.extend((n as i16).to_be_bytes());
+            }
+            (None, Some(n)) if n >= i32::MIN as i64 => {
+                out.push(0xd2);
+                out.extend((n as i32).to_be_bytes());
+            }
+            (None, Some(n)) => {
+                out.push(0xd3);
+                out.extend(n.to_be_bytes());
+            }
+            (None, None) => {
+                out.push(0xcb);
+                out.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        },
+        Value::String(string) => {
+            header(out, string.len(), 0xa0, 31, [0xd9, 0xda, 0xdb]);
+            out.extend(string.as_bytes());
+        }
+        Value::Array(array) => {
+            header(out, array.len(), 0x90, 15, [0, 0xdc, 0xdd]);
+            for value in array {
+                write(out, value);
+            }
+        }
+        Value::Object(map) => {
+            header(out, map.len(), 0x80, 15, [0, 0xde, 0xdf]);
+            for (key, value) in map {
+                write(out, &Value::String(key.clone()));
+                write(out, value);
+            }
+        }
+    }
+}
+
+/// A string's, array's or map's type and length: in the type byte `fixed`
+/// up to `max`, otherwise after the marker for an 8, 16 or 32-bit length.
+/// Arrays and maps have no 8-bit one, marked 0.
+fn header(out: &mut Vec<u8>, len: usize, fixed: u8, max: usize, markers: [u8; 3]) {
+    match len {
+        len if len <= max => out.push(fixed | len as u8),
+        len if len <= u8::MAX as usize && markers[0] != 0 => out.extend([markers[0], len as u8]),
+        len if len <= u16::MAX as usize => {
+            out.push(markers[1]);
+            out.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(markers[2]);
+            out.extend((len as u32).to_be_bytes());
+        }
+    }
+}
+
+/// The next MessagePack value on `input`. Binary strings are read as text,
+/// and extension types (Neovim's buffer and window handles) as nil.
+fn read(input: &mut impl Read) -> io::Result<Value> {
+    let marker = bytes::<1>(input)?[0];
+    Ok(match marker {
+        0x00..=0x7f => Value::from(marker),
+        0x80..=0x8f => map(input, (marker & 0x0f) as usize)?,
+        0x90..=0x9f => array(input, (marker & 0x0f) as usize)?,
+        0xa0..=0xbf => string(input, (marker & 0x1f) as usize)?,
+        0xc0 => Value::Null,
+        0xc2 => Value::Bool(false),
+        0xc3 => Value::Bool(true),
+        0xc4 | 0xd9 => {
+            let len = length(input, 1)?;
+            string(input, len)?
+        }
+        0xc5 | 0xda => {
+            let len = length(input, 2)?;
+            string(input, len)?
+        }
+        0xc6 | 0xdb => {
+            let len = length(input, 4)?;
+            string(input, len)?
+        }
+        0xc7..=0xc9 => {
+            let len = length(input, 1 << (marker - 0xc7))?;
+            // The type byte, then the data.
+            skip(input, len + 1)?;
+            Value::Null
+        }
+        0xca => float(f32::from_be_bytes(bytes(input)?) as f64),
+        0xcb => float(f64::from_be_bytes(bytes(input)?)),
+        0xcc => Value::from(bytes::<1>(input)?[0]),
+        0xcd => Value::from(u16::from_be_bytes(bytes(input)?)),
+        0xce => Value::from(u32::from_be_bytes(bytes(input)?)),
+        0xcf => Value::from(u64::from_be_bytes(bytes(input)?)),
+        0xd0 => Value::from(i8::from_be_bytes(bytes(input)?)),
+        0xd1 => Value::from(i16::from_be_bytes(bytes(input)?)),
+        0xd2 => Value::from(i32::from_be_bytes(bytes(input)?)),
+        0xd3 => Value::from(i64::from_be_bytes(bytes(input)?)),
+        0xd4..=0xd8 => {
+            skip(input, (1 << (marker - 0xd4)) + 1)?;
+            Value::Null
+        }
+        0xdc => {
+            let len = length(input, 2)?;
+            array(input, len)?
+        }
+        0xdd => {
+            let len = length(input, 4)?;
+            array(input, len)?
+        }
+        0xde => {
+            let len = length(input, 2)?;
+            map(input, len)?
+        }
+        0xdf => {
+            let len = length(input, 4)?;
+            map(input, len)?
+        }
+        0xe0..=0xff => Value::from(marker as i8),
+        0xc1 => {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "0xc1 isn't used in MessagePack",
+            ))
+        }
+    })
+}
+
+fn bytes<const N: usize>(input: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A big-endian length `size` bytes long.
+fn length(input: &mut impl Read, size: usize) -> io::Result<usize> {
+    let mut len = 0;
+    for _ in 0..size {
+        len = len << 8 | bytes::<1>(input)?[0] as usize;
+    }
+    Ok(len)
+}
+
+fn skip(input: &mut impl Read, len: usize) -> io::Result<()> {
+    io::copy(&mut input.take(len as u64), &mut io::sink())?;
+    Ok(())
+}
+
+fn string(input: &mut impl Read, len: usize) -> io::Result<Value> {
+    let mut bytes = Vec::new();
+    input.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+fn array(input: &mut impl Read, len: usize) -> io::Result<Value> {
+    let values: io::Result<Vec<Value>> = (0..len).map(|_| read(input)).collect();
+    values.map(Value::Array)
+}
+
+/// Keys that aren't strings are written out as JSON.
+fn map(input: &mut impl Read, len: usize) -> io::Result<Value> {
+    let mut map = Map::new();
+    for _ in 0..len {
+        let key = match read(input)? {
+            Value::String(key) => key,
+            key => key.to_string(),
+        };
+        map.insert(key, read(input)?);
+    }
+    Ok(Value::Object(map))
+}
+
+/// JSON has no NaN or infinity; they're read as nil.
+fn float(n: f64) -> Value {
+    Number::from_f64(n).map_or(Value::Null, Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(value: &Value) -> Vec<u8> {
+        let mut out = Vec::new();
+        write(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let numbers = [
+            json!(0),
+            json!(127),
+            json!(128),
+            json!(255),
+            json!(256),
+            json!(65535),
+            json!(65536),
+            json!(u32::MAX),
+            json!(u32::MAX as u64 + 1),
+            json!(u64::MAX),
+            json!(-1),
+            json!(-32),
+            json!(-33),
+            json!(-128),
+            json!(-129),
+            json!(-32768),
+            json!(-32769),
+            json!(i32::MIN),
+            json!(i32::MIN as i64 - 1),
+            json!(i64::MIN),
+            json!(1.5),
+        ];
+        let strings = [0, 31, 32, 255, 256, 65535, 65536].map(|len| json!("x".repeat(len)));
+        let value = json!({
+            "numbers": numbers,
+            "strings": strings,
+            "short": (0..15).collect::<Vec<_>>(),
+            "long": (0..70000).collect::<Vec<_>>(),
+            "map": (0..16).map(|i| (i.to_string(), json!(i))).collect::<Map<_, _>>(),
+            "nested": [null, true, false, { "word": "café", "found": true }],
+        });
+        let bytes = encoded(&value);
+        assert_eq!(read(&mut bytes.as_slice()).unwrap(), value);
+    }
+
+    #[test]
+    fn writes_the_smallest_encoding() {
+        assert_eq!(
+            encoded(&json!([1, "a", null])),
+            [0x93, 0x01, 0xa1, b'a', 0xc0]
+        );
+        assert_eq!(encoded(&json!(200)), [0xcc, 200]);
+        assert_eq!(encoded(&json!(-1)), [0xff]);
+        assert_eq!(encoded(&json!(-100)), [0xd0, 0x9c]);
+        assert_eq!(encoded(&json!(-1000)), [0xd1, 0xfc, 0x18]);
+        assert_eq!(encoded(&json!("x".repeat(40)))[..2], [0xd9, 40]);
+        assert_eq!(encoded(&json!(vec![0; 16]))[..3], [0xdc, 0, 16]);
+    }
+
+    #[test]
+    fn reads_what_neovim_sends() {
+        // A binary string, a buffer handle (fixext 1) and a float32.
+        let bytes = [
+            0x93, 0xc4, 0x02, b'h', b'i', 0xd4, 0x00, 0x01, 0xca, 0x3f, 0xc0, 0, 0,
+        ];
+        assert_eq!(
+            read(&mut bytes.as_slice()).unwrap(),
+            json!(["hi", null, 1.5])
+        );
+        // Integer keys are written out.
+        assert_eq!(
+            read(&mut [0x81, 0x01, 0xa1, b'a'].as_slice()).unwrap(),
+            json!({ "1": "a" })
+        );
+        let error = read(&mut [0xa3, b'a'].as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        let error = read(&mut [0xc1].as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
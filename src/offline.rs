@@ -0,0 +1,439 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    dsl::Dsl,
+    gender::Gender,
+    html,
+    mdict::Mdict,
+    slob::{Article, Slob},
+    storage,
+    tei::Tei,
+    wiktionary,
+    zim::Zim,
+};
+
+/// How an MDX article says "see this other article instead".
+const LINK: &str = "@@@LINK=";
+/// The URL scheme MDX articles use for audio stored in the `.mdd`.
+const SOUND: &str = "sound://";
+
+/// Wiktionary section headings that hold definitions.
+const PARTS_OF_SPEECH: &[&str] = &[
+    "noun",
+    "proper noun",
+    "verb",
+    "adjective",
+    "adverb",
+    "pronoun",
+    "preposition",
+    "conjunction",
+    "interjection",
+    "determiner",
+    "article",
+    "numeral",
+    "particle",
+    "participle",
+    "phrase",
+    "prepositional phrase",
+    "proverb",
+    "idiom",
+    "prefix",
+    "suffix",
+    "contraction",
+    "abbreviation",
+    "initialism",
+    "acronym",
+    "symbol",
+    "letter",
+];
+
+/// Where offline dictionaries are installed: drop files in and they're used.
+pub fn dictionaries_dir() -> PathBuf {
+    storage::data_dir().join("dictionaries")
+}
+
+/// Why a compressed dictionary can't be read by a build without the
+/// `compression` feature; `what` says what is compressed.
+#[cfg(not(feature = "compression"))]
+pub fn without_compression(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} are compressed, and this build has no \"compression\" feature",
+            what
+        ),
+    )
+}
+
+/// An installed dictionary file, in whichever format it came in.
+pub enum Dictionary {
+    Slob(Slob),
+    Dsl(Dsl),
+    /// An `.mdx` file and the `.mdd` resources next to it, if there are any.
+    Mdict {
+        path: PathBuf,
+        mdx: Mdict,
+        mdd: Option<Mdict>,
+    },
+    Zim(Zim),
+    Tei {
+        tei: Tei,
+        /// The language headwords are in, from FreeDict's `eng-deu` naming.
+        source: Option<&'static str>,
+    },
+}
+impl Dictionary {
+    /// Opens `path` if its extension is a supported format. Companion files
+    /// (`.mdd`) aren't dictionaries of their own and give `None`.
+    pub fn open(path: &Path) -> Option<io::Result<Self>> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".slob") {
+            Some(Slob::open(path).map(Dictionary::Slob))
+        } else if name.ends_with(".dsl") || name.ends_with(".dsl.dz") {
+            Some(Dsl::open(path).map(Dictionary::Dsl))
+        } else if name.ends_with(".tei") {
+            let source = name
+                .split(['-', '.'])
+                .next()
+                .and_then(|code| {
+                    wiktionary::LANGUAGES
+                        .iter()
+                        .find(|(_, iso3, _)| *iso3 == code)
+                })
+                .map(|(code, _, _)| *code);
+            Some(Tei::open(path).map(|tei| Dictionary::Tei { tei, source }))
+        } else if name.ends_with(".zim") {
+            Some(Zim::open(path).map(Dictionary::Zim))
+        } else if name.ends_with(".mdx") {
+            let mdd = path.with_extension("mdd");
+            Some(Mdict::open(path).map(|mdx| Dictionary::Mdict {
+                path: path.to_path_buf(),
+                mdx,
+                mdd: Mdict::open(&mdd).ok(),
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// The words the dictionary has articles for, for the suggestion index.
+    pub fn headwords(&self) -> io::Result<Vec<String>> {
+        match self {
+            Dictionary::Slob(slob) => slob.headwords(),
+            Dictionary::Dsl(dsl) => Ok(dsl.headwords()),
+            Dictionary::Mdict { mdx, .. } => mdx.headwords(),
+            Dictionary::Zim(zim) => zim.headwords(),
+            Dictionary::Tei { tei, .. } => Ok(tei.headwords()),
+        }
+    }
+
+    /// Entries for `word`, keeping only the `lang` section of multilingual articles.
+    pub fn lookup(&self, word: &str, lang: &str) -> io::Result<Vec<DefinitionResponse>> {
+        Ok(match self {
+            Dictionary::Slob(slob) => slob
+                .lookup(word)?
+                .into_iter()
+                .filter_map(|article| from_article(article, lang))
+                .collect(),
+            Dictionary::Dsl(dsl) => dsl.lookup(word),
+            Dictionary::Mdict { path, mdx, mdd } => {
+                let mut entries = Vec::new();
+                for record in mdx.lookup(word)? {
+                    let mut article = record_text(&record);
+                    // Alternative spellings are stored as links to the main article.
+                    if let Some(target) = article.trim().strip_prefix(LINK) {
+                        let target = target.trim().to_string();
+                        let Some(record) = mdx.lookup(&target)?.into_iter().next() else {
+                            continue;
+                        };
+                        article = record_text(&record);
+                    }
+                    let audio = mdd
+                        .as_ref()
+                        .and_then(|mdd| extract_sound(path, mdd, &article));
+                    entries.extend(from_html(word, &article, lang, audio));
+                }
+                entries
+            }
+            Dictionary::Zim(zim) => match zim.article(word)? {
+                Some((mime_type, content)) if mime_type.starts_with("text/html") => {
+                    from_html(word, &String::from_utf8_lossy(&content), lang, None)
+                        .into_iter()
+                        .collect()
+                }
+                _ => Vec::new(),
+            },
+            // A bilingual dictionary only answers for its source language.
+            Dictionary::Tei { tei, source } => match source.is_none_or(|source| source == lang) {
+                true => tei.lookup(word),
+                false => Vec::new(),
+            },
+        })
+    }
+}
+
+/// The files in [`dictionaries_dir`] and the folder itself, whose time changes
+/// when one is added or removed: what the suggestion index is built from.
+pub fn sources() -> Vec<PathBuf> {
+    let dir = dictionaries_dir();
+    let mut sources: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|dir| dir.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    sources.push(dir);
+    sources
+}
+
+/// Every supported dictionary in [`dictionaries_dir`], opened once per process
+/// and sorted by file name. Files that fail to open are reported and skipped.
+pub fn installed() -> &'static [(PathBuf, Dictionary)] {
+    static INSTALLED: OnceLock<Vec<(PathBuf, Dictionary)>> = OnceLock::new();
+    INSTALLED.get_or_init(|| {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dictionaries_dir())
+            .map(|dir| dir.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        paths.sort();
+        paths
+            .into_iter()
+            .filter_map(|path| match Dictionary::open(&path)? {
+                Ok(dictionary) => Some((path, dictionary)),
+                Err(e) => {
+                    eprintln!("Warning: skipping {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Looks `word` up in every installed dictionary, in file-name order. One
+/// that fails to read is reported and skipped; it's an error only when they
+/// all fail.
+pub fn lookup(word: &str, lang: &str) -> io::Result<Lookup> {
+    let (mut entries, mut read) = (Vec::new(), false);
+    let mut failed = None;
+    for (path, dictionary) in installed() {
+        match dictionary.lookup(word, lang) {
+            Ok(found) => {
+                entries.extend(found);
+                read = true;
+            }
+            Err(e) => {
+                eprintln!("Warning: couldn't read {}: {}", path.display(), e);
+                failed = Some(e);
+            }
+        }
+    }
+    if let Some(e) = failed.filter(|_| !read) {
+        return Err(e);
+    }
+    Ok(if entries.is_empty() {
+        Lookup::NotFound
+    } else {
+        Lookup::Found(entries)
+    })
+}
+
+fn from_article(article: Article, lang: &str) -> Option<DefinitionResponse> {
+    let content = String::from_utf8_lossy(&article.content);
+    if article.content_type.starts_with("text/html") {
+        from_html(&article.key, &content, lang, None)
+    } else if article.content_type.starts_with("text/plain") {
+        let meanings = vec![meaning("definition", vec![content.trim().to_string()])];
+        Some(entry(&article.key, None, None, meanings))
+    } else {
+        // Images, CSS and other resources bundled with the articles.
+        None
+    }
+}
+
+fn from_html(
+    word: &str,
+    article: &str,
+    lang: &str,
+    audio: Option<String>,
+) -> Option<DefinitionResponse> {
+    let article = language_section(article, lang).unwrap_or(article);
+    let meanings = html_meanings(article);
+    if meanings.is_empty() {
+        return None;
+    }
+    Some(entry(word, html_ipa(article), audio, meanings))
+}
+
+fn entry(
+    word: &str,
+    ipa: Option<String>,
+    audio: Option<String>,
+    meanings: Vec<Meaning>,
+) -> DefinitionResponse {
+    let phonetics = if ipa.is_some() || audio.is_some() {
+        vec![Phonetic {
+            text: ipa.clone(),
+            audio,
+            source_url: None,
+            license: None,
+        }]
+    } else {
+        Vec::new()
+    };
+    DefinitionResponse {
+        word: word.to_string(),
+        phonetic: ipa,
+        phonetics,
+        meanings,
+        source_urls: None,
+        origin: None,
+        provider: None,
+    }
+}
+
+/// MDX articles are NUL-terminated.
+fn record_text(record: &[u8]) -> String {
+    String::from_utf8_lossy(record)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// Copies the first `sound://` resource an MDX article links to out of its
+/// `.mdd` into the cache, since players need a real file. Returns its URL.
+fn extract_sound(dictionary: &Path, mdd: &Mdict, article: &str) -> Option<String> {
+    let start = article.find(SOUND)? + SOUND.len();
+    let end = article[start..].find(['"', '\'', ' ', '>'])? + start;
+    let name = &article[start..end];
+    let key = format!("\\{}", name.replace('/', "\\"));
+    let data = mdd.lookup(&key).ok()?.into_iter().next()?;
+
+    let stem = dictionary.file_stem()?.to_string_lossy();
+    let file_name = Path::new(name).file_name()?;
+    let path = storage::cache_dir()
+        .join("audio")
+        .join(&*stem)
+        .join(file_name);
+    if !path.exists() {
+        storage::replace_file(&path, &data).ok()?;
+    }
+    Some(format!("file://{}", path.display()))
+}
+
+/// Meanings from a Wiktionary-style article: a definition list under each
+/// part-of-speech heading. Articles without that structure become a single
+/// sense holding their text.
+fn html_meanings(article: &str) -> Vec<Meaning> {
+    let headings = html::headings(article);
+    let mut meanings = Vec::new();
+    for (i, (_, title, range)) in headings.iter().enumerate() {
+        let pos = title.to_lowercase();
+        if !PARTS_OF_SPEECH.contains(&pos.as_str()) {
+            continue;
+        }
+        let end = headings.get(i + 1).map_or(article.len(), |h| h.2.start);
+        let section = &article[range.end..end];
+        let Some(list) = html::elements(section, "ol").into_iter().next() else {
+            continue;
+        };
+        let definitions: Vec<Definition> = html::list_items(&section[list.clone()])
+            .into_iter()
+            .filter_map(definition)
+            .collect();
+        if !definitions.is_empty() {
+            meanings.push(Meaning {
+                part_of_speech: pos,
+                definitions,
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender: html_gender(&section[..list.start]),
+            });
+        }
+    }
+    if meanings.is_empty() {
+        let text = html::text(article);
+        if !text.is_empty() {
+            meanings.push(meaning("definition", vec![text]));
+        }
+    }
+    meanings
+}
+
+/// The gender Wiktionary's headword line gives a noun, in
+/// `<span class="gender"><abbr title="masculine gender">m</abbr></span>`.
+fn html_gender(headword_line: &str) -> Option<Gender> {
+    // The headword line is a span too, so look from the gender's own.
+    let start = headword_line.find("<span class=\"gender\"")?;
+    let rest = &headword_line[start..];
+    let span = html::elements(rest, "span").into_iter().next()?;
+    Gender::parse(&html::text(&rest[span]))
+}
+
+/// A sense from a definition list item. Usage examples sit in a nested `<dl>`,
+/// quotations and subsenses in nested lists.
+fn definition(item: &str) -> Option<Definition> {
+    let example = html::elements(item, "dd")
+        .into_iter()
+        .next()
+        .map(|range| html::text(&item[range]))
+        .filter(|text| !text.is_empty());
+    let mut gloss = item.to_string();
+    for tag in ["dl", "ul", "ol"] {
+        gloss = html::without(&gloss, tag);
+    }
+    let gloss = html::text(&gloss);
+    (!gloss.is_empty()).then_some(Definition {
+        definition: gloss,
+        example,
+        synonyms: None,
+        antonyms: None,
+        level: None,
+        region: None,
+        labels: Vec::new(),
+    })
+}
+
+fn meaning(part_of_speech: &str, definitions: Vec<String>) -> Meaning {
+    Meaning {
+        part_of_speech: part_of_speech.to_string(),
+        definitions: definitions
+            .into_iter()
+            .map(|definition| Definition {
+                definition,
+                example: None,
+                synonyms: None,
+                antonyms: None,
+                level: None,
+                region: None,
+                labels: Vec::new(),
+            })
+            .collect(),
+        synonyms: None,
+        antonyms: None,
+        forms: None,
+        gender: None,
+    }
+}
+
+/// The part of a multilingual article under the `<h2>` for `lang`, if it has one.
+fn language_section<'a>(article: &'a str, lang: &str) -> Option<&'a str> {
+    let name = wiktionary::LANGUAGES
+        .iter()
+        .find(|(code, _, _)| *code == lang)
+        .map(|(_, _, name)| *name)?;
+    let headings = html::headings(article);
+    let mut top = headings.iter().filter(|h| h.0 <= 2);
+    let start = top.by_ref().find(|h| h.1 == name)?.2.end;
+    let end = top.next().map_or(article.len(), |h| h.2.start);
+    Some(&article[start..end])
+}
+
+/// The first IPA transcription, which Wiktionary marks with `class="IPA"`.
+fn html_ipa(article: &str) -> Option<String> {
+    let start = article.find("class=\"IPA\"")?;
+    let open_end = start + article[start..].find('>')? + 1;
+    let close = open_end + article[open_end..].find("</")?;
+    let ipa = html::text(&article[open_end..close]);
+    (!ipa.is_empty()).then_some(ipa)
+}
@@ -0,0 +1,361 @@
+use futures_lite::AsyncReadExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{http, offline, providers::Provider, storage, text};
+
+/// The curated packs, unless the user keeps their own `packs.json` in the
+/// config dir.
+const CATALOG: &str = include_str!("../assets/packs.json");
+/// How long a download may go without receiving anything before it's given
+/// up, to be resumed by the next `dict install`.
+const STALL: Duration = Duration::from_secs(60);
+
+/// A downloadable dictionary from the catalog.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pack {
+    pub id: String,
+    pub description: String,
+    pub url: String,
+    /// Name the dictionary gets in [`offline::dictionaries_dir`].
+    pub file: String,
+    /// Expected SHA-256 of the download, in hex.
+    pub sha256: Option<String>,
+    /// Where the publisher lists the SHA-256, as `sha256sum` prints it.
+    pub checksum_url: Option<String>,
+    /// For `.tar.xz` downloads, the member to install.
+    pub archive_path: Option<String>,
+    /// The provider that reads the dictionary, when it isn't `offline`.
+    #[serde(default)]
+    pub provider: Option<Provider>,
+}
+
+/// A pack on disk, as recorded in the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Installed {
+    id: String,
+    file: String,
+    url: String,
+    sha256: String,
+    size: u64,
+    /// Seconds since the Unix epoch.
+    installed: u64,
+}
+
+#[derive(Debug)]
+pub enum Action {
+    /// A catalog id, or the URL of any supported dictionary file.
+    Install(String),
+    List,
+    Remove(String),
+}
+
+pub async fn run(action: Action) -> Result<(), Box<dyn Error>> {
+    match action {
+        Action::List => list(),
+        Action::Install(name) => install(&name).await,
+        Action::Remove(id) => remove(&id),
+    }
+}
+
+pub fn catalog() -> Vec<Pack> {
+    let path = storage::config_dir().join("packs.json");
+    if let Ok(text) = fs::read_to_string(&path) {
+        match serde_json::from_str(&text) {
+            Ok(packs) => return packs,
+            Err(e) => eprintln!("Warning: ignoring {}: {}", path.display(), e),
+        }
+    }
+    serde_json::from_str(CATALOG).expect("built-in pack catalog is valid")
+}
+
+fn manifest_path() -> PathBuf {
+    offline::dictionaries_dir().join("manifest.json")
+}
+
+fn list() -> Result<(), Box<dyn Error>> {
+    let installed: Vec<Installed> = storage::load_json(&manifest_path());
+    let catalog = catalog();
+    let mut rows = vec![vec![
+        String::from("PACK"),
+        String::from("STATUS"),
+        String::from("DESCRIPTION"),
+    ]];
+    for pack in &catalog {
+        let status = match installed.iter().find(|i| i.id == pack.id) {
+            Some(i) => format!("installed, {}", text::size(i.size)),
+            None => String::from("-"),
+        };
+        rows.push(vec![pack.id.clone(), status, pack.description.clone()]);
+    }
+    // Packs installed straight from a URL.
+    for i in installed
+        .iter()
+        .filter(|i| !catalog.iter().any(|p| p.id == i.id))
+    {
+        rows.push(vec![
+            i.id.clone(),
+            format!("installed, {}", text::size(i.size)),
+            i.url.clone(),
+        ]);
+    }
+    print!("{}", text::table(&rows));
+    Ok(())
+}
+
+async fn install(name: &str) -> Result<(), Box<dyn Error>> {
+    let catalog = catalog();
+    let (pack, listed) = match catalog.iter().find(|p| p.id == name) {
+        Some(pack) => (pack.clone(), true),
+        None if name.starts_with("https://") || name.starts_with("http://") => {
+            // A listed pack's URL still installs it where its provider
+            // looks for it.
+            match catalog.iter().find(|p| p.url == name) {
+                Some(pack) => (pack.clone(), false),
+                None => (from_url(name)?, false),
+            }
+        }
+        None => return Err(format!("Unknown pack '{}'. See 'dict list'.", name).into()),
+    };
+    // A listed pack is only ever installed verified; a URL the user gave is
+    // theirs to vouch for.
+    if listed && pack.sha256.is_none() && pack.checksum_url.is_none() {
+        return Err(format!(
+            "The pack list has no checksum to verify {} with, so it isn't installed. \
+             Add its \"sha256\" to {}, or install it from its URL unverified.",
+            pack.id,
+            storage::config_dir().join("packs.json").display()
+        )
+        .into());
+    }
+    let dir = offline::dictionaries_dir();
+    fs::create_dir_all(&dir)?;
+    let download = dir.join(format!("{}.part", pack.file));
+    fetch(&pack.url, &download).await?;
+
+    eprintln!("Verifying…");
+    let digest = sha256(&download)?;
+    let expected = match &pack.checksum_url {
+        Some(url) => Some(published_checksum(url).await?),
+        None => pack.sha256.clone(),
+    };
+    match expected {
+        Some(expected) if !expected.eq_ignore_ascii_case(&digest) => {
+            fs::remove_file(&download)?;
+            return Err(format!(
+                "Checksum mismatch for {} (expected {}, got {}). The download was deleted; try again.",
+                pack.id, expected, digest
+            )
+            .into());
+        }
+        Some(_) => {}
+        None => eprintln!(
+            "Warning: nothing to verify {} against; installing it as downloaded (SHA-256 {})",
+            pack.url, digest
+        ),
+    }
+
+    let target = dir.join(&pack.file);
+    match &pack.archive_path {
+        Some(member) => {
+            extract(&download, member, &target)?;
+            fs::remove_file(&download)?;
+        }
+        None => fs::rename(&download, &target)?,
+    }
+    let provider = pack.provider.unwrap_or(Provider::Offline);
+    match offline::Dictionary::open(&target) {
+        _ if provider != Provider::Offline => {}
+        Some(Ok(_)) => {}
+        Some(Err(e)) => eprintln!("Warning: {} doesn't open: {}", target.display(), e),
+        None => eprintln!(
+            "Warning: {} isn't a format the offline provider reads",
+            pack.file
+        ),
+    }
+
+    let mut manifest: Vec<Installed> = storage::load_json(&manifest_path());
+    manifest.retain(|i| i.id != pack.id);
+    manifest.push(Installed {
+        id: pack.id.clone(),
+        file: pack.file.clone(),
+        url: pack.url.clone(),
+        sha256: digest,
+        size: fs::metadata(&target)?.len(),
+        installed: now(),
+    });
+    storage::save_json(&manifest_path(), &manifest)?;
+    println!(
+        "Installed {}. Look words up in it with --provider {}.",
+        pack.id, provider
+    );
+    Ok(())
+}
+
+fn remove(id: &str) -> Result<(), Box<dyn Error>> {
+    let mut manifest: Vec<Installed> = storage::load_json(&manifest_path());
+    let Some(position) = manifest.iter().position(|i| i.id == id) else {
+        return Err(format!("'{}' isn't installed", id).into());
+    };
+    let pack = manifest.remove(position);
+    let dir = offline::dictionaries_dir();
+    for path in [
+        dir.join(&pack.file),
+        dir.join(format!("{}.part", pack.file)),
+        storage::cache_dir()
+            .join("tei")
+            .join(format!("{}.fst", pack.file)),
+    ] {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    storage::save_json(&manifest_path(), &manifest)?;
+    println!("Removed {}.", pack.id);
+    Ok(())
+}
+
+/// A pack for a dictionary file given by URL, named after the file.
+fn from_url(url: &str) -> Result<Pack, Box<dyn Error>> {
+    let file = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|file| !file.is_empty())
+        .ok_or("That URL doesn't name a file")?;
+    let id = file.split('.').next().unwrap_or(file).to_string();
+    Ok(Pack {
+        id,
+        description: String::new(),
+        url: url.to_string(),
+        file: file.to_string(),
+        sha256: None,
+        checksum_url: None,
+        archive_path: None,
+        provider: None,
+    })
+}
+
+/// Downloads `url` to `path`, resuming where an earlier attempt stopped if the
+/// server supports ranges.
+async fn fetch(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let have = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let client = http::downloads().with(surf::middleware::Redirect::default());
+    let mut request = client.get(url);
+    if have > 0 {
+        request = request.header("Range", format!("bytes={}-", have));
+    }
+    let mut response = request.await?;
+    // There's nothing after the end: an earlier attempt got all of it, and
+    // the checksum tells whether it's right.
+    if have > 0 && response.status() == surf::StatusCode::RequestedRangeNotSatisfiable {
+        return Ok(());
+    }
+    let resumed = response.status() == surf::StatusCode::PartialContent;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: {} for {}", response.status(), url).into());
+    }
+    let mut done = if resumed { have } else { 0 };
+    let total = response.len().map(|len| len as u64 + done);
+    if resumed {
+        eprintln!("Resuming at {}", text::size(have));
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(path)?;
+
+    let mut body = response.take_body().into_reader();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = tokio::time::timeout(STALL, body.read(&mut buffer))
+            .await
+            .map_err(|_| {
+                format!(
+                    "The download stalled at {}; run 'dict install' again to resume it",
+                    text::size(done)
+                )
+            })??;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        done += read as u64;
+        match total {
+            Some(total) => eprint!("\r{} of {}", text::size(done), text::size(total)),
+            None => eprint!("\r{}", text::size(done)),
+        }
+    }
+    eprintln!();
+    Ok(())
+}
+
+async fn published_checksum(url: &str) -> Result<String, Box<dyn Error>> {
+    let client = http::client().with(surf::middleware::Redirect::default());
+    let listing = client.get(url).recv_string().await?;
+    listing
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| format!("No checksum at {}", url).into())
+}
+
+fn sha256(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Copies `member` out of the `.tar.xz` at `archive` to `target`.
+#[cfg(feature = "compression")]
+fn extract(archive: &Path, member: &str, target: &Path) -> Result<(), Box<dyn Error>> {
+    let mut tar = Vec::new();
+    let mut compressed = io::BufReader::new(fs::File::open(archive)?);
+    lzma_rs::xz_decompress(&mut compressed, &mut tar)
+        .map_err(|e| format!("Couldn't unpack {}: {}", archive.display(), e))?;
+    let mut entries = tar::Archive::new(&tar[..]);
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.ends_with(member) {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            storage::replace_file(target, &contents)?;
+            return Ok(());
+        }
+    }
+    Err(format!("{} has no {}", archive.display(), member).into())
+}
+
+#[cfg(not(feature = "compression"))]
+fn extract(archive: &Path, _: &str, _: &Path) -> Result<(), Box<dyn Error>> {
+    Err(offline::without_compression(&archive.display().to_string()).into())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,548 @@
+use serde::Deserialize;
+use std::{fmt, path::PathBuf};
+
+#[cfg(feature = "tui")]
+use crate::desktop::Tts;
+use crate::{
+    api::{self, Accent, DefinitionResponse, Lookup},
+    cedict,
+    cefr::{self, Level},
+    cmudict,
+    config::Config,
+    content_filter::{self, ContentFilter},
+    domains::Domain,
+    hindi, jargon, jisho, krdict,
+    labels::{self, Label},
+    merge::{self, MergePolicy},
+    offline,
+    query::Query,
+    regional,
+    routes::Route,
+    rules::Rules,
+    segment,
+    sherlock::Protocol,
+    simple, simple_wiktionary,
+    spelling::{self, KeyboardLayout},
+    urban, wikidata,
+    wikipedia::{self, Summary},
+    wordlist::WordList,
+};
+
+/// Content words of a phrase tried, rarest first, before giving up on it.
+const SEGMENT_TRIES: usize = 4;
+
+/// A source of definitions. Every provider answers with the dictionaryapi.dev
+/// entry model so the frontends don't need to care where an entry came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Provider {
+    DictionaryApi,
+    /// Dictionaries installed in [`offline::dictionaries_dir`].
+    Offline,
+    /// Wikidata lexemes: senses, inflected forms and glosses in many languages.
+    Wikidata,
+    /// Programming terms and hacker jargon, from a curated list and the
+    /// Jargon File.
+    Jargon,
+    /// Slang from Urban Dictionary.
+    Urban,
+    /// Japanese words from Jisho, looked up by kanji, kana or English.
+    Jisho,
+    /// Chinese words from an installed CC-CEDICT, looked up by characters or
+    /// pinyin.
+    Cedict,
+    /// Korean words from the Basic Korean Dictionary, with definitions in
+    /// English or one of ten other languages.
+    Krdict,
+    /// Hindi words from Wiktionary, romanized.
+    Hindi,
+    /// English words from Simple English Wiktionary, defined for learners;
+    /// `simple` asks it first.
+    SimpleWiktionary,
+    /// A specialty glossary, named after its domain.
+    Domain(Domain),
+}
+impl Provider {
+    pub const ALL: &'static [Provider] = &[
+        Provider::DictionaryApi,
+        Provider::Offline,
+        Provider::Wikidata,
+        Provider::Jargon,
+        Provider::Urban,
+        Provider::Jisho,
+        Provider::Cedict,
+        Provider::Krdict,
+        Provider::Hindi,
+        Provider::SimpleWiktionary,
+        Provider::Domain(Domain::Medical),
+        Provider::Domain(Domain::Legal),
+        Provider::Domain(Domain::Technical),
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::DictionaryApi => "dictionaryapi",
+            Provider::Offline => "offline",
+            Provider::Wikidata => "wikidata",
+            Provider::Jargon => "jargon",
+            Provider::Urban => "urban",
+            Provider::Jisho => "jisho",
+            Provider::Cedict => "cedict",
+            Provider::Krdict => "krdict",
+            Provider::Hindi => "hindi",
+            Provider::SimpleWiktionary => "simplewiktionary",
+            Provider::Domain(domain) => domain.name(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.name() == name)
+    }
+
+    /// Parses a comma-separated fallback chain such as `offline,dictionaryapi`.
+    pub fn parse_chain(names: &str) -> Result<Vec<Self>, String> {
+        names
+            .split(',')
+            .map(|name| {
+                Self::from_name(name.trim()).ok_or_else(|| format!("Unknown provider '{}'", name))
+            })
+            .collect()
+    }
+
+    /// The provider that stands in for this one for words in `lang`.
+    /// dictionaryapi.dev has next to no Korean or Hindi, so it hands those
+    /// to krdict, when there's a key for it, and to Wiktionary's Hindi.
+    pub fn for_lang(self, lang: &str) -> Self {
+        match (self, lang) {
+            (Provider::DictionaryApi, "ko") if Config::load().krdict_key.is_some() => {
+                Provider::Krdict
+            }
+            (Provider::DictionaryApi, "hi") => Provider::Hindi,
+            (provider, _) => provider,
+        }
+    }
+
+    pub async fn lookup(self, word: &str, lang: &str) -> Result<Lookup, surf::Error> {
+        match self {
+            Provider::DictionaryApi => api::lookup(word, lang).await,
+            Provider::Offline => Ok(offline::lookup(word, lang)?),
+            Provider::Wikidata => wikidata::lookup(word, lang).await,
+            Provider::Jargon => Ok(jargon::lookup(word, lang)?),
+            Provider::Urban => urban::lookup(word).await,
+            Provider::Jisho => jisho::lookup(word).await,
+            Provider::Cedict => Ok(cedict::lookup(word)?),
+            Provider::Krdict => krdict::lookup(word, lang).await,
+            Provider::Hindi => hindi::lookup(word).await,
+            Provider::SimpleWiktionary => simple_wiktionary::lookup(word).await,
+            Provider::Domain(domain) => domain.lookup(word, lang).await,
+        }
+    }
+}
+impl TryFrom<String> for Provider {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        Self::from_name(&name).ok_or_else(|| format!("unknown provider '{}'", name))
+    }
+}
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What to look words up in and how to present them. Starts out from the
+/// config file and is adjustable per invocation or mid-session.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Tried in order until one has an entry. Never empty.
+    pub providers: Vec<Provider>,
+    /// What to do when more than one provider knows the word.
+    pub merge_policy: MergePolicy,
+    /// Language code as understood by the provider, e.g. `en`.
+    pub lang: String,
+    pub content_filter: ContentFilter,
+    /// Learner-friendly output: fewer senses, no jargon, respelled pronunciation.
+    pub simple: bool,
+    /// Tag senses with an estimated CEFR level.
+    pub cefr: bool,
+    /// Hide senses graded above this level; implies `cefr`.
+    pub max_level: Option<Level>,
+    pub keyboard_layout: KeyboardLayout,
+    pub rules: Rules,
+    /// Pronunciations to show first when there are several.
+    pub accent: Option<Accent>,
+    /// Speaks words that have no pronunciation audio.
+    #[cfg(feature = "tui")]
+    pub tts: Tts,
+    /// Directory of text files `concordance` searches.
+    pub corpus: Option<PathBuf>,
+    /// Specialty glossary consulted before the rest of the chain.
+    pub domain: Option<Domain>,
+    /// Per-query provider chains, checked before the lookup.
+    pub routes: Vec<Route>,
+    /// Only show senses with this part of speech.
+    pub part_of_speech: Option<String>,
+    /// Hide senses with any of these usage labels.
+    pub exclude_labels: Vec<Label>,
+    /// Look up the query as typed: no dictionary forms, other spellings,
+    /// suggestions or Wikipedia.
+    pub exact: bool,
+    /// Look up the closest spelling when the query has no entry.
+    pub fuzzy: bool,
+    /// How many of the most common words the user knows, if they said.
+    pub vocabulary: Option<usize>,
+    /// Leave lookups out of the history, session and caches.
+    pub private: bool,
+    /// Connect over IPv4 only, for networks where IPv6 is broken.
+    pub ipv4_only: bool,
+    /// Answer from the response cache, however old, without going online.
+    pub cached: bool,
+    /// The config profile these came from.
+    pub profile: Option<String>,
+    /// The language labels and messages are shown in, as `--ui-lang` takes
+    /// it; the environment's when unset.
+    pub ui_lang: Option<String>,
+    /// The version of the pipe format tiles are printed in.
+    pub protocol: Protocol,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            providers: vec![Provider::DictionaryApi],
+            merge_policy: MergePolicy::default(),
+            lang: String::from("en"),
+            content_filter: ContentFilter::default(),
+            simple: false,
+            cefr: false,
+            max_level: None,
+            keyboard_layout: KeyboardLayout::default(),
+            rules: Rules::default(),
+            accent: None,
+            #[cfg(feature = "tui")]
+            tts: Tts::default(),
+            corpus: None,
+            domain: None,
+            routes: Vec::new(),
+            part_of_speech: None,
+            exclude_labels: Vec::new(),
+            exact: false,
+            fuzzy: false,
+            vocabulary: None,
+            private: false,
+            ipv4_only: false,
+            cached: false,
+            profile: None,
+            ui_lang: None,
+            protocol: Protocol::default(),
+        }
+    }
+}
+impl From<&Config> for Settings {
+    fn from(config: &Config) -> Self {
+        let mut settings = Self {
+            content_filter: config.content_filter,
+            keyboard_layout: config.keyboard_layout,
+            merge_policy: config.merge_policy,
+            rules: config.rules.clone(),
+            accent: config.accent,
+            #[cfg(feature = "tui")]
+            tts: config.tts.clone(),
+            corpus: config.corpus.clone(),
+            domain: config.domain,
+            routes: config.routes.clone(),
+            vocabulary: config.vocabulary,
+            private: config.private,
+            ipv4_only: config.ipv4_only,
+            simple: config.simple,
+            cefr: config.cefr,
+            max_level: config.max_level,
+            exclude_labels: config.exclude_labels.clone(),
+            profile: crate::config::profile().map(String::from),
+            ui_lang: config.ui_lang.clone(),
+            protocol: config.protocol,
+            ..Self::default()
+        };
+        if !config.providers.is_empty() {
+            settings.providers = config.providers.clone();
+        }
+        if let Some(lang) = &config.lang {
+            settings.lang = lang.clone();
+        }
+        settings
+    }
+}
+impl Settings {
+    /// The provider chain as `--provider` takes it.
+    pub fn providers_name(&self) -> String {
+        let names: Vec<&str> = self.providers.iter().map(|p| p.name()).collect();
+        names.join(",")
+    }
+
+    /// The settings to look `input` up with, and the word to look up. The
+    /// first route that matches swaps in its providers and language and has
+    /// its prefix removed, then the modifiers typed into the query apply.
+    /// With `--exact`, neither does: the query is looked up as it is.
+    pub fn interpret(&self, input: &str) -> (Settings, String) {
+        if self.exact {
+            return (self.clone(), input.trim().to_string());
+        }
+        let mut settings = self.clone();
+        let mut input = input;
+        if let Some((route, rest)) = self
+            .routes
+            .iter()
+            .find_map(|route| Some((route, route.matches(input)?)))
+        {
+            if !route.providers.is_empty() {
+                settings.providers = route.providers.clone();
+            }
+            if let Some(lang) = &route.lang {
+                settings.lang = lang.clone();
+            }
+            input = rest;
+        }
+        let query = Query::parse(input);
+        if let Some(lang) = query.lang {
+            settings.lang = lang;
+        }
+        if query.part_of_speech.is_some() {
+            settings.part_of_speech = query.part_of_speech;
+        }
+        settings.exact |= query.exact;
+        settings.fuzzy |= query.fuzzy;
+        (settings, query.word)
+    }
+
+    pub async fn lookup(&self, word: &str) -> Result<Lookup, surf::Error> {
+        Ok(self.lookup_with_sources(word).await?.1)
+    }
+
+    /// Walks the provider chain, moving on when a provider has no entry or
+    /// fails, and combines what was found according to the merge policy.
+    /// Returns the providers that answered, or the most useful failure: a
+    /// "not found" beats an error from a provider that couldn't be reached.
+    async fn lookup_with_sources(
+        &self,
+        word: &str,
+    ) -> Result<(Vec<Provider>, Lookup), surf::Error> {
+        let mut sources = Vec::new();
+        let mut found = Vec::new();
+        let mut failure = None;
+        for provider in self.chain() {
+            match provider.lookup(word, &self.lang).await {
+                Ok(Lookup::Found(entries)) => {
+                    sources.push(provider);
+                    found.extend(entries.into_iter().map(|entry| DefinitionResponse {
+                        provider: Some(provider),
+                        ..entry
+                    }));
+                    if !self.merge_policy.asks_all() {
+                        break;
+                    }
+                }
+                Ok(Lookup::NotFound) => failure = Some(Ok(Lookup::NotFound)),
+                other => {
+                    if !matches!(failure, Some(Ok(Lookup::NotFound))) {
+                        failure = Some(other);
+                    }
+                }
+            }
+        }
+        if found.is_empty() {
+            return Ok((sources, failure.unwrap_or(Ok(Lookup::NotFound))?));
+        }
+        if self.merge_policy == MergePolicy::InterleaveByPos && sources.len() > 1 {
+            found = merge::interleave(found);
+        }
+        Ok((sources, Lookup::Found(found)))
+    }
+
+    /// The providers asked, in the order they're asked in.
+    fn chain(&self) -> Vec<Provider> {
+        let mut chain = self.providers.clone();
+        if self.merge_policy == MergePolicy::PreferOffline {
+            // Stable, so the configured order holds otherwise.
+            chain.sort_by_key(|&provider| provider != Provider::Offline);
+        }
+        // Definitions written for learners beat the shortest of the rest.
+        if self.simple && self.lang == "en" {
+            chain.retain(|&provider| provider != Provider::SimpleWiktionary);
+            chain.insert(0, Provider::SimpleWiktionary);
+        }
+        if let Some(domain) = self.domain {
+            chain.retain(|&provider| provider != Provider::Domain(domain));
+            chain.insert(0, Provider::Domain(domain));
+        }
+        chain
+            .into_iter()
+            .map(|provider| provider.for_lang(&self.lang))
+            .collect()
+    }
+
+    /// Whether dictionaryapi.dev's entries can be shown as they're read (see
+    /// [`api::stream_to`]): it's asked first, so whatever it has is the
+    /// answer, nothing is merged into them, and each is prepared on its own.
+    /// `simple` counts senses across entries, so it has to see them all.
+    pub fn streams(&self) -> bool {
+        self.chain().first() == Some(&Provider::DictionaryApi)
+            && !self.merge_policy.asks_all()
+            && !self.simple
+    }
+
+    /// Looks `query` up through the provider chain, falling back to its
+    /// dictionary form ("running" → "run"), then to its spelling on the other
+    /// side of the Atlantic ("colour" → "color"), then with `fuzzy` to the
+    /// closest spelling, when the query itself has no entry. `exact` turns
+    /// all of that off.
+    pub async fn resolve(&self, query: &str) -> Result<Resolved, surf::Error> {
+        let (sources, lookup) = self.lookup_with_sources(query).await?;
+        let mut tried = Vec::new();
+        if matches!(lookup, Lookup::NotFound) && !self.exact {
+            let fuzzy = match self.fuzzy {
+                true => spelling::suggest(query, self, 1),
+                false => Vec::new(),
+            };
+            let fallbacks = spelling::lemma(query, &self.lang)
+                .into_iter()
+                .chain(regional::variants(query, &self.lang))
+                .chain(fuzzy);
+            for fallback in fallbacks {
+                // The query has no entry, so a form that can't be looked up
+                // is one more without one rather than a failed lookup.
+                let (sources, fallback_lookup) = match self.lookup_with_sources(&fallback).await {
+                    Ok(looked_up) => looked_up,
+                    Err(e) => {
+                        eprintln!("Warning: couldn't look up '{}': {}", fallback, e);
+                        tried.push(fallback);
+                        continue;
+                    }
+                };
+                if matches!(fallback_lookup, Lookup::Found(_)) {
+                    return Ok(Resolved {
+                        query: query.to_string(),
+                        word: fallback,
+                        sources,
+                        lookup: fallback_lookup,
+                        summary: None,
+                        others: Vec::new(),
+                        tried,
+                    });
+                }
+                tried.push(fallback);
+            }
+        }
+        // Names and places aren't in dictionaries, but Wikipedia knows them.
+        // It's only a fallback, so failing to reach it isn't an error.
+        let summary = match matches!(lookup, Lookup::NotFound)
+            && !self.exact
+            && wikipedia::looks_like_name(query)
+        {
+            true => wikipedia::summary(query, &self.lang).await.ok().flatten(),
+            false => None,
+        };
+        // A pasted phrase is usually there for its one unusual word.
+        if matches!(lookup, Lookup::NotFound)
+            && summary.is_none()
+            && !self.exact
+            && query.split_whitespace().count() > 1
+        {
+            let candidates = segment::content_words(query);
+            for candidate in candidates.iter().take(SEGMENT_TRIES) {
+                let (sources, candidate_lookup) = match self.lookup_with_sources(candidate).await {
+                    Ok(looked_up) => looked_up,
+                    Err(e) => {
+                        eprintln!("Warning: couldn't look up '{}': {}", candidate, e);
+                        tried.push(candidate.clone());
+                        continue;
+                    }
+                };
+                if matches!(candidate_lookup, Lookup::Found(_)) {
+                    return Ok(Resolved {
+                        query: query.to_string(),
+                        word: candidate.clone(),
+                        sources,
+                        lookup: candidate_lookup,
+                        summary: None,
+                        others: candidates
+                            .iter()
+                            .filter(|c| *c != candidate)
+                            .cloned()
+                            .collect(),
+                        tried,
+                    });
+                }
+                tried.push(candidate.clone());
+            }
+        }
+        Ok(Resolved {
+            query: query.to_string(),
+            word: query.to_string(),
+            sources,
+            lookup,
+            summary,
+            others: Vec::new(),
+            tried,
+        })
+    }
+
+    /// Applies the user's rules and the presentation options to looked-up entries. `filter` is
+    /// usually [`Settings::content_filter`], or `Off` to reveal everything.
+    pub fn prepare(
+        &self,
+        mut entries: Vec<DefinitionResponse>,
+        filter: ContentFilter,
+    ) -> Vec<DefinitionResponse> {
+        entries = self.rules.apply(entries);
+        if let Some(pos) = &self.part_of_speech {
+            for entry in &mut entries {
+                entry
+                    .meanings
+                    .retain(|meaning| meaning.part_of_speech.to_lowercase().starts_with(pos));
+            }
+            entries.retain(|entry| !entry.meanings.is_empty());
+        }
+        regional::annotate(&mut entries);
+        if self.lang == "en" {
+            cmudict::annotate(&mut entries);
+        }
+        labels::annotate(&mut entries);
+        if !self.exclude_labels.is_empty() {
+            entries = labels::exclude(entries, &self.exclude_labels);
+        }
+        if let Some(accent) = self.accent {
+            for entry in &mut entries {
+                entry.prefer_accent(accent);
+            }
+        }
+        if self.cefr || self.max_level.is_some() {
+            cefr::annotate(&mut entries, WordList::shared(), self.max_level);
+        }
+        if self.simple {
+            entries = simple::apply(entries);
+        }
+        content_filter::apply(entries, filter)
+    }
+}
+
+/// The outcome of [`Settings::resolve`].
+#[derive(Debug)]
+pub struct Resolved {
+    /// What the user asked for.
+    pub query: String,
+    /// What was actually looked up: the query, its lemma or a regional
+    /// spelling of it.
+    pub word: String,
+    /// The providers in the chain whose entries made up the answer.
+    pub sources: Vec<Provider>,
+    pub lookup: Lookup,
+    /// Wikipedia's summary, for names the dictionaries don't have.
+    pub summary: Option<Summary>,
+    /// When `word` was picked out of a phrase, the phrase's other content
+    /// words, rarest first.
+    pub others: Vec<String>,
+    /// What was looked up in vain before `word`, when the query had no
+    /// entry: its dictionary form, other spellings, the closest spelling or a
+    /// phrase's words.
+    pub tried: Vec<String>,
+}
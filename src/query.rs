@@ -0,0 +1,121 @@
+/// Part-of-speech shorthands `#` takes besides the full names.
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("n", "noun"),
+    ("v", "verb"),
+    ("adj", "adjective"),
+    ("adv", "adverb"),
+    ("prep", "preposition"),
+    ("conj", "conjunction"),
+    ("pron", "pronoun"),
+    ("interj", "interjection"),
+];
+
+/// A query with the modifiers Sherlock users can type into it, since the
+/// launcher passes a single string: `run!fr` for the French dictionary,
+/// `run#verb` for the verb senses only, `~recieve` for the closest spelling,
+/// `=running` for exactly that word. They combine: `=runs#n!en`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub word: String,
+    pub lang: Option<String>,
+    pub part_of_speech: Option<String>,
+    pub fuzzy: bool,
+    pub exact: bool,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Query::default();
+        let mut word = input.trim();
+        loop {
+            if let Some(rest) = word.strip_prefix('~') {
+                query.fuzzy = true;
+                word = rest;
+            } else if let Some(rest) = word.strip_prefix('=') {
+                query.exact = true;
+                word = rest;
+            } else {
+                break;
+            }
+        }
+        // Suffixes only count when what follows looks like one, so "yahoo!"
+        // and "C#" stay as they are.
+        loop {
+            if let Some((rest, lang)) = word.rsplit_once('!').filter(|(rest, lang)| {
+                !rest.is_empty()
+                    && (2..=3).contains(&lang.len())
+                    && lang.chars().all(|c| c.is_ascii_lowercase())
+            }) {
+                query.lang.get_or_insert_with(|| lang.to_string());
+                word = rest;
+            } else if let Some((rest, pos)) = word.rsplit_once('#').filter(|(rest, pos)| {
+                !rest.is_empty() && !pos.is_empty() && pos.chars().all(char::is_alphabetic)
+            }) {
+                let pos = pos.to_lowercase();
+                let pos = PARTS_OF_SPEECH
+                    .iter()
+                    .find(|(short, _)| *short == pos)
+                    .map_or(pos.clone(), |(_, name)| name.to_string());
+                query.part_of_speech.get_or_insert(pos);
+                word = rest;
+            } else {
+                break;
+            }
+        }
+        query.word = word.trim().to_string();
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str) -> Query {
+        Query {
+            word: word.to_string(),
+            ..Query::default()
+        }
+    }
+
+    #[test]
+    fn parses_modifiers() {
+        assert_eq!(Query::parse("run"), word("run"));
+        assert_eq!(Query::parse(" run "), word("run"));
+        assert_eq!(
+            Query::parse("=runs#n!en"),
+            Query {
+                word: String::from("runs"),
+                lang: Some(String::from("en")),
+                part_of_speech: Some(String::from("noun")),
+                fuzzy: false,
+                exact: true,
+            }
+        );
+        assert_eq!(Query::parse("=runs!en#n"), Query::parse("=runs#n!en"));
+        assert_eq!(
+            Query::parse("~recieve!fr"),
+            Query {
+                fuzzy: true,
+                lang: Some(String::from("fr")),
+                ..word("recieve")
+            }
+        );
+        assert_eq!(
+            Query::parse("run#Verb").part_of_speech.as_deref(),
+            Some("verb")
+        );
+    }
+
+    #[test]
+    fn keeps_what_only_looks_like_a_modifier() {
+        assert_eq!(Query::parse("yahoo!"), word("yahoo!"));
+        assert_eq!(Query::parse("C#"), word("C#"));
+        assert_eq!(Query::parse("F#!en").lang.as_deref(), Some("en"));
+        assert_eq!(Query::parse("F#!en").word, "F#");
+        assert_eq!(Query::parse("wow!!"), word("wow!!"));
+        assert_eq!(Query::parse("!en"), word("!en"));
+        assert_eq!(Query::parse("#1"), word("#1"));
+        assert_eq!(Query::parse("C#7"), word("C#7"));
+    }
+}
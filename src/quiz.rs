@@ -0,0 +1,235 @@
+use regex::Regex;
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    hash::{Hash, Hasher},
+    io::{self, BufRead, Write},
+};
+
+use crate::{
+    cli::Format,
+    gloss::{self, Gloss},
+    history,
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    srs::Deck,
+    wordlist::WordList,
+};
+
+/// Words offered for each question, the right one included.
+const CHOICES: usize = 4;
+
+/// A definition to name the word for.
+struct Question {
+    word: String,
+    gloss: Gloss,
+    /// Shuffled, the right word among them.
+    choices: Vec<String>,
+}
+
+/// `quiz`: asks for the words due for review by their definitions. In
+/// Sherlock each question is a tile whose actions are the choices, and
+/// picking one runs `quiz <word> <guess>`, which scores it and asks the next.
+pub async fn run(
+    answer: Option<(String, String)>,
+    settings: &Settings,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let mut deck = Deck::load();
+    match format {
+        Format::Sherlock => tile(&mut deck, answer, settings).await,
+        Format::Plain | Format::Oneline | Format::Sexp => terminal(&mut deck, settings).await,
+    }
+}
+
+/// The tile shown for an empty query: a reminder when reviews are due.
+pub fn reminder(settings: &Settings) {
+    let due = Deck::load().due(history::now()).len();
+    let response = match due {
+        0 => SherlockPipeResponse::message(String::from("Type a word to define it"), String::new()),
+        n => {
+            let title = match n {
+                1 => String::from("1 word due for review"),
+                n => format!("{} words due for review", n),
+            };
+            let action = quiz_action(String::from("Start the quiz"), &[], settings);
+            SherlockPipeResponse::new(title, String::new(), vec![action])
+        }
+    };
+    response.print();
+}
+
+/// Runs `quiz` again with `args`, keeping the provider chain and language the
+/// definitions come from.
+fn quiz_action(name: String, args: &[&str], settings: &Settings) -> ApplicationAction {
+    let providers = settings.providers_name();
+    // `quiz` alone would otherwise be looked up as a word.
+    let mut all = vec!["--", "--provider", &providers, "--lang", &settings.lang];
+    if settings.ipv4_only {
+        all.push("--ipv4-only");
+    }
+    if let Some(profile) = &settings.profile {
+        all.extend(["--profile", profile]);
+    }
+    all.push("quiz");
+    all.extend_from_slice(args);
+    ApplicationAction::rerun(name, &all)
+}
+
+async fn tile(
+    deck: &mut Deck,
+    answer: Option<(String, String)>,
+    settings: &Settings,
+) -> Result<(), Box<dyn Error>> {
+    let now = history::now();
+    let mut feedback = String::new();
+    if let Some((word, guess)) = answer {
+        let correct = guess.eq_ignore_ascii_case(&word);
+        Deck::update(|saved| saved.review(&word, correct, now))??;
+        *deck = Deck::load();
+        feedback = match correct {
+            true => String::from("Right! "),
+            false => format!("It was {}. ", word),
+        };
+    }
+
+    let Some(question) = next_question(deck, now, settings).await else {
+        let response = SherlockPipeResponse::message(
+            format!("{}Nothing left to review", feedback),
+            String::new(),
+        );
+        response.print();
+        return Ok(());
+    };
+    let content = format!(
+        "<i>{}</i>  {}",
+        question.gloss.part_of_speech,
+        masked(&question.gloss.definition, &question.word)
+    );
+    let actions = question
+        .choices
+        .iter()
+        .map(|choice| quiz_action(choice.clone(), &[&question.word, choice], settings))
+        .collect();
+    let due = deck.due(now).len();
+    let response = SherlockPipeResponse::new(
+        format!("{}Which word is this? ({} due)", feedback, due),
+        content,
+        actions,
+    );
+    response.print();
+    Ok(())
+}
+
+/// Asks about every due word at a prompt, taking the word or its number.
+async fn terminal(deck: &mut Deck, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let (mut asked, mut right) = (0, 0);
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        let now = history::now();
+        let Some(question) = next_question(deck, now, settings).await else {
+            break;
+        };
+        println!(
+            "\n({}) {}",
+            question.gloss.part_of_speech,
+            masked(&question.gloss.definition, &question.word)
+        );
+        for (i, choice) in question.choices.iter().enumerate() {
+            println!("  {}. {}", i + 1, choice);
+        }
+        print!("> ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        let guess = line.trim();
+        let guess = match guess.parse::<usize>() {
+            Ok(n) if (1..=question.choices.len()).contains(&n) => &question.choices[n - 1],
+            _ => guess,
+        };
+        let correct = guess.eq_ignore_ascii_case(&question.word);
+        match correct {
+            true => println!("Right!"),
+            false => println!("It was {}.", question.word),
+        }
+        // Saved after each answer, so one made elsewhere meanwhile isn't lost.
+        Deck::update(|saved| saved.review(&question.word, correct, now))??;
+        *deck = Deck::load();
+        asked += 1;
+        right += usize::from(correct);
+    }
+    match asked {
+        0 => println!("Nothing to review."),
+        _ => println!("\n{} of {} right.", right, asked),
+    }
+    Ok(())
+}
+
+/// The longest-overdue word that has a definition to ask with.
+async fn next_question(deck: &Deck, now: u64, settings: &Settings) -> Option<Question> {
+    // A few at a time, so a long backlog isn't all looked up for one question.
+    let due: Vec<String> = deck
+        .due(now)
+        .iter()
+        .take(CHOICES)
+        .map(|card| card.word.clone())
+        .collect();
+    let glosses = gloss::glosses(&due, settings).await;
+    let (word, gloss) = due
+        .into_iter()
+        .find_map(|word| Some((word.clone(), glosses.get(&word)?.clone())))?;
+
+    // Other words being learned make the best wrong answers, since they're
+    // about as hard; common words near it in the frequency list fill in.
+    let mut others: Vec<String> = deck
+        .cards
+        .iter()
+        .map(|card| card.word.clone())
+        .filter(|other| *other != word)
+        .collect();
+    others = shuffled(others, now);
+    if others.len() < CHOICES - 1 {
+        let list = WordList::shared();
+        let words: Vec<&str> = list.iter().collect();
+        let start = list
+            .rank(&word)
+            .unwrap_or(words.len())
+            .saturating_sub(50)
+            .min(words.len().saturating_sub(100));
+        let fillers: Vec<String> = words[start..]
+            .iter()
+            .filter(|other| other.len() > 3 && **other != word)
+            .take(100)
+            .map(|other| other.to_string())
+            .collect();
+        others.extend(shuffled(fillers, now));
+    }
+    others.truncate(CHOICES - 1);
+    others.push(word.clone());
+    Some(Question {
+        choices: shuffled(others, now),
+        word,
+        gloss,
+    })
+}
+
+/// `definition` with `word` and words starting with it ("run" in "running")
+/// blanked out, so it doesn't give the answer away.
+fn masked(definition: &str, word: &str) -> String {
+    let pattern = format!(r"(?i)\b{}\w*", regex::escape(word));
+    match Regex::new(&pattern) {
+        Ok(re) => re.replace_all(definition, "___").into_owned(),
+        Err(_) => definition.to_string(),
+    }
+}
+
+/// `words` in an order that changes with `seed`.
+fn shuffled(mut words: Vec<String>, seed: u64) -> Vec<String> {
+    words.sort_by_cached_key(|word| {
+        let mut hasher = DefaultHasher::new();
+        (seed, word).hash(&mut hasher);
+        hasher.finish()
+    });
+    words
+}
@@ -0,0 +1,190 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::{api::DefinitionResponse, hunspell::Hunspell, index::WordIndex};
+
+/// A leading "(chiefly Britain, informal)" label list, and the rest of the sense.
+static LABELS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\(([^)]*)\)\s*(.*)$").unwrap());
+/// Hedges in front of a region, as in "chiefly US".
+static HEDGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(chiefly|mainly|mostly|especially|originally|usually|often|now)\s+").unwrap()
+});
+
+/// Region tags and the labels dictionaries use for them.
+const REGIONS: &[(&str, &[&str])] = &[
+    (
+        "UK",
+        &[
+            "uk",
+            "british",
+            "britain",
+            "british english",
+            "england",
+            "english",
+        ],
+    ),
+    (
+        "US",
+        &[
+            "us",
+            "u.s.",
+            "american",
+            "america",
+            "north america",
+            "north american",
+        ],
+    ),
+    ("AU", &["australia", "australian", "australian english"]),
+    ("CA", &["canada", "canadian", "canadian english"]),
+    ("IE", &["ireland", "irish", "irish english"]),
+    ("NZ", &["new zealand", "nz"]),
+    ("IN", &["india", "indian", "indian english"]),
+    ("ZA", &["south africa", "south african"]),
+    ("Scot", &["scotland", "scottish"]),
+];
+
+/// Spellings that rules can't derive, British first.
+const IRREGULAR: &[(&str, &str)] = &[
+    ("grey", "gray"),
+    ("aluminium", "aluminum"),
+    ("tyre", "tire"),
+    ("kerb", "curb"),
+    ("plough", "plow"),
+    ("programme", "program"),
+    ("mould", "mold"),
+    ("moult", "molt"),
+    ("smoulder", "smolder"),
+    ("sceptic", "skeptic"),
+    ("sceptical", "skeptical"),
+    ("pyjamas", "pajamas"),
+    ("aeroplane", "airplane"),
+    ("manoeuvre", "maneuver"),
+    ("draught", "draft"),
+    ("jewellery", "jewelry"),
+    ("cheque", "check"),
+    ("gaol", "jail"),
+    ("doughnut", "donut"),
+    ("moustache", "mustache"),
+    ("cosy", "cozy"),
+    ("ageing", "aging"),
+    ("judgement", "judgment"),
+    ("acknowledgement", "acknowledgment"),
+    ("paediatric", "pediatric"),
+    ("encyclopaedia", "encyclopedia"),
+    ("anaemia", "anemia"),
+    ("oestrogen", "estrogen"),
+    ("foetus", "fetus"),
+    ("sulphur", "sulfur"),
+    ("artefact", "artifact"),
+    ("storey", "story"),
+];
+
+/// Ending swaps between British and American spelling, British first.
+const ENDINGS: &[(&str, &str)] = &[
+    ("isation", "ization"),
+    ("ising", "izing"),
+    ("ised", "ized"),
+    ("ises", "izes"),
+    ("ise", "ize"),
+    ("ysing", "yzing"),
+    ("ysed", "yzed"),
+    ("yse", "yze"),
+    ("ourite", "orite"),
+    ("ouring", "oring"),
+    ("oured", "ored"),
+    ("ours", "ors"),
+    ("our", "or"),
+    ("ogue", "og"),
+    ("tre", "ter"),
+    ("tres", "ters"),
+    ("ence", "ense"),
+    ("elled", "eled"),
+    ("elling", "eling"),
+    ("eller", "eler"),
+];
+
+/// The same word in the other side of the Atlantic's spelling, most common
+/// first. Spellings derived from endings are only returned when the word list
+/// or an installed Hunspell dictionary knows them, so "four" doesn't become
+/// "for". English only.
+pub fn variants(word: &str, lang: &str) -> Vec<String> {
+    if lang != "en" {
+        return Vec::new();
+    }
+    let word = word.trim().to_lowercase();
+    let mut irregular: Vec<String> = Vec::new();
+    for &(british, american) in IRREGULAR {
+        if word == british {
+            irregular.push(american.to_string());
+        } else if word == american {
+            irregular.push(british.to_string());
+        }
+    }
+    let mut candidates: Vec<String> = Vec::new();
+    for &(british, american) in ENDINGS {
+        for (from, to) in [(british, american), (american, british)] {
+            if let Some(stem) = word.strip_suffix(from) {
+                // Short stems are whole words, not endings: "rise", "pour".
+                if stem.chars().count() >= 2 {
+                    candidates.push(format!("{}{}", stem, to));
+                }
+            }
+        }
+    }
+    let words = WordIndex::shared();
+    let known = |candidate: &String| {
+        words.contains(candidate)
+            || ["en_GB", "en_US"]
+                .into_iter()
+                .filter_map(Hunspell::shared)
+                .any(|hunspell| hunspell.check(candidate))
+    };
+    candidates.retain(|c| *c != word && known(c));
+    candidates.sort_by_key(|c| words.rank(c).unwrap_or(usize::MAX));
+    candidates.dedup();
+    irregular.extend(candidates);
+    irregular
+}
+
+/// The region tag for a single label such as "chiefly Britain".
+fn region(label: &str) -> Option<&'static str> {
+    let label = HEDGE.replace(label.trim(), "").to_lowercase();
+    REGIONS
+        .iter()
+        .find(|(_, names)| names.contains(&label.as_str()))
+        .map(|(tag, _)| *tag)
+}
+
+/// Moves regional usage labels out of each sense's label list into its
+/// `region` tag: "(chiefly Britain, informal) a chap" becomes "(informal) a
+/// chap" tagged UK.
+pub fn annotate(entries: &mut [DefinitionResponse]) {
+    let definitions = entries
+        .iter_mut()
+        .flat_map(|e| &mut e.meanings)
+        .flat_map(|m| &mut m.definitions);
+    for definition in definitions {
+        let Some(captures) = LABELS.captures(&definition.definition) else {
+            continue;
+        };
+        let mut regions = Vec::new();
+        let mut others = Vec::new();
+        for label in captures[1].split(',') {
+            match region(label) {
+                Some(tag) if !regions.contains(&tag) => regions.push(tag),
+                Some(_) => {}
+                None => others.push(label.trim()),
+            }
+        }
+        if regions.is_empty() {
+            continue;
+        }
+        let rest = &captures[2];
+        let text = match others.is_empty() {
+            true => rest.to_string(),
+            false => format!("({}) {}", others.join(", "), rest),
+        };
+        definition.region = Some(regions.join("/"));
+        definition.definition = text;
+    }
+}
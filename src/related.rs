@@ -0,0 +1,59 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::api::DefinitionResponse;
+
+/// How many words a "See also" footer lists at most.
+const SEE_ALSO_LIMIT: usize = 8;
+
+/// Cross references in the spirit of a paper dictionary's "See also": synonyms
+/// shared by two or more senses (the strongest relations), followed by forms that
+/// definitions point at ("plural of …", "past tense of …").
+pub fn see_also(entries: &[DefinitionResponse]) -> Vec<String> {
+    let headwords: Vec<String> = entries.iter().map(|e| e.word.to_lowercase()).collect();
+
+    // Count how many senses mention each synonym, remembering first appearance
+    // so ties keep the API's order.
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut order = 0;
+    for meaning in entries.iter().flat_map(|e| &e.meanings) {
+        let senses = std::iter::once(&meaning.synonyms)
+            .chain(meaning.definitions.iter().map(|d| &d.synonyms));
+        for synonyms in senses.flatten() {
+            let mut in_sense: Vec<&str> = synonyms.iter().map(String::as_str).collect();
+            in_sense.sort_unstable();
+            in_sense.dedup();
+            for word in in_sense {
+                let count = counts.entry(word).or_insert((0, order));
+                count.0 += 1;
+                order += 1;
+            }
+        }
+    }
+    let mut shared: Vec<(&str, (usize, usize))> =
+        counts.into_iter().filter(|(_, (n, _))| *n >= 2).collect();
+    shared.sort_by_key(|(_, (n, first))| (std::cmp::Reverse(*n), *first));
+
+    let form_of = Regex::new(
+        r"(?i)\b(?:form|plural|tense|participle|spelling|variant|comparative|superlative) of ([A-Za-z][A-Za-z'-]*)",
+    )
+    .unwrap();
+    let forms = entries
+        .iter()
+        .flat_map(|e| &e.meanings)
+        .flat_map(|m| &m.definitions)
+        .flat_map(|d| form_of.captures_iter(&d.definition))
+        .map(|c| c[1].to_string());
+
+    let mut related: Vec<String> = Vec::new();
+    for word in shared.into_iter().map(|(w, _)| w.to_string()).chain(forms) {
+        if related.len() == SEE_ALSO_LIMIT {
+            break;
+        }
+        let lower = word.to_lowercase();
+        if !headwords.contains(&lower) && !related.iter().any(|r| r.to_lowercase() == lower) {
+            related.push(word);
+        }
+    }
+    related
+}
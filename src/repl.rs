@@ -0,0 +1,188 @@
+use rustyline::{
+    completion::Completer, error::ReadlineError, history::DefaultHistory, Context, Editor, Helper,
+    Highlighter, Hinter, Validator,
+};
+use tokio::runtime::Handle;
+
+use crate::{
+    api::Lookup,
+    history::History,
+    i18n,
+    index::WordIndex,
+    providers::{Provider, Settings},
+    spelling, storage, text,
+};
+
+const COMMANDS: &[&str] = &[":help", ":lang", ":provider", ":quit"];
+const COMPLETION_LIMIT: usize = 50;
+const HELP: &str = "\
+Type a word to define it. Commands:
+  :lang [code]       Show or switch the dictionary language
+  :provider [name]   Show or switch the provider
+  :help              Show this help
+  :quit              Leave (Ctrl-D works too)";
+
+/// Runs the prompt loop until the user quits.
+pub async fn run(settings: Settings) -> Result<(), Box<dyn std::error::Error>> {
+    let runtime = Handle::current();
+    // rustyline blocks on stdin, so keep it off the async workers.
+    tokio::task::spawn_blocking(move || repl(runtime, settings)).await??;
+    Ok(())
+}
+
+#[derive(Helper, Hinter, Highlighter, Validator)]
+struct WordCompleter {
+    words: &'static WordIndex,
+}
+impl Completer for WordCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let prefix = &line[start..pos];
+        let candidates = if start == 0 && prefix.starts_with(':') {
+            COMMANDS
+                .iter()
+                .filter(|c| c.starts_with(prefix))
+                .map(|c| c.to_string())
+                .collect()
+        } else if line.starts_with(":provider") {
+            Provider::ALL
+                .iter()
+                .map(|p| p.name().to_string())
+                .filter(|name| name.starts_with(prefix))
+                .collect()
+        } else {
+            self.words.suggest(prefix, COMPLETION_LIMIT)
+        };
+        Ok((start, candidates))
+    }
+}
+
+fn repl(runtime: Handle, mut settings: Settings) -> rustyline::Result<()> {
+    let mut editor: Editor<WordCompleter, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(WordCompleter {
+        words: WordIndex::shared(),
+    }));
+    let history_path = storage::data_dir().join("repl_history.txt");
+    // A missing history file just means this is the first session.
+    let _ = editor.load_history(&history_path);
+
+    loop {
+        let prompt = format!("define ({}/{})> ", settings.providers_name(), settings.lang);
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        if let Some(command) = line.strip_prefix(':') {
+            let mut parts = command.split_whitespace();
+            match (parts.next().unwrap_or_default(), parts.next()) {
+                ("q" | "quit" | "exit", _) => break,
+                ("h" | "help", _) => println!("{}", HELP),
+                ("lang", None) => println!("Language: {}", settings.lang),
+                ("lang", Some(code)) => {
+                    settings.lang = code.to_string();
+                    println!("Language set to {}", code);
+                }
+                ("provider", None) => {
+                    let names: Vec<&str> = Provider::ALL.iter().map(|p| p.name()).collect();
+                    println!(
+                        "Provider: {} (available: {})",
+                        settings.providers_name(),
+                        names.join(", ")
+                    );
+                }
+                ("provider", Some(names)) => match Provider::parse_chain(names) {
+                    Ok(providers) => {
+                        settings.providers = providers;
+                        println!("Provider set to {}", settings.providers_name());
+                    }
+                    Err(e) => eprintln!("{}", e),
+                },
+                (other, _) => eprintln!("Unknown command ':{}', try :help", other),
+            }
+            continue;
+        }
+
+        // Routes and query modifiers only apply to this one lookup.
+        let (routed, line) = settings.interpret(line);
+        let line = line.as_str();
+        let resolved = runtime.block_on(routed.resolve(line));
+        if let Ok(resolved) = resolved.as_ref() {
+            if resolved.word != resolved.query {
+                println!("(showing '{}' for '{}')", resolved.word, resolved.query);
+            }
+            if !resolved.others.is_empty() {
+                println!("(also in the phrase: {})", resolved.others.join(", "));
+            }
+            if let Some(summary) = &resolved.summary {
+                print!("{}", summary.plain());
+                continue;
+            }
+        }
+        match resolved.map(|r| r.lookup) {
+            Ok(Lookup::Found(entries)) => {
+                let entries = routed.prepare(entries, routed.content_filter);
+                print!("{}", text::plain(&entries, &routed.lang));
+                if !settings.private {
+                    if let Err(e) = History::update(|history| history.record(line)) {
+                        eprintln!("Could not save history: {}", e);
+                    }
+                }
+            }
+            Ok(Lookup::NotFound) => {
+                let suggestions = spelling::suggest(line, &routed, 5);
+                let not_found = i18n::tr("not-found-for", &[("word", line)]);
+                if suggestions.is_empty() {
+                    println!("{}.", not_found);
+                } else {
+                    println!(
+                        "{}. {}",
+                        not_found,
+                        i18n::tr("did-you-mean", &[("words", &suggestions.join(", "))])
+                    );
+                }
+            }
+            Ok(Lookup::ApiError { status, error }) => {
+                let status = status.to_string();
+                println!(
+                    "{}",
+                    i18n::tr(
+                        "api-error-status",
+                        &[("status", &status), ("title", &error.title)]
+                    )
+                );
+                println!("{} {}", i18n::text("message"), error.message);
+                println!("{} {}", i18n::text("resolution"), error.resolution);
+            }
+            Ok(Lookup::ParseError { error, .. }) => {
+                println!("Failed to parse API response for '{}': {}", line, error);
+            }
+            Err(e) => println!("Error fetching definition for '{}': {}", line, e),
+        }
+    }
+
+    if settings.private {
+        return Ok(());
+    }
+    let saved = std::fs::create_dir_all(storage::data_dir())
+        .map_err(ReadlineError::from)
+        .and_then(|()| editor.save_history(&history_path));
+    if let Err(e) = saved {
+        eprintln!("Could not save prompt history: {}", e);
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, io};
+
+use crate::{
+    cli::Format,
+    cmudict::{self, Pronunciation},
+    datamuse::{self, Query},
+    homophones::{rank, slashed},
+    providers::Settings,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Rhymes shown in each group.
+const LIMIT: usize = 30;
+/// How close a slant rhyme has to be, out of 100: the same stressed vowel,
+/// or the same sounds after a different one.
+const SLANT: u8 = 50;
+
+/// A word that rhymes, and how well, out of 100.
+struct Rhyme {
+    word: String,
+    ipa: Option<String>,
+    score: u8,
+}
+
+/// What `rhymes` found for a word.
+#[derive(Default)]
+struct Found {
+    ipa: Option<String>,
+    perfect: Vec<Rhyme>,
+    /// Near rhymes: assonance and consonance, best first.
+    slant: Vec<Rhyme>,
+}
+
+/// `rhymes <word>`: perfect rhymes, then slant ones scored by how close they
+/// come, from CMUdict when it's installed and Datamuse otherwise. English
+/// only.
+pub async fn run(word: &str, settings: &Settings, format: Format) -> Result<(), surf::Error> {
+    let word = word.trim().to_lowercase();
+    if settings.lang != "en" {
+        return Err(surf::Error::from_str(
+            surf::StatusCode::NotImplemented,
+            "Rhymes are only available in English",
+        ));
+    }
+    let found = match cmudict::is_installed() {
+        true => from_cmudict(&word)?,
+        false => from_datamuse(&word).await?,
+    };
+
+    if found.perfect.is_empty() && found.slant.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response =
+                    SherlockPipeResponse::message("No rhymes found".to_string(), String::new());
+                response.print();
+            }
+            _ => {
+                eprintln!("No rhymes found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let heading = match &found.ipa {
+        Some(ipa) => format!("{}  /{}/", word, ipa),
+        None => word.clone(),
+    };
+    let perfect: Vec<Vec<String>> = found
+        .perfect
+        .iter()
+        .map(|rhyme| vec![rhyme.word.clone(), slashed(&rhyme.ipa)])
+        .collect();
+    let slant: Vec<Vec<String>> = found
+        .slant
+        .iter()
+        .map(|rhyme| {
+            vec![
+                rhyme.word.clone(),
+                slashed(&rhyme.ipa),
+                format!("{}%", rhyme.score),
+            ]
+        })
+        .collect();
+    match format {
+        Format::Sherlock => {
+            let mut content = format!("<span font_desc=\"monospace\">\n<b>{}</b>\n\n", heading);
+            if !perfect.is_empty() {
+                content.push_str("─── <b><i>perfect rhymes</i></b> ───\n\n");
+                content.push_str(&text::table(&perfect));
+                content.push('\n');
+            }
+            if !slant.is_empty() {
+                content.push_str("─── <b><i>slant rhymes</i></b> ───\n\n");
+                content.push_str(&text::table(&slant));
+                content.push('\n');
+            }
+            content.push_str("────────────\n</span>");
+            let actions = found
+                .perfect
+                .iter()
+                .chain(&found.slant)
+                .map(|rhyme| ApplicationAction::lookup_word("Define", &rhyme.word, settings))
+                .collect();
+            let response =
+                SherlockPipeResponse::new(format!(r#"Rhymes for "{}""#, word), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            println!("{}\n", heading);
+            if !perfect.is_empty() {
+                println!("─── perfect rhymes ───");
+                println!("{}", text::table(&perfect));
+            }
+            if !slant.is_empty() {
+                println!("─── slant rhymes ───");
+                println!("{}", text::table(&slant));
+            }
+        }
+        Format::Oneline => {
+            let words: Vec<&str> = found.perfect.iter().map(|r| r.word.as_str()).collect();
+            println!("{}: {}", word, words.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// Datamuse's perfect and near rhymes. Its scores only rank words within
+/// one answer, so slant ones are scored against the best of them.
+async fn from_datamuse(word: &str) -> Result<Found, surf::Error> {
+    let perfect = datamuse::words(&Query {
+        rel_rhy: Some(word),
+        max: LIMIT as u16,
+        ..Query::default()
+    })
+    .await?;
+    let near = datamuse::words(&Query {
+        rel_nry: Some(word),
+        max: LIMIT as u16,
+        ..Query::default()
+    })
+    .await?;
+    let best = near.iter().map(|w| w.score).max().unwrap_or(1).max(1);
+    Ok(Found {
+        ipa: datamuse::pronunciation(word)
+            .await?
+            .as_ref()
+            .and_then(datamuse::Word::ipa),
+        perfect: perfect
+            .iter()
+            .map(|w| Rhyme {
+                word: w.word.clone(),
+                ipa: w.ipa(),
+                score: 100,
+            })
+            .collect(),
+        slant: near
+            .iter()
+            .filter(|w| !perfect.iter().any(|p| p.word == w.word))
+            .map(|w| Rhyme {
+                word: w.word.clone(),
+                ipa: w.ipa(),
+                score: (w.score * 99 / best) as u8,
+            })
+            .collect(),
+    })
+}
+
+fn from_cmudict(word: &str) -> io::Result<Found> {
+    let dictionary = cmudict::Dictionary::shared()?;
+    let Some(own) = dictionary.lookup(word).into_iter().next() else {
+        return Ok(Found::default());
+    };
+    let mut best: HashMap<&str, (u8, &Pronunciation)> = HashMap::new();
+    for other in dictionary.entries() {
+        if other.word == word || other.word.contains(|c: char| !c.is_alphabetic()) {
+            continue;
+        }
+        let score = score(own, other);
+        if score >= SLANT
+            && best
+                .get(other.word.as_str())
+                .is_none_or(|(s, _)| score > *s)
+        {
+            best.insert(&other.word, (score, other));
+        }
+    }
+    let (perfect, slant): (Vec<Rhyme>, Vec<Rhyme>) = best
+        .into_values()
+        .map(|(score, pronunciation)| Rhyme {
+            word: pronunciation.word.clone(),
+            ipa: Some(pronunciation.ipa()),
+            score,
+        })
+        .partition(|rhyme| rhyme.score == 100);
+    let mut found = Found {
+        ipa: Some(own.ipa()),
+        perfect,
+        slant,
+    };
+    found.perfect.sort_by_cached_key(|rhyme| rank(&rhyme.word));
+    found
+        .slant
+        .sort_by_cached_key(|rhyme| (100 - rhyme.score, rank(&rhyme.word)));
+    found.perfect.truncate(LIMIT);
+    found.slant.truncate(LIMIT);
+    Ok(found)
+}
+
+/// How well `b` rhymes with `a`, out of 100: half for the same stressed
+/// vowel and half for how much of what follows it matches, from the end.
+/// A perfect rhyme scores 100, unless the sound before the vowel is the
+/// same too, which makes it the same word again: "flower" and "deflower".
+fn score(a: &Pronunciation, b: &Pronunciation) -> u8 {
+    let same = |x: &String, y: &String| cmudict::unstressed(x) == cmudict::unstressed(y);
+    let (Some((vowel_a, tail_a)), Some((vowel_b, tail_b))) =
+        (a.rhyme().split_first(), b.rhyme().split_first())
+    else {
+        return 0;
+    };
+    let vowel = match same(vowel_a, vowel_b) {
+        true => 50,
+        false => 0,
+    };
+    let matching = tail_a
+        .iter()
+        .rev()
+        .zip(tail_b.iter().rev())
+        .take_while(|(x, y)| same(x, y))
+        .count();
+    let longest = tail_a.len().max(tail_b.len());
+    // Open syllables only rhyme on their vowel: "see" and "go" don't.
+    let tail = match longest {
+        0 => vowel,
+        n => 50 * matching / n,
+    };
+    match (vowel + tail) as u8 {
+        100 if onset(a) == onset(b) => 0,
+        score => score,
+    }
+}
+
+/// The sound before `pronunciation`'s rhyme, if there's one.
+fn onset(pronunciation: &Pronunciation) -> Option<&str> {
+    let start = pronunciation.phonemes.len() - pronunciation.rhyme().len();
+    start
+        .checked_sub(1)
+        .map(|i| cmudict::unstressed(&pronunciation.phonemes[i]))
+}
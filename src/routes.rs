@@ -0,0 +1,113 @@
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::providers::Provider;
+
+/// Letters that only a few Latin-script languages use.
+const LETTERS: &[(char, &[&str])] = &[
+    ('ß', &["de"]),
+    ('ä', &["de", "sv", "fi"]),
+    ('ö', &["de", "sv", "fi", "tr", "hu"]),
+    ('ü', &["de", "tr", "hu"]),
+    ('ñ', &["es"]),
+    ('¿', &["es"]),
+    ('¡', &["es"]),
+    ('œ', &["fr"]),
+    ('ç', &["fr", "pt", "tr"]),
+    ('ã', &["pt"]),
+    ('õ', &["pt"]),
+    ('å', &["sv", "da", "no"]),
+    ('ø', &["da", "no"]),
+    ('æ', &["da", "no"]),
+    ('ł', &["pl"]),
+    ('ą', &["pl"]),
+    ('ę', &["pl"]),
+    ('ő', &["hu"]),
+    ('ű', &["hu"]),
+    ('ğ', &["tr"]),
+    ('ş', &["tr"]),
+    ('ı', &["tr"]),
+];
+
+/// Sends queries that match to their own providers, from the `routes`
+/// section of `config.json`. Every condition given has to hold; the first
+/// route that matches is used.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Route {
+    /// Typed before the query, e.g. `u:` in `u:yeet`. Removed before the
+    /// lookup.
+    pub prefix: Option<String>,
+    /// Matched against the query.
+    #[serde(default, deserialize_with = "regex")]
+    pub pattern: Option<Regex>,
+    /// A language code the query's script or letters point to: `ja` for kana,
+    /// `ko` for hangul, `de` for "ß".
+    pub language: Option<String>,
+    /// In characters, prefix not counted.
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// The chain to use instead of the configured one.
+    #[serde(default)]
+    pub providers: Vec<Provider>,
+    /// The lookup language to use instead.
+    pub lang: Option<String>,
+}
+
+fn regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Regex>, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+impl Route {
+    /// The query without the route's prefix, if the route takes it.
+    pub fn matches<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let query = match &self.prefix {
+            Some(prefix) => query.strip_prefix(prefix.as_str())?.trim_start(),
+            None => query,
+        };
+        let length = query.chars().count();
+        let matches = self.pattern.as_ref().is_none_or(|p| p.is_match(query))
+            && self
+                .language
+                .as_ref()
+                .is_none_or(|lang| languages(query).contains(&lang.as_str()))
+            && self.min_length.is_none_or(|min| length >= min)
+            && self.max_length.is_none_or(|max| length <= max);
+        matches.then_some(query)
+    }
+}
+
+/// The languages `text` could be in, judging by its script, or by the
+/// letters it uses when it's in the Latin alphabet. Plain ASCII says nothing.
+pub fn languages(text: &str) -> Vec<&'static str> {
+    let mut found: Vec<&'static str> = Vec::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        let langs: &[&str] = match c {
+            '\u{3040}'..='\u{30ff}' => &["ja"],
+            '\u{ac00}'..='\u{d7af}' | '\u{1100}'..='\u{11ff}' => &["ko"],
+            '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => &["zh", "ja"],
+            '\u{0400}'..='\u{04ff}' => &["ru", "uk", "bg", "sr"],
+            '\u{0370}'..='\u{03ff}' => &["el"],
+            '\u{0590}'..='\u{05ff}' => &["he"],
+            '\u{0600}'..='\u{06ff}' => &["ar", "fa", "ur"],
+            '\u{0900}'..='\u{097f}' => &["hi", "mr", "ne"],
+            '\u{0e00}'..='\u{0e7f}' => &["th"],
+            c => LETTERS
+                .iter()
+                .find(|(letter, _)| *letter == c)
+                .map_or(&[], |(_, langs)| langs),
+        };
+        for lang in langs {
+            if !found.contains(lang) {
+                found.push(lang);
+            }
+        }
+    }
+    // Kana settles whether Han characters are Japanese.
+    if found.contains(&"ja") && text.chars().any(|c| ('\u{3040}'..='\u{30ff}').contains(&c)) {
+        found.retain(|lang| *lang != "zh");
+    }
+    found
+}
@@ -0,0 +1,114 @@
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::{api::DefinitionResponse, providers::Provider};
+
+/// The user's own clean-up of provider output, from the `rules` section of
+/// `config.json`. Applied before anything else touches the entries.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// Find/replace on the text of senses and examples.
+    pub rewrite: Vec<Rewrite>,
+    /// Senses that are never shown.
+    pub hide: Vec<Hide>,
+    /// Keep at most this many synonyms and antonyms per list.
+    pub max_synonyms: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rewrite {
+    #[serde(deserialize_with = "regex")]
+    pub find: Regex,
+    /// May refer to capture groups as `$1` or `${name}`.
+    #[serde(default)]
+    pub replace: String,
+    /// Only rewrite entries from this provider.
+    pub provider: Option<Provider>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hide {
+    /// Senses whose text matches are dropped.
+    #[serde(deserialize_with = "regex")]
+    pub sense: Regex,
+    /// Only for this headword (any case), rather than every word.
+    pub word: Option<String>,
+    /// Only in entries from this provider.
+    pub provider: Option<Provider>,
+}
+
+fn regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(serde::de::Error::custom)
+}
+
+/// Whether a rule limited to `provider` covers `entry`.
+fn applies(provider: Option<Provider>, entry: &DefinitionResponse) -> bool {
+    provider.is_none() || provider == entry.provider
+}
+
+impl Rules {
+    pub fn is_empty(&self) -> bool {
+        self.rewrite.is_empty() && self.hide.is_empty() && self.max_synonyms.is_none()
+    }
+
+    /// Rewrites, then hides, then trims synonym lists. Meanings and entries
+    /// left without senses are dropped.
+    pub fn apply(&self, mut entries: Vec<DefinitionResponse>) -> Vec<DefinitionResponse> {
+        if self.is_empty() {
+            return entries;
+        }
+        for entry in &mut entries {
+            let rewrites: Vec<&Rewrite> = self
+                .rewrite
+                .iter()
+                .filter(|rule| applies(rule.provider, entry))
+                .collect();
+            let hides: Vec<&Hide> = self
+                .hide
+                .iter()
+                .filter(|rule| applies(rule.provider, entry))
+                .filter(|rule| {
+                    rule.word
+                        .as_ref()
+                        .is_none_or(|word| word.to_lowercase() == entry.word.to_lowercase())
+                })
+                .collect();
+            let rewrite = |text: &mut String| {
+                for rule in &rewrites {
+                    *text = rule.find.replace_all(text, &rule.replace).into_owned();
+                }
+            };
+
+            for meaning in &mut entry.meanings {
+                for definition in &mut meaning.definitions {
+                    rewrite(&mut definition.definition);
+                    if let Some(example) = &mut definition.example {
+                        rewrite(example);
+                    }
+                }
+                meaning.definitions.retain(|definition| {
+                    !definition.definition.trim().is_empty()
+                        && !hides
+                            .iter()
+                            .any(|rule| rule.sense.is_match(&definition.definition))
+                });
+                if let Some(max) = self.max_synonyms {
+                    let lists = meaning.definitions.iter_mut().flat_map(|definition| {
+                        [&mut definition.synonyms, &mut definition.antonyms]
+                    });
+                    for list in lists
+                        .chain([&mut meaning.synonyms, &mut meaning.antonyms])
+                        .flatten()
+                    {
+                        list.truncate(max);
+                    }
+                }
+            }
+            entry.meanings.retain(|m| !m.definitions.is_empty());
+        }
+        entries.retain(|e| !e.meanings.is_empty());
+        entries
+    }
+}
@@ -0,0 +1,307 @@
+use serde_json::{json, Value};
+
+use crate::{
+    cefr::Level,
+    labels::Label,
+    sherlock::{self, Protocol},
+};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// What `schema` describes.
+#[derive(Debug)]
+pub enum Kind {
+    /// What `--format sherlock` prints, in the chosen `--protocol`.
+    Sherlock,
+    /// The entries the providers' answers are turned into, in
+    /// dictionaryapi.dev's layout, as `--msgpack-rpc`'s `define` returns
+    /// them.
+    Entries,
+}
+
+/// `schema`: prints a JSON Schema of one of the tool's JSON outputs, so the
+/// scripts reading it can check it and generate types from it.
+pub fn run(kind: Kind) {
+    let schema = match kind {
+        Kind::Sherlock => sherlock(sherlock::protocol()),
+        Kind::Entries => entries(),
+    };
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
+
+fn sherlock(protocol: Protocol) -> Value {
+    let tile = json!({
+        "description": "A Sherlock launcher tile: the word and its senses in Pango markup, and the actions the tile offers.",
+        "type": "object",
+        "properties": {
+            "title": { "type": "string" },
+            "content": { "type": "string", "description": "Pango markup" },
+            "next_content": { "type": "string", "description": "Pango markup for the detail view" },
+            "actions": { "type": "array", "items": { "$ref": "#/$defs/ApplicationAction" } },
+            "not_found": {
+                "description": "Set when no provider has the word.",
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "tried": {
+                        "description": "The other forms looked up in vain.",
+                        "type": "array",
+                        "items": { "type": "string" },
+                    },
+                    "suggestions": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["query", "tried", "suggestions"],
+            },
+            "error": {
+                "description": "Why the lookup failed, when a site couldn't be reached or answered with an error.",
+                "type": "string",
+            },
+        },
+        "required": ["title", "content", "next_content", "actions"],
+    });
+    let action = json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": ["string", "null"] },
+            "exec": {
+                "description": "The text copied, or the command run.",
+                "type": ["string", "null"],
+            },
+            "icon": { "type": ["string", "null"] },
+            "method": match protocol {
+                Protocol::SherlockV1 => json!({ "enum": ["copy", "command"] }),
+                Protocol::SherlockV2 => json!({ "type": "string", "examples": ["copy", "command"] }),
+            },
+            "exit": { "type": "boolean" },
+        },
+        "required": ["name", "exec", "icon", "method", "exit"],
+    });
+    match protocol {
+        Protocol::SherlockV1 => {
+            let mut schema = json!({ "$schema": DIALECT, "title": "SherlockPipeResponse" });
+            schema
+                .as_object_mut()
+                .unwrap()
+                .extend(tile.as_object().unwrap().clone());
+            schema["$defs"] = json!({ "ApplicationAction": action });
+            schema
+        }
+        Protocol::SherlockV2 => json!({
+            "$schema": DIALECT,
+            "title": "Sherlock tiles",
+            "description": "The answer's tile first, then the ones that go with it.",
+            "type": "array",
+            "items": { "$ref": "#/$defs/SherlockPipeResponse" },
+            "minItems": 1,
+            "$defs": { "SherlockPipeResponse": tile, "ApplicationAction": action },
+        }),
+    }
+}
+
+fn entries() -> Value {
+    let strings = json!({ "type": ["array", "null"], "items": { "type": "string" } });
+    let levels: Vec<Value> = Level::ALL.iter().map(|level| json!(level)).collect();
+    let labels: Vec<Value> = Label::ALL.iter().map(|label| json!(label)).collect();
+    json!({
+        "$schema": DIALECT,
+        "title": "Entries",
+        "description": "A word's dictionary entries, one per headword and etymology, after the rules, filters and labels are applied.",
+        "type": "array",
+        "items": { "$ref": "#/$defs/DefinitionResponse" },
+        "$defs": {
+            "DefinitionResponse": {
+                "type": "object",
+                "properties": {
+                    "word": { "type": "string" },
+                    "phonetic": { "type": ["string", "null"], "description": "IPA" },
+                    "phonetics": { "type": "array", "items": { "$ref": "#/$defs/Phonetic" } },
+                    "meanings": { "type": "array", "items": { "$ref": "#/$defs/Meaning" } },
+                    "source_urls": strings,
+                    "origin": { "type": ["string", "null"] },
+                },
+                "required": ["word", "phonetic", "phonetics", "meanings", "source_urls", "origin"],
+            },
+            "Phonetic": {
+                "type": "object",
+                "properties": {
+                    "text": { "type": ["string", "null"] },
+                    "audio": { "type": ["string", "null"], "description": "A recording's URL" },
+                    "source_url": { "type": ["string", "null"] },
+                    "license": {
+                        "anyOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "name": { "type": ["string", "null"] },
+                                    "url": { "type": ["string", "null"] },
+                                },
+                                "required": ["name", "url"],
+                            },
+                            { "type": "null" },
+                        ],
+                    },
+                },
+                "required": ["text", "audio", "source_url", "license"],
+            },
+            "Meaning": {
+                "type": "object",
+                "properties": {
+                    "partOfSpeech": { "type": "string" },
+                    "definitions": { "type": "array", "items": { "$ref": "#/$defs/Definition" } },
+                    "synonyms": strings,
+                    "antonyms": strings,
+                    "forms": {
+                        "description": "Inflected forms with what they are, \"ran (simple past)\".",
+                        "type": "array",
+                        "items": { "type": "string" },
+                    },
+                    "gender": {
+                        "enum": ["masculine", "feminine", "neuter", "common", "masculine-feminine"],
+                    },
+                },
+                "required": ["partOfSpeech", "definitions", "synonyms", "antonyms"],
+            },
+            "Definition": {
+                "type": "object",
+                "properties": {
+                    "definition": { "type": "string" },
+                    "example": { "type": ["string", "null"] },
+                    "synonyms": strings,
+                    "antonyms": strings,
+                    "level": {
+                        "description": "The estimated CEFR level, with --cefr.",
+                        "enum": levels,
+                    },
+                    "region": {
+                        "description": "Where the sense is used, \"UK\" or \"US\".",
+                        "type": "string",
+                    },
+                    "labels": { "type": "array", "items": { "enum": labels } },
+                },
+                "required": ["definition", "example", "synonyms", "antonyms"],
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{Definition, DefinitionResponse, License, Lookup, Meaning, Phonetic},
+        gender::Gender,
+        providers::{Resolved, Settings},
+    };
+
+    /// Checks `value` against `schema`: every key it has is described and
+    /// every required one is there, all the way down.
+    fn check(value: &Value, schema: &Value, root: &Value, at: &str) {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/$defs/");
+            return check(value, &root["$defs"][name], root, at);
+        }
+        if let Some(options) = schema["anyOf"].as_array() {
+            if value.is_object() {
+                let object = options.iter().find(|o| o["properties"].is_object());
+                return check(value, object.unwrap(), root, at);
+            }
+            return;
+        }
+        if let Some(allowed) = schema["enum"].as_array() {
+            assert!(
+                allowed.contains(value),
+                "{at}: {value} isn't in {allowed:?}"
+            );
+        }
+        if let (Some(object), Some(properties)) =
+            (value.as_object(), schema["properties"].as_object())
+        {
+            for (key, value) in object {
+                let property = properties
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{at}.{key} isn't in the schema"));
+                check(value, property, root, &format!("{at}.{key}"));
+            }
+            for key in schema["required"].as_array().unwrap() {
+                let key = key.as_str().unwrap();
+                assert!(object.contains_key(key), "{at}.{key} is required");
+            }
+        }
+        if let (Some(items), Some(schema)) = (value.as_array(), schema.get("items")) {
+            for (i, item) in items.iter().enumerate() {
+                check(item, schema, root, &format!("{at}[{i}]"));
+            }
+        }
+    }
+
+    /// An entry with every optional field filled in.
+    fn entry() -> DefinitionResponse {
+        DefinitionResponse {
+            word: String::from("run"),
+            phonetic: Some(String::from("/ɹʌn/")),
+            phonetics: vec![Phonetic {
+                text: Some(String::from("/ɹʌn/")),
+                audio: Some(String::from("https://example.org/run.mp3")),
+                source_url: Some(String::from("https://example.org/run")),
+                license: Some(License {
+                    name: Some(String::from("CC BY-SA 4.0")),
+                    url: Some(String::from(
+                        "https://creativecommons.org/licenses/by-sa/4.0",
+                    )),
+                }),
+            }],
+            meanings: vec![Meaning {
+                part_of_speech: String::from("verb"),
+                definitions: vec![Definition {
+                    definition: String::from("To move swiftly on foot."),
+                    example: Some(String::from("She runs every morning.")),
+                    synonyms: Some(vec![String::from("sprint")]),
+                    antonyms: Some(vec![String::from("walk")]),
+                    level: Some(Level::ALL[0]),
+                    region: Some(String::from("UK")),
+                    labels: vec![Label::ALL[0]],
+                }],
+                synonyms: Some(vec![String::from("jog")]),
+                antonyms: Some(Vec::new()),
+                forms: Some(vec![String::from("ran (simple past)")]),
+                gender: Some(Gender::MasculineFeminine),
+            }],
+            source_urls: Some(vec![String::from("https://en.wiktionary.org/wiki/run")]),
+            origin: Some(String::from("From Old English rinnan.")),
+            provider: None,
+        }
+    }
+
+    #[test]
+    fn entries_match_their_schema() {
+        let schema = entries();
+        let value = serde_json::to_value(vec![entry()]).unwrap();
+        check(&value, &schema, &schema, "entries");
+    }
+
+    #[test]
+    fn tiles_match_their_schema() {
+        let settings = Settings::default();
+        let resolved = |lookup| Resolved {
+            query: String::from("run"),
+            word: String::from("run"),
+            sources: Vec::new(),
+            lookup,
+            summary: None,
+            others: Vec::new(),
+            tried: vec![String::from("runn")],
+        };
+        let tiles = [
+            sherlock::respond(resolved(Lookup::Found(vec![entry()])), &settings),
+            sherlock::respond(resolved(Lookup::NotFound), &settings),
+            sherlock::failed("run", &surf::Error::from_str(500, "down"), &settings),
+        ];
+        for protocol in [Protocol::SherlockV1, Protocol::SherlockV2] {
+            let schema = sherlock(protocol);
+            for tile in &tiles {
+                let value: Value = serde_json::from_str(&tile.to_json(protocol)).unwrap();
+                check(&value, &schema, &schema, protocol.name());
+            }
+        }
+    }
+}
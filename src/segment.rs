@@ -0,0 +1,36 @@
+use crate::wordlist::WordList;
+
+/// Words too common to be what a pasted phrase was looked up for.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "am", "an", "and", "any", "are", "as", "at", "be",
+    "been", "but", "by", "can", "could", "did", "do", "does", "for", "from", "had", "has", "have",
+    "he", "her", "him", "his", "how", "i", "if", "in", "indeed", "into", "is", "it", "its", "just",
+    "me", "more", "my", "no", "not", "of", "on", "one", "or", "our", "out", "quite", "rather",
+    "really", "she", "so", "some", "such", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "to", "too", "up", "us", "very", "was", "we", "were", "what",
+    "when", "which", "who", "why", "will", "with", "would", "you", "your",
+];
+
+/// The words of `phrase` in the order they appear, lower-cased, with
+/// stopwords and repeats dropped.
+pub fn words(phrase: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in phrase.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-')) {
+        let word = word.trim_matches(|c| c == '\'' || c == '-').to_lowercase();
+        if word.is_empty() || STOPWORDS.contains(&word.as_str()) || words.contains(&word) {
+            continue;
+        }
+        words.push(word);
+    }
+    words
+}
+
+/// The words of `phrase` worth defining, rarest first. Words the frequency
+/// list doesn't rank count as rarest.
+pub fn content_words(phrase: &str) -> Vec<String> {
+    let mut words = words(phrase);
+    let list = WordList::shared();
+    // Stable, so equally rare words keep the phrase's order.
+    words.sort_by_key(|word| std::cmp::Reverse(list.rank(word).unwrap_or(usize::MAX)));
+    words
+}
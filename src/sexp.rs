@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::{api::DefinitionResponse, wikipedia::Summary};
+
+/// A Lisp value, written so Emacs's `read` gives it back: strings with their
+/// quotes and backslashes escaped, symbols with whatever would end them.
+pub enum Sexp {
+    String(String),
+    Symbol(String),
+    /// A plist's key, `:word`.
+    Keyword(&'static str),
+    List(Vec<Sexp>),
+}
+impl Sexp {
+    pub fn string(text: &str) -> Self {
+        Sexp::String(text.to_string())
+    }
+
+    /// `name` as a symbol the way Lisp spells them: lower case, words joined
+    /// by hyphens, "proper noun" as `proper-noun`.
+    pub fn symbol(name: &str) -> Self {
+        let words: Vec<&str> = name.split_whitespace().collect();
+        Sexp::Symbol(words.join("-").to_lowercase())
+    }
+
+    pub fn strings<'a>(texts: impl IntoIterator<Item = &'a String>) -> Self {
+        Sexp::List(texts.into_iter().map(|text| Sexp::string(text)).collect())
+    }
+
+    /// A property list of the `properties` that have a value, `(:word "run"
+    /// :phonetic "/ɹʌn/")`; a property is left out rather than nil.
+    pub fn plist(properties: Vec<(&'static str, Option<Sexp>)>) -> Self {
+        Sexp::List(
+            properties
+                .into_iter()
+                .filter_map(|(key, value)| Some([Sexp::Keyword(key), value?]))
+                .flatten()
+                .collect(),
+        )
+    }
+}
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sexp::String(text) => {
+                f.write_str("\"")?;
+                for c in text.chars() {
+                    match c {
+                        '"' | '\\' => write!(f, "\\{}", c)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"")
+            }
+            // The empty symbol reads as `##`.
+            Sexp::Symbol(name) if name.is_empty() => f.write_str("##"),
+            Sexp::Symbol(name) => {
+                // A symbol that would read as a number needs its first
+                // character escaped: `\1`.
+                if name.parse::<f64>().is_ok() {
+                    f.write_str("\\")?;
+                }
+                for (i, c) in name.chars().enumerate() {
+                    let special = matches!(
+                        c,
+                        '"' | '\''
+                            | '\\'
+                            | ';'
+                            | '('
+                            | ')'
+                            | '['
+                            | ']'
+                            | '#'
+                            | '`'
+                            | ','
+                            | '.'
+                            | '?'
+                    ) || c.is_whitespace();
+                    // `:` only makes a keyword at the start.
+                    if special || (c == ':' && i == 0) {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
+                Ok(())
+            }
+            Sexp::Keyword(name) => write!(f, ":{}", name),
+            Sexp::List(items) => {
+                f.write_str("(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// A plist per entry, the parts of speech, usage labels, CEFR levels and
+/// genders as symbols.
+pub fn entries(entries: &[DefinitionResponse]) -> Sexp {
+    Sexp::List(entries.iter().map(entry).collect())
+}
+
+fn entry(entry: &DefinitionResponse) -> Sexp {
+    let meanings = entry.meanings.iter().map(|meaning| {
+        let definitions = meaning.definitions.iter().map(|def| {
+            Sexp::plist(vec![
+                ("definition", Some(Sexp::string(&def.definition))),
+                ("example", def.example.as_deref().map(Sexp::string)),
+                ("synonyms", def.synonyms.as_ref().map(Sexp::strings)),
+                ("antonyms", def.antonyms.as_ref().map(Sexp::strings)),
+                (
+                    "labels",
+                    Some(&def.labels)
+                        .filter(|labels| !labels.is_empty())
+                        .map(|labels| {
+                            Sexp::List(
+                                labels
+                                    .iter()
+                                    .map(|label| Sexp::symbol(&label.to_string()))
+                                    .collect(),
+                            )
+                        }),
+                ),
+                (
+                    "level",
+                    def.level.map(|level| Sexp::symbol(&level.to_string())),
+                ),
+                ("region", def.region.as_deref().map(Sexp::string)),
+            ])
+        });
+        Sexp::plist(vec![
+            (
+                "part-of-speech",
+                Some(Sexp::symbol(&meaning.part_of_speech)),
+            ),
+            (
+                "gender",
+                meaning
+                    .gender
+                    .map(|gender| Sexp::symbol(gender.abbreviation())),
+            ),
+            (
+                "forms",
+                meaning
+                    .forms
+                    .as_ref()
+                    .filter(|f| !f.is_empty())
+                    .map(Sexp::strings),
+            ),
+            ("definitions", Some(Sexp::List(definitions.collect()))),
+            ("synonyms", meaning.synonyms.as_ref().map(Sexp::strings)),
+            ("antonyms", meaning.antonyms.as_ref().map(Sexp::strings)),
+        ])
+    });
+    Sexp::plist(vec![
+        ("word", Some(Sexp::string(&entry.word))),
+        ("phonetic", entry.ipa().map(Sexp::string)),
+        ("audio", entry.audio_url().map(Sexp::string)),
+        ("provider", entry.provider.map(|p| Sexp::symbol(p.name()))),
+        ("meanings", Some(Sexp::List(meanings.collect()))),
+        ("origin", entry.origin.as_deref().map(Sexp::string)),
+        ("source-urls", entry.source_urls.as_ref().map(Sexp::strings)),
+    ])
+}
+
+/// A Wikipedia summary, for names the dictionaries don't have.
+pub fn summary(summary: &Summary) -> Sexp {
+    Sexp::plist(vec![
+        ("title", Some(Sexp::string(&summary.title))),
+        (
+            "description",
+            summary.description.as_deref().map(Sexp::string),
+        ),
+        ("extract", Some(Sexp::string(&summary.extract))),
+        ("url", Some(Sexp::string(summary.url()))),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_what_read_gives_back() {
+        assert_eq!(
+            Sexp::string(r#"say "hi" \ bye"#).to_string(),
+            r#""say \"hi\" \\ bye""#
+        );
+        assert_eq!(Sexp::symbol("proper noun").to_string(), "proper-noun");
+        assert_eq!(Sexp::symbol("Noun").to_string(), "noun");
+        assert_eq!(Sexp::symbol("1").to_string(), r"\1");
+        assert_eq!(Sexp::symbol("1.5").to_string(), r"\1\.5");
+        assert_eq!(Sexp::symbol(":x").to_string(), r"\:x");
+        assert_eq!(Sexp::symbol("a:b").to_string(), "a:b");
+        assert_eq!(Sexp::symbol("it's").to_string(), r"it\'s");
+        assert_eq!(Sexp::symbol("(a)").to_string(), r"\(a\)");
+        assert_eq!(Sexp::symbol("").to_string(), "##");
+        assert_eq!(
+            Sexp::plist(vec![
+                ("word", Some(Sexp::string("run"))),
+                ("origin", None),
+                ("pos", Some(Sexp::symbol("verb"))),
+            ])
+            .to_string(),
+            r#"(:word "run" :pos verb)"#
+        );
+    }
+}
@@ -0,0 +1,759 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    env,
+    fmt::{self, Write},
+    iter,
+    str::FromStr,
+    sync::{LazyLock, OnceLock},
+};
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup},
+    cedict,
+    config::Config,
+    confusables,
+    content_filter::{self, ContentFilter},
+    desktop,
+    furigana::Ruby,
+    gender, http,
+    i18n::{text, tr},
+    labels,
+    merge::MergePolicy,
+    providers::{Resolved, Settings},
+    related, spelling,
+    text::{self, Numbering},
+};
+
+/// How many "Did you mean" suggestions a not-found tile offers.
+const SUGGESTIONS: usize = 5;
+
+/// Where "Search the web" looks for a word, when the config doesn't say.
+const WEB_SEARCH: &str = "https://duckduckgo.com/?q=define+{word}";
+/// Stands for the word in a `web_search` URL.
+pub const SEARCH_WORD: &str = "{word}";
+
+/// A parenthesized aside and the space after it.
+static PARENS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\([^)]*\)\s*").unwrap());
+
+/// Which version of the pipe format is printed, from `--protocol` or
+/// `protocol` in the config. A Sherlock setup written for one version keeps
+/// getting it when later versions change the format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Protocol {
+    /// One tile, as a JSON object, whose actions copy or run a command.
+    #[default]
+    SherlockV1,
+    /// An array of tiles: the answer's, then any of their own that go with
+    /// it, such as a tile per "Did you mean" suggestion. Actions may use
+    /// other methods in later releases.
+    SherlockV2,
+}
+impl Protocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::SherlockV1 => "sherlock-v1",
+            Protocol::SherlockV2 => "sherlock-v2",
+        }
+    }
+}
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "sherlock-v1" => Ok(Protocol::SherlockV1),
+            "sherlock-v2" => Ok(Protocol::SherlockV2),
+            _ => Err(format!(
+                "Unknown protocol '{}' (expected sherlock-v1 or sherlock-v2)",
+                s
+            )),
+        }
+    }
+}
+
+static PROTOCOL: OnceLock<Protocol> = OnceLock::new();
+
+/// Chooses the protocol tiles are printed in for the rest of the run.
+pub fn select_protocol(protocol: Protocol) {
+    let _ = PROTOCOL.set(protocol);
+}
+
+pub fn protocol() -> Protocol {
+    PROTOCOL.get().copied().unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SherlockPipeResponse {
+    title: String,
+    content: String,
+    next_content: String,
+    actions: Vec<ApplicationAction>,
+    /// Set on the tile for a word no provider has. Sherlock ignores it, but
+    /// scripts reading the tile can tell an unknown word from a failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_found: Option<NotFound>,
+    /// Why the lookup failed, on the tiles for sites that couldn't be
+    /// reached or answered with an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// The tiles shown after this one with `sherlock-v2`, and left out with
+    /// `sherlock-v1`.
+    #[serde(skip)]
+    more: Vec<SherlockPipeResponse>,
+}
+impl SherlockPipeResponse {
+    pub fn new(title: String, content: String, actions: Vec<ApplicationAction>) -> Self {
+        Self {
+            title,
+            next_content: content.clone(),
+            content,
+            actions,
+            not_found: None,
+            error: None,
+            more: Vec::new(),
+        }
+    }
+
+    /// A tile with just a title, used for concise messages like "not found".
+    pub fn message(title: String, content: String) -> Self {
+        Self {
+            title,
+            content,
+            next_content: String::new(),
+            actions: vec![],
+            not_found: None,
+            error: None,
+            more: Vec::new(),
+        }
+    }
+
+    /// Prints the tile in the chosen [`Protocol`], on one line.
+    pub fn print(&self) {
+        println!("{}", self.to_json(protocol()));
+    }
+
+    /// The tile as `protocol` has it: on its own with `sherlock-v1`, and
+    /// first of the ones that go with it with `sherlock-v2`.
+    pub fn to_json(&self, protocol: Protocol) -> String {
+        let json = match protocol {
+            Protocol::SherlockV1 => serde_json::to_string(self),
+            Protocol::SherlockV2 => {
+                let tiles: Vec<&Self> = iter::once(self).chain(&self.more).collect();
+                serde_json::to_string(&tiles)
+            }
+        };
+        json.unwrap()
+    }
+
+    fn failure(self, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..self
+        }
+    }
+}
+
+/// What was looked up for a word that wasn't found.
+#[derive(Debug, Serialize, Deserialize)]
+struct NotFound {
+    query: String,
+    /// The other forms looked up in vain (see [`Resolved::tried`]).
+    tried: Vec<String>,
+    suggestions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplicationAction {
+    name: Option<String>,
+    exec: Option<String>,
+    icon: Option<String>,
+    method: String,
+    exit: bool,
+}
+impl ApplicationAction {
+    /// Copies `text` to the clipboard.
+    pub fn copy(name: String, text: String) -> Self {
+        Self {
+            name: Some(name),
+            exec: Some(text),
+            icon: Some(String::from("edit-copy")),
+            method: String::from("copy"),
+            exit: true,
+        }
+    }
+
+    /// Opens `url` in the default browser.
+    pub fn open_url(name: &str, url: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            exec: Some(format!("{} {}", desktop::URL_OPENER, shell_quote(url))),
+            icon: Some(String::from("web-browser")),
+            method: String::from("command"),
+            exit: true,
+        }
+    }
+
+    fn from_definition(definition: &Definition) -> Self {
+        let name = remove_parens(&definition.definition);
+        let short = definition.to_vec().join("\n");
+        Self::copy(name, short)
+    }
+
+    /// Searches the web for what `word` means, with the config's
+    /// `web_search` or else DuckDuckGo.
+    fn search_web(word: &str) -> Self {
+        let template = Config::load().web_search.as_deref().unwrap_or(WEB_SEARCH);
+        let encoded = utf8_percent_encode(word, NON_ALPHANUMERIC).to_string();
+        let url = template.replace(SEARCH_WORD, &encoded);
+        Self::open_url(&tr("search-web", &[("word", word)]), &url)
+    }
+
+    /// An action that looks `word` up again and shows the result in a new
+    /// Sherlock window, so related words can be clicked through.
+    pub fn lookup_word(label: &str, word: &str, settings: &Settings) -> Self {
+        Self::lookup_with(label, word, settings, &[])
+    }
+
+    /// Like [`lookup_word`](Self::lookup_word), adding `flags` to the
+    /// lookup's own.
+    fn lookup_with(label: &str, word: &str, settings: &Settings, flags: &[&str]) -> Self {
+        let providers = settings.providers_name();
+        let mut args = vec!["--provider", &providers, "--lang", &settings.lang];
+        // Clicking through from a private lookup stays private.
+        if settings.private {
+            args.push("--private");
+        }
+        if settings.ipv4_only {
+            args.push("--ipv4-only");
+        }
+        if let Some(profile) = &settings.profile {
+            args.extend(["--profile", profile]);
+        }
+        if let Some(ui_lang) = &settings.ui_lang {
+            args.extend(["--ui-lang", ui_lang]);
+        }
+        args.extend(flags);
+        args.extend(["--", word]);
+        Self::rerun(format!("{}: {}", label, word), &args)
+    }
+
+    /// An action that runs this program again with `args` and shows what it
+    /// prints in a new Sherlock window.
+    pub fn rerun(name: String, args: &[&str]) -> Self {
+        let exe = env::current_exe()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| String::from("sherlock-dictionary"));
+        // The new window reads the tiles in the same protocol; the flag goes
+        // after a leading `--`, which has to stay first.
+        let protocol: &[&str] = match protocol() {
+            Protocol::SherlockV1 => &[],
+            protocol => &["--protocol", protocol.name()],
+        };
+        let (marker, args) = match args {
+            ["--", rest @ ..] => (&args[..1], rest),
+            _ => (&[][..], args),
+        };
+        let args: Vec<String> = marker
+            .iter()
+            .chain(protocol)
+            .chain(args)
+            .map(|arg| shell_quote(arg))
+            .collect();
+        Self {
+            name: Some(name),
+            exec: Some(format!(
+                "{} {} | sherlock",
+                shell_quote(&exe),
+                args.join(" ")
+            )),
+            icon: Some(String::from("go-next")),
+            method: String::from("command"),
+            exit: true,
+        }
+    }
+}
+
+/// What an error tile offers instead of retyping `word`: trying again,
+/// searching the web and, when a site that failed answered the same request
+/// before, that answer.
+fn recovery(word: &str, settings: &Settings) -> Vec<ApplicationAction> {
+    // Otherwise the modifiers would be read off the query all over again.
+    let exact: &[&str] = match settings.exact {
+        true => &["--exact"],
+        false => &[],
+    };
+    let mut actions = vec![
+        ApplicationAction::lookup_with(text("action-retry"), word, settings, exact),
+        ApplicationAction::search_web(word),
+    ];
+    if !settings.cached && http::has_cached_copy() {
+        actions.push(ApplicationAction::lookup_with(
+            text("action-cached"),
+            word,
+            settings,
+            &[exact, &["--cached"]].concat(),
+        ));
+    }
+    actions
+}
+
+/// The tile for a lookup that didn't get an answer at all, such as when the
+/// network is down, with the ways to recover from [`recovery`].
+pub fn failed(query: &str, error: &surf::Error, settings: &Settings) -> SherlockPipeResponse {
+    SherlockPipeResponse::new(
+        tr("could-not-look-up", &[("word", query)]),
+        error.to_string(),
+        recovery(query, settings),
+    )
+    .failure(error.to_string())
+}
+
+/// Navigation actions for the "See also" words, then every other distinct synonym
+/// and antonym across the entries.
+fn cross_references(
+    entries: &[DefinitionResponse],
+    see_also: &[String],
+    settings: &Settings,
+) -> Vec<ApplicationAction> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut actions = Vec::new();
+    for word in see_also {
+        seen.insert(word);
+        actions.push(ApplicationAction::lookup_word(
+            text("action-see-also"),
+            word,
+            settings,
+        ));
+    }
+    for meaning in entries.iter().flat_map(|entry| &entry.meanings) {
+        let (synonym, antonym) = (text("action-synonym"), text("action-antonym"));
+        let related = [(synonym, &meaning.synonyms), (antonym, &meaning.antonyms)]
+            .into_iter()
+            .chain(
+                meaning
+                    .definitions
+                    .iter()
+                    .flat_map(|def| [(synonym, &def.synonyms), (antonym, &def.antonyms)]),
+            );
+        for (label, words) in related {
+            for word in words.iter().flatten() {
+                if seen.insert(word) {
+                    actions.push(ApplicationAction::lookup_word(label, word, settings));
+                }
+            }
+        }
+    }
+    actions
+}
+
+/// Wraps `s` in single quotes for `sh`.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+pub fn remove_parens(s: &str) -> String {
+    let cleaned = PARENS.replace_all(s, "");
+    cleaned
+        .split_once(',')
+        .map_or_else(
+            || cleaned.trim_end_matches('.'),
+            |(first, _)| first.trim_end_matches('.'),
+        )
+        .to_string()
+}
+
+impl DefinitionResponse {
+    /// Appends the entry's tile markup to `out` and a copy action for each
+    /// sense to `actions`.
+    fn format_content_for_sherlock(&self, out: &mut String, actions: &mut Vec<ApplicationAction>) {
+        // Writing to a String can't fail.
+        out.push_str("<span font_desc=\"monospace\">\n");
+
+        // The title has the word; only its reading, when it has ruby, needs
+        // the word under it.
+        if let Some(ruby) = Ruby::of(self) {
+            let mut lines = ruby.lines();
+            let word = lines.pop().unwrap_or_default();
+            for reading in lines {
+                writeln!(out, "<span alpha=\"60%\">{}</span>", reading).unwrap();
+            }
+            writeln!(out, "<b>{}</b>\n", word).unwrap();
+        }
+        // Chinese is hard to read without its pinyin, which the title lacks.
+        if let Some(reading) = self.ipa().filter(|_| cedict::is_from(self)) {
+            writeln!(out, "<b>{}</b>  {}\n", self.word, cedict::pango(reading)).unwrap();
+        }
+        let numbering = Numbering::of([self]);
+        for meaning in &self.meanings {
+            writeln!(out, "─── <b><i>{}</i></b> ───\n", meaning.heading()).unwrap();
+            if let Some(forms) = meaning.forms.as_ref().filter(|f| !f.is_empty()) {
+                write!(out, "     {} ", text("forms")).unwrap();
+                push_joined(out, forms);
+                out.push_str("\n\n");
+            }
+            for (i, def) in meaning.definitions.iter().enumerate() {
+                if def.definition != content_filter::MASK {
+                    actions.push(ApplicationAction::from_definition(def));
+                }
+                write!(out, " {}", numbering.label(i + 1)).unwrap();
+                if let Some(level) = def.level {
+                    write!(out, "<small>[{}]</small> ", level).unwrap();
+                }
+                if let Some(region) = &def.region {
+                    write!(out, "<small>[{}]</small> ", region).unwrap();
+                }
+                out.push_str(&labels::pango(&def.labels));
+                out.push_str(&text::isolate(&def.definition));
+                out.push('\n');
+                if let Some(example) = &def.example {
+                    let example = text::isolate(example);
+                    writeln!(out, "     {} \"{}\"", text("example"), example).unwrap();
+                }
+                if let Some(synonyms) = def.synonyms.as_ref().filter(|s| !s.is_empty()) {
+                    write!(out, "     {} ", text("synonyms")).unwrap();
+                    push_joined(out, synonyms);
+                    out.push('\n');
+                }
+                if let Some(antonyms) = def.antonyms.as_ref().filter(|a| !a.is_empty()) {
+                    write!(out, "     {} ", text("antonyms")).unwrap();
+                    push_joined(out, antonyms);
+                    out.push('\n');
+                }
+                out.push('\n');
+            }
+        }
+        out.push_str("────────────\n");
+        out.push_str("</span>");
+    }
+
+    /// Roughly how long the entry's tile markup is, to size the buffer once.
+    fn formatted_len(&self) -> usize {
+        let senses = self
+            .meanings
+            .iter()
+            .flat_map(|meaning| &meaning.definitions);
+        let text: usize = senses
+            .map(|def| {
+                let words = [&def.synonyms, &def.antonyms]
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|word| word.len() + 2)
+                    .sum::<usize>();
+                def.definition.len() + def.example.as_ref().map_or(0, String::len) + words
+            })
+            .sum();
+        // The markup around each sense and meaning.
+        text + 64 * self.meanings.len()
+            + 48 * self
+                .meanings
+                .iter()
+                .map(|m| m.definitions.len())
+                .sum::<usize>()
+    }
+}
+
+/// Appends `words` to `out`, comma-separated, without joining them first,
+/// and isolated when they are right to left.
+fn push_joined(out: &mut String, words: &[String]) {
+    let (open, close) = text::isolation(words.iter().any(|word| text::has_rtl(word)));
+    out.push_str(open);
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(word);
+    }
+    out.push_str(close);
+}
+
+/// Consolidates all entries into a single content string plus their copy actions.
+/// With `headers`, each provider's entries are put under its name.
+fn format_entries(
+    definitions: &[DefinitionResponse],
+    see_also: &[String],
+    headers: bool,
+) -> (String, Vec<ApplicationAction>) {
+    let mut actions: Vec<ApplicationAction> = Vec::new();
+    let capacity = definitions
+        .iter()
+        .map(DefinitionResponse::formatted_len)
+        .sum();
+    let mut all_definitions_content = String::with_capacity(capacity);
+    for (i, def_response) in definitions.iter().enumerate() {
+        let provider = def_response.provider;
+        if headers && (i == 0 || definitions[i - 1].provider != provider) {
+            if let Some(provider) = provider {
+                writeln!(
+                    all_definitions_content,
+                    "<span font_desc=\"monospace\"><b>═══ {} ═══</b></span>",
+                    provider
+                )
+                .unwrap();
+            }
+        }
+        def_response.format_content_for_sherlock(&mut all_definitions_content, &mut actions);
+    }
+    if !see_also.is_empty() {
+        write!(
+            all_definitions_content,
+            "\n<span font_desc=\"monospace\"><i>{}</i> ",
+            text("see-also")
+        )
+        .unwrap();
+        push_joined(&mut all_definitions_content, see_also);
+        all_definitions_content.push_str("</span>");
+    }
+    (all_definitions_content, actions)
+}
+
+/// The tile for a word no provider has an entry for, or none that's left
+/// after the filters: the closest spellings to pick from, and searching the
+/// web.
+fn not_found(query: String, tried: Vec<String>, settings: &Settings) -> SherlockPipeResponse {
+    let suggestions = spelling::suggest(&query, settings, SUGGESTIONS);
+    // Searching the web is the way on when nothing else is offered.
+    let search = ApplicationAction::search_web(&query);
+    let mut response = match suggestions.is_empty() {
+        true => {
+            SherlockPipeResponse::new(text("not-found").to_string(), String::new(), vec![search])
+        }
+        false => {
+            let mut actions: Vec<ApplicationAction> = suggestions
+                .iter()
+                .map(|s| ApplicationAction::lookup_word(text("action-did-you-mean"), s, settings))
+                .collect();
+            actions.push(search);
+            SherlockPipeResponse::new(
+                text("not-found").to_string(),
+                format!(
+                    "<span font_desc=\"monospace\">{}</span>",
+                    tr("did-you-mean", &[("words", &suggestions.join(", "))])
+                ),
+                actions,
+            )
+        }
+    };
+    // With sherlock-v2, each suggestion gets a tile to pick it from.
+    response.more = suggestions
+        .iter()
+        .map(|suggestion| {
+            let action =
+                ApplicationAction::lookup_word(text("action-did-you-mean"), suggestion, settings);
+            SherlockPipeResponse::new(
+                suggestion.clone(),
+                text("action-did-you-mean").to_string(),
+                vec![action],
+            )
+        })
+        .collect();
+    response.not_found = Some(NotFound {
+        query,
+        tried,
+        suggestions,
+    });
+    response
+}
+
+/// Turns a lookup result into the tile Sherlock displays, logging details to stderr.
+pub fn respond(resolved: Resolved, settings: &Settings) -> SherlockPipeResponse {
+    let word_to_define = resolved.word.as_str();
+    match resolved.lookup {
+        Lookup::Found(definitions) => {
+            let full = settings.prepare(definitions.clone(), ContentFilter::Off);
+            let definitions = settings.prepare(definitions, settings.content_filter);
+            if definitions.is_empty() {
+                eprintln!(
+                    "Every sense of '{}' was hidden by the content filter or part of speech.",
+                    word_to_define
+                );
+                return not_found(resolved.query, resolved.tried, settings);
+            }
+            let see_also = related::see_also(&definitions);
+            let related = cross_references(&definitions, &see_also, settings);
+            let headers = settings.merge_policy == MergePolicy::ConcatWithHeaders;
+            let (mut all_definitions_content, mut actions) =
+                format_entries(&definitions, &see_also, headers);
+            actions.extend(related);
+
+            // Blurred senses stay readable in the detail view.
+            let mut next_content = if settings.content_filter == ContentFilter::Blur {
+                format_entries(&full, &see_also, headers).0
+            } else {
+                all_definitions_content.clone()
+            };
+
+            // Simple mode's respelling replaces the IPA, which learners can't read.
+            if let Some(respelling) = settings
+                .simple
+                .then(|| full.iter().find_map(|e| e.ipa()))
+                .flatten()
+            {
+                let line = format!(
+                    "<span font_desc=\"monospace\">{} <b>{}</b></span>\n",
+                    text("say-it"),
+                    respelling
+                );
+                all_definitions_content.insert_str(0, &line);
+                next_content.insert_str(0, &line);
+            }
+
+            for (other, hint) in confusables::confused_with(word_to_define, &settings.lang) {
+                let note = format!(
+                    "\n<span font_desc=\"monospace\">⚠ <i>{}</i> <b>{}</b>: {}</span>",
+                    text("confused-with"),
+                    other,
+                    hint
+                );
+                all_definitions_content.push_str(&note);
+                next_content.push_str(&note);
+                actions.push(ApplicationAction::lookup_word(
+                    text("confused-with"),
+                    other,
+                    settings,
+                ));
+            }
+
+            // The detail view lists every pronunciation, not just the preferred one.
+            let mut pronunciations: Vec<String> = Vec::new();
+            for line in full.iter().flat_map(|e| e.pronunciations()) {
+                if !pronunciations.contains(&line) {
+                    pronunciations.push(line);
+                }
+            }
+            if pronunciations.len() > 1 {
+                next_content.insert_str(
+                    0,
+                    &format!(
+                        "<span font_desc=\"monospace\"><i>{}</i> {}</span>\n",
+                        text("pronunciations"),
+                        pronunciations.join(" · ")
+                    ),
+                );
+            }
+
+            // With a fallback chain, say which providers answered.
+            if settings.providers.len() > 1 {
+                let names: Vec<&str> = resolved.sources.iter().map(|p| p.name()).collect();
+                let source = format!(
+                    "\n<span font_desc=\"monospace\"><i>{}</i> {}</span>",
+                    text("source"),
+                    names.join(", ")
+                );
+                all_definitions_content.push_str(&source);
+                next_content.push_str(&source);
+            }
+
+            actions.extend(resolved.others.iter().map(|other| {
+                ApplicationAction::lookup_word(text("action-phrase"), other, settings)
+            }));
+
+            // A noun's title has its article and gender: "der Hund" (m).
+            let gender = definitions
+                .first()
+                .filter(|entry| entry.word.to_lowercase() == word_to_define.to_lowercase())
+                .and_then(|entry| Some((entry, gender::of(entry)?)));
+            let headword = match gender {
+                Some((entry, gender)) => gender::with_article(&entry.word, gender, &settings.lang),
+                None => word_to_define.to_string(),
+            };
+            let mut title = tr("definition-of", &[("word", &headword)]);
+            if let Some((_, gender)) = gender {
+                title.push_str(&format!(" ({})", gender.abbreviation()));
+            }
+            if resolved.query != resolved.word {
+                title.push(' ');
+                title.push_str(&tr("from-query", &[("query", &resolved.query)]));
+            }
+
+            // Create a single SherlockPipeResponse with all content
+            SherlockPipeResponse {
+                title,
+                content: all_definitions_content,
+                next_content, // Populate if Sherlock supports pagination
+                actions,
+                not_found: None,
+                error: None,
+                more: Vec::new(),
+            }
+        }
+        Lookup::NotFound => {
+            if let Some(summary) = resolved.summary {
+                return summary.response();
+            }
+            eprintln!("{}.", tr("not-found-for", &[("word", word_to_define)]));
+            not_found(resolved.query, resolved.tried, settings)
+        }
+        Lookup::ApiError { status, error } => {
+            // For other API errors, output the detailed message
+            let title = if status.is_success() {
+                tr("api-error", &[("title", &error.title)])
+            } else {
+                let status = status.to_string();
+                tr(
+                    "api-error-status",
+                    &[("status", &status), ("title", &error.title)],
+                )
+            };
+            let content = format!(
+                "{} {}\n{} {}",
+                text("message"),
+                error.message,
+                text("resolution"),
+                error.resolution
+            );
+            eprintln!("{}\n{}", title, content);
+            SherlockPipeResponse::new(title, content, recovery(&resolved.query, settings))
+                .failure(error.title)
+        }
+        Lookup::ParseError {
+            status,
+            body,
+            error,
+        } if status.is_success() => {
+            // If it's neither a definition array nor a known error object,
+            // print the raw body and the original parsing error for debugging.
+            eprintln!("Failed to parse API response for '{}'.", word_to_define);
+            eprintln!("Raw response body: {}", body);
+            eprintln!("Parsing error: {}", error);
+            // Output generic parsing error as JSON for Sherlock
+            SherlockPipeResponse::new(
+                tr("parse-error", &[("word", word_to_define)]),
+                tr("parse-error-body", &[("body", &body)]),
+                recovery(&resolved.query, settings),
+            )
+            .failure(error.to_string())
+        }
+        Lookup::ParseError {
+            status,
+            body,
+            error,
+        } => {
+            // If the status is not successful, and we can't parse it into our
+            // known error format, print a generic error with the raw body.
+            eprintln!("Error fetching definition for '{}'.", word_to_define);
+            eprintln!("HTTP Status: {}", status);
+            eprintln!("Failed to parse error response: {}", error);
+            eprintln!("Raw response body: {}", body);
+            // Output generic HTTP error as JSON for Sherlock
+            SherlockPipeResponse::new(
+                tr(
+                    "http-error",
+                    &[("status", &status.to_string()), ("word", word_to_define)],
+                ),
+                tr("http-error-body", &[("body", &body)]),
+                recovery(&resolved.query, settings),
+            )
+            .failure(format!("status {}", status))
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::{
+    api::{Definition, DefinitionResponse},
+    labels::Label,
+};
+
+/// How many senses simple mode keeps, in the provider's (roughly by
+/// frequency) order.
+const MAX_SENSES: usize = 3;
+
+/// Subject and period labels that mark a sense as jargon or of no use to a learner.
+static JARGON: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*\([^)]*\b(mathematics|math|geometry|algebra|physics|chemistry|biochemistry|biology|botany|zoology|anatomy|medicine|pathology|pharmacology|law|legal|finance|economics|computing|programming|linguistics|grammar|philosophy|theology|music|nautical|military|heraldry|astronomy|geology|obsolete|archaic|dated|rare|historical|dialectal|technical)\b[^)]*\)",
+    )
+    .unwrap()
+});
+
+fn is_jargon(definition: &Definition) -> bool {
+    definition.labels.iter().any(|label| {
+        matches!(
+            label,
+            Label::Archaic
+                | Label::Dated
+                | Label::Obsolete
+                | Label::Historical
+                | Label::Rare
+                | Label::Dialectal
+                | Label::Technical
+        )
+    }) || JARGON.is_match(&definition.definition)
+}
+
+/// Simple-English presentation: drops jargon and archaic senses, keeps the first
+/// [`MAX_SENSES`] of what is left, and replaces IPA with a plain respelling.
+pub fn apply(mut entries: Vec<DefinitionResponse>) -> Vec<DefinitionResponse> {
+    let mut kept = 0;
+    for entry in &mut entries {
+        for meaning in &mut entry.meanings {
+            meaning.definitions.retain(|def| {
+                let keep = kept < MAX_SENSES && !is_jargon(def);
+                kept += usize::from(keep);
+                keep
+            });
+        }
+        entry.meanings.retain(|m| !m.definitions.is_empty());
+
+        if let Some(ipa) = entry.ipa() {
+            entry.phonetic = Some(respell(ipa));
+            for phonetic in &mut entry.phonetics {
+                phonetic.text = None;
+            }
+        }
+    }
+    entries.retain(|e| !e.meanings.is_empty());
+    entries
+}
+
+/// IPA symbols (longest first) and how simple mode spells them.
+const SOUNDS: &[(&str, &str, bool)] = &[
+    // (ipa, respelling, is_vowel)
+    ("tʃ", "ch", false),
+    ("dʒ", "j", false),
+    ("aɪ", "eye", true),
+    ("aʊ", "ow", true),
+    ("ɔɪ", "oy", true),
+    ("eɪ", "ay", true),
+    ("oʊ", "oh", true),
+    ("əʊ", "oh", true),
+    ("ɪə", "eer", true),
+    ("eə", "air", true),
+    ("ɛə", "air", true),
+    ("ʊə", "oor", true),
+    ("ɑɹ", "ar", true),
+    ("ɑr", "ar", true),
+    ("ɔɹ", "or", true),
+    ("ɔr", "or", true),
+    ("ɜɹ", "ur", true),
+    ("ɜr", "ur", true),
+    ("ɛɹ", "air", true),
+    ("ɛr", "air", true),
+    ("əɹ", "er", true),
+    ("ər", "er", true),
+    ("i", "ee", true),
+    ("ɪ", "ih", true),
+    ("e", "eh", true),
+    ("ɛ", "eh", true),
+    ("æ", "a", true),
+    ("ɑ", "ah", true),
+    ("ɒ", "o", true),
+    ("ɔ", "aw", true),
+    ("o", "oh", true),
+    ("ʊ", "uu", true),
+    ("u", "oo", true),
+    ("ʌ", "uh", true),
+    ("ə", "uh", true),
+    ("ɜ", "ur", true),
+    ("ɝ", "ur", true),
+    ("ɚ", "er", true),
+    ("ɐ", "uh", true),
+    ("p", "p", false),
+    ("b", "b", false),
+    ("t", "t", false),
+    ("d", "d", false),
+    ("ɾ", "t", false),
+    ("k", "k", false),
+    ("ɡ", "g", false),
+    ("g", "g", false),
+    ("f", "f", false),
+    ("v", "v", false),
+    ("θ", "th", false),
+    ("ð", "th", false),
+    ("s", "s", false),
+    ("z", "z", false),
+    ("ʃ", "sh", false),
+    ("ʒ", "zh", false),
+    ("h", "h", false),
+    ("x", "kh", false),
+    ("m", "m", false),
+    ("n", "n", false),
+    ("ŋ", "ng", false),
+    ("l", "l", false),
+    ("ɫ", "l", false),
+    ("ɹ", "r", false),
+    ("r", "r", false),
+    ("j", "y", false),
+    ("w", "w", false),
+];
+
+/// Vowels short enough that a following consonant closes their syllable
+/// ("FEM", not "FE-m…"). An unstressed ə leaves it to start the next one
+/// ("uh-ruhl").
+const CHECKED: &[&str] = &["ɪ", "e", "ɛ", "æ", "ɒ", "ʊ", "ʌ"];
+
+#[derive(Default)]
+struct Syllable {
+    onset: Vec<&'static str>,
+    nucleus: Option<&'static str>,
+    checked: bool,
+    coda: Vec<&'static str>,
+    stressed: bool,
+}
+
+/// Whether `symbol` is a vowel and the r after it, as in "car".
+fn is_r_colored(symbol: &str) -> bool {
+    symbol.chars().count() == 2 && symbol.ends_with(['ɹ', 'r'])
+}
+
+/// Whether the first sound of `ipa` is a vowel.
+fn starts_with_vowel(ipa: &str) -> bool {
+    let ipa = ipa.trim_start_matches(['ː', 'ˑ']);
+    SOUNDS
+        .iter()
+        .any(|(symbol, _, vowel)| *vowel && ipa.starts_with(symbol))
+}
+
+/// Turns IPA like `/ɪˈfɛm(ə)ɹəl/` into a reading aid like `ih-FEM-uh-ruhl`.
+/// Unknown symbols are skipped; this is a learner's approximation, not phonetics.
+pub fn respell(ipa: &str) -> String {
+    // Only the first transcription if several are given, e.g. "/a/, /b/".
+    let ipa = ipa.split(',').next().unwrap_or(ipa);
+    // Optional sounds like the "(ə)" in ephemeral are kept.
+    let ipa = ipa.replace(['(', ')'], "");
+    let mut syllables: Vec<Syllable> = vec![Syllable::default()];
+    // Consonants seen since the last vowel, not yet assigned to a syllable.
+    let mut pending: Vec<&'static str> = Vec::new();
+    let mut stress_next = false;
+
+    let mut rest = ipa.as_str();
+    while let Some(c) = rest.chars().next() {
+        if c == 'ˈ' || c == '.' || c == 'ˌ' {
+            // An explicit boundary: whatever is pending closes the previous syllable.
+            let previous = syllables.last_mut().unwrap();
+            if previous.nucleus.is_some() {
+                previous.coda.append(&mut pending);
+                syllables.push(Syllable::default());
+            }
+            stress_next |= c == 'ˈ';
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        // An r before a vowel starts the next syllable ("BER-ee", not
+        // "BAIR-ee") instead of coloring the vowel before it.
+        let Some((symbol, spelled, vowel)) = SOUNDS.iter().find(|(symbol, _, _)| {
+            rest.starts_with(symbol)
+                && !(is_r_colored(symbol) && starts_with_vowel(&rest[symbol.len()..]))
+        }) else {
+            // Length marks, brackets, tie bars, slashes, spaces…
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        rest = &rest[symbol.len()..];
+        if !vowel {
+            pending.push(spelled);
+            continue;
+        }
+
+        let previous = syllables.last_mut().unwrap();
+        if previous.nucleus.is_some() {
+            // Split the consonants between two vowels: a checked vowel keeps
+            // the first one, the rest start the new syllable.
+            let keep = usize::from(!pending.is_empty() && previous.checked);
+            previous.coda.extend(pending.drain(..keep));
+            syllables.push(Syllable::default());
+        }
+        let current = syllables.last_mut().unwrap();
+        current.onset.append(&mut pending);
+        current.nucleus = Some(spelled);
+        current.checked = CHECKED.contains(symbol);
+        current.stressed |= std::mem::take(&mut stress_next);
+    }
+    if let Some(last) = syllables.last_mut() {
+        last.coda.append(&mut pending);
+    }
+
+    // Only mark stress when there's more than one syllable to contrast it with.
+    let mark_stress = syllables.len() > 1;
+    syllables
+        .iter()
+        .filter(|s| s.nucleus.is_some() || !s.onset.is_empty() || !s.coda.is_empty())
+        .map(|s| {
+            // "eh"/"ih" only need the h to stay readable at the end of a syllable.
+            let nucleus = match s.nucleus {
+                Some("eh") if !s.coda.is_empty() => Some("e"),
+                Some("ih") if !s.coda.is_empty() => Some("i"),
+                other => other,
+            };
+            let text: String = s
+                .onset
+                .iter()
+                .chain(&nucleus)
+                .chain(&s.coda)
+                .copied()
+                .collect();
+            if mark_stress && s.stressed {
+                text.to_uppercase()
+            } else {
+                text
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respells_ipa() {
+        for (ipa, respelled) in [
+            ("/ɪˈfɛm(ə)ɹəl/", "ih-FEM-uh-ruhl"),
+            ("/ˈbɛɹi/", "BER-ee"),
+            ("/kɑɹ/", "kar"),
+            ("/ˈwɔtəɹ/", "WAW-ter"),
+            ("/həˈloʊ/", "huh-LOH"),
+            ("/ˈmɛlədi/", "MEL-uh-dee"),
+            ("/ˈkæt/, /kat/", "kat"),
+        ] {
+            assert_eq!(respell(ipa), respelled, "{}", ipa);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    http,
+    providers::Provider,
+    wiktionary,
+};
+
+/// The part-of-speech headings Simple English Wiktionary gives its entries,
+/// in the order their senses are shown.
+const PARTS_OF_SPEECH: &[&str] = &[
+    "Noun",
+    "Proper noun",
+    "Verb",
+    "Adjective",
+    "Adverb",
+    "Pronoun",
+    "Preposition",
+    "Conjunction",
+    "Determiner",
+    "Interjection",
+    "Phrase",
+    "Contraction",
+    "Abbreviation",
+];
+
+/// A template call with nothing nested in it.
+static TEMPLATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{([^{}|]+)((?:\|[^{}]*)?)\}\}").unwrap());
+
+/// Words from Simple English Wiktionary, which is written for learners: its
+/// definitions only use common words, and each sense has an example.
+pub async fn lookup(word: &str) -> Result<Lookup, surf::Error> {
+    let url = format!(
+        "https://simple.wiktionary.org/w/index.php?title={}&action=raw",
+        word.replace(' ', "_")
+    );
+    let mut response = http::provider_get(Provider::SimpleWiktionary, url)?.await?;
+    if !response.status().is_success() {
+        return Ok(Lookup::NotFound);
+    }
+    let page = response.body_string().await?;
+    Ok(match entry(word, &page) {
+        Some(entry) => Lookup::Found(vec![entry]),
+        None => Lookup::NotFound,
+    })
+}
+
+/// The entry on the page for `word`, which has no language sections: the
+/// part-of-speech headings are at the top level.
+fn entry(word: &str, page: &str) -> Option<DefinitionResponse> {
+    let mut meanings = Vec::new();
+    for part_of_speech in PARTS_OF_SPEECH {
+        let definitions: Vec<Definition> = wiktionary::sections(page, part_of_speech)
+            .into_iter()
+            .flat_map(definitions)
+            .collect();
+        if !definitions.is_empty() {
+            meanings.push(Meaning {
+                part_of_speech: part_of_speech.to_lowercase(),
+                definitions,
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender: None,
+            });
+        }
+    }
+    if meanings.is_empty() {
+        return None;
+    }
+    let ipa = wiktionary::templates(page, "IPA")
+        .into_iter()
+        .flat_map(|template| template.positional)
+        .find(|ipa| ipa.starts_with(['/', '[']))
+        .map(str::to_string);
+    Some(DefinitionResponse {
+        word: word.to_string(),
+        phonetic: ipa.clone(),
+        phonetics: ipa
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings,
+        source_urls: Some(vec![format!(
+            "https://simple.wiktionary.org/wiki/{}",
+            word.replace(' ', "_")
+        )]),
+        origin: None,
+        provider: None,
+    })
+}
+
+/// The senses under a part-of-speech heading: its `#` lines, each with the
+/// first example under it.
+fn definitions(section: &str) -> Vec<Definition> {
+    let mut definitions: Vec<Definition> = Vec::new();
+    for line in section.lines() {
+        if let Some(example) = line.strip_prefix("#:") {
+            if let Some(last) = definitions.last_mut().filter(|d| d.example.is_none()) {
+                last.example = Some(expand(example)).filter(|example| !example.is_empty());
+            }
+            continue;
+        }
+        let Some(gloss) = line
+            .strip_prefix('#')
+            .filter(|rest| !rest.starts_with(['*', ':', '#']))
+        else {
+            continue;
+        };
+        let gloss = expand(gloss);
+        if gloss.is_empty() {
+            continue;
+        }
+        definitions.push(Definition {
+            definition: gloss,
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        });
+    }
+    definitions
+}
+
+/// A gloss with its links written out. Templates without parameters, such
+/// as `{{countable}}` or `{{informal}}`, are labels; the rest are dropped.
+fn expand(wikitext: &str) -> String {
+    let text = TEMPLATE.replace_all(wikitext, |caps: &regex::Captures| match &caps[2] {
+        "" => format!("({})", caps[1].trim()),
+        _ => String::new(),
+    });
+    let text = wiktionary::plain(&text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_entries() {
+        let page = "\
+{{BE850}}
+== Pronunciation ==
+* {{IPA|/ˈæpəl/}}
+
+== Noun ==
+{{noun}}
+# {{countable}} An '''apple''' is a round [[fruit]] that grows on a [[tree|trees]].
+#: ''I ate an '''apple''' for lunch.''
+#: ''Apples are red or green.''
+# {{uncountable}} Apple is the [[flesh]] of this fruit.
+
+=== Related words ===
+* [[apple tree]]
+";
+        let apple = entry("apple", page).unwrap();
+        assert_eq!(apple.phonetic.as_deref(), Some("/ˈæpəl/"));
+        assert_eq!(apple.meanings.len(), 1);
+        let senses = &apple.meanings[0].definitions;
+        assert_eq!(
+            senses[0].definition,
+            "(countable) An apple is a round fruit that grows on a trees."
+        );
+        assert_eq!(
+            senses[0].example.as_deref(),
+            Some("I ate an apple for lunch.")
+        );
+        assert_eq!(
+            senses[1].definition,
+            "(uncountable) Apple is the flesh of this fruit."
+        );
+        assert!(entry("apple", "#REDIRECT [[Apple]]").is_none());
+    }
+}
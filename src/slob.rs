@@ -0,0 +1,434 @@
+#[cfg(feature = "compression")]
+use std::io::Read;
+use std::{io, path::Path};
+
+use memmap2::Mmap;
+
+use crate::{storage, text};
+
+const MAGIC: &[u8] = b"!-1SLOB\x1F";
+
+/// An Aard2 `.slob` dictionary: a sorted index of keys pointing into bins of
+/// compressed articles. Only the header is parsed up front; the index is
+/// binary-searched straight from the mapped file.
+pub struct Slob {
+    bytes: Mmap,
+    compression: Compression,
+    content_types: Vec<String>,
+    refs: ItemList,
+    store: ItemList,
+}
+
+/// One article a key points at.
+#[derive(Debug)]
+pub struct Article {
+    pub key: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Compression {
+    None,
+    Lzma2,
+    Zlib,
+}
+
+/// A count-prefixed list of items addressed through a table of 64-bit offsets,
+/// which is how slob lays out both its key index and its article store.
+#[derive(Debug, Clone, Copy)]
+struct ItemList {
+    count: usize,
+    offsets: usize,
+    data: usize,
+}
+impl ItemList {
+    fn read(bytes: &[u8], at: usize) -> io::Result<Self> {
+        let mut cursor = Cursor { bytes, pos: at };
+        let count = cursor.u32()? as usize;
+        let offsets = cursor.pos;
+        Ok(Self {
+            count,
+            offsets,
+            data: offsets + count * 8,
+        })
+    }
+
+    /// A cursor at the start of item `i`.
+    fn item<'a>(&self, bytes: &'a [u8], i: usize) -> io::Result<Cursor<'a>> {
+        let offset = Cursor {
+            bytes,
+            pos: self.offsets + i * 8,
+        }
+        .u64()?;
+        Ok(Cursor {
+            bytes,
+            pos: self.data + offset as usize,
+        })
+    }
+}
+
+/// Big-endian reads with bounds checks, since a truncated download shouldn't
+/// take the process down.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len());
+        let Some(end) = end else {
+            return Err(invalid("slob file is truncated"));
+        };
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Text with a one-byte length. Editable header fields are padded with NULs.
+    fn tiny_text(&mut self) -> io::Result<String> {
+        let len = self.u8()? as usize;
+        Ok(utf8(self.take(len)?).trim_end_matches('\0').to_string())
+    }
+
+    /// Text with a two-byte length.
+    fn text(&mut self) -> io::Result<String> {
+        let len = self.u16()? as usize;
+        Ok(utf8(self.take(len)?).into_owned())
+    }
+}
+
+impl Slob {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let bytes = storage::map_file(path)?;
+        let mut cursor = Cursor {
+            bytes: &bytes,
+            pos: 0,
+        };
+        if cursor.take(MAGIC.len())? != MAGIC {
+            return Err(invalid("not a slob file"));
+        }
+        cursor.take(16)?; // uuid
+        let encoding = cursor.tiny_text()?;
+        if !encoding.eq_ignore_ascii_case("utf-8") {
+            return Err(invalid(&format!("unsupported slob encoding {}", encoding)));
+        }
+        let compression = match cursor.tiny_text()?.as_str() {
+            "" => Compression::None,
+            "lzma2" => Compression::Lzma2,
+            "zlib" => Compression::Zlib,
+            other => return Err(invalid(&format!("unsupported slob compression {}", other))),
+        };
+        // Tags (label, source, license…): key and value each.
+        for _ in 0..cursor.u8()? {
+            cursor.tiny_text()?;
+            cursor.tiny_text()?;
+        }
+        let content_types = (0..cursor.u8()?)
+            .map(|_| cursor.text())
+            .collect::<io::Result<_>>()?;
+        let _blob_count = cursor.u32()?;
+        let store_offset = cursor.u64()? as usize;
+        let _size = cursor.u64()?;
+        let refs = ItemList::read(&bytes, cursor.pos)?;
+        let store = ItemList::read(&bytes, store_offset)?;
+        Ok(Self {
+            bytes,
+            compression,
+            content_types,
+            refs,
+            store,
+        })
+    }
+
+    /// Articles whose key is `word`, ignoring case.
+    pub fn lookup(&self, word: &str) -> io::Result<Vec<Article>> {
+        let target = sort_key(word);
+        let wanted = word.to_lowercase();
+
+        // Keys are sorted by ICU collation, which `sort_key` approximates
+        // closely enough for a binary search to land on the right run of keys.
+        let (mut low, mut high) = (0, self.refs.count);
+        while low < high {
+            let mid = (low + high) / 2;
+            if sort_key(&self.reference(mid)?.key) < target {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        let mut articles: Vec<Article> = Vec::new();
+        let mut seen = Vec::new();
+        for i in low..self.refs.count {
+            let reference = self.reference(i)?;
+            if sort_key(&reference.key) != target {
+                break;
+            }
+            // Several keys (and #fragments) can point at the same article.
+            let blob = (reference.bin, reference.item);
+            if reference.key.to_lowercase() != wanted || seen.contains(&blob) {
+                continue;
+            }
+            seen.push(blob);
+            let (content_type, content) = self.blob(reference.bin, reference.item)?;
+            articles.push(Article {
+                key: reference.key,
+                content_type,
+                content,
+            });
+        }
+        // Exact-case matches first: "Polish" before "polish" for "Polish".
+        articles.sort_by_key(|a| a.key != word);
+        Ok(articles)
+    }
+
+    /// Every key, as stored.
+    pub fn headwords(&self) -> io::Result<Vec<String>> {
+        (0..self.refs.count)
+            .map(|i| Ok(self.reference(i)?.key))
+            .collect()
+    }
+
+    fn reference(&self, i: usize) -> io::Result<Reference> {
+        let mut cursor = self.refs.item(&self.bytes, i)?;
+        Ok(Reference {
+            key: cursor.text()?,
+            bin: cursor.u32()? as usize,
+            item: cursor.u16()? as usize,
+        })
+    }
+
+    /// Content type and decompressed bytes of item `item` in bin `bin`.
+    fn blob(&self, bin: usize, item: usize) -> io::Result<(String, Vec<u8>)> {
+        if bin >= self.store.count {
+            return Err(invalid("slob reference points past the store"));
+        }
+        let mut cursor = self.store.item(&self.bytes, bin)?;
+        let type_count = cursor.u32()? as usize;
+        let type_ids = cursor.take(type_count)?;
+        let compressed_len = cursor.u32()? as usize;
+        let compressed = cursor.take(compressed_len)?;
+        let content_type = type_ids
+            .get(item)
+            .and_then(|&id| self.content_types.get(id as usize))
+            .cloned()
+            .ok_or_else(|| invalid("slob reference points past its bin"))?;
+
+        let bin_bytes = decompress(self.compression, compressed)?;
+        // A bin is a table of 32-bit offsets followed by length-prefixed items.
+        let offset = Cursor {
+            bytes: &bin_bytes,
+            pos: item * 4,
+        }
+        .u32()? as usize;
+        let mut cursor = Cursor {
+            bytes: &bin_bytes,
+            pos: type_count * 4 + offset,
+        };
+        let len = cursor.u32()? as usize;
+        let content = cursor.take(len)?.to_vec();
+        Ok((content_type, content))
+    }
+}
+
+struct Reference {
+    key: String,
+    bin: usize,
+    item: usize,
+}
+
+fn decompress(compression: Compression, bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match compression {
+        Compression::None => out.extend_from_slice(bytes),
+        #[cfg(feature = "compression")]
+        Compression::Lzma2 => lzma_rs::lzma2_decompress(&mut &bytes[..], &mut out)
+            .map_err(|e| invalid(&format!("corrupt slob bin: {}", e)))?,
+        #[cfg(feature = "compression")]
+        Compression::Zlib => {
+            flate2::read::ZlibDecoder::new(bytes).read_to_end(&mut out)?;
+        }
+        #[cfg(not(feature = "compression"))]
+        Compression::Lzma2 | Compression::Zlib => {
+            return Err(crate::offline::without_compression("this slob's bins"))
+        }
+    }
+    Ok(out)
+}
+
+/// Approximates the primary-strength ICU collation slob keys are sorted by:
+/// case and accents don't matter, and spaces and punctuation are skipped.
+fn sort_key(key: &str) -> String {
+    key.chars()
+        .flat_map(char::to_lowercase)
+        .map(text::fold_accent)
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn utf8(bytes: &[u8]) -> std::borrow::Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, path::PathBuf};
+
+    fn text(out: &mut Vec<u8>, text: &str) {
+        out.extend((text.len() as u16).to_be_bytes());
+        out.extend(text.as_bytes());
+    }
+
+    fn tiny_text(out: &mut Vec<u8>, text: &str) {
+        out.push(text.len() as u8);
+        out.extend(text.as_bytes());
+    }
+
+    /// A count, the items' offsets and the items.
+    fn item_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = (items.len() as u32).to_be_bytes().to_vec();
+        let mut offset = 0u64;
+        for item in items {
+            out.extend(offset.to_be_bytes());
+            offset += item.len() as u64;
+        }
+        for item in items {
+            out.extend(item);
+        }
+        out
+    }
+
+    /// An uncompressed slob with one bin holding `articles`, and `keys`
+    /// pointing at them by index.
+    fn slob(keys: &[(&str, u16)], articles: &[&str]) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend([0; 16]);
+        tiny_text(&mut header, "utf-8");
+        tiny_text(&mut header, "");
+        header.push(1);
+        tiny_text(&mut header, "label");
+        tiny_text(&mut header, "Test\0\0");
+        header.push(1);
+        text(&mut header, "text/html; charset=utf-8");
+        header.extend(1u32.to_be_bytes());
+
+        let refs: Vec<Vec<u8>> = keys
+            .iter()
+            .map(|(key, item)| {
+                let mut out = Vec::new();
+                text(&mut out, key);
+                out.extend(0u32.to_be_bytes());
+                out.extend(item.to_be_bytes());
+                out
+            })
+            .collect();
+        let refs = item_list(&refs);
+
+        let mut bin = Vec::new();
+        let mut offset = 0u32;
+        for article in articles {
+            bin.extend(offset.to_be_bytes());
+            offset += 4 + article.len() as u32;
+        }
+        for article in articles {
+            bin.extend((article.len() as u32).to_be_bytes());
+            bin.extend(article.as_bytes());
+        }
+        let mut store_item = (articles.len() as u32).to_be_bytes().to_vec();
+        store_item.extend(vec![0; articles.len()]);
+        store_item.extend((bin.len() as u32).to_be_bytes());
+        store_item.extend(bin);
+        let store = item_list(&[store_item]);
+
+        let store_offset = header.len() + 16 + refs.len();
+        let size = store_offset + store.len();
+        header.extend((store_offset as u64).to_be_bytes());
+        header.extend((size as u64).to_be_bytes());
+        [header, refs, store].concat()
+    }
+
+    fn write(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("{}-{}.slob", name, std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn finds_articles_by_key_ignoring_case() {
+        let bytes = slob(
+            &[("Apple", 0), ("apple", 1), ("apple", 1), ("banana", 2)],
+            &["<p>the company</p>", "<p>the fruit</p>", "<p>yellow</p>"],
+        );
+        let path = write("lookup", &bytes);
+        let slob = Slob::open(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let articles = slob.lookup("apple").unwrap();
+        let contents: Vec<&[u8]> = articles.iter().map(|a| a.content.as_slice()).collect();
+        // The same article under two keys comes once, exact case first.
+        assert_eq!(
+            contents,
+            [&b"<p>the fruit</p>"[..], &b"<p>the company</p>"[..]]
+        );
+        assert_eq!(articles[0].content_type, "text/html; charset=utf-8");
+        assert_eq!(slob.lookup("Apple").unwrap()[0].key, "Apple");
+        assert_eq!(slob.lookup("banana").unwrap().len(), 1);
+        assert!(slob.lookup("cherry").unwrap().is_empty());
+        assert_eq!(
+            slob.headwords().unwrap(),
+            ["Apple", "apple", "apple", "banana"]
+        );
+    }
+
+    #[test]
+    fn truncated_files_are_errors() {
+        let bytes = slob(&[("apple", 0)], &["<p>the fruit</p>"]);
+        for len in [4, 30, bytes.len() - 3] {
+            let path = write(&format!("truncated{}", len), &bytes[..len]);
+            let slob = Slob::open(&path);
+            fs::remove_file(&path).ok();
+            // Either the header or the article can't be read; neither panics.
+            if let Ok(slob) = slob {
+                assert!(slob.lookup("apple").is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let path = write("magic", b"PK\x03\x04 not a slob at all");
+        assert!(Slob::open(&path).is_err());
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn sort_key_ignores_case_accents_and_punctuation() {
+        assert_eq!(sort_key("Café au lait"), "cafeaulait");
+        assert_eq!(sort_key("rock'n'roll"), "rocknroll");
+    }
+}
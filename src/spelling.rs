@@ -0,0 +1,156 @@
+use serde::Deserialize;
+
+use crate::{hunspell::Hunspell, index::WordIndex, providers::Settings};
+
+/// Words further than this many edits away aren't worth suggesting.
+const MAX_DISTANCE: usize = 2;
+
+/// The keyboard the user types on, for telling fat-finger typos ("wotd" for
+/// "word") apart from unrelated words.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Qwertz,
+    Colemak,
+    Dvorak,
+}
+impl KeyboardLayout {
+    /// Letter rows, top to bottom, each staggered half a key right of the one above.
+    fn rows(self) -> [&'static str; 3] {
+        match self {
+            KeyboardLayout::Qwerty => ["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            KeyboardLayout::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn"],
+            KeyboardLayout::Qwertz => ["qwertzuiop", "asdfghjkl", "yxcvbnm"],
+            KeyboardLayout::Colemak => ["qwfpgjluy", "arstdhneio", "zxcvbkm"],
+            KeyboardLayout::Dvorak => ["',.pyfgcrl", "aoeuidhtns", ";qjkxbmwvz"],
+        }
+    }
+
+    fn position(self, c: char) -> Option<(usize, usize)> {
+        self.rows()
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.chars().position(|k| k == c).map(|col| (row, col)))
+    }
+
+    /// Whether two keys touch: same row side by side, or diagonal neighbours
+    /// on the staggered rows above and below.
+    pub fn adjacent(self, a: char, b: char) -> bool {
+        let (Some((ra, ca)), Some((rb, cb))) = (self.position(a), self.position(b)) else {
+            return false;
+        };
+        match rb as isize - ra as isize {
+            0 => ca.abs_diff(cb) == 1,
+            // The row below sits half a key to the right.
+            1 => cb == ca || cb + 1 == ca,
+            -1 => ca == cb || ca + 1 == cb,
+            _ => false,
+        }
+    }
+}
+
+/// Edit distance in half-edits, where hitting a neighbouring key or swapping
+/// two letters costs half as much as any other mistake. Used to rank
+/// candidates that are equally far apart by plain edit distance.
+pub fn typo_cost(a: &str, b: &str, layout: KeyboardLayout) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i * 2;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j * 2;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = match (a[i - 1], b[j - 1]) {
+                (x, y) if x == y => 0,
+                (x, y) if layout.adjacent(x, y) => 1,
+                _ => 2,
+            };
+            // Doubled or stray neighbouring keys are cheap insertions too.
+            let stray = |extra: char, next_to: Option<char>| {
+                if next_to.is_some_and(|n| n == extra || layout.adjacent(n, extra)) {
+                    1
+                } else {
+                    2
+                }
+            };
+            let mut best = (rows[i - 1][j] + stray(a[i - 1], b.get(j - 1).copied()))
+                .min(rows[i][j - 1] + stray(b[j - 1], a.get(i - 1).copied()))
+                .min(rows[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// adjacent transpositions each cost one.
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// "Did you mean" candidates for `word`, likeliest typo and most common first.
+/// Uses an installed Hunspell dictionary for the language when there is one,
+/// the offline word list otherwise. None for `exact` lookups, whose unusual
+/// spelling is on purpose.
+pub fn suggest(word: &str, settings: &Settings, limit: usize) -> Vec<String> {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() || settings.exact {
+        return Vec::new();
+    }
+    let words = WordIndex::shared();
+    let mut candidates: Vec<String> = match Hunspell::shared(&settings.lang) {
+        Some(hunspell) => hunspell.suggest(&word),
+        None => words.fuzzy(&word, MAX_DISTANCE as u32).unwrap_or_default(),
+    };
+    candidates.retain(|c| *c != word);
+
+    let mut ranked: Vec<(usize, usize, String)> = candidates
+        .into_iter()
+        .filter(|c| distance(&word, &c.to_lowercase()) <= MAX_DISTANCE)
+        .map(|c| {
+            let lower = c.to_lowercase();
+            let cost = typo_cost(&word, &lower, settings.keyboard_layout);
+            (cost, words.rank(&lower).unwrap_or(usize::MAX), c)
+        })
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.2 == b.2);
+    ranked.into_iter().take(limit).map(|(_, _, c)| c).collect()
+}
+
+/// The dictionary form of an inflected `word`, if a Hunspell dictionary for
+/// `lang` is installed and knows it.
+pub fn lemma(word: &str, lang: &str) -> Option<String> {
+    Hunspell::shared(lang)?.lemma(word)
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+
+use crate::{
+    history::{self, Bookmarks, DAY},
+    known::Known,
+    storage,
+};
+
+/// How soon a word answered wrong comes back.
+const RELEARN: u64 = 10 * 60;
+/// Ease a new card starts with: each right answer multiplies its interval by this.
+const START_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+/// Days between reviews after which a word counts as learned and is added to
+/// the known-words list.
+const MATURE_DAYS: f64 = 21.0;
+
+/// A word being learned, with when to ask about it next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Card {
+    pub word: String,
+    /// Days until the next review after a right answer; 0 while new.
+    pub interval: f64,
+    pub ease: f64,
+    /// Seconds since the Unix epoch.
+    pub due: u64,
+    /// Times asked, and of those, answered wrong.
+    pub reviews: u32,
+    pub lapses: u32,
+}
+
+/// The bookmarked words, scheduled for review with a simplified SM-2: a right
+/// answer pushes a word further out, a wrong one brings it back in minutes.
+pub struct Deck {
+    path: PathBuf,
+    pub cards: Vec<Card>,
+}
+impl Deck {
+    /// Loads the schedule, adding newly bookmarked words (due straight away)
+    /// and dropping words that are no longer bookmarked.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("srs.json");
+        let mut cards: Vec<Card> = storage::load_json(&path);
+        let bookmarks = Bookmarks::load();
+        cards.retain(|card| bookmarks.contains(&card.word));
+        for word in &bookmarks.words {
+            if !cards.iter().any(|card| card.word == *word) {
+                cards.push(Card {
+                    word: word.clone(),
+                    interval: 0.0,
+                    ease: START_EASE,
+                    due: history::now(),
+                    reviews: 0,
+                    lapses: 0,
+                });
+            }
+        }
+        Self { path, cards }
+    }
+
+    /// Cards due by `now`, the longest overdue first.
+    pub fn due(&self, now: u64) -> Vec<&Card> {
+        let mut due: Vec<&Card> = self.cards.iter().filter(|card| card.due <= now).collect();
+        due.sort_by_key(|card| card.due);
+        due
+    }
+
+    /// Reschedules `word` after an answer. Words that reach [`MATURE_DAYS`]
+    /// go on the known-words list.
+    pub fn review(&mut self, word: &str, correct: bool, now: u64) -> io::Result<()> {
+        let Some(card) = self.cards.iter_mut().find(|card| card.word == word) else {
+            return Ok(());
+        };
+        card.reviews += 1;
+        if correct {
+            card.interval = match card.interval < 1.0 {
+                true => 1.0,
+                false => (card.interval * card.ease).round(),
+            };
+            card.ease += 0.1;
+            card.due = now + (card.interval * DAY as f64) as u64;
+        } else {
+            card.lapses += 1;
+            card.interval = 0.0;
+            card.ease = (card.ease - 0.2).max(MIN_EASE);
+            card.due = now + RELEARN;
+        }
+        if card.interval >= MATURE_DAYS {
+            Known::update(|known| known.insert(word))?;
+        }
+        Ok(())
+    }
+
+    /// Like [`History::update`](crate::history::History::update), for the
+    /// schedule.
+    pub fn update<R>(change: impl FnOnce(&mut Self) -> R) -> io::Result<R> {
+        storage::with_lock(&storage::data_dir().join("srs.json"), || {
+            let mut deck = Self::load();
+            let changed = change(&mut deck);
+            deck.save().map(|()| changed)
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save_json(&self.path, &self.cards)
+    }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+    cli::Format,
+    history::{self, History, DAY},
+    locale,
+    sherlock::SherlockPipeResponse,
+    srs::Deck,
+    storage, text,
+};
+
+/// Days of lookups shown per day.
+const DAYS: u64 = 7;
+/// Most-looked-up words shown.
+const TOP_WORDS: usize = 10;
+
+/// Running totals that can't be worked out from the history.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Counters {
+    /// Words answered from a cache rather than looked up.
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+impl Counters {
+    fn path() -> PathBuf {
+        storage::data_dir().join("stats.json")
+    }
+
+    pub fn load() -> Self {
+        storage::load_json(&Self::path())
+    }
+
+    /// Adds `hits` words found in a cache and `misses` that had to be looked
+    /// up to the totals.
+    pub fn record_cache(hits: usize, misses: usize) {
+        let saved = storage::with_lock(&Self::path(), || {
+            let mut counters = Self::load();
+            counters.cache_hits += hits as u64;
+            counters.cache_misses += misses as u64;
+            storage::save_json(&Self::path(), &counters)
+        });
+        if let Err(e) = saved {
+            eprintln!("Warning: couldn't save the statistics: {}", e);
+        }
+    }
+}
+
+/// What `stats` reports, worked out once for every output.
+struct Summary {
+    /// (date, lookups) for the last [`DAYS`] days, oldest first.
+    per_day: Vec<(String, usize)>,
+    /// Days in a row, up to today, with at least one lookup.
+    streak: usize,
+    /// (word, lookups), most looked up first.
+    top: Vec<(String, usize)>,
+    reviews: u32,
+    /// Share of reviews answered right, if there were any.
+    accuracy: Option<f64>,
+    due: usize,
+    cache_hit_rate: Option<f64>,
+}
+
+impl Summary {
+    fn collect() -> Self {
+        let history = History::load();
+        let today = history::now() / DAY;
+        let mut by_day: HashMap<u64, usize> = HashMap::new();
+        for entry in &history.entries {
+            *by_day.entry(entry.timestamp / DAY).or_default() += 1;
+        }
+        let per_day = (today + 1 - DAYS..=today)
+            .map(|day| {
+                let count = by_day.get(&day).copied().unwrap_or_default();
+                (locale::date(day * DAY), count)
+            })
+            .collect();
+        // A day without lookups yet doesn't break the streak until it's over.
+        let mut day = match by_day.contains_key(&today) {
+            true => today,
+            false => today - 1,
+        };
+        let mut streak = 0;
+        while by_day.contains_key(&day) {
+            streak += 1;
+            day -= 1;
+        }
+        let top = history.most_looked_up(TOP_WORDS);
+
+        let deck = Deck::load();
+        let reviews: u32 = deck.cards.iter().map(|card| card.reviews).sum();
+        let lapses: u32 = deck.cards.iter().map(|card| card.lapses).sum();
+        let counters = Counters::load();
+        let lookups = counters.cache_hits + counters.cache_misses;
+        Self {
+            per_day,
+            streak,
+            top,
+            reviews,
+            accuracy: (reviews > 0).then(|| f64::from(reviews - lapses) / f64::from(reviews)),
+            due: deck.due(history::now()).len(),
+            cache_hit_rate: (lookups > 0).then(|| counters.cache_hits as f64 / lookups as f64),
+        }
+    }
+
+    fn today(&self) -> usize {
+        self.per_day.last().map_or(0, |(_, count)| *count)
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let percent =
+            |share: Option<f64>| share.map_or(String::from("-"), |s| format!("{:.0}%", s * 100.0));
+        vec![
+            vec![String::from("Lookups today"), count(self.today())],
+            vec![String::from("Streak"), days(self.streak)],
+            vec![String::from("Reviews"), locale::number(self.reviews.into())],
+            vec![String::from("Quiz accuracy"), percent(self.accuracy)],
+            vec![String::from("Due for review"), count(self.due)],
+            vec![String::from("Cache hit rate"), percent(self.cache_hit_rate)],
+        ]
+    }
+
+    fn per_day_rows(&self) -> Vec<Vec<String>> {
+        let most = self
+            .per_day
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        self.per_day
+            .iter()
+            .map(|(date, count)| {
+                vec![
+                    date.clone(),
+                    self::count(*count),
+                    "█".repeat((count * 20).div_ceil(most)),
+                ]
+            })
+            .collect()
+    }
+
+    fn top_rows(&self) -> Vec<Vec<String>> {
+        self.top
+            .iter()
+            .map(|(word, n)| vec![word.clone(), count(*n)])
+            .collect()
+    }
+}
+
+fn days(n: usize) -> String {
+    match n {
+        1 => String::from("1 day"),
+        n => format!("{} days", count(n)),
+    }
+}
+
+fn count(n: usize) -> String {
+    locale::number(n as u64)
+}
+
+/// `stats`: lookups per day, the most looked-up words, quiz accuracy and the
+/// cache hit rate. With `waybar`, the JSON a waybar custom module reads.
+pub fn run(waybar: bool, format: Format) {
+    let summary = Summary::collect();
+    if waybar {
+        let mut tooltip = text::table(&summary.rows());
+        tooltip.push('\n');
+        tooltip.push_str(&text::table(&summary.per_day_rows()));
+        let class = match summary.due {
+            0 => "idle",
+            _ => "due",
+        };
+        let payload = serde_json::json!({
+            "text": format!("{} · {}d", summary.today(), summary.streak),
+            "tooltip": tooltip.trim_end(),
+            "class": class,
+        });
+        println!("{}", payload);
+        return;
+    }
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            content.push_str(&text::table(&summary.rows()));
+            content.push_str("\n─── <b><i>Lookups per day</i></b> ───\n\n");
+            content.push_str(&text::table(&summary.per_day_rows()));
+            if !summary.top.is_empty() {
+                content.push_str("\n─── <b><i>Most looked up</i></b> ───\n\n");
+                content.push_str(&text::table(&summary.top_rows()));
+            }
+            content.push_str("</span>");
+            let title = format!(
+                "{} streak, {} lookups today",
+                days(summary.streak),
+                count(summary.today())
+            );
+            let response = SherlockPipeResponse::new(title, content, Vec::new());
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            print!("{}", text::table(&summary.rows()));
+            println!("\n─── Lookups per day ───\n");
+            print!("{}", text::table(&summary.per_day_rows()));
+            if !summary.top.is_empty() {
+                println!("\n─── Most looked up ───\n");
+                print!("{}", text::table(&summary.top_rows()));
+            }
+        }
+        Format::Oneline => {
+            let rows: Vec<String> = summary
+                .rows()
+                .into_iter()
+                .map(|row| format!("{}: {}", row[0].to_lowercase(), row[1]))
+                .collect();
+            println!("{}", rows.join(", "));
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use memmap2::Mmap;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::crypto;
+
+const APP_DIR: &str = "sherlock-dictionary";
+
+/// `$XDG_DATA_HOME/sherlock-dictionary`, falling back to `~/.local/share`,
+/// `~/Library/Application Support` on macOS or `%APPDATA%` on Windows.
+pub fn data_dir() -> PathBuf {
+    app_dir("XDG_DATA_HOME", PlatformDir::Data)
+}
+
+/// `$XDG_CONFIG_HOME/sherlock-dictionary`, falling back to `~/.config`,
+/// `~/Library/Application Support` on macOS or `%APPDATA%` on Windows.
+pub fn config_dir() -> PathBuf {
+    app_dir("XDG_CONFIG_HOME", PlatformDir::Config)
+}
+
+/// `$XDG_CACHE_HOME/sherlock-dictionary`, falling back to `~/.cache`,
+/// `~/Library/Caches` on macOS or `%LOCALAPPDATA%` on Windows. Anything in
+/// here can be rebuilt, so it is safe to delete.
+pub fn cache_dir() -> PathBuf {
+    app_dir("XDG_CACHE_HOME", PlatformDir::Cache)
+}
+
+#[derive(Clone, Copy)]
+enum PlatformDir {
+    Data,
+    Config,
+    Cache,
+}
+
+/// The XDG variable wins on every platform, so the same setup can be used
+/// everywhere; without it each platform's usual place is used.
+fn app_dir(var: &str, kind: PlatformDir) -> PathBuf {
+    let base = env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| platform_dir(kind));
+    base.join(APP_DIR)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_dir(kind: PlatformDir) -> PathBuf {
+    match kind {
+        PlatformDir::Data | PlatformDir::Config => home().join("Library/Application Support"),
+        PlatformDir::Cache => home().join("Library/Caches"),
+    }
+}
+
+#[cfg(windows)]
+fn platform_dir(kind: PlatformDir) -> PathBuf {
+    let (var, fallback) = match kind {
+        PlatformDir::Data | PlatformDir::Config => ("APPDATA", "AppData\\Roaming"),
+        PlatformDir::Cache => ("LOCALAPPDATA", "AppData\\Local"),
+    };
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home().join(fallback))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_dir(kind: PlatformDir) -> PathBuf {
+    match kind {
+        PlatformDir::Data => home().join(".local/share"),
+        PlatformDir::Config => home().join(".config"),
+        PlatformDir::Cache => home().join(".cache"),
+    }
+}
+
+fn home() -> PathBuf {
+    env::home_dir().unwrap_or_default()
+}
+
+/// `~/books` as the shell would expand it, for paths from `config.json`.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home().join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Reads a JSON file, treating a missing or unreadable file as empty state.
+/// A file that doesn't parse is moved aside (see [`parse`]).
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match fs::read(path) {
+        Ok(bytes) => parse(path, &bytes),
+        Err(_) => T::default(),
+    }
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let text = serde_json::to_vec_pretty(value)?;
+    replace_file(path, &text)
+}
+
+/// `bytes`, read from `path`, as JSON. When they aren't, the file is renamed
+/// to `<name>.corrupt` rather than left to be overwritten with empty state by
+/// the next save, so whatever can be saved from it is still there.
+fn parse<T: DeserializeOwned + Default>(path: &Path, bytes: &[u8]) -> T {
+    serde_json::from_slice(bytes).unwrap_or_else(|e| {
+        let aside = with_suffix(path, ".corrupt");
+        match fs::rename(path, &aside) {
+            Ok(()) => eprintln!(
+                "Warning: {} was damaged ({}); moved it to {} and started afresh",
+                path.display(),
+                e,
+                Path::new(&aside).display()
+            ),
+            // Another process got to it first.
+            Err(_) => eprintln!("Warning: {} is damaged: {}", path.display(), e),
+        }
+        T::default()
+    })
+}
+
+/// Runs `update` holding an advisory lock on `path`, so that processes
+/// loading, changing and saving the same file at once (the launcher starts
+/// one per keystroke) take turns instead of each saving over the others'
+/// changes. The lock is on `<name>.lock`, because `path` itself is replaced
+/// rather than written to. Reading needs no lock, since every file is
+/// replaced whole. Where locks aren't supported, `update` runs without one.
+pub fn with_lock<R>(path: &Path, update: impl FnOnce() -> R) -> R {
+    let _lock = lock(path);
+    update()
+}
+
+/// The open lock file for `path`; closing it releases the lock.
+fn lock(path: &Path) -> Option<fs::File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok()?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_suffix(path, ".lock"))
+        .ok()?;
+    file.lock().ok()?;
+    Some(file)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> OsString {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    name
+}
+
+/// Like [`load_json`], for the files that say what the user looked up: they
+/// are decrypted if they were saved with encryption on.
+pub fn load_private<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let Ok(bytes) = fs::read(path) else {
+        return T::default();
+    };
+    let bytes = match crypto::is_sealed(&bytes) {
+        false => bytes,
+        true => match crypto::key().and_then(|key| crypto::open(key, &bytes)) {
+            Some(plaintext) => plaintext,
+            None => {
+                eprintln!(
+                    "Warning: can't decrypt {}; check the passphrase",
+                    path.display()
+                );
+                return T::default();
+            }
+        },
+    };
+    parse(path, &bytes)
+}
+
+/// Like [`load_private`], for files other machines write, where starting
+/// afresh would lose their changes: a missing file is `None`, and one that
+/// can't be read, decrypted or parsed is an error and left as it is.
+pub fn read_private<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let bytes = match crypto::is_sealed(&bytes) {
+        false => bytes,
+        true => crypto::key()
+            .and_then(|key| crypto::open(key, &bytes))
+            .ok_or_else(|| {
+                io::Error::other(format!(
+                    "can't decrypt {}; check the passphrase",
+                    path.display()
+                ))
+            })?,
+    };
+    serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} doesn't parse: {}", path.display(), e),
+        )
+    })
+}
+
+/// Like [`save_json`], encrypting when encryption is set up. An encrypted
+/// file is only replaced when the current passphrase opens it, so a missing or
+/// mistyped passphrase can't lose what's in it.
+pub fn save_private<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let key = crypto::key();
+    if let Ok(existing) = fs::read(path) {
+        let opens = key.and_then(|key| crypto::open(key, &existing)).is_some();
+        if crypto::is_sealed(&existing) && !opens {
+            return Err(io::Error::other(format!(
+                "{} is encrypted with a passphrase that isn't set",
+                path.display()
+            )));
+        }
+    }
+    let text = serde_json::to_vec_pretty(value)?;
+    match key {
+        Some(key) => replace_file(path, &crypto::seal(key, &text)?),
+        None => replace_file(path, &text),
+    }
+}
+
+/// Maps a file into memory, so only the pages actually read get loaded. Meant
+/// for large read-mostly files like indexes and offline dictionaries.
+pub fn map_file(path: &Path) -> io::Result<Mmap> {
+    let file = fs::File::open(path)?;
+    // SAFETY: files written by this program are only ever replaced by renaming
+    // a new file over them (see `replace_file`), never modified in place, so
+    // the mapping stays valid. Files from elsewhere are assumed to be static.
+    unsafe { Mmap::map(&file) }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into
+/// place, so readers (including other processes with the file mapped) see
+/// either the old file or the new one, never a partial write.
+pub fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    // Tasks of one process can replace the same file at once, too.
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    let temp = with_suffix(path, &format!(".{}.{}.tmp", std::process::id(), write));
+    if let Err(e) = fs::write(&temp, contents) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    fs::rename(&temp, path)
+}
+
+/// Whether the cache file at `cache` exists and is newer than every source it
+/// was built from that still exists.
+pub fn is_fresh(cache: &Path, sources: &[PathBuf]) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let Some(built) = modified(cache) else {
+        return false;
+    };
+    sources
+        .iter()
+        .filter_map(|source| modified(source))
+        .all(|source| source <= built)
+}
+
+/// The cache file at `cache`, mapped, when it's newer than `sources`, and
+/// otherwise what `build` makes, written there for next time.
+pub fn cached(
+    cache: &Path,
+    sources: &[PathBuf],
+    build: impl FnOnce() -> io::Result<Vec<u8>>,
+) -> io::Result<Bytes> {
+    if is_fresh(cache, sources) {
+        if let Ok(mmap) = map_file(cache) {
+            return Ok(Bytes::Mapped(mmap));
+        }
+    }
+    let built = build()?;
+    if let Err(e) = replace_file(cache, &built) {
+        eprintln!("Warning: could not cache {}: {}", cache.display(), e);
+    }
+    Ok(Bytes::Built(built))
+}
+
+/// The bytes behind an on-disk index: the cache file mapped straight from
+/// disk, or a freshly built index when the cache couldn't be written.
+pub enum Bytes {
+    Mapped(Mmap),
+    Built(Vec<u8>),
+}
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Bytes::Mapped(mmap) => mmap,
+            Bytes::Built(bytes) => bytes,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use serde::Serialize;
+
+use crate::{
+    analyze,
+    cli::Format,
+    cmudict,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Forms whose lines have a set number of syllables.
+const FORMS: &[(&str, &[usize])] = &[("haiku", &[5, 7, 5]), ("tanka", &[5, 7, 5, 7, 7])];
+
+/// One word and its syllables.
+#[derive(Serialize)]
+struct Word {
+    word: String,
+    syllables: usize,
+    /// Counted from the spelling, because CMUdict isn't installed or doesn't
+    /// have the word.
+    estimated: bool,
+}
+
+#[derive(Serialize)]
+struct Line {
+    text: String,
+    syllables: usize,
+    words: Vec<Word>,
+}
+
+/// What `syllables` prints with `json`.
+#[derive(Serialize)]
+struct Count {
+    lines: Vec<Line>,
+    syllables: usize,
+    /// The form the lines' counts make, when they make one: "haiku".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    form: Option<&'static str>,
+}
+impl Count {
+    fn of(text: &str) -> Self {
+        let lines: Vec<Line> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let words: Vec<Word> = line
+                    .split(|c: char| !(c.is_alphabetic() || c == '\''))
+                    .map(|word| word.trim_matches('\''))
+                    .filter(|word| !word.is_empty())
+                    .map(word)
+                    .collect();
+                Line {
+                    text: line.to_string(),
+                    syllables: words.iter().map(|w| w.syllables).sum(),
+                    words,
+                }
+            })
+            .collect();
+        let counts: Vec<usize> = lines.iter().map(|line| line.syllables).collect();
+        Self {
+            syllables: counts.iter().sum(),
+            form: FORMS
+                .iter()
+                .find(|(_, pattern)| *pattern == counts.as_slice())
+                .map(|(form, _)| *form),
+            lines,
+        }
+    }
+
+    /// The lines' counts, "5-7-5".
+    fn pattern(&self) -> String {
+        let counts: Vec<String> = self.lines.iter().map(|l| l.syllables.to_string()).collect();
+        counts.join("-")
+    }
+
+    /// The total, and the form when there's one: "17 syllables, a haiku
+    /// (5-7-5)".
+    fn summary(&self) -> String {
+        let total = match self.syllables {
+            1 => String::from("1 syllable"),
+            n => format!("{} syllables", n),
+        };
+        match (self.form, self.lines.len()) {
+            (Some(form), _) => format!("{}, a {} ({})", total, form, self.pattern()),
+            (None, 1) => total,
+            (None, _) => format!("{} ({})", total, self.pattern()),
+        }
+    }
+
+    /// A row per line: its count, then each word's, "~" marking estimates.
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let words: Vec<String> = line
+                    .words
+                    .iter()
+                    .map(|w| {
+                        let estimate = match w.estimated {
+                            true => "~",
+                            false => "",
+                        };
+                        format!("{} {}{}", w.word, estimate, w.syllables)
+                    })
+                    .collect();
+                vec![line.syllables.to_string(), words.join(", ")]
+            })
+            .collect()
+    }
+}
+
+/// `word`'s syllables from CMUdict, possessives as the word they're of, or
+/// estimated from the spelling.
+fn word(word: &str) -> Word {
+    let known =
+        cmudict::syllables(word).or_else(|| word.strip_suffix("'s").and_then(cmudict::syllables));
+    Word {
+        word: word.to_string(),
+        syllables: known.unwrap_or_else(|| analyze::estimate_syllables(word)),
+        estimated: known.is_none(),
+    }
+}
+
+/// `syllables "<text>"`: the syllables in each word and line of `text` and in
+/// all of it, and whether the lines make a haiku or a tanka. With `json`, the
+/// counts for scripts and editors.
+pub fn run(text: &str, json: bool, format: Format) {
+    let count = Count::of(text);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&count).unwrap());
+        return;
+    }
+    let summary = count.summary();
+    let rows = count.rows();
+    match format {
+        Format::Sherlock => {
+            let content = format!(
+                "<span font_desc=\"monospace\">\n<b>{}</b>\n\n{}</span>",
+                summary,
+                text::table(&rows)
+            );
+            let actions = vec![ApplicationAction::copy(
+                String::from("Copy the counts"),
+                count.pattern(),
+            )];
+            let first = count.lines.first().map_or("", |line| line.text.as_str());
+            let response =
+                SherlockPipeResponse::new(format!(r#"Syllables in "{}""#, first), content, actions);
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            println!("{}\n", summary);
+            print!("{}", text::table(&rows));
+        }
+        Format::Oneline => println!("{}", summary),
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashSet, error::Error, hash::Hash, path::Path};
+
+use crate::{
+    history::{Bookmarks, History, HistoryEntry, MAX_HISTORY},
+    known::Known,
+    srs::{Card, Deck},
+    storage,
+};
+
+/// `sync`: merges the bookmarks, known words, review schedule and history
+/// with the copies in `dir`, and writes the result to both. `dir` is any
+/// folder the machines share, through git, Syncthing or a cloud drive; the
+/// files in it are sorted, pretty-printed JSON, so git diffs and merges them
+/// well, or encrypted like the local ones when encryption is on.
+///
+/// Bookmarks and known words are merged against the state of the last sync,
+/// kept in the data dir, so a word removed on one machine stays removed.
+pub fn run(dir: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let Some(dir) = dir else {
+        eprintln!("Error: set 'sync_dir' in the config to the folder to sync through");
+        std::process::exit(1);
+    };
+    let dir = storage::expand_home(dir);
+    // An unmounted drive would otherwise look like everything was deleted.
+    if !dir.is_dir() {
+        eprintln!("Error: {} isn't a folder", dir.display());
+        std::process::exit(1);
+    }
+    let base_dir = storage::data_dir().join("sync");
+    // Read before anything is written, so a copy that can't be read, such as
+    // one git left conflict markers in, stops the sync instead of counting
+    // as a list that had everything removed.
+    let their_bookmarks: Option<Vec<String>> = theirs(&dir, "bookmarks.json");
+    let their_known: Option<Vec<String>> = theirs(&dir, "known.json");
+    let their_cards: Option<Vec<Card>> = theirs(&dir, "srs.json");
+    let their_history: Option<Vec<HistoryEntry>> = theirs(&dir, "history.json");
+
+    let bookmarks = Bookmarks::update(|bookmarks| {
+        bookmarks.words = merge_file(
+            &dir,
+            &base_dir,
+            "bookmarks.json",
+            &bookmarks.words,
+            their_bookmarks,
+        )?;
+        Ok::<_, Box<dyn Error>>(bookmarks.words.len())
+    })??;
+
+    let known = Known::update(|known| {
+        let words: Vec<String> = known.words.iter().cloned().collect();
+        known.words = merge_file(&dir, &base_dir, "known.json", &words, their_known)?
+            .into_iter()
+            .collect();
+        Ok::<_, Box<dyn Error>>(known.words.len())
+    })??;
+
+    // Loaded after the bookmarks are saved, so it has a card for every word.
+    let cards = Deck::update(|deck| {
+        merge_cards(&mut deck.cards, their_cards.unwrap_or_default());
+        deck.cards.clone()
+    })?;
+    storage::save_private(&dir.join("srs.json"), &cards)?;
+
+    let entries = History::update(|history| {
+        merge_history(&mut history.entries, their_history.unwrap_or_default());
+        history.entries.clone()
+    })?;
+    storage::save_private(&dir.join("history.json"), &entries)?;
+
+    println!(
+        "Synced {} bookmarks, {} known words and {} lookups with {}.",
+        bookmarks,
+        known,
+        entries.len(),
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Takes the cards from `theirs` that were reviewed more often than `mine`'s
+/// copy, the one that's further along. Cards for words `mine` doesn't have
+/// are left out: the bookmarks, merged first, decide which words have one.
+fn merge_cards(mine: &mut [Card], theirs: Vec<Card>) {
+    for card in theirs {
+        if let Some(mine) = mine.iter_mut().find(|mine| mine.word == card.word) {
+            if card.reviews > mine.reviews {
+                *mine = card;
+            }
+        }
+    }
+    mine.sort_by(|a, b| a.word.cmp(&b.word));
+}
+
+/// Both machines' lookups in time order, each once, keeping the newest
+/// [`MAX_HISTORY`].
+fn merge_history(mine: &mut Vec<HistoryEntry>, theirs: Vec<HistoryEntry>) {
+    mine.extend(theirs);
+    mine.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.word.cmp(&b.word)));
+    mine.dedup();
+    let excess = mine.len().saturating_sub(MAX_HISTORY);
+    mine.drain(..excess);
+}
+
+/// The copy of `name` in `dir`, `None` when there's none yet. One that
+/// can't be read stops the sync.
+fn theirs<T: DeserializeOwned>(dir: &Path, name: &str) -> Option<T> {
+    match storage::read_private(&dir.join(name)) {
+        Ok(theirs) => theirs,
+        Err(e) => {
+            eprintln!(
+                "Error: {}; fix it, or remove it to sync this machine's copy, and sync again",
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Merges `mine` with `theirs`, the copy of `name` in `dir`, saving the
+/// result there and as the new base for the next sync.
+fn merge_file<T>(
+    dir: &Path,
+    base_dir: &Path,
+    name: &str,
+    mine: &[T],
+    theirs: Option<Vec<T>>,
+) -> Result<Vec<T>, Box<dyn Error>>
+where
+    T: Clone + Eq + Hash + Serialize + DeserializeOwned,
+{
+    let base: Vec<T> = storage::load_private(&base_dir.join(name));
+    let theirs = theirs.unwrap_or_else(|| mine.to_vec());
+    let merged = merge(&base, mine, &theirs);
+    storage::save_private(&dir.join(name), &merged)?;
+    storage::save_private(&base_dir.join(name), &merged)?;
+    Ok(merged)
+}
+
+/// A three-way merge of two copies of a list that were both `base` at the
+/// last sync: whatever either side added is kept and whatever either side
+/// removed is dropped. `mine`'s order comes first, then what only `theirs` has.
+fn merge<T: Clone + Eq + Hash>(base: &[T], mine: &[T], theirs: &[T]) -> Vec<T> {
+    let base: HashSet<&T> = base.iter().collect();
+    let (my_set, their_set): (HashSet<&T>, HashSet<&T>) =
+        (mine.iter().collect(), theirs.iter().collect());
+    let mut merged: Vec<T> = mine
+        .iter()
+        .filter(|item| their_set.contains(item) || !base.contains(item))
+        .cloned()
+        .collect();
+    merged.extend(
+        theirs
+            .iter()
+            .filter(|item| !my_set.contains(item) && !base.contains(item))
+            .cloned(),
+    );
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn card(word: &str, reviews: u32) -> Card {
+        Card {
+            word: word.to_string(),
+            interval: f64::from(reviews),
+            ease: 2.5,
+            due: 0,
+            reviews,
+            lapses: 0,
+        }
+    }
+
+    fn entry(word: &str, timestamp: u64) -> HistoryEntry {
+        HistoryEntry {
+            word: word.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn keeps_what_either_side_added() {
+        let base = words(&["apple"]);
+        let merged = merge(
+            &base,
+            &words(&["apple", "pear"]),
+            &words(&["apple", "plum"]),
+        );
+        assert_eq!(merged, words(&["apple", "pear", "plum"]));
+    }
+
+    #[test]
+    fn drops_what_either_side_removed() {
+        let base = words(&["apple", "pear", "plum"]);
+        let merged = merge(
+            &base,
+            &words(&["apple", "plum"]),
+            &words(&["apple", "pear"]),
+        );
+        assert_eq!(merged, words(&["apple"]));
+    }
+
+    #[test]
+    fn removal_and_concurrent_add() {
+        // Pear removed here and left alone there is gone; fig added there
+        // meanwhile is kept.
+        let base = words(&["apple", "pear"]);
+        let merged = merge(&base, &words(&["apple"]), &words(&["apple", "pear", "fig"]));
+        assert_eq!(merged, words(&["apple", "fig"]));
+        // Added on both sides, it's there once.
+        let merged = merge(&base, &words(&["apple", "fig"]), &words(&["fig", "apple"]));
+        assert_eq!(merged, words(&["apple", "fig"]));
+        // Removed on both sides, nothing brings it back.
+        let merged = merge(&base, &words(&["apple"]), &words(&["apple"]));
+        assert_eq!(merged, words(&["apple"]));
+    }
+
+    #[test]
+    fn first_sync_is_a_union() {
+        let merged = merge(&[], &words(&["b", "a"]), &words(&["c", "a"]));
+        assert_eq!(merged, words(&["b", "a", "c"]));
+    }
+
+    #[test]
+    fn unreadable_copies_stop_the_sync() {
+        let dir = std::env::temp_dir().join(format!("sync-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conflicted = "<<<<<<< HEAD\n[\"apple\"]\n=======\n[\"pear\"]\n>>>>>>> theirs\n";
+        std::fs::write(dir.join("bookmarks.json"), conflicted).unwrap();
+        assert!(storage::read_private::<Vec<String>>(&dir.join("bookmarks.json")).is_err());
+        // Left as it was, for the user to resolve.
+        assert_eq!(
+            std::fs::read_to_string(dir.join("bookmarks.json")).unwrap(),
+            conflicted
+        );
+        assert!(!dir.join("bookmarks.json.corrupt").exists());
+        assert_eq!(
+            storage::read_private::<Vec<String>>(&dir.join("known.json")).unwrap(),
+            None
+        );
+        std::fs::write(dir.join("known.json"), "[\"fig\"]").unwrap();
+        assert_eq!(
+            storage::read_private::<Vec<String>>(&dir.join("known.json")).unwrap(),
+            Some(words(&["fig"]))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn more_reviews_win() {
+        let mut mine = vec![card("pear", 3), card("apple", 1)];
+        merge_cards(
+            &mut mine,
+            vec![card("apple", 4), card("pear", 2), card("fig", 9)],
+        );
+        let reviews: Vec<(&str, u32)> = mine
+            .iter()
+            .map(|card| (card.word.as_str(), card.reviews))
+            .collect();
+        // Sorted, theirs for apple, mine for pear, and no card without a
+        // bookmark.
+        assert_eq!(reviews, [("apple", 4), ("pear", 3)]);
+    }
+
+    #[test]
+    fn equal_reviews_keep_mine() {
+        let mut mine = vec![card("apple", 2)];
+        let mut theirs = card("apple", 2);
+        theirs.lapses = 2;
+        merge_cards(&mut mine, vec![theirs]);
+        assert_eq!(mine[0].lapses, 0);
+    }
+
+    #[test]
+    fn history_is_deduplicated_in_order() {
+        let mut mine = vec![entry("pear", 20), entry("apple", 10)];
+        let theirs = vec![entry("apple", 10), entry("fig", 15), entry("plum", 20)];
+        merge_history(&mut mine, theirs);
+        assert_eq!(
+            mine,
+            [
+                entry("apple", 10),
+                entry("fig", 15),
+                entry("pear", 20),
+                entry("plum", 20)
+            ]
+        );
+    }
+
+    #[test]
+    fn history_keeps_the_newest() {
+        let mut mine: Vec<HistoryEntry> =
+            (0..MAX_HISTORY as u64).map(|i| entry("word", i)).collect();
+        merge_history(&mut mine, vec![entry("last", MAX_HISTORY as u64)]);
+        assert_eq!(mine.len(), MAX_HISTORY);
+        assert_eq!(mine[0], entry("word", 1));
+        assert_eq!(mine.last(), Some(&entry("last", MAX_HISTORY as u64)));
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use fst::{automaton::Str, Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+use memmap2::Mmap;
+
+use crate::{
+    api::{Definition, DefinitionResponse, Meaning, Phonetic},
+    gender::Gender,
+    html,
+    storage::{self, Bytes},
+};
+
+/// FreeDict's part-of-speech values, long and abbreviated.
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("n", "noun"),
+    ("pn", "proper noun"),
+    ("v", "verb"),
+    ("vi", "verb"),
+    ("vt", "verb"),
+    ("adj", "adjective"),
+    ("adv", "adverb"),
+    ("prep", "preposition"),
+    ("conj", "conjunction"),
+    ("pron", "pronoun"),
+    ("int", "interjection"),
+    ("interj", "interjection"),
+    ("num", "numeral"),
+    ("art", "article"),
+    ("det", "determiner"),
+];
+
+/// A FreeDict TEI XML dictionary. The XML is mapped rather than parsed; a
+/// headword index pointing at each `<entry>` is built on first use and cached.
+pub struct Tei {
+    xml: Mmap,
+    index: Map<Bytes>,
+}
+
+impl Tei {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let xml = storage::map_file(path)?;
+        let cache = index_path(path);
+        if storage::is_fresh(&cache, &[path.to_path_buf()]) {
+            if let Some(index) = storage::map_file(&cache)
+                .ok()
+                .and_then(|mmap| Map::new(Bytes::Mapped(mmap)).ok())
+            {
+                return Ok(Self { xml, index });
+            }
+        }
+        let built = build_index(&xml);
+        if let Err(e) = storage::replace_file(&cache, &built) {
+            eprintln!(
+                "Warning: could not cache the index of {} in {}: {}",
+                path.display(),
+                cache.display(),
+                e
+            );
+        }
+        let index = Map::new(Bytes::Built(built)).map_err(|e| invalid(&e.to_string()))?;
+        Ok(Self { xml, index })
+    }
+
+    /// Every headword, lower-cased.
+    pub fn headwords(&self) -> Vec<String> {
+        let mut headwords: Vec<String> = Vec::new();
+        let mut stream = self.index.keys();
+        while let Some(key) = stream.next() {
+            let key = String::from_utf8_lossy(key);
+            let headword = key.split('\0').next().unwrap_or_default();
+            // Homographs are next to each other.
+            if headwords.last().is_none_or(|last| last != headword) {
+                headwords.push(headword.to_string());
+            }
+        }
+        headwords
+    }
+
+    pub fn lookup(&self, word: &str) -> Vec<DefinitionResponse> {
+        let prefix = format!("{}\0", word.trim().to_lowercase());
+        let mut stream = self
+            .index
+            .search(Str::new(&prefix).starts_with())
+            .into_stream();
+        let mut entries = Vec::new();
+        while let Some((_, location)) = stream.next() {
+            let (start, len) = ((location >> 24) as usize, (location & 0xff_ffff) as usize);
+            let Some(xml) = self.xml.get(start..start + len) else {
+                continue;
+            };
+            if let Some(entry) = entry(&String::from_utf8_lossy(xml)) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+}
+
+/// Cached indexes are named after the dictionary file.
+fn index_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    storage::cache_dir()
+        .join("tei")
+        .join(format!("{}.fst", name))
+}
+
+/// Maps `headword\0n` (lower-cased; `n` keeps homographs apart) to the
+/// entry's byte offset and length, packed into 40 and 24 bits.
+fn build_index(xml: &[u8]) -> Vec<u8> {
+    let text = String::from_utf8_lossy(xml);
+    let mut keys: Vec<(String, u64)> = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find("<entry") {
+        let start = pos + found;
+        let Some(close) = text[start..].find("</entry>") else {
+            break;
+        };
+        let end = start + close + "</entry>".len();
+        pos = end;
+        let len = end - start;
+        if len > 0xff_ffff {
+            continue;
+        }
+        let location = ((start as u64) << 24) | len as u64;
+        for orth in html::elements(&text[start..end], "orth") {
+            let headword = html::text(&text[start..end][orth]).to_lowercase();
+            if !headword.is_empty() {
+                keys.push((headword, location));
+            }
+        }
+    }
+    keys.sort();
+    keys.dedup();
+
+    let mut builder = MapBuilder::memory();
+    let mut homograph = 0;
+    for (i, (headword, location)) in keys.iter().enumerate() {
+        homograph = match i > 0 && keys[i - 1].0 == *headword {
+            true => homograph + 1,
+            false => 0,
+        };
+        let key = format!("{}\0{:06}", headword, homograph);
+        builder
+            .insert(key, *location)
+            .expect("index keys are sorted and unique");
+    }
+    builder.into_inner().expect("in-memory writes can't fail")
+}
+
+/// Converts one `<entry>`. Newer FreeDict files put translations in
+/// `<sense><cit type="trans"><quote>`, older ones in `<trans><tr>`.
+fn entry(xml: &str) -> Option<DefinitionResponse> {
+    let first = |tag: &str, within: &str| {
+        html::elements(within, tag)
+            .into_iter()
+            .next()
+            .map(|range| html::text(&within[range]))
+            .filter(|text| !text.is_empty())
+    };
+    let word = first("orth", xml)?;
+    let pron = first("pron", xml).map(|p| match p.starts_with('/') {
+        true => p,
+        false => format!("/{}/", p),
+    });
+    let outside_senses = html::without(xml, "sense");
+    let entry_pos = first("pos", &outside_senses);
+    let entry_gender = first("gen", &outside_senses);
+
+    let senses: Vec<String> = html::elements(xml, "sense")
+        .into_iter()
+        .map(|range| xml[range].to_string())
+        .collect();
+    // Entries without senses are a single implicit one.
+    let senses = match senses.is_empty() {
+        true => vec![xml.to_string()],
+        false => senses,
+    };
+
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for sense in &senses {
+        let Some(definition) = sense_definition(sense) else {
+            continue;
+        };
+        let pos = first("pos", sense)
+            .or_else(|| entry_pos.clone())
+            .map(|pos| part_of_speech(&pos))
+            .unwrap_or_else(|| String::from("translation"));
+        let gender = first("gen", sense)
+            .or_else(|| entry_gender.clone())
+            .and_then(|gender| Gender::parse(&gender));
+        match meanings.iter_mut().find(|m| m.part_of_speech == pos) {
+            Some(meaning) => {
+                meaning.definitions.push(definition);
+                meaning.gender = meaning.gender.or(gender);
+            }
+            None => meanings.push(Meaning {
+                part_of_speech: pos,
+                definitions: vec![definition],
+                synonyms: None,
+                antonyms: None,
+                forms: None,
+                gender,
+            }),
+        }
+    }
+    if meanings.is_empty() {
+        return None;
+    }
+    Some(DefinitionResponse {
+        word,
+        phonetic: pron.clone(),
+        phonetics: pron
+            .into_iter()
+            .map(|text| Phonetic {
+                text: Some(text),
+                audio: None,
+                source_url: None,
+                license: None,
+            })
+            .collect(),
+        meanings,
+        source_urls: None,
+        origin: None,
+        provider: None,
+    })
+}
+
+/// A sense's translations (and definition, when there is one), prefixed with
+/// its usage labels, plus its first example and that example's translation.
+fn sense_definition(sense: &str) -> Option<Definition> {
+    let texts = |xml: &str, tag: &str| -> Vec<String> {
+        html::elements(xml, tag)
+            .into_iter()
+            .map(|range| html::text(&xml[range]))
+            .filter(|text| !text.is_empty())
+            .collect()
+    };
+    let mut translations = Vec::new();
+    let mut example = None;
+    for cit in html::elements(sense, "cit") {
+        let cit = &sense[cit];
+        let kind = cit[..cit.find('>').unwrap_or(0)].to_string();
+        if kind.contains("type=\"example\"") {
+            if example.is_none() {
+                let own = texts(&html::without(html::inner(cit), "cit"), "quote");
+                let translated = texts(html::inner(cit), "cit")
+                    .into_iter()
+                    .next()
+                    .map(|t| format!(" — {}", t))
+                    .unwrap_or_default();
+                example = own.first().map(|quote| format!("{}{}", quote, translated));
+            }
+        } else if kind.contains("type=\"trans\"") || kind.contains("type=\"translation\"") {
+            translations.extend(texts(cit, "quote"));
+        }
+    }
+    translations.extend(texts(sense, "tr"));
+
+    let definitions = texts(&html::without(sense, "cit"), "def");
+    let mut gloss = translations.join(", ");
+    if let Some(definition) = definitions.first() {
+        gloss = match gloss.is_empty() {
+            true => definition.clone(),
+            false => format!("{} ({})", gloss, definition),
+        };
+    }
+    if gloss.is_empty() {
+        return None;
+    }
+    let labels = texts(&html::without(sense, "cit"), "usg");
+    if !labels.is_empty() {
+        gloss = format!("({}) {}", labels.join(", "), gloss);
+    }
+    Some(Definition {
+        definition: gloss,
+        example,
+        synonyms: None,
+        antonyms: None,
+        level: None,
+        region: None,
+        labels: Vec::new(),
+    })
+}
+
+fn part_of_speech(pos: &str) -> String {
+    let pos = pos.trim_end_matches('.').to_lowercase();
+    PARTS_OF_SPEECH
+        .iter()
+        .find(|(abbr, _)| *abbr == pos)
+        .map_or(pos.clone(), |(_, name)| name.to_string())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    const XML: &str = r#"<TEI><text><body>
+<entry><form><orth>Bank</orth><pron>baŋk</pron></form><gramGrp><pos>n</pos><gen>fem</gen></gramGrp>
+<sense><cit type="trans"><quote>bench</quote></cit></sense></entry>
+<entry><form><orth>Bank</orth></form><gramGrp><pos>n</pos></gramGrp>
+<sense><cit type="trans"><quote>bank</quote></cit></sense></entry>
+<entry><form><orth>Haus</orth><orth>Hause</orth></form><trans><tr>house</tr></trans></entry>
+<entry><form><orth></orth></form></entry>
+<entry><form><orth>kaputt</orth></form>
+</body></text></TEI>"#;
+
+    /// The index's keys and the XML each points at.
+    fn keys(xml: &str) -> Vec<(String, String)> {
+        let map = Map::new(build_index(xml.as_bytes())).unwrap();
+        let mut stream = map.stream();
+        let mut keys = Vec::new();
+        while let Some((key, location)) = stream.next() {
+            let (start, len) = ((location >> 24) as usize, (location & 0xff_ffff) as usize);
+            keys.push((
+                String::from_utf8_lossy(key).into_owned(),
+                xml[start..start + len].to_string(),
+            ));
+        }
+        keys
+    }
+
+    #[test]
+    fn index_points_at_each_entry() {
+        let keys = keys(XML);
+        let names: Vec<&str> = keys.iter().map(|(key, _)| key.as_str()).collect();
+        // Lower-cased, homographs numbered, every <orth> a key, and neither
+        // the empty headword nor the unclosed entry.
+        assert_eq!(
+            names,
+            [
+                "bank\x00000000",
+                "bank\x00000001",
+                "haus\x00000000",
+                "hause\x00000000"
+            ]
+        );
+        for (_, xml) in &keys {
+            assert!(xml.starts_with("<entry>") && xml.ends_with("</entry>"));
+        }
+        assert!(keys[0].1.contains("bench") != keys[1].1.contains("bench"));
+        assert_eq!(keys[2].1, keys[3].1);
+    }
+
+    #[test]
+    fn looks_up_homographs_and_alternative_forms() {
+        let path = env::temp_dir().join(format!("test-{}.tei", std::process::id()));
+        fs::write(&path, XML).unwrap();
+        let tei = Tei {
+            xml: storage::map_file(&path).unwrap(),
+            index: Map::new(Bytes::Built(build_index(XML.as_bytes()))).unwrap(),
+        };
+        fs::remove_file(&path).ok();
+
+        let banks = tei.lookup("BANK");
+        assert_eq!(banks.len(), 2);
+        assert_eq!(banks[0].phonetic.as_deref(), Some("/baŋk/"));
+        assert_eq!(banks[0].meanings[0].part_of_speech, "noun");
+        let translations: Vec<&str> = banks
+            .iter()
+            .map(|entry| entry.meanings[0].definitions[0].definition.as_str())
+            .collect();
+        assert!(translations.contains(&"bench") && translations.contains(&"bank"));
+        assert_eq!(
+            tei.lookup("hause")[0].meanings[0].definitions[0].definition,
+            "house"
+        );
+        assert!(tei.lookup("ban").is_empty());
+        assert_eq!(tei.headwords(), ["bank", "haus", "hause"]);
+    }
+}
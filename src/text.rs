@@ -0,0 +1,446 @@
+use std::{borrow::Cow, fmt::Write, iter};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    api::DefinitionResponse, cedict, config::Config, confusables, furigana::Ruby, gender,
+    i18n::text, labels, locale, related,
+};
+
+/// What right-to-left text is put between when `isolate_rtl` is on: U+2068
+/// FIRST STRONG ISOLATE and U+2069 POP DIRECTIONAL ISOLATE.
+const ISOLATE: (&str, &str) = ("\u{2068}", "\u{2069}");
+
+/// Plain-text rendering of entries for terminals, without Pango markup.
+pub fn plain(entries: &[DefinitionResponse], lang: &str) -> String {
+    let mut out: String = entries
+        .iter()
+        .map(|entry| plain_entry(entry, lang))
+        .collect();
+    out.push_str(&plain_end(entries, lang));
+    out
+}
+
+/// One entry of [`plain`]'s output.
+pub fn plain_entry(entry: &DefinitionResponse, lang: &str) -> String {
+    let mut out = String::new();
+    let headline = gender::headline(entry, lang);
+    let word = isolate(&headline);
+    // The reading ruby shows is the pronunciation otherwise shown.
+    match (Ruby::of(entry), entry.ipa()) {
+        (Some(ruby), _) => writeln!(out, "{}\n", ruby.lines().join("\n")),
+        (None, Some(ipa)) if cedict::is_from(entry) => {
+            writeln!(out, "{}  {}\n", word, cedict::ansi(ipa))
+        }
+        (None, Some(ipa)) => writeln!(out, "{}  {}\n", word, ipa),
+        (None, None) => writeln!(out, "{}\n", word),
+    }
+    .unwrap();
+    let numbering = Numbering::of([entry]);
+    for meaning in &entry.meanings {
+        writeln!(out, "─── {} ───", meaning.heading()).unwrap();
+        if let Some(forms) = meaning.forms.as_ref().filter(|f| !f.is_empty()) {
+            writeln!(out, "     {} {}", text("forms"), isolate(&forms.join(", "))).unwrap();
+        }
+        for (i, def) in meaning.definitions.iter().enumerate() {
+            let level = def
+                .level
+                .map(|level| format!("[{}] ", level))
+                .unwrap_or_default();
+            let region = def
+                .region
+                .as_ref()
+                .map(|region| format!("[{}] ", region))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                " {}{}{}{}{}",
+                numbering.label(i + 1),
+                level,
+                region,
+                labels::ansi(&def.labels),
+                isolate(&def.definition)
+            )
+            .unwrap();
+            if let Some(example) = &def.example {
+                writeln!(out, "     {} \"{}\"", text("example"), isolate(example)).unwrap();
+            }
+            if let Some(synonyms) = def.synonyms.as_ref().filter(|s| !s.is_empty()) {
+                let synonyms = isolate(&synonyms.join(", ")).into_owned();
+                writeln!(out, "     {} {}", text("synonyms"), synonyms).unwrap();
+            }
+            if let Some(antonyms) = def.antonyms.as_ref().filter(|a| !a.is_empty()) {
+                let antonyms = isolate(&antonyms.join(", ")).into_owned();
+                writeln!(out, "     {} {}", text("antonyms"), antonyms).unwrap();
+            }
+        }
+        out.push('\n');
+    }
+    if let Some(origin) = &entry.origin {
+        writeln!(out, "{} {}\n", text("origin"), isolate(origin)).unwrap();
+    }
+    out
+}
+
+/// What [`plain`] prints after the entries: the related words they point to
+/// and the words the first is confused with.
+pub fn plain_end(entries: &[DefinitionResponse], lang: &str) -> String {
+    let mut out = String::new();
+    let see_also = related::see_also(entries);
+    if !see_also.is_empty() {
+        writeln!(
+            out,
+            "{} {}",
+            text("see-also"),
+            isolate(&see_also.join(", "))
+        )
+        .unwrap();
+    }
+    if let Some(entry) = entries.first() {
+        for (other, hint) in confusables::confused_with(&entry.word, lang) {
+            writeln!(out, "⚠ {} {}: {}", text("confused-with"), other, hint).unwrap();
+        }
+    }
+    out
+}
+
+/// `word /IPA/ (pos): first definition`, or `None` if there is no definition.
+/// A noun with a gender has its article and the gender with the part of
+/// speech: `der Hund /hʊnt/ (noun, m): dog`.
+pub fn oneline(entries: &[DefinitionResponse], lang: &str) -> Option<String> {
+    let (entry, meaning, definition) = entries.iter().find_map(|entry| {
+        entry.meanings.iter().find_map(|meaning| {
+            let definition = meaning.definitions.first()?;
+            Some((entry, meaning, definition))
+        })
+    })?;
+    let mut line = match meaning.gender {
+        Some(gender) => gender::with_article(&entry.word, gender, lang),
+        None => entry.word.clone(),
+    };
+    if let Some(ipa) = entry.ipa() {
+        write!(line, " {}", ipa).unwrap();
+    }
+    let part_of_speech = match meaning.gender {
+        Some(gender) => format!("{}, {}", meaning.part_of_speech, gender.abbreviation()),
+        None => meaning.part_of_speech.clone(),
+    };
+    write!(
+        line,
+        " ({}): {}",
+        part_of_speech,
+        // Definitions occasionally contain line breaks; this must stay one line.
+        definition
+            .definition
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+    .unwrap();
+    Some(line)
+}
+
+/// Markdown rendering of entries for editors' hovers: the headword and its
+/// pronunciation, then the first few senses of each part of speech.
+pub fn markdown(entries: &[DefinitionResponse], lang: &str, senses: usize) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let headline = markdown_escape(&gender::headline(entry, lang));
+        match entry.ipa() {
+            Some(ipa) => writeln!(out, "**{}** {}\n", headline, markdown_escape(ipa)),
+            None => writeln!(out, "**{}**\n", headline),
+        }
+        .unwrap();
+        for meaning in &entry.meanings {
+            writeln!(out, "*{}*\n", markdown_escape(&meaning.heading())).unwrap();
+            for (i, def) in meaning.definitions.iter().take(senses).enumerate() {
+                let labels: String = def
+                    .labels
+                    .iter()
+                    .map(|label| format!("_{}_ ", label))
+                    .collect();
+                writeln!(
+                    out,
+                    "{}. {}{}",
+                    i + 1,
+                    labels,
+                    markdown_escape(&def.definition)
+                )
+                .unwrap();
+            }
+            if meaning.definitions.len() > senses {
+                let more = meaning.definitions.len() - senses;
+                writeln!(out, "\n…and {} more", more).unwrap();
+            }
+            out.push('\n');
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// `text` with Markdown's emphasis, link and HTML characters escaped, so a
+/// definition shows as it was written.
+pub fn markdown_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        // A definition's line breaks would end the list item.
+        match c {
+            '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// HTML rendering of entries for feeds and pages: the headword and its
+/// pronunciation, then each part of speech's senses as a numbered list with
+/// their examples.
+pub fn html(entries: &[DefinitionResponse], lang: &str) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let headline = html_escape(&gender::headline(entry, lang));
+        match entry.ipa() {
+            Some(ipa) => writeln!(
+                out,
+                "<h2>{} <small>{}</small></h2>",
+                headline,
+                html_escape(ipa)
+            ),
+            None => writeln!(out, "<h2>{}</h2>", headline),
+        }
+        .unwrap();
+        for meaning in &entry.meanings {
+            writeln!(out, "<h3>{}</h3>\n<ol>", html_escape(&meaning.heading())).unwrap();
+            for def in &meaning.definitions {
+                let labels: String = def
+                    .labels
+                    .iter()
+                    .map(|label| format!("<i>[{}]</i> ", label))
+                    .collect();
+                write!(out, "<li>{}{}", labels, html_escape(&def.definition)).unwrap();
+                if let Some(example) = &def.example {
+                    write!(out, "<br><q>{}</q>", html_escape(example)).unwrap();
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str("</ol>\n");
+        }
+        if let Some(origin) = &entry.origin {
+            let label = html_escape(text("origin"));
+            writeln!(out, "<p>{} {}</p>", label, html_escape(origin)).unwrap();
+        }
+    }
+    out
+}
+
+/// `text` with the characters that are special in HTML, and in its
+/// attributes, escaped.
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Left-aligns `rows` into columns separated by two spaces.
+pub fn table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| width(cell))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut out = String::new();
+    for row in rows {
+        let mut line = String::new();
+        for (i, cell) in row.iter().enumerate() {
+            line.push_str(&isolate(cell));
+            if i + 1 < row.len() {
+                pad(&mut line, widths[i] - width(cell) + 2);
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Breaks `text` into lines at most `columns` wide at spaces, and between
+/// any two Chinese, Japanese or Korean characters, which are written without
+/// spaces. Other words longer than a line get a line of their own.
+pub fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let pieces: Vec<&str> = match has_wide(word) {
+            true => word
+                .char_indices()
+                .map(|(i, c)| &word[i..i + c.len_utf8()])
+                .collect(),
+            false => vec![word],
+        };
+        for (i, piece) in pieces.into_iter().enumerate() {
+            let space = usize::from(i == 0 && !line.is_empty());
+            if !line.is_empty() && width(&line) + space + width(piece) > columns {
+                lines.push(std::mem::take(&mut line));
+            } else if space == 1 {
+                line.push(' ');
+            }
+            line.push_str(piece);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Two columns of lines next to each other, the left one padded to
+/// `columns`.
+pub fn side_by_side(left: &[String], right: &[String], columns: usize) -> String {
+    let mut out = String::new();
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map_or("", String::as_str);
+        let r = right.get(i).map_or("", String::as_str);
+        let mut line = isolate(l).into_owned();
+        pad(&mut line, columns.saturating_sub(width(l)));
+        write!(line, " │ {}", isolate(r)).unwrap();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// How many columns `s` takes in a terminal: two for each Chinese, Japanese
+/// or Korean character, none for combining marks and direction controls.
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Appends `n` spaces to `line`.
+fn pad(line: &mut String, n: usize) {
+    line.extend(iter::repeat_n(' ', n));
+}
+
+/// Whether `s` has characters two columns wide.
+fn has_wide(s: &str) -> bool {
+    s.chars().any(|c| c.width() == Some(2))
+}
+
+/// Whether `c` is a Chinese character, as Chinese, Japanese kanji and Korean
+/// hanja use them.
+pub fn is_ideograph(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        // The ideographs and their extensions, and 々, which repeats one.
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x3134F | 0x3005
+    )
+}
+
+/// Whether `s` has letters written right to left: Hebrew, Arabic, Syriac,
+/// Thaana, N'Ko and the like.
+pub fn has_rtl(s: &str) -> bool {
+    s.chars().any(|c| {
+        matches!(
+            u32::from(c),
+            0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+        )
+    })
+}
+
+/// The marks to put around text, so that when `rtl` it's laid out right to
+/// left without turning the rest of the line around. None unless the
+/// config's `isolate_rtl` is on, as some fonts draw them as boxes.
+pub fn isolation(rtl: bool) -> (&'static str, &'static str) {
+    match rtl && Config::load().isolate_rtl {
+        true => ISOLATE,
+        false => ("", ""),
+    }
+}
+
+/// `s` between the [`isolation`] marks when it has right-to-left letters.
+pub fn isolate(s: &str) -> Cow<'_, str> {
+    match isolation(has_rtl(s)) {
+        ("", _) => Cow::Borrowed(s),
+        (open, close) => Cow::Owned(format!("{}{}{}", open, s, close)),
+    }
+}
+
+/// How senses are numbered. Right-aligning the numbers with spaces assumes
+/// every character is a column wide and the line runs left to right; a sense
+/// with wide letters, or right-to-left ones that aren't isolated, would move
+/// the padding somewhere it doesn't line anything up.
+pub struct Numbering {
+    aligned: bool,
+}
+impl Numbering {
+    /// The numbering for the senses of `entries`.
+    pub fn of<'a>(entries: impl IntoIterator<Item = &'a DefinitionResponse>) -> Self {
+        let (mut wide, mut rtl) = (false, false);
+        let senses = entries
+            .into_iter()
+            .flat_map(|entry| &entry.meanings)
+            .flat_map(|meaning| &meaning.definitions);
+        for text in senses.flat_map(|def| iter::once(&def.definition).chain(&def.example)) {
+            wide |= has_wide(text);
+            rtl |= has_rtl(text);
+        }
+        let isolated = isolation(true) != ("", "");
+        Self {
+            aligned: !wide && (!rtl || isolated),
+        }
+    }
+
+    /// The label in front of sense `n`: ` 1. ` lined up with `12. `, or
+    /// just `1. `.
+    pub fn label(&self, n: usize) -> String {
+        match self.aligned {
+            true => format!("{:>2}. ", n),
+            false => format!("{}. ", n),
+        }
+    }
+}
+
+/// The unaccented Latin letter for `c`: é → e, ñ → n. Other characters are
+/// returned as they are.
+pub fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'ğ' => 'g',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => 'i',
+        'ł' | 'ľ' => 'l',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ř' => 'r',
+        'ś' | 'š' | 'ş' => 's',
+        'ť' | 'ţ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        other => other,
+    }
+}
+
+/// A byte count for people: "12.3 MB".
+pub fn size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit + 1 < UNITS.len() {
+        value /= 1000.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{} {}", locale::decimal(value, 1), UNITS[unit]),
+    }
+}
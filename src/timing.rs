@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use crate::text;
+
+/// How long each part of a lookup took, for `--bench-lookup`. Does nothing
+/// unless it's on, so the lookup path can time itself unconditionally.
+pub struct Stopwatch {
+    on: bool,
+    started: Instant,
+    last: Instant,
+    laps: Vec<(&'static str, Duration)>,
+}
+impl Stopwatch {
+    /// A stopwatch that has been running since `started`.
+    pub fn new(started: Instant, on: bool) -> Self {
+        Self {
+            on,
+            started,
+            last: started,
+            laps: Vec::new(),
+        }
+    }
+
+    /// Ends the part called `name`, which began where the last one ended.
+    pub fn lap(&mut self, name: &'static str) {
+        if self.on {
+            let now = Instant::now();
+            self.laps.push((name, now - self.last));
+            self.last = now;
+        }
+    }
+
+    /// Prints the parts and the total to stderr, leaving the output itself
+    /// as it would be without the flag.
+    pub fn report(&self) {
+        if !self.on {
+            return;
+        }
+        let total = self.last - self.started;
+        let rows: Vec<Vec<String>> = self
+            .laps
+            .iter()
+            .chain([&("total", total)])
+            .map(|(name, took)| vec![name.to_string(), millis(*took)])
+            .collect();
+        eprint!("{}", text::table(&rows));
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:>8.2} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,179 @@
+use crate::{
+    cli::Format,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text, wiktionary,
+};
+
+/// Templates in a Translations section: the box for each sense, and the
+/// translation templates inside it.
+const TEMPLATES: &[&str] = &["trans-top", "trans-bottom", "t", "t+", "tt", "tt+"];
+
+/// The translations listed for one sense of the word.
+struct Sense {
+    gloss: String,
+    /// Language codes and the terms in each, in the order they're listed.
+    languages: Vec<(String, Vec<String>)>,
+}
+
+/// `translations <word>`: Wiktionary's translation tables for each sense,
+/// limited to the `to` language codes, or every language on our list when
+/// there are none.
+pub async fn run(word: &str, to: &[String], format: Format) -> Result<(), surf::Error> {
+    let word = word.trim();
+    let mut senses = match wiktionary::fetch_page(word).await? {
+        Some(page) => from_page(word, &page).await?,
+        None => Vec::new(),
+    };
+    for sense in &mut senses {
+        sense.languages.retain(|(code, _)| match to.is_empty() {
+            true => wiktionary::LANGUAGES.iter().any(|(c, _, _)| c == code),
+            false => to.contains(code),
+        });
+        sense
+            .languages
+            .sort_by_key(|(code, _)| to.iter().position(|c| c == code));
+    }
+    senses.retain(|sense| !sense.languages.is_empty());
+
+    if senses.is_empty() {
+        match format {
+            Format::Sherlock => {
+                let response = SherlockPipeResponse::message(
+                    "No translations found".to_string(),
+                    String::new(),
+                );
+                response.print();
+            }
+            _ => {
+                eprintln!("No translations found for '{}'.", word);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        Format::Sherlock => {
+            let mut content = String::from("<span font_desc=\"monospace\">\n");
+            let mut actions = Vec::new();
+            let mut copied: Vec<(&str, &str)> = Vec::new();
+            for sense in &senses {
+                content.push_str(&format!("─── <b><i>{}</i></b> ───\n\n", sense.gloss));
+                content.push_str(&text::table(&rows(sense)));
+                content.push('\n');
+                for (code, terms) in &sense.languages {
+                    for term in terms {
+                        if !copied.contains(&(code, term)) {
+                            copied.push((code, term));
+                            actions.push(ApplicationAction::copy(
+                                format!("{} {}", language_name(code), term),
+                                term.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+            content.push_str("────────────\n</span>");
+            let response = SherlockPipeResponse::new(
+                format!(r#"Translations of "{}""#, word),
+                content,
+                actions,
+            );
+            response.print();
+        }
+        Format::Plain | Format::Sexp => {
+            for sense in &senses {
+                println!("─── {} ───\n", sense.gloss);
+                print!("{}", text::table(&rows(sense)));
+                println!();
+            }
+        }
+        Format::Oneline => {
+            let glosses: Vec<String> = senses
+                .iter()
+                .map(|sense| {
+                    let terms: Vec<String> = sense
+                        .languages
+                        .iter()
+                        .map(|(code, terms)| format!("{} {}", code, terms.join(", ")))
+                        .collect();
+                    format!("({}) {}", sense.gloss, terms.join("; "))
+                })
+                .collect();
+            println!("{}: {}", word, glosses.join(" | "));
+        }
+    }
+    Ok(())
+}
+
+fn language_name(code: &str) -> &str {
+    wiktionary::language_name(code).unwrap_or(code)
+}
+
+fn rows(sense: &Sense) -> Vec<Vec<String>> {
+    sense
+        .languages
+        .iter()
+        .map(|(code, terms)| vec![language_name(code).to_string(), terms.join(", ")])
+        .collect()
+}
+
+/// The senses in the English section of `page`. Words with many
+/// translations keep them on a `word/translations` subpage, which is fetched
+/// instead.
+async fn from_page(word: &str, page: &str) -> Result<Vec<Sense>, surf::Error> {
+    let Some(english) = wiktionary::language_section(page, "English") else {
+        return Ok(Vec::new());
+    };
+    let found = senses(english);
+    if !found.is_empty() || !english.contains("{{see translation subpage") {
+        return Ok(found);
+    }
+    let subpage = wiktionary::fetch_page(&format!("{}/translations", word)).await?;
+    Ok(subpage
+        .as_deref()
+        .and_then(|page| wiktionary::language_section(page, "English"))
+        .map(senses)
+        .unwrap_or_default())
+}
+
+/// One [`Sense`] per `{{trans-top|gloss}}` box in the Translations sections.
+fn senses(english: &str) -> Vec<Sense> {
+    let mut senses: Vec<Sense> = Vec::new();
+    for section in wiktionary::sections(english, "Translations") {
+        let mut open = false;
+        for (name, template) in wiktionary::templates_named(section, TEMPLATES) {
+            match name {
+                "trans-top" => {
+                    let gloss = template.positional.first().map(|g| wiktionary::plain(g));
+                    senses.push(Sense {
+                        gloss: gloss
+                            .filter(|g| !g.is_empty())
+                            .unwrap_or_else(|| String::from("all senses")),
+                        languages: Vec::new(),
+                    });
+                    open = true;
+                }
+                "trans-bottom" => open = false,
+                _ if open => {
+                    let [code, term, ..] = template.positional[..] else {
+                        continue;
+                    };
+                    let term = wiktionary::plain(template.param("alt").unwrap_or(term));
+                    if term.is_empty() {
+                        continue;
+                    }
+                    let sense = senses.last_mut().unwrap();
+                    match sense.languages.iter_mut().find(|(c, _)| c == code) {
+                        Some((_, terms)) if !terms.contains(&term) => terms.push(term),
+                        Some(_) => {}
+                        None => sense.languages.push((code.to_string(), vec![term])),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    senses.retain(|sense| !sense.languages.is_empty());
+    senses
+}
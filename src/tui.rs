@@ -0,0 +1,559 @@
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::{
+    io,
+    str::FromStr,
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+use tokio::runtime::Handle;
+
+use crate::{
+    api::{DefinitionResponse, Lookup},
+    audio, cedict,
+    content_filter::ContentFilter,
+    desktop,
+    furigana::Ruby,
+    gender,
+    history::{Bookmarks, History},
+    i18n::{text, tr},
+    index::WordIndex,
+    providers::Settings,
+    text::{self, Numbering},
+};
+
+const SUGGESTION_LIMIT: usize = 50;
+
+/// Runs the interactive browser until the user quits.
+pub async fn run(settings: Settings) -> Result<(), Box<dyn std::error::Error>> {
+    let runtime = Handle::current();
+    // The event loop blocks on terminal input, so keep it off the async workers.
+    tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::init();
+        let result = App::new(runtime, settings).run(&mut terminal);
+        ratatui::restore();
+        result
+    })
+    .await??;
+    Ok(())
+}
+
+#[derive(PartialEq)]
+enum Focus {
+    Search,
+    Definition,
+}
+
+struct LookupDone {
+    word: String,
+    record: bool,
+    result: Result<Lookup, String>,
+}
+
+struct App {
+    runtime: Handle,
+    settings: Settings,
+    words: &'static WordIndex,
+    history: History,
+    bookmarks: Bookmarks,
+    focus: Focus,
+    query: String,
+    suggestions: ListState,
+    /// Headword currently shown in the definition pane.
+    current: Option<String>,
+    entries: Vec<DefinitionResponse>,
+    /// Whether senses hidden by the content filter are shown anyway.
+    revealed: bool,
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    /// Index into the history while browsing it; `None` means "at the newest".
+    history_pos: Option<usize>,
+    status: String,
+    sender: Sender<LookupDone>,
+    receiver: Receiver<LookupDone>,
+    quit: bool,
+}
+
+impl App {
+    fn new(runtime: Handle, settings: Settings) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            runtime,
+            settings,
+            words: WordIndex::shared(),
+            history: History::load(),
+            bookmarks: Bookmarks::load(),
+            focus: Focus::Search,
+            query: String::new(),
+            suggestions: ListState::default(),
+            current: None,
+            entries: Vec::new(),
+            revealed: false,
+            lines: vec![Line::from(text("tui-prompt"))],
+            scroll: 0,
+            history_pos: None,
+            status: String::new(),
+            sender,
+            receiver,
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            while let Ok(done) = self.receiver.try_recv() {
+                self.finish_lookup(done);
+            }
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The suggestion column: completions for the query, or bookmarks when it's empty.
+    fn suggestion_items(&self) -> Vec<String> {
+        if self.query.is_empty() {
+            self.bookmarks.words.iter().rev().cloned().collect()
+        } else {
+            self.words.suggest(&self.query, SUGGESTION_LIMIT)
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [search_area, body_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, definition_area] =
+            Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(body_area);
+
+        let focused = Style::new().add_modifier(Modifier::BOLD);
+        let search_block = Block::bordered().title(" Search ");
+        let search_block = if self.focus == Focus::Search {
+            search_block.border_style(focused)
+        } else {
+            search_block
+        };
+        frame.render_widget(
+            Paragraph::new(self.query.as_str()).block(search_block),
+            search_area,
+        );
+        if self.focus == Focus::Search {
+            let cursor_x = search_area.x + 1 + text::width(&self.query) as u16;
+            frame.set_cursor_position(Position::new(cursor_x, search_area.y + 1));
+        }
+
+        let list_title = if self.query.is_empty() {
+            " Bookmarks "
+        } else {
+            " Suggestions "
+        };
+        let items: Vec<ListItem> = self
+            .suggestion_items()
+            .into_iter()
+            .map(ListItem::new)
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(list_title))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, list_area, &mut self.suggestions);
+
+        let mut title = match &self.current {
+            Some(word) => format!(" {} ", word),
+            None => String::from(" Definition "),
+        };
+        if self
+            .current
+            .as_deref()
+            .is_some_and(|w| self.bookmarks.contains(w))
+        {
+            title.push_str("★ ");
+        }
+        let definition_block = Block::bordered().title(title);
+        let definition_block = if self.focus == Focus::Definition {
+            definition_block.border_style(focused)
+        } else {
+            definition_block
+        };
+        frame.render_widget(
+            Paragraph::new(self.lines.clone())
+                .block(definition_block)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            definition_area,
+        );
+
+        let footer = if self.status.is_empty() {
+            text("tui-help")
+        } else {
+            self.status.as_str()
+        };
+        frame.render_widget(Paragraph::new(footer).dim(), help_area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => self.toggle_focus(),
+            KeyCode::Left if alt => self.history_back(),
+            KeyCode::Right if alt => self.history_forward(),
+            _ if self.focus == Focus::Search => self.handle_search_key(key),
+            _ => self.handle_definition_key(key),
+        }
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Enter => {
+                let selected = self
+                    .suggestions
+                    .selected()
+                    .and_then(|i| self.suggestion_items().into_iter().nth(i));
+                let word = selected.unwrap_or_else(|| self.query.trim().to_string());
+                if !word.is_empty() {
+                    self.history_pos = None;
+                    self.start_lookup(word, true);
+                }
+            }
+            KeyCode::Down => self.suggestions.select_next(),
+            KeyCode::Up => self.suggestions.select_previous(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.suggestions.select(None);
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.suggestions.select(None);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_definition_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc | KeyCode::Char('/') => self.focus = Focus::Search,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('[') => self.history_back(),
+            KeyCode::Char(']') => self.history_forward(),
+            KeyCode::Char('c') => self.copy(),
+            KeyCode::Char('p') => self.play(),
+            KeyCode::Char('b') => self.toggle_bookmark(),
+            KeyCode::Char('v') if self.settings.content_filter != ContentFilter::Off => {
+                self.revealed = !self.revealed;
+                self.refresh_lines();
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Search => Focus::Definition,
+            Focus::Definition => Focus::Search,
+        };
+    }
+
+    fn start_lookup(&mut self, word: String, record: bool) {
+        self.status = tr("tui-looking-up", &[("word", &word)]);
+        let sender = self.sender.clone();
+        let settings = self.settings.clone();
+        self.runtime.spawn(async move {
+            let result = settings.lookup(&word).await.map_err(|e| e.to_string());
+            let recording = match &result {
+                Ok(Lookup::Found(entries)) if !settings.private => entries
+                    .iter()
+                    .find_map(|e| e.audio_url())
+                    .map(str::to_string),
+                _ => None,
+            };
+            let _ = sender.send(LookupDone {
+                word,
+                record,
+                result,
+            });
+            // Downloaded while the definition is read, so `p` plays it at once.
+            if let Some(url) = recording {
+                let _ = audio::fetch(&url).await;
+            }
+        });
+    }
+
+    fn finish_lookup(&mut self, done: LookupDone) {
+        self.status.clear();
+        self.scroll = 0;
+        self.entries.clear();
+        self.current = Some(done.word.clone());
+        match done.result {
+            Ok(Lookup::Found(entries)) => {
+                if done.record && !self.settings.private {
+                    let recorded = History::update(|history| {
+                        history.record(&done.word);
+                        history.entries.clone()
+                    });
+                    match recorded {
+                        Ok(entries) => self.history.entries = entries,
+                        Err(e) => {
+                            self.status = tr("tui-history-not-saved", &[("error", &e.to_string())])
+                        }
+                    }
+                }
+                self.entries = entries;
+                self.revealed = false;
+                self.refresh_lines();
+                self.focus = Focus::Definition;
+            }
+            Ok(Lookup::NotFound) => {
+                self.lines = vec![Line::from(format!(
+                    "{}.",
+                    tr("not-found-for", &[("word", &done.word)])
+                ))];
+            }
+            Ok(Lookup::ApiError { status, error }) => {
+                self.lines = vec![
+                    Line::from(tr(
+                        "api-error-status",
+                        &[("status", &status.to_string()), ("title", &error.title)],
+                    ))
+                    .bold(),
+                    Line::from(format!("{} {}", text("message"), error.message)),
+                    Line::from(format!("{} {}", text("resolution"), error.resolution)),
+                ];
+            }
+            Ok(Lookup::ParseError { status, error, .. }) => {
+                self.lines = vec![
+                    Line::from(tr("tui-parse-failed", &[("status", &status.to_string())])).bold(),
+                    Line::from(tr("tui-parse-error", &[("error", &error.to_string())])),
+                ];
+            }
+            Err(e) => {
+                self.lines = vec![
+                    Line::from(tr("tui-fetch-failed", &[("word", &done.word)])).bold(),
+                    Line::from(e),
+                ];
+            }
+        }
+    }
+
+    fn refresh_lines(&mut self) {
+        let filter = if self.revealed {
+            ContentFilter::Off
+        } else {
+            self.settings.content_filter
+        };
+        let shown = self.settings.prepare(self.entries.clone(), filter);
+        self.lines = if shown.is_empty() {
+            vec![Line::from(
+                "Every sense was hidden by the content filter; press v to show them.",
+            )]
+        } else {
+            entry_lines(&shown, &self.settings.lang)
+        };
+    }
+
+    fn history_back(&mut self) {
+        let pos = self.history_pos.unwrap_or(self.history.entries.len());
+        if pos == 0 {
+            self.status = text("tui-oldest").to_string();
+            return;
+        }
+        self.history_pos = Some(pos - 1);
+        let word = self.history.entries[pos - 1].word.clone();
+        self.start_lookup(word, false);
+    }
+
+    fn history_forward(&mut self) {
+        match self.history_pos {
+            Some(pos) if pos + 1 < self.history.entries.len() => {
+                self.history_pos = Some(pos + 1);
+                let word = self.history.entries[pos + 1].word.clone();
+                self.start_lookup(word, false);
+            }
+            _ => self.status = text("tui-newest").to_string(),
+        }
+    }
+
+    fn copy(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let definition = self
+            .lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.status = match desktop::copy_to_clipboard(&definition) {
+            Ok(()) => text("tui-copied").to_string(),
+            Err(e) => tr("tui-copy-failed", &[("error", &e.to_string())]),
+        };
+    }
+
+    fn play(&mut self) {
+        let Some(url) = self.entries.iter().find_map(|e| e.audio_url()) else {
+            let Some(word) = &self.current else {
+                return;
+            };
+            self.status = match desktop::speak(word, &self.settings.lang, &self.settings.tts) {
+                Ok(()) => text("tui-speaking").to_string(),
+                Err(e) => tr("tui-no-audio", &[("error", &e.to_string())]),
+            };
+            return;
+        };
+        if let Err(e) = desktop::audio_player() {
+            self.status = tr("tui-playback-failed", &[("error", &e.to_string())]);
+            return;
+        }
+        let url = url.to_string();
+        let private = self.settings.private;
+        self.runtime.spawn(async move {
+            // Private lookups leave no recording behind, and one that can't be
+            // downloaded can still be streamed.
+            let path = match private {
+                true => None,
+                false => audio::fetch(&url).await.ok(),
+            };
+            let source = path.map_or(url, |path| path.to_string_lossy().into_owned());
+            let _ = desktop::play_audio(&source);
+        });
+        self.status = text("tui-playing").to_string();
+    }
+
+    fn toggle_bookmark(&mut self) {
+        let Some(word) = &self.current else {
+            return;
+        };
+        let toggled = Bookmarks::update(|bookmarks| {
+            let added = bookmarks.toggle(word);
+            (added, bookmarks.words.clone())
+        });
+        self.status = match toggled {
+            Err(e) => tr("tui-bookmarks-not-saved", &[("error", &e.to_string())]),
+            Ok((added, words)) => {
+                self.bookmarks.words = words;
+                match added {
+                    true => tr("tui-bookmarked", &[("word", word)]),
+                    false => tr("tui-unbookmarked", &[("word", word)]),
+                }
+            }
+        };
+    }
+}
+
+/// A Chinese reading after the headword, each syllable in its tone's color
+/// when `tone_colors` is on.
+fn tone_spans(reading: &str) -> Vec<Span<'static>> {
+    let Some(syllables) = cedict::tones(reading) else {
+        return vec![Span::from(format!("  {}", reading)).dim()];
+    };
+    let mut spans = vec![Span::from(" ")];
+    for (syllable, tone) in syllables {
+        let color = Color::from_str(cedict::TONE_COLORS[tone - 1]).unwrap_or_default();
+        spans.push(Span::from(format!(" {}", syllable)).fg(color));
+    }
+    spans
+}
+
+/// Plain-text (no Pango markup) rendering of the entries for the terminal.
+fn entry_lines(entries: &[DefinitionResponse], lang: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        match Ruby::of(entry) {
+            Some(ruby) => {
+                let mut ruby = ruby.lines();
+                let word = ruby.pop().unwrap_or_default();
+                lines.extend(ruby.into_iter().map(|reading| Line::from(reading).dim()));
+                lines.push(Line::from(word).bold());
+            }
+            None => {
+                let headline = gender::headline(entry, lang);
+                let mut heading = vec![Span::from(text::isolate(&headline).into_owned()).bold()];
+                match (entry.ipa(), cedict::is_from(entry)) {
+                    (Some(ipa), true) => heading.extend(tone_spans(ipa)),
+                    (Some(ipa), false) => heading.push(Span::from(format!("  {}", ipa)).dim()),
+                    (None, _) => {}
+                }
+                lines.push(Line::from(heading));
+            }
+        }
+        let pronunciations = entry.pronunciations();
+        if pronunciations.len() > 1 {
+            lines.push(Line::from(pronunciations.join(" · ")).dim());
+        }
+        lines.push(Line::default());
+
+        let numbering = Numbering::of([entry]);
+        for meaning in &entry.meanings {
+            lines.push(
+                Line::from(format!("─── {} ───", meaning.heading()))
+                    .bold()
+                    .italic(),
+            );
+            if let Some(forms) = meaning.forms.as_ref().filter(|f| !f.is_empty()) {
+                lines
+                    .push(Line::from(format!("     {} {}", text("forms"), forms.join(", "))).dim());
+            }
+            for (i, def) in meaning.definitions.iter().enumerate() {
+                let mut line = vec![Span::from(format!(" {}", numbering.label(i + 1)))];
+                if let Some(level) = def.level {
+                    line.push(Span::from(format!("[{}] ", level)).dim());
+                }
+                if let Some(region) = &def.region {
+                    line.push(Span::from(format!("[{}] ", region)).dim());
+                }
+                for label in &def.labels {
+                    let color = Color::from_str(label.color()).unwrap_or_default();
+                    line.push(Span::styled(format!("[{}] ", label), color));
+                }
+                line.push(Span::from(text::isolate(&def.definition).into_owned()));
+                lines.push(Line::from(line));
+                if let Some(example) = &def.example {
+                    lines.push(
+                        Line::from(format!("     {} \"{}\"", text("example"), example)).italic(),
+                    );
+                }
+                if let Some(synonyms) = def.synonyms.as_ref().filter(|s| !s.is_empty()) {
+                    lines.push(Line::from(format!(
+                        "     {} {}",
+                        text("synonyms"),
+                        synonyms.join(", ")
+                    )));
+                }
+                if let Some(antonyms) = def.antonyms.as_ref().filter(|a| !a.is_empty()) {
+                    lines.push(Line::from(format!(
+                        "     {} {}",
+                        text("antonyms"),
+                        antonyms.join(", ")
+                    )));
+                }
+            }
+            lines.push(Line::default());
+        }
+        if let Some(origin) = &entry.origin {
+            lines.push(Line::from(format!("{} {}", text("origin"), origin)).dim());
+            lines.push(Line::default());
+        }
+    }
+    lines
+}
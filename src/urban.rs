@@ -0,0 +1,93 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning},
+    http,
+    providers::Provider,
+};
+
+/// Definitions kept, most upvoted first; the long tail is mostly jokes.
+const LIMIT: usize = 5;
+
+/// `[word]`, Urban Dictionary's markup for a link to another definition.
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]*)\]").unwrap());
+
+#[derive(Serialize)]
+struct Query<'a> {
+    term: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    list: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    word: String,
+    definition: String,
+    #[serde(default)]
+    example: String,
+    #[serde(default)]
+    thumbs_up: i64,
+    #[serde(default)]
+    thumbs_down: i64,
+    permalink: Option<String>,
+}
+
+/// Slang from Urban Dictionary, as a single "slang" meaning.
+pub async fn lookup(word: &str) -> Result<Lookup, surf::Error> {
+    let mut response: Response =
+        http::provider_get(Provider::Urban, "https://api.urbandictionary.com/v0/define")?
+            .query(&Query { term: word })?
+            .recv_json()
+            .await?;
+    response
+        .list
+        .retain(|entry| entry.word.to_lowercase() == word.trim().to_lowercase());
+    response
+        .list
+        .sort_by_key(|entry| entry.thumbs_down - entry.thumbs_up);
+    response.list.truncate(LIMIT);
+    if response.list.is_empty() {
+        return Ok(Lookup::NotFound);
+    }
+
+    let unlink = |text: &str| LINK.replace_all(text.trim(), "$1").replace("\r\n", " ");
+    let definitions = response
+        .list
+        .iter()
+        .map(|entry| Definition {
+            definition: unlink(&entry.definition),
+            example: Some(unlink(&entry.example)).filter(|example| !example.is_empty()),
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        })
+        .collect();
+    let urls = response
+        .list
+        .iter()
+        .filter_map(|entry| entry.permalink.clone())
+        .collect();
+    Ok(Lookup::Found(vec![DefinitionResponse {
+        word: response.list[0].word.clone(),
+        phonetic: None,
+        phonetics: Vec::new(),
+        meanings: vec![Meaning {
+            part_of_speech: String::from("slang"),
+            definitions,
+            synonyms: None,
+            antonyms: None,
+            forms: None,
+            gender: None,
+        }],
+        source_urls: Some(urls),
+        origin: None,
+        provider: None,
+    }]))
+}
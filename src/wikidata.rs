@@ -0,0 +1,374 @@
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{
+    api::{Definition, DefinitionResponse, Lookup, Meaning, Phonetic},
+    gender::Gender,
+    http,
+    providers::Provider,
+};
+
+const API: &str = "https://www.wikidata.org/w/api.php";
+/// Wikimedia turns away requests that don't say who's asking.
+const USER_AGENT: &str = "sherlock-dictionary (https://github.com/MoonBurst/sherlock_dict_rs)";
+/// The "IPA transcription" property on a form.
+const IPA: &str = "P898";
+/// The "grammatical gender" property on a lexeme.
+const GRAMMATICAL_GENDER: &str = "P5185";
+/// Lexemes fetched per lookup; homographs rarely go past a handful.
+const LIMIT: usize = 8;
+
+/// The items for the languages lexemes are in, by language code.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("en", "Q1860"),
+    ("de", "Q188"),
+    ("fr", "Q150"),
+    ("es", "Q1321"),
+    ("it", "Q652"),
+    ("pt", "Q5146"),
+    ("nl", "Q7411"),
+    ("sv", "Q9027"),
+    ("pl", "Q809"),
+    ("ru", "Q7737"),
+    ("ja", "Q5287"),
+    ("zh", "Q7850"),
+    ("ko", "Q9176"),
+    ("hi", "Q1568"),
+    ("ar", "Q13955"),
+    ("tr", "Q256"),
+    ("la", "Q397"),
+];
+
+/// Lexical categories, as parts of speech.
+const CATEGORIES: &[(&str, &str)] = &[
+    ("Q1084", "noun"),
+    ("Q147276", "proper noun"),
+    ("Q24905", "verb"),
+    ("Q34698", "adjective"),
+    ("Q380057", "adverb"),
+    ("Q36224", "pronoun"),
+    ("Q4833830", "preposition"),
+    ("Q36484", "conjunction"),
+    ("Q83034", "interjection"),
+    ("Q576271", "determiner"),
+    ("Q103184", "article"),
+    ("Q63116", "numeral"),
+    ("Q184943", "particle"),
+    ("Q187931", "phrase"),
+    ("Q184511", "idiom"),
+    ("Q134830", "prefix"),
+    ("Q102047", "suffix"),
+];
+
+/// Grammatical features of forms. Ones not listed are left out of the label.
+const FEATURES: &[(&str, &str)] = &[
+    ("Q110786", "singular"),
+    ("Q146786", "plural"),
+    ("Q21714344", "first person"),
+    ("Q51929049", "second person"),
+    ("Q51929074", "third person"),
+    ("Q179230", "infinitive"),
+    ("Q3910936", "simple present"),
+    ("Q192613", "present"),
+    ("Q1994301", "past"),
+    ("Q3482678", "simple past"),
+    ("Q442485", "preterite"),
+    ("Q501405", "future"),
+    ("Q10345583", "present participle"),
+    ("Q1230649", "past participle"),
+    ("Q1923028", "gerund"),
+    ("Q682111", "indicative"),
+    ("Q473746", "subjunctive"),
+    ("Q22716", "imperative"),
+    ("Q14169499", "comparative"),
+    ("Q1817208", "superlative"),
+    ("Q499327", "masculine"),
+    ("Q1775415", "feminine"),
+    ("Q1775461", "neuter"),
+    ("Q131105", "nominative"),
+    ("Q146078", "accusative"),
+    ("Q145599", "dative"),
+    ("Q146233", "genitive"),
+];
+
+/// The items for grammatical genders.
+const GENDERS: &[(&str, Gender)] = &[
+    ("Q499327", Gender::Masculine),
+    ("Q1775415", Gender::Feminine),
+    ("Q1775461", Gender::Neuter),
+    ("Q1305037", Gender::Common),
+];
+
+#[derive(Serialize)]
+struct Search<'a> {
+    action: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    search: &'a str,
+    language: &'a str,
+    limit: usize,
+    format: &'static str,
+}
+
+#[derive(Serialize)]
+struct GetEntities {
+    action: &'static str,
+    ids: String,
+    format: &'static str,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    search: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Entities {
+    #[serde(default)]
+    entities: HashMap<String, Lexeme>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Lexeme {
+    id: String,
+    #[serde(default, deserialize_with = "map")]
+    lemmas: HashMap<String, Text>,
+    #[serde(default)]
+    lexical_category: String,
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    forms: Vec<Form>,
+    #[serde(default)]
+    senses: Vec<Sense>,
+    /// Statements about the lexeme, by property. Only its gender is read.
+    #[serde(default, deserialize_with = "map")]
+    claims: HashMap<String, Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Form {
+    #[serde(default, deserialize_with = "map")]
+    representations: HashMap<String, Text>,
+    #[serde(default)]
+    grammatical_features: Vec<String>,
+    /// Statements about the form, by property. Left loose: only the IPA is read.
+    #[serde(default, deserialize_with = "map")]
+    claims: HashMap<String, Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+struct Sense {
+    #[serde(default, deserialize_with = "map")]
+    glosses: HashMap<String, Text>,
+}
+
+#[derive(Deserialize)]
+struct Text {
+    value: String,
+}
+
+/// A JSON object of `T`s. Wikibase writes empty ones as `[]`.
+fn map<'de, D: Deserializer<'de>, T: DeserializeOwned>(
+    deserializer: D,
+) -> Result<HashMap<String, T>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Array(items) if items.is_empty() => Ok(HashMap::new()),
+        value => serde_json::from_value(value).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Looks `word` up among the Wikidata lexemes in `lang`: one meaning per
+/// lexeme, with its senses and inflected forms.
+pub async fn lookup(word: &str, lang: &str) -> Result<Lookup, surf::Error> {
+    let Some(&(_, language)) = LANGUAGES.iter().find(|(code, _)| *code == lang) else {
+        return Ok(Lookup::NotFound);
+    };
+    let search = Search {
+        action: "wbsearchentities",
+        kind: "lexeme",
+        search: word,
+        language: lang,
+        limit: LIMIT,
+        format: "json",
+    };
+    let found: SearchResponse = http::provider_get(Provider::Wikidata, API)?
+        .header("User-Agent", USER_AGENT)
+        .query(&search)?
+        .recv_json()
+        .await?;
+    if found.search.is_empty() {
+        return Ok(Lookup::NotFound);
+    }
+    let ids: Vec<String> = found.search.into_iter().map(|r| r.id).collect();
+    let get = GetEntities {
+        action: "wbgetentities",
+        ids: ids.join("|"),
+        format: "json",
+    };
+    let mut entities: Entities = http::provider_get(Provider::Wikidata, API)?
+        .header("User-Agent", USER_AGENT)
+        .query(&get)?
+        .recv_json()
+        .await?;
+
+    // Search matches prefixes and other languages too; keep exact lemmas in
+    // the lookup language, in search order.
+    let lexemes: Vec<Lexeme> = ids
+        .iter()
+        .filter_map(|id| entities.entities.remove(id))
+        .filter(|lexeme| lexeme.language == language)
+        .filter(|lexeme| {
+            lexeme
+                .lemmas
+                .values()
+                .any(|lemma| lemma.value.to_lowercase() == word.to_lowercase())
+        })
+        .collect();
+    let meanings: Vec<Meaning> = lexemes.iter().filter_map(|l| meaning(l, lang)).collect();
+    if meanings.is_empty() {
+        return Ok(Lookup::NotFound);
+    }
+
+    let ipa = lexemes.iter().find_map(|lexeme| lemma_ipa(lexeme, word));
+    let phonetics = ipa
+        .iter()
+        .map(|text| Phonetic {
+            text: Some(text.clone()),
+            audio: None,
+            source_url: None,
+            license: None,
+        })
+        .collect();
+    let urls = lexemes
+        .iter()
+        .map(|lexeme| format!("https://www.wikidata.org/wiki/Lexeme:{}", lexeme.id))
+        .collect();
+    Ok(Lookup::Found(vec![DefinitionResponse {
+        word: word.to_string(),
+        phonetic: ipa,
+        phonetics,
+        meanings,
+        source_urls: Some(urls),
+        origin: None,
+        provider: None,
+    }]))
+}
+
+/// The text in `lang`, or in English, or in whatever language there is.
+/// Text not in `lang` is marked with its language: "[en] to move swiftly".
+fn localized(texts: &HashMap<String, Text>, lang: &str) -> Option<String> {
+    if let Some(text) = texts.get(lang) {
+        return Some(text.value.clone());
+    }
+    let (other, text) = texts
+        .get_key_value("en")
+        .or_else(|| texts.iter().min_by_key(|(code, _)| code.as_str()))?;
+    Some(format!("[{}] {}", other, text.value))
+}
+
+fn meaning(lexeme: &Lexeme, lang: &str) -> Option<Meaning> {
+    let definitions: Vec<Definition> = lexeme
+        .senses
+        .iter()
+        .filter_map(|sense| localized(&sense.glosses, lang))
+        .map(|definition| Definition {
+            definition,
+            example: None,
+            synonyms: None,
+            antonyms: None,
+            level: None,
+            region: None,
+            labels: Vec::new(),
+        })
+        .collect();
+    if definitions.is_empty() {
+        return None;
+    }
+    let part_of_speech = CATEGORIES
+        .iter()
+        .find(|(id, _)| *id == lexeme.lexical_category)
+        .map_or("lexeme", |(_, name)| name);
+
+    let mut forms: Vec<String> = Vec::new();
+    for form in &lexeme.forms {
+        let Some(text) = form.representations.values().next() else {
+            continue;
+        };
+        let features: Vec<&str> = form
+            .grammatical_features
+            .iter()
+            .filter_map(|id| {
+                FEATURES
+                    .iter()
+                    .find(|(f, _)| f == id)
+                    .map(|(_, name)| *name)
+            })
+            .collect();
+        let label = match features.is_empty() {
+            // The lemma's own form says nothing new.
+            true if lexeme.lemmas.values().any(|l| l.value == text.value) => continue,
+            true => text.value.clone(),
+            false => format!("{} ({})", text.value, features.join(", ")),
+        };
+        if !forms.contains(&label) {
+            forms.push(label);
+        }
+    }
+    Some(Meaning {
+        part_of_speech: part_of_speech.to_string(),
+        definitions,
+        synonyms: None,
+        antonyms: None,
+        forms: Some(forms).filter(|forms| !forms.is_empty()),
+        gender: gender(lexeme),
+    })
+}
+
+/// The gender a noun's lexeme states. Nouns that are both masculine and
+/// feminine state both.
+fn gender(lexeme: &Lexeme) -> Option<Gender> {
+    let genders: Vec<Gender> = lexeme
+        .claims
+        .get(GRAMMATICAL_GENDER)
+        .into_iter()
+        .flatten()
+        .filter_map(|claim| claim.pointer("/mainsnak/datavalue/value/id")?.as_str())
+        .filter_map(|id| GENDERS.iter().find(|(item, _)| *item == id))
+        .map(|(_, gender)| *gender)
+        .collect();
+    match genders.as_slice() {
+        [gender] => Some(*gender),
+        [Gender::Masculine, Gender::Feminine] | [Gender::Feminine, Gender::Masculine] => {
+            Some(Gender::MasculineFeminine)
+        }
+        _ => None,
+    }
+}
+
+/// The IPA given on the form spelled like the lemma, if any.
+fn lemma_ipa(lexeme: &Lexeme, word: &str) -> Option<String> {
+    lexeme
+        .forms
+        .iter()
+        .filter(|form| {
+            form.representations
+                .values()
+                .any(|text| text.value.to_lowercase() == word.to_lowercase())
+        })
+        .flat_map(|form| form.claims.get(IPA).into_iter().flatten())
+        .find_map(|claim| claim.pointer("/mainsnak/datavalue/value")?.as_str())
+        .map(|ipa| match ipa.starts_with(['/', '[']) {
+            true => ipa.to_string(),
+            false => format!("/{}/", ipa),
+        })
+}
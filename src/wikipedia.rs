@@ -0,0 +1,123 @@
+use serde::Deserialize;
+
+use crate::{
+    http,
+    sherlock::{ApplicationAction, SherlockPipeResponse},
+    text,
+};
+
+/// Lower-case words that still belong in a name: "Statue of Liberty",
+/// "Leonardo da Vinci".
+const CONNECTORS: &[&str] = &[
+    "of", "the", "and", "de", "da", "di", "del", "la", "le", "van", "von", "der", "den", "du", "y",
+    "upon", "on", "in",
+];
+
+/// The lead of a Wikipedia article.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Summary {
+    pub title: String,
+    /// A few words such as "capital of France".
+    pub description: Option<String>,
+    pub extract: String,
+    #[serde(rename = "type")]
+    kind: String,
+    content_urls: ContentUrls,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ContentUrls {
+    desktop: PageUrl,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PageUrl {
+    page: String,
+}
+
+/// Whether `query` is capitalised like the name of a person, place or thing
+/// rather than an ordinary word: "Paris", "Ada Lovelace", "Statue of Liberty".
+pub fn looks_like_name(query: &str) -> bool {
+    let mut words = query.split_whitespace();
+    let capitalised = |word: &str| word.chars().next().is_some_and(char::is_uppercase);
+    words.next().is_some_and(capitalised)
+        && words.all(|word| capitalised(word) || CONNECTORS.contains(&word))
+}
+
+/// The summary of the `lang` Wikipedia article titled `title`. Missing
+/// articles and disambiguation pages give `None`.
+pub async fn summary(title: &str, lang: &str) -> Result<Option<Summary>, surf::Error> {
+    let title = title
+        .trim()
+        .replace('%', "%25")
+        .replace(' ', "_")
+        .replace('/', "%2F")
+        .replace('?', "%3F")
+        .replace('#', "%23");
+    let url = format!(
+        "https://{}.wikipedia.org/api/rest_v1/page/summary/{}",
+        lang, title
+    );
+    let mut response = http::get(&url).await?;
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+    let summary: Summary = response.body_json().await?;
+    Ok(match summary.kind.as_str() {
+        "disambiguation" => None,
+        _ => Some(summary),
+    })
+}
+
+impl Summary {
+    pub fn url(&self) -> &str {
+        &self.content_urls.desktop.page
+    }
+
+    /// A tile with the extract and an action to read the whole article.
+    pub fn response(&self) -> SherlockPipeResponse {
+        let mut content = String::new();
+        if let Some(description) = &self.description {
+            content.push_str(&format!("<i>{}</i>\n\n", description));
+        }
+        content.push_str(&self.extract);
+        content.push_str("\n\n<span font_desc=\"monospace\"><i>Source:</i> Wikipedia</span>");
+        let actions = vec![
+            ApplicationAction::open_url("Open article", self.url()),
+            ApplicationAction::copy(String::from("Copy summary"), self.extract.clone()),
+        ];
+        SherlockPipeResponse::new(self.title.clone(), content, actions)
+    }
+
+    /// The summary for terminals, with the article's address last.
+    pub fn plain(&self) -> String {
+        let mut text = format!("{}\n", self.title);
+        if let Some(description) = &self.description {
+            text.push_str(&format!("({})\n", description));
+        }
+        text.push_str(&format!("\n{}\n\n{}\n", self.extract, self.url()));
+        text
+    }
+
+    /// The summary for editors' hovers, in Markdown.
+    pub fn markdown(&self) -> String {
+        let mut out = format!("**{}**\n\n", text::markdown_escape(&self.title));
+        if let Some(description) = &self.description {
+            out.push_str(&format!("*{}*\n\n", text::markdown_escape(description)));
+        }
+        out.push_str(&format!(
+            "{}\n\n[Wikipedia]({})",
+            text::markdown_escape(&self.extract),
+            self.url()
+        ));
+        out
+    }
+
+    /// "Paris (capital of France): Paris is the capital…"
+    pub fn oneline(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{} ({}): {}", self.title, description, self.extract),
+            None => format!("{}: {}", self.title, self.extract),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::http;
+
+/// A `== Language ==` level-2 heading.
+static LANGUAGE_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^==\s*([^=]+?)\s*==\s*$").unwrap());
+/// A heading of any level.
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(={2,6})\s*([^=]+?)\s*={2,6}\s*$").unwrap());
+/// `[[target|shown]]` and `[[target]]` links.
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[(?:[^\]|]*\|)?([^\]|]*)\]\]").unwrap());
+
+/// Language codes, their three-letter forms and the names Wiktionary uses
+/// for their sections.
+pub const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("en", "eng", "English"),
+    ("de", "deu", "German"),
+    ("fr", "fra", "French"),
+    ("es", "spa", "Spanish"),
+    ("it", "ita", "Italian"),
+    ("pt", "por", "Portuguese"),
+    ("nl", "nld", "Dutch"),
+    ("sv", "swe", "Swedish"),
+    ("pl", "pol", "Polish"),
+    ("ru", "rus", "Russian"),
+    ("ja", "jpn", "Japanese"),
+    ("zh", "zho", "Chinese"),
+    ("ko", "kor", "Korean"),
+    ("hi", "hin", "Hindi"),
+    ("ar", "ara", "Arabic"),
+    ("tr", "tur", "Turkish"),
+    ("la", "lat", "Latin"),
+];
+
+/// Codes for the older, reconstructed and less common languages etymologies
+/// cite.
+const HISTORICAL: &[(&str, &str)] = &[
+    ("enm", "Middle English"),
+    ("ang", "Old English"),
+    ("fro", "Old French"),
+    ("frm", "Middle French"),
+    ("xno", "Anglo-Norman"),
+    ("LL.", "Late Latin"),
+    ("ML.", "Medieval Latin"),
+    ("NL.", "New Latin"),
+    ("VL.", "Vulgar Latin"),
+    ("la-lat", "Late Latin"),
+    ("la-med", "Medieval Latin"),
+    ("la-new", "New Latin"),
+    ("la-vul", "Vulgar Latin"),
+    ("grc", "Ancient Greek"),
+    ("el", "Greek"),
+    ("non", "Old Norse"),
+    ("odt", "Old Dutch"),
+    ("dum", "Middle Dutch"),
+    ("goh", "Old High German"),
+    ("gmh", "Middle High German"),
+    ("osx", "Old Saxon"),
+    ("gml", "Middle Low German"),
+    ("osp", "Old Spanish"),
+    ("pro", "Old Occitan"),
+    ("ine-pro", "Proto-Indo-European"),
+    ("gem-pro", "Proto-Germanic"),
+    ("gmw-pro", "Proto-West Germanic"),
+    ("itc-pro", "Proto-Italic"),
+    ("grk-pro", "Proto-Hellenic"),
+    ("cel-pro", "Proto-Celtic"),
+    ("sla-pro", "Proto-Slavic"),
+    ("iir-pro", "Proto-Indo-Iranian"),
+    ("sa", "Sanskrit"),
+    ("fa", "Persian"),
+    ("he", "Hebrew"),
+    ("ga", "Irish"),
+    ("cy", "Welsh"),
+    ("da", "Danish"),
+    ("no", "Norwegian"),
+    ("is", "Icelandic"),
+    ("fi", "Finnish"),
+    ("hu", "Hungarian"),
+    ("cs", "Czech"),
+    ("uk", "Ukrainian"),
+    ("ro", "Romanian"),
+    ("ca", "Catalan"),
+];
+
+/// The name Wiktionary gives the language with code `code`, if it's one we
+/// know.
+pub fn language_name(code: &str) -> Option<&'static str> {
+    LANGUAGES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, _, name)| *name)
+        .or_else(|| HISTORICAL.iter().find(|(c, _)| *c == code).map(|(_, n)| *n))
+}
+
+/// Where the raw wikitext of the English Wiktionary page for `word` is.
+pub fn page_url(word: &str) -> String {
+    format!(
+        "https://en.wiktionary.org/w/index.php?title={}&action=raw",
+        word.replace(' ', "_")
+    )
+}
+
+/// Fetches the raw wikitext of the English Wiktionary page for `word`.
+/// Returns `None` if the page doesn't exist.
+pub async fn fetch_page(word: &str) -> Result<Option<String>, surf::Error> {
+    let mut response = http::get(page_url(word)).await?;
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+    Ok(Some(response.body_string().await?))
+}
+
+/// The part of a page under the `== language ==` heading, e.g. "English".
+pub fn language_section<'a>(page: &'a str, language: &str) -> Option<&'a str> {
+    let mut headings = LANGUAGE_HEADING.captures_iter(page);
+    let start = headings.by_ref().find(|c| &c[1] == language)?;
+    let body_start = start.get(0).unwrap().end();
+    let body_end = headings
+        .next()
+        .map_or(page.len(), |c| c.get(0).unwrap().start());
+    Some(&page[body_start..body_end])
+}
+
+/// The bodies of every subsection called `title` in `text`, numbered ones
+/// included ("Etymology 1", "Etymology 2"). Each runs to the next heading.
+pub fn sections<'a>(text: &'a str, title: &str) -> Vec<&'a str> {
+    let headings: Vec<_> = HEADING.captures_iter(text).collect();
+    let mut bodies = Vec::new();
+    for (i, heading) in headings.iter().enumerate() {
+        let matches = heading[2]
+            .strip_prefix(title)
+            .is_some_and(|rest| rest.trim().chars().all(|c| c.is_ascii_digit()));
+        if !matches {
+            continue;
+        }
+        let start = heading.get(0).unwrap().end();
+        let end = headings
+            .get(i + 1)
+            .map_or(text.len(), |next| next.get(0).unwrap().start());
+        bodies.push(&text[start..end]);
+    }
+    bodies
+}
+
+/// Wikitext with links replaced by their text and bold and italics dropped.
+pub fn plain(wikitext: &str) -> String {
+    LINK.replace_all(wikitext, "$1")
+        .replace("'''", "")
+        .replace("''", "")
+        .trim()
+        .to_string()
+}
+
+/// The positional and named parameters of a `{{name|…}}` template call.
+#[derive(Debug)]
+pub struct Template<'a> {
+    pub positional: Vec<&'a str>,
+    pub named: Vec<(&'a str, &'a str)>,
+}
+
+/// All non-nested templates called `name` in `text`, in order.
+pub fn templates<'a>(text: &'a str, name: &str) -> Vec<Template<'a>> {
+    templates_named(text, &[name])
+        .into_iter()
+        .map(|(_, template)| template)
+        .collect()
+}
+
+/// All non-nested templates called any of `names` in `text`, in order, each
+/// with the name it was called by.
+pub fn templates_named<'a>(text: &'a str, names: &[&str]) -> Vec<(&'a str, Template<'a>)> {
+    let names: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+    let pattern = Regex::new(&format!(r"\{{\{{({})(\|[^{{}}]*)?\}}\}}", names.join("|"))).unwrap();
+    pattern
+        .captures_iter(text)
+        .map(|c| {
+            let mut template = Template {
+                positional: Vec::new(),
+                named: Vec::new(),
+            };
+            if let Some(params) = c.get(2) {
+                for param in params.as_str()[1..].split('|') {
+                    match param.split_once('=') {
+                        Some((key, value)) => template.named.push((key.trim(), value.trim())),
+                        None => template.positional.push(param.trim()),
+                    }
+                }
+            }
+            (c.get(1).unwrap().as_str(), template)
+        })
+        .collect()
+}
+impl Template<'_> {
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.named.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use crate::storage;
+
+/// A small frequency-ordered list of common words, always available offline.
+const EMBEDDED: &str = include_str!("../assets/words.txt");
+const SYSTEM_WORDS: &str = "/usr/share/dict/words";
+
+/// Words used for offline suggestions, most common first.
+pub struct WordList {
+    words: Vec<String>,
+    /// Frequency rank of each word from a frequency-ordered source (the system
+    /// dictionary is alphabetical, so its words have no rank).
+    ranks: HashMap<String, usize>,
+}
+impl WordList {
+    /// The list loaded once per process, for features that only read it.
+    pub fn shared() -> &'static WordList {
+        static LIST: OnceLock<WordList> = OnceLock::new();
+        LIST.get_or_init(WordList::load)
+    }
+
+    /// Loads `words.txt` from the data dir if the user provided one (one word per
+    /// line, optionally followed by a count, most frequent first, like the
+    /// FrequencyWords lists). Otherwise the embedded list is used, topped up with
+    /// the system dictionary for coverage.
+    pub fn load() -> Self {
+        let user_list = fs::read_to_string(user_path()).ok();
+        // (text, whether it is ordered by frequency)
+        let sources: Vec<(String, bool)> = match user_list {
+            Some(text) => vec![(text, true)],
+            None => {
+                let mut sources = vec![(EMBEDDED.to_string(), true)];
+                sources.extend(fs::read_to_string(SYSTEM_WORDS).ok().map(|t| (t, false)));
+                sources
+            }
+        };
+
+        let mut seen = HashSet::new();
+        let mut words = Vec::new();
+        let mut ranks = HashMap::new();
+        for (text, ranked) in &sources {
+            for line in text.lines() {
+                let Some(word) = line.split_whitespace().next() else {
+                    continue;
+                };
+                // Skip possessives and other noise the system list is full of.
+                if word.contains('\'') {
+                    continue;
+                }
+                let word = word.to_lowercase();
+                if seen.insert(word.clone()) {
+                    if *ranked {
+                        ranks.insert(word.clone(), words.len());
+                    }
+                    words.push(word);
+                }
+            }
+        }
+        Self { words, ranks }
+    }
+
+    /// Every word, most common first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// Frequency rank of `word` (0 is the most common), if it is ranked.
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.ranks.get(word).copied()
+    }
+}
+
+/// Files the list may be read from, whether or not they exist.
+pub fn source_paths() -> [PathBuf; 2] {
+    [user_path(), PathBuf::from(SYSTEM_WORDS)]
+}
+
+fn user_path() -> PathBuf {
+    storage::data_dir().join("words.txt")
+}
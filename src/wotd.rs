@@ -0,0 +1,419 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    fs,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::PathBuf,
+    process::Stdio,
+    sync::LazyLock,
+};
+
+use crate::{
+    api::{DefinitionResponse, Lookup},
+    crypto,
+    history::{self, DAY},
+    known::Known,
+    providers::Settings,
+    storage, text,
+    wordlist::WordList,
+};
+
+/// Words after the day's in the order that are tried when the day's isn't in
+/// the dictionaries.
+const TRIES: usize = 5;
+
+/// Colors `text::plain` adds when run in a terminal, which don't belong in
+/// an email.
+static ANSI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+/// Where `wotd publish` puts the word of the day, from the `wotd` section of
+/// `config.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Wotd {
+    /// The words to go through, one a line, instead of the rarer half of the
+    /// frequency list.
+    pub words: Option<PathBuf>,
+    /// The feed file to write, e.g. in a folder a web server serves.
+    pub feed: Option<PathBuf>,
+    pub feed_format: FeedFormat,
+    /// The feed's title, and the subject the words are mailed under.
+    pub title: String,
+    /// The address the feed is served at.
+    pub link: Option<String>,
+    pub mail: Option<Mail>,
+    /// How many days' words the feed keeps.
+    pub keep: usize,
+}
+impl Default for Wotd {
+    fn default() -> Self {
+        Self {
+            words: None,
+            feed: None,
+            feed_format: FeedFormat::default(),
+            title: String::from("Word of the day"),
+            link: None,
+            mail: None,
+            keep: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Atom,
+    Rss,
+}
+
+/// Who the word is mailed to, and the command that sends it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Mail {
+    pub to: Vec<String>,
+    pub from: Option<String>,
+    /// Reads the message, headers and all, on stdin.
+    #[serde(default = "default_sendmail")]
+    pub command: String,
+}
+
+fn default_sendmail() -> String {
+    String::from("sendmail -t -i")
+}
+
+/// A day's word as it was published, so the feed keeps the words before it
+/// and a second run the same day doesn't mail it again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+    /// `2026-10-14`, in UTC.
+    date: String,
+    word: String,
+    html: String,
+    mailed: bool,
+}
+
+fn path() -> PathBuf {
+    storage::data_dir().join("wotd.json")
+}
+
+/// `wotd`: the word of the day, which is then looked up like any other.
+pub async fn today(settings: &Settings, wotd: &Wotd) -> Result<String, Box<dyn Error>> {
+    let date = history::date(history::now());
+    let posts: Vec<Post> = storage::load_json(&path());
+    if let Some(post) = posts.iter().find(|post| post.date == date) {
+        return Ok(post.word.clone());
+    }
+    Ok(pick(settings, wotd).await?.0)
+}
+
+/// `wotd publish`: adds the word of the day to the feed and mails it, as
+/// the config says; meant to run daily from cron or a systemd timer. Running
+/// it again the same day rewrites the feed but doesn't mail the word twice.
+pub async fn publish(settings: &Settings, wotd: &Wotd) -> Result<(), Box<dyn Error>> {
+    if wotd.feed.is_none() && wotd.mail.is_none() {
+        fail("'wotd publish' needs a feed or mail in the config's \"wotd\" section");
+    }
+    let date = history::date(history::now());
+    let mut posts: Vec<Post> = storage::load_json(&path());
+    let mut plain = None;
+    if !posts.iter().any(|post| post.date == date) {
+        let (word, entries) = pick(settings, wotd).await?;
+        plain = Some(text::plain(&entries, &settings.lang));
+        posts.push(Post {
+            date: date.clone(),
+            word,
+            html: text::html(&entries, &settings.lang),
+            mailed: false,
+        });
+    }
+    let excess = posts.len().saturating_sub(wotd.keep.max(1));
+    posts.drain(..excess);
+    // Saved before it's mailed, so it's the same word whatever happens next.
+    storage::save_json(&path(), &posts)?;
+
+    if let Some(feed) = &wotd.feed {
+        let feed = storage::expand_home(feed);
+        let xml = match wotd.feed_format {
+            FeedFormat::Atom => atom(wotd, &posts),
+            FeedFormat::Rss => rss(wotd, &posts),
+        };
+        storage::replace_file(&feed, xml.as_bytes())?;
+    }
+    let post = posts.last_mut().unwrap();
+    if let (Some(mail), false) = (&wotd.mail, post.mailed) {
+        // A day picked before mail was set up is looked up again.
+        let body = match plain {
+            Some(body) => body,
+            None => match settings.resolve(&post.word).await?.lookup {
+                Lookup::Found(entries) => text::plain(
+                    &settings.prepare(entries, settings.content_filter),
+                    &settings.lang,
+                ),
+                _ => fail(&format!("'{}' isn't in the dictionaries", post.word)),
+            },
+        };
+        send(mail, &format!("{}: {}", wotd.title, post.word), &body)?;
+        post.mailed = true;
+        storage::save_json(&path(), &posts)?;
+    }
+    Ok(())
+}
+
+/// The day's word and its entries: the day's place in an order that goes
+/// through every word once before any comes again, or one of the next few
+/// when the dictionaries don't have it.
+async fn pick(
+    settings: &Settings,
+    wotd: &Wotd,
+) -> Result<(String, Vec<DefinitionResponse>), Box<dyn Error>> {
+    let words = candidates(settings, wotd);
+    if words.is_empty() {
+        fail("there are no words to pick the word of the day from");
+    }
+    let day = history::now() / DAY;
+    let cycle = day / words.len() as u64;
+    let mut order: Vec<&String> = words.iter().collect();
+    order.sort_by_key(|word| {
+        let mut hasher = DefaultHasher::new();
+        (cycle, word).hash(&mut hasher);
+        hasher.finish()
+    });
+    for i in 0..TRIES.min(order.len()) {
+        let word = order[(day as usize + i) % order.len()];
+        let resolved = settings.resolve(word).await?;
+        if let Lookup::Found(entries) = resolved.lookup {
+            let entries = settings.prepare(entries, settings.content_filter);
+            if !entries.is_empty() {
+                return Ok((resolved.word, entries));
+            }
+        }
+    }
+    fail(&format!(
+        "none of the {} words up next are in the dictionaries",
+        TRIES
+    ))
+}
+
+/// Stops with `message`, for what has to be fixed in the config or the list.
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+/// The config's list, or the words of the frequency list rarer than the
+/// `vocabulary` the user knows (or its rarer half, when it's shorter) that
+/// aren't in the known-words list.
+fn candidates(settings: &Settings, wotd: &Wotd) -> Vec<String> {
+    if let Some(words) = &wotd.words {
+        let text = fs::read_to_string(storage::expand_home(words))
+            .unwrap_or_else(|e| fail(&format!("couldn't read {}: {}", words.display(), e)));
+        return text
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .map(String::from)
+            .collect();
+    }
+    let list = WordList::shared();
+    let known = Known::load();
+    let ranked = list.iter().filter(|word| list.rank(word).is_some()).count();
+    let rarer = settings.vocabulary.unwrap_or(3000).min(ranked / 2);
+    list.iter()
+        .filter(|word| list.rank(word).is_some_and(|rank| rank >= rarer))
+        .filter(|word| word.chars().count() > 3 && word.chars().all(char::is_alphabetic))
+        .filter(|word| !known.contains(word))
+        .map(String::from)
+        .collect()
+}
+
+/// An Atom feed of `posts`, newest first.
+fn atom(wotd: &Wotd, posts: &[Post]) -> String {
+    let escape = text::html_escape;
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape(&wotd.title)));
+    xml.push_str(&format!("  <id>{}</id>\n", escape(&feed_id(wotd))));
+    if let Some(link) = &wotd.link {
+        xml.push_str(&format!(
+            "  <link rel=\"self\" href=\"{}\"/>\n",
+            escape(link)
+        ));
+    }
+    let updated = posts
+        .last()
+        .map_or_else(String::new, |post| post.date.clone());
+    xml.push_str(&format!("  <updated>{}T00:00:00Z</updated>\n", updated));
+    xml.push_str(&format!(
+        "  <author><name>{}</name></author>\n",
+        env!("CARGO_PKG_NAME")
+    ));
+    for post in posts.iter().rev() {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&post.word)));
+        xml.push_str(&format!(
+            "    <id>{}:{}</id>\n",
+            escape(&feed_id(wotd)),
+            post.date
+        ));
+        xml.push_str(&format!("    <updated>{}T00:00:00Z</updated>\n", post.date));
+        xml.push_str(&format!(
+            "    <content type=\"html\">{}</content>\n",
+            escape(&post.html)
+        ));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// An RSS 2.0 feed of `posts`, newest first.
+fn rss(wotd: &Wotd, posts: &[Post]) -> String {
+    let escape = text::html_escape;
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\">\n<channel>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape(&wotd.title)));
+    if let Some(link) = &wotd.link {
+        xml.push_str(&format!("  <link>{}</link>\n", escape(link)));
+    }
+    xml.push_str(&format!(
+        "  <description>{}</description>\n",
+        escape(&wotd.title)
+    ));
+    for post in posts.iter().rev() {
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&post.word)));
+        xml.push_str(&format!(
+            "    <guid isPermaLink=\"false\">{}:{}</guid>\n",
+            escape(&feed_id(wotd)),
+            post.date
+        ));
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", rfc822(&post.date)));
+        xml.push_str(&format!(
+            "    <description>{}</description>\n",
+            escape(&post.html)
+        ));
+        xml.push_str("  </item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// What the feed's entries are identified by: its address, or a URN when
+/// it has none.
+fn feed_id(wotd: &Wotd) -> String {
+    wotd.link
+        .clone()
+        .unwrap_or_else(|| format!("urn:{}:wotd", env!("CARGO_PKG_NAME")))
+}
+
+/// Midnight UTC of `2026-10-14` the way RSS writes dates, `Wed, 14 Oct 2026
+/// 00:00:00 +0000`.
+fn rfc822(date: &str) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let parts: Vec<usize> = date
+        .split('-')
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    let [year, month @ 1..=12, day] = parts[..] else {
+        return String::new();
+    };
+    // Sakamoto's method, counting January and February with the year before.
+    const OFFSETS: [usize; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let y = year - usize::from(month < 3);
+    let weekday = (y + y / 4 - y / 100 + y / 400 + OFFSETS[month - 1] + day) % 7;
+    format!(
+        "{}, {:02} {} {} 00:00:00 +0000",
+        WEEKDAYS[weekday],
+        day,
+        MONTHS[month - 1],
+        year
+    )
+}
+
+/// Mails `body` to `mail.to` through `mail.command`, as plain UTF-8 text.
+fn send(mail: &Mail, subject: &str, body: &str) -> io::Result<()> {
+    let mut message = format!("To: {}\n", mail.to.join(", "));
+    if let Some(from) = &mail.from {
+        message.push_str(&format!("From: {}\n", from));
+    }
+    message.push_str(&format!("Subject: {}\n", header(subject)));
+    message.push_str(
+        "MIME-Version: 1.0\n\
+         Content-Type: text/plain; charset=utf-8\n\
+         Content-Transfer-Encoding: 8bit\n\n",
+    );
+    message.push_str(&ANSI.replace_all(body, ""));
+
+    let mut child = crypto::shell(&mail.command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("couldn't run '{}': {}", mail.command, e)))?;
+    child.stdin.take().unwrap().write_all(message.as_bytes())?;
+    let status = child.wait()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(format!(
+            "'{}' failed: {}",
+            mail.command, status
+        ))),
+    }
+}
+
+/// `text` as a header value: as is when it's ASCII, otherwise as an RFC 2047
+/// encoded word.
+fn header(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    let encoded: String = text
+        .bytes()
+        .map(|byte| match byte {
+            b' ' => String::from("_"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => (byte as char).to_string(),
+            byte => format!("={:02X}", byte),
+        })
+        .collect();
+    format!("=?utf-8?Q?{}?=", encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc822_dates() {
+        for (date, expected) in [
+            ("2026-10-14", "Wed, 14 Oct 2026 00:00:00 +0000"),
+            ("1970-01-01", "Thu, 01 Jan 1970 00:00:00 +0000"),
+            ("2024-01-01", "Mon, 01 Jan 2024 00:00:00 +0000"),
+            ("2024-02-29", "Thu, 29 Feb 2024 00:00:00 +0000"),
+            ("2000-02-29", "Tue, 29 Feb 2000 00:00:00 +0000"),
+            ("2100-03-01", "Mon, 01 Mar 2100 00:00:00 +0000"),
+            ("2023-12-31", "Sun, 31 Dec 2023 00:00:00 +0000"),
+        ] {
+            assert_eq!(rfc822(date), expected, "{}", date);
+        }
+    }
+
+    #[test]
+    fn rfc822_rejects_malformed_dates() {
+        for date in ["", "2026-10", "2026-13-01", "2026-00-01", "today"] {
+            assert_eq!(rfc822(date), "", "{}", date);
+        }
+    }
+
+    #[test]
+    fn subjects_are_encoded_when_not_ascii() {
+        assert_eq!(header("Word of the day: ennui"), "Word of the day: ennui");
+        assert_eq!(
+            header("Wort des Tages: Größe"),
+            "=?utf-8?Q?Wort_des_Tages=3A_Gr=C3=B6=C3=9Fe?="
+        );
+    }
+}
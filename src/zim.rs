@@ -0,0 +1,282 @@
+#[cfg(feature = "compression")]
+use std::io::Read;
+use std::{io, path::Path};
+
+use memmap2::Mmap;
+
+use crate::storage;
+
+const MAGIC: u32 = 72173914;
+/// MIME type values that mark a directory entry as something other than content.
+const REDIRECT: u16 = 0xffff;
+const LINK_TARGET: u16 = 0xfffe;
+const DELETED: u16 = 0xfffd;
+/// How many redirects to follow before giving up on a loop.
+const MAX_REDIRECTS: usize = 8;
+
+/// A Kiwix `.zim` archive, such as the Wiktionary dumps. Entries are found by
+/// binary-searching the URL pointer list straight from the mapped file.
+pub struct Zim {
+    bytes: Mmap,
+    entry_count: usize,
+    cluster_count: usize,
+    url_pointers: usize,
+    cluster_pointers: usize,
+    checksum: usize,
+    mime_types: Vec<String>,
+}
+
+/// A directory entry: either content stored in a cluster or a redirect.
+enum Entry {
+    Content {
+        mime_type: u16,
+        cluster: usize,
+        blob: usize,
+    },
+    Redirect(usize),
+    Other,
+}
+
+impl Zim {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let bytes = storage::map_file(path)?;
+        let header = bytes
+            .get(..80)
+            .ok_or_else(|| invalid("ZIM file is truncated"))?;
+        if u32_at(header, 0)? != MAGIC {
+            return Err(invalid("not a ZIM file"));
+        }
+        let mime_list = u64_at(header, 56)? as usize;
+        let mut mime_types = Vec::new();
+        let mut pos = mime_list;
+        loop {
+            let (mime_type, next) = c_string(&bytes, pos)?;
+            if mime_type.is_empty() {
+                break;
+            }
+            mime_types.push(mime_type);
+            pos = next;
+        }
+        Ok(Self {
+            entry_count: u32_at(header, 24)? as usize,
+            cluster_count: u32_at(header, 28)? as usize,
+            url_pointers: u64_at(header, 32)? as usize,
+            cluster_pointers: u64_at(header, 48)? as usize,
+            checksum: u64_at(header, 72)? as usize,
+            mime_types,
+            bytes,
+        })
+    }
+
+    /// The MIME type and content of the article for `word`, trying the exact
+    /// title before the lower-cased one. Redirects are followed.
+    pub fn article(&self, word: &str) -> io::Result<Option<(String, Vec<u8>)>> {
+        let title = word.trim().replace(' ', "_");
+        let mut candidates = vec![title.clone()];
+        if title.to_lowercase() != title {
+            candidates.push(title.to_lowercase());
+        }
+        for url in candidates {
+            // Articles live in `C` in current archives and `A` in older ones.
+            for namespace in [b'C', b'A'] {
+                if let Some(index) = self.find(namespace, &url)? {
+                    if let Some(article) = self.content(index)? {
+                        return Ok(Some(article));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The titles of the articles and the redirects to them, with spaces
+    /// for the URLs' underscores. Images, styles and the like are left out.
+    pub fn headwords(&self) -> io::Result<Vec<String>> {
+        let mut headwords = Vec::new();
+        for index in 0..self.entry_count {
+            let offset = self.entry_offset(index)?;
+            let (namespace, url) = self.entry_key(offset)?;
+            if namespace != b'C' && namespace != b'A' {
+                continue;
+            }
+            let article = match self.entry(offset)? {
+                Entry::Redirect(_) => true,
+                Entry::Content { mime_type, .. } => self
+                    .mime_types
+                    .get(mime_type as usize)
+                    .is_some_and(|mime_type| mime_type.starts_with("text/html")),
+                Entry::Other => false,
+            };
+            if article {
+                headwords.push(url.replace('_', " "));
+            }
+        }
+        Ok(headwords)
+    }
+
+    /// Index of the entry at `namespace`/`url`. Entries are sorted by
+    /// namespace, then URL, byte for byte.
+    fn find(&self, namespace: u8, url: &str) -> io::Result<Option<usize>> {
+        let (mut low, mut high) = (0, self.entry_count);
+        while low < high {
+            let mid = (low + high) / 2;
+            let offset = self.entry_offset(mid)?;
+            let (entry_namespace, entry_url) = self.entry_key(offset)?;
+            match (entry_namespace, entry_url.as_bytes()).cmp(&(namespace, url.as_bytes())) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(Some(mid)),
+            }
+        }
+        Ok(None)
+    }
+
+    fn content(&self, mut index: usize) -> io::Result<Option<(String, Vec<u8>)>> {
+        for _ in 0..MAX_REDIRECTS {
+            match self.entry(self.entry_offset(index)?)? {
+                Entry::Redirect(target) => index = target,
+                Entry::Content {
+                    mime_type,
+                    cluster,
+                    blob,
+                } => {
+                    let mime_type = self
+                        .mime_types
+                        .get(mime_type as usize)
+                        .cloned()
+                        .unwrap_or_default();
+                    return Ok(Some((mime_type, self.blob(cluster, blob)?)));
+                }
+                Entry::Other => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    fn entry_offset(&self, index: usize) -> io::Result<usize> {
+        if index >= self.entry_count {
+            return Err(invalid("ZIM entry index out of range"));
+        }
+        Ok(u64_at(&self.bytes, self.url_pointers + index * 8)? as usize)
+    }
+
+    /// Namespace and URL of the directory entry at `offset`.
+    fn entry_key(&self, offset: usize) -> io::Result<(u8, String)> {
+        let mime_type = u16_at(&self.bytes, offset)?;
+        let namespace = *self
+            .bytes
+            .get(offset + 3)
+            .ok_or_else(|| invalid("ZIM file is truncated"))?;
+        // Redirects have one 32-bit field where content has two.
+        let url_start = match mime_type {
+            REDIRECT => offset + 12,
+            LINK_TARGET | DELETED => offset + 8,
+            _ => offset + 16,
+        };
+        Ok((namespace, c_string(&self.bytes, url_start)?.0))
+    }
+
+    fn entry(&self, offset: usize) -> io::Result<Entry> {
+        Ok(match u16_at(&self.bytes, offset)? {
+            REDIRECT => Entry::Redirect(u32_at(&self.bytes, offset + 8)? as usize),
+            LINK_TARGET | DELETED => Entry::Other,
+            mime_type => Entry::Content {
+                mime_type,
+                cluster: u32_at(&self.bytes, offset + 8)? as usize,
+                blob: u32_at(&self.bytes, offset + 12)? as usize,
+            },
+        })
+    }
+
+    fn blob(&self, cluster: usize, blob: usize) -> io::Result<Vec<u8>> {
+        if cluster >= self.cluster_count {
+            return Err(invalid("ZIM cluster index out of range"));
+        }
+        let start = u64_at(&self.bytes, self.cluster_pointers + cluster * 8)? as usize;
+        let end = match cluster + 1 < self.cluster_count {
+            true => u64_at(&self.bytes, self.cluster_pointers + (cluster + 1) * 8)? as usize,
+            false => self.checksum,
+        };
+        let raw = self
+            .bytes
+            .get(start..end.max(start + 1))
+            .ok_or_else(|| invalid("ZIM cluster is out of bounds"))?;
+        let info = raw[0];
+        let data = decompress(info & 0x0f, &raw[1..])?;
+
+        // Blob offsets are relative to the decompressed cluster, and the first
+        // one also tells how many there are.
+        let extended = info & 0x10 != 0;
+        let offset = |i: usize| match extended {
+            true => u64_at(&data, i * 8).map(|o| o as usize),
+            false => u32_at(&data, i * 4).map(|o| o as usize),
+        };
+        let (from, to) = (offset(blob)?, offset(blob + 1)?);
+        data.get(from..to)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| invalid("ZIM blob is out of bounds"))
+    }
+}
+
+fn decompress(compression: u8, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match compression {
+        0 | 1 => out.extend_from_slice(data),
+        #[cfg(feature = "compression")]
+        4 => lzma_rs::xz_decompress(&mut &data[..], &mut out)
+            .map_err(|e| invalid(&format!("corrupt ZIM cluster: {}", e)))?,
+        #[cfg(feature = "compression")]
+        5 => {
+            ruzstd::decoding::StreamingDecoder::new(data)
+                .map_err(|e| invalid(&format!("corrupt ZIM cluster: {}", e)))?
+                .read_to_end(&mut out)?;
+        }
+        #[cfg(not(feature = "compression"))]
+        4 | 5 => return Err(crate::offline::without_compression("this ZIM's clusters")),
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported ZIM compression {}", other),
+            ))
+        }
+    }
+    Ok(out)
+}
+
+fn field<const N: usize>(bytes: &[u8], at: usize) -> io::Result<[u8; N]> {
+    bytes
+        .get(at..at + N)
+        .map(|b| b.try_into().unwrap())
+        .ok_or_else(|| invalid("ZIM file is truncated"))
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> io::Result<u16> {
+    field(bytes, at).map(u16::from_le_bytes)
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> io::Result<u32> {
+    field(bytes, at).map(u32::from_le_bytes)
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> io::Result<u64> {
+    field(bytes, at).map(u64::from_le_bytes)
+}
+
+/// The NUL-terminated string at `at`, and the position after its terminator.
+fn c_string(bytes: &[u8], at: usize) -> io::Result<(String, usize)> {
+    let rest = bytes
+        .get(at..)
+        .ok_or_else(|| invalid("ZIM file is truncated"))?;
+    let len = rest
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| invalid("ZIM string is unterminated"))?;
+    Ok((
+        String::from_utf8_lossy(&rest[..len]).into_owned(),
+        at + len + 1,
+    ))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}